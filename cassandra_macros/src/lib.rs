@@ -0,0 +1,295 @@
+extern crate cassandra_schema;
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use cassandra_schema::CassValueType;
+use cassandra_schema::CheckedQuery;
+use cassandra_schema::CqlType;
+use cassandra_schema::QueryError;
+use cassandra_schema::SchemaSnapshot;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use proc_macro2::TokenStream as Tokens;
+use syn::Expr;
+use syn::Ident;
+use syn::LitStr;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
+use syn::spanned::Spanned;
+
+// cql!("SELECT a, b FROM ks.t WHERE k = ?", key) checks the query against
+// schema.cql next to the Cargo.toml of the crate it's in, or the file
+// CQL_SCHEMA names when the crate is compiled, and
+// cql!("db/shop.cql" => "SELECT ...", key) against another file there. The
+// file is the DDL SchemaSnapshot::to_cql writes, and editing it recompiles the
+// crate; setting CQL_SCHEMA differently doesn't.
+//
+// Unless the table and columns exist, there is a value for each bind marker
+// and each value is BindAs the type of its parameter, the crate doesn't
+// compile. Values of tuples, user types, varints, decimals, custom types and
+// blobs inside collections can't be checked this way, and are an error too.
+//
+// It expands to a Result<TypedStatement<Row>, QueryError> with the values
+// bound in order. For a SELECT, Row is a struct with an optional field for
+// each column; user types, and calls to functions other than count, ttl,
+// writetime and token, are read as a CqlValue. Other statements have () rows.
+
+#[proc_macro]
+pub fn cql(input: TokenStream) -> TokenStream {
+    match syn::parse(input).and_then(|input| expand(&input)) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => {
+            // syn's to_compile_error names ::core, which 2015 crates don't have
+            let message = err.to_string();
+            quote_spanned!(err.span() => compile_error!(#message)).into()
+        }
+    }
+}
+
+struct Input {
+    schema: Option<LitStr>,
+    query: LitStr,
+    values: Vec<Expr>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Input> {
+        let first: LitStr = try!(input.parse());
+        let (schema, query) = if input.peek(Token![=>]) {
+            try!(input.parse::<Token![=>]>());
+            (Some(first), try!(input.parse()))
+        } else {
+            (None, first)
+        };
+        let mut values = Vec::new();
+        while !input.is_empty() {
+            try!(input.parse::<Token![,]>());
+            if input.is_empty() {
+                break;
+            }
+            values.push(try!(input.parse()));
+        }
+        Ok(Input {
+            schema: schema,
+            query: query,
+            values: values,
+        })
+    }
+}
+
+fn expand(input: &Input) -> syn::Result<Tokens> {
+    let path = schema_path(input.schema.as_ref());
+    let span = input.schema.as_ref().unwrap_or(&input.query).span();
+    let mut cql = String::new();
+    if let Err(err) = File::open(&path).and_then(|mut file| file.read_to_string(&mut cql)) {
+        return Err(syn::Error::new(span, format!("can't read {}: {}", path.display(), err)));
+    }
+    let schema = try!(SchemaSnapshot::from_cql(&cql).map_err(|err| {
+        syn::Error::new(span, format!("{}: {}", path.display(), err))
+    }));
+    let query = input.query.value();
+    let checked = try!(schema.check_query(&query)
+                             .map_err(|err| syn::Error::new(input.query.span(), err)));
+
+    if checked.parameters.len() != input.values.len() {
+        let err = QueryError::ParameterCount {
+            expected: checked.parameters.len(),
+            given: input.values.len(),
+        };
+        return Err(syn::Error::new(input.query.span(), err));
+    }
+    let mut values = Vec::new();
+    for (value, &(ref name, ref cql_type)) in input.values.iter().zip(&checked.parameters) {
+        let bound_as = match bound_type(cql_type, false) {
+            Some(bound_as) => bound_as,
+            None => {
+                return Err(syn::Error::new(value.span(),
+                                           format!("cql! can't check a value for {} ({}); \
+                                                    bind it with a CheckedQuery",
+                                                   name,
+                                                   cql_type)))
+            }
+        };
+        values.push(quote_spanned! { value.span() =>
+            ::cassandra::bind_as::<#bound_as, _>(&(#value))
+        });
+    }
+
+    let (row, row_struct) = if checked.columns.is_empty() {
+        (quote!(()), Tokens::new())
+    } else {
+        (quote!(__cql_row::Row), row_struct(&checked))
+    };
+    let path = path.to_string_lossy().into_owned();
+    Ok(quote! {{
+        const _: &'static str = include_str!(#path);
+        #row_struct
+        ::cassandra::TypedStatement::<#row>::new(#query, &[#(#values),*])
+    }})
+}
+
+fn schema_path(schema: Option<&LitStr>) -> PathBuf {
+    let dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default());
+    match schema {
+        Some(schema) => dir.join(schema.value()),
+        None => dir.join(env::var_os("CQL_SCHEMA").unwrap_or_else(|| "schema.cql".into())),
+    }
+}
+
+fn row_struct(checked: &CheckedQuery) -> Tokens {
+    let mut taken = BTreeSet::new();
+    let mut fields = Vec::new();
+    let mut types = Vec::new();
+    let mut reads = Vec::new();
+    for (index, &(ref column, ref cql_type)) in checked.columns.iter().enumerate() {
+        fields.push(Ident::new(&field_name(column, &mut taken), Span::call_site()));
+        let index = index as u64;
+        let blob = CqlType::Native(CassValueType::BLOB);
+        match cql_type.as_ref() {
+            Some(cql_type) if *cql_type.unfrozen() == blob => {
+                // read as a slice and copied, which FromCql can't do
+                types.push(quote!(::std::vec::Vec<u8>));
+                reads.push(quote! {
+                    row.get_column(#index)?.get::<Option<&[u8]>>()?.map(|bytes| bytes.to_vec())
+                });
+            }
+            _ => {
+                let read_as = cql_type.as_ref().and_then(|cql_type| read_type(cql_type));
+                types.push(read_as.unwrap_or_else(|| quote!(::cassandra::CqlValue)));
+                reads.push(quote!(row.get_column(#index)?.get()?));
+            }
+        }
+    }
+    quote! {
+        #[allow(dead_code)]
+        mod __cql_row {
+            #[derive(Debug,Clone,PartialEq)]
+            pub struct Row {
+                #(pub #fields: Option<#types>,)*
+            }
+
+            impl ::cassandra::FromRow for Row {
+                fn from_row(row: &::cassandra::CassRow) -> Result<Row, ::cassandra::CassError> {
+                    Ok(Row {
+                        #(#fields: #reads,)*
+                    })
+                }
+            }
+        }
+    }
+}
+
+// Lower case, with anything that isn't an ASCII letter or digit replaced by an
+// underscore, and unique among the struct's fields.
+fn field_name(column: &str, taken: &mut BTreeSet<String>) -> String {
+    let mut name: String = column.chars()
+                                 .map(|c| {
+                                     if c.is_ascii_alphanumeric() {
+                                         c.to_ascii_lowercase()
+                                     } else {
+                                         '_'
+                                     }
+                                 })
+                                 .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    // keywords, and _ on its own
+    if syn::parse_str::<Ident>(&name).is_err() {
+        name.push('_');
+    }
+    let mut unique = name.clone();
+    let mut number = 2;
+    while taken.contains(&unique) {
+        unique = format!("{}{}", name, number);
+        number += 1;
+    }
+    taken.insert(unique.clone());
+    unique
+}
+
+// The type FromCql reads a column or collection item into, if it reads one.
+fn read_type(cql_type: &CqlType) -> Option<Tokens> {
+    match *cql_type.unfrozen() {
+        CqlType::Native(CassValueType::TIMESTAMP) => Some(quote!(i64)),
+        CqlType::Native(value_type) => native_type(value_type),
+        CqlType::List(ref item) | CqlType::Set(ref item) => {
+            read_type(item).map(|item| quote!(::std::vec::Vec<#item>))
+        }
+        CqlType::Map(ref key, ref value) if orderable(key) => {
+            match (read_type(key), read_type(value)) {
+                (Some(key), Some(value)) => {
+                    Some(quote!(::std::collections::BTreeMap<#key, #value>))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// The T of the BindAs a value for a parameter of the type has to be.
+fn bound_type(cql_type: &CqlType, in_collection: bool) -> Option<Tokens> {
+    match *cql_type.unfrozen() {
+        CqlType::Native(CassValueType::TIMESTAMP) => Some(quote!(::std::time::SystemTime)),
+        CqlType::Native(CassValueType::BLOB) if !in_collection => Some(quote!([u8])),
+        CqlType::Native(value_type) => native_type(value_type),
+        CqlType::List(ref item) => {
+            bound_type(item, true).map(|item| quote!(::std::vec::Vec<#item>))
+        }
+        CqlType::Set(ref item) => {
+            bound_type(item, true).map(|item| quote!(::std::collections::BTreeSet<#item>))
+        }
+        CqlType::Map(ref key, ref value) => {
+            match (bound_type(key, true), bound_type(value, true)) {
+                (Some(key), Some(value)) => {
+                    Some(quote!(::std::collections::BTreeMap<#key, #value>))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// Timestamps and blobs are left to the callers, which treat them differently.
+fn native_type(value_type: CassValueType) -> Option<Tokens> {
+    match value_type {
+        CassValueType::ASCII | CassValueType::TEXT | CassValueType::VARCHAR => {
+            Some(quote!(::std::string::String))
+        }
+        CassValueType::BIGINT | CassValueType::COUNTER | CassValueType::TIME => Some(quote!(i64)),
+        CassValueType::INT => Some(quote!(i32)),
+        CassValueType::SMALL_INT => Some(quote!(i16)),
+        CassValueType::TINY_INT => Some(quote!(i8)),
+        CassValueType::DATE => Some(quote!(u32)),
+        CassValueType::FLOAT => Some(quote!(f32)),
+        CassValueType::DOUBLE => Some(quote!(f64)),
+        CassValueType::BOOLEAN => Some(quote!(bool)),
+        CassValueType::UUID | CassValueType::TIMEUUID => Some(quote!(::cassandra::CassUuid)),
+        CassValueType::INET => Some(quote!(::std::net::IpAddr)),
+        CassValueType::DURATION => Some(quote!(::cassandra::CqlDuration)),
+        _ => None,
+    }
+}
+
+fn orderable(cql_type: &CqlType) -> bool {
+    match *cql_type.unfrozen() {
+        CqlType::Native(CassValueType::FLOAT) |
+        CqlType::Native(CassValueType::DOUBLE) |
+        CqlType::Native(CassValueType::DURATION) => false,
+        CqlType::List(ref item) | CqlType::Set(ref item) => orderable(item),
+        CqlType::Map(ref key, ref value) => orderable(key) && orderable(value),
+        _ => true,
+    }
+}
@@ -0,0 +1,13 @@
+extern crate trybuild;
+
+use std::env;
+use std::path::Path;
+
+#[test]
+fn queries_are_checked_when_compiled() {
+    env::set_var("CQL_SCHEMA",
+                 Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("schema.cql"));
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/pass/*.rs");
+    cases.compile_fail("tests/ui/fail/*.rs");
+}
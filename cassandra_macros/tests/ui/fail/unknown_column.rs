@@ -0,0 +1,8 @@
+extern crate cassandra;
+extern crate cassandra_macros;
+
+use cassandra_macros::cql;
+
+fn main() {
+    let _ = cql!("SELECT total FROM shop.orders");
+}
@@ -0,0 +1,11 @@
+extern crate cassandra;
+extern crate cassandra_macros;
+
+use cassandra::CassUuid;
+use cassandra_macros::cql;
+
+fn orders(customer: CassUuid) {
+    let _ = cql!("SELECT items FROM shop.orders WHERE customer = ? AND placed = ?", customer);
+}
+
+fn main() {}
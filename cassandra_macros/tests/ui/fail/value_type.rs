@@ -0,0 +1,14 @@
+extern crate cassandra;
+extern crate cassandra_macros;
+
+use cassandra::CassUuid;
+use cassandra_macros::cql;
+
+fn tag(customer: CassUuid) {
+    let _ = cql!("UPDATE shop.orders SET tags = ? WHERE customer = ? AND placed = ?",
+                 vec!["a", "b"],
+                 customer,
+                 "yesterday");
+}
+
+fn main() {}
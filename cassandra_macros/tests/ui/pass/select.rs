@@ -0,0 +1,55 @@
+extern crate cassandra;
+extern crate cassandra_macros;
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::time::SystemTime;
+
+use cassandra::*;
+use cassandra_macros::cql;
+
+// Only compiled: running it would need a node.
+#[allow(dead_code)]
+fn orders(session: &CassSession, customer: CassUuid) -> Result<(), QueryError> {
+    let query = try!(cql!("SELECT placed, \"Total\", items, prices, receipt, home, count(*) \
+                           FROM shop.orders WHERE customer = ? AND placed > ? LIMIT ?",
+                          customer,
+                          SystemTime::now(),
+                          10));
+    let result = try!(session.execute_statement(query.statement()).wait());
+    for order in try!(query.rows(&result)) {
+        let _: Option<i64> = order.placed;
+        let _: Option<f64> = order.total;
+        let _: Option<Vec<String>> = order.items;
+        let _: Option<BTreeMap<String, f64>> = order.prices;
+        let _: Option<Vec<u8>> = order.receipt;
+        let _: Option<CqlValue> = order.home;
+        let _: Option<i64> = order.count___;
+    }
+    Ok(())
+}
+
+#[allow(dead_code)]
+fn insert(customer: &CassUuid, items: &[&str]) -> Result<TypedStatement<()>, QueryError> {
+    let mut tags = HashSet::new();
+    tags.insert(1);
+    cql!("INSERT INTO shop.orders (customer, placed, items, tags, receipt) VALUES (?, ?, ?, ?, ?)",
+         customer,
+         1500000000000i64,
+         items.to_vec(),
+         tags,
+         &b"paid"[..])
+}
+
+#[allow(dead_code)]
+fn update(customer: CassUuid, total: Option<f64>) -> Result<TypedStatement<()>, QueryError> {
+    let mut prices = BTreeMap::new();
+    prices.insert("tea".to_owned(), 2.5);
+    cql!("UPDATE shop.orders SET \"Total\" = ?, prices = prices + ? WHERE customer = ? AND placed = ?",
+         total,
+         prices,
+         customer,
+         SystemTime::now())
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+// The parts of the cassandra crate that work without the C++ driver: CQL types
+// and values, the schema model, reading and describing schemas as CQL, and
+// checking queries against them. The cassandra crate re-exports all of it, and
+// the cql! macro depends on this crate alone, so expanding a query doesn't
+// need the driver installed.
+#[macro_use]
+extern crate log;
+#[cfg(feature = "uuid")]
+extern crate uuid;
+
+pub use schema::error::*;
+pub use schema::value_type::*;
+pub use schema::uuid::*;
+pub use schema::time::*;
+pub use schema::cql_type::*;
+pub use schema::cql_value::*;
+pub use schema::metadata::*;
+pub use schema::ddl::*;
+pub use schema::diff::*;
+pub use schema::checked_query::*;
+
+mod schema {
+    pub mod error;
+    pub mod value_type;
+    pub mod uuid;
+    pub mod time;
+    pub mod cql_type;
+    pub mod cql_value;
+    pub mod metadata;
+    pub mod describe;
+    pub mod ddl;
+    pub mod diff;
+    pub mod codegen;
+    pub mod checked_query;
+}
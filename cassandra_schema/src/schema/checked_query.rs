@@ -0,0 +1,848 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
+
+use schema::cql_type::CqlType;
+use schema::ddl::DdlError;
+use schema::ddl::Token;
+use schema::ddl::TokenKind;
+use schema::ddl::tokenize;
+use schema::metadata::ColumnMeta;
+use schema::metadata::SchemaSnapshot;
+use schema::value_type::CassValueType;
+
+// Queries checked against a schema before they are sent: that the table and
+// columns exist, and that there is a value of the right type for each bind
+// marker. A schema file checked in next to the code (what to_cql writes) can be
+// read with SchemaSnapshot::from_cql, so the checks don't need a cluster.
+//
+// The cql! macro of the cassandra_macros crate makes the same checks when the
+// code is compiled. Binding the values is up to the cassandra crate, whose
+// CheckedStatement checks them against a CheckedQuery first.
+
+#[derive(Debug)]
+pub enum QueryError {
+    Syntax(String),
+    UnknownTable(String),
+    UnknownColumn {
+        table: String,
+        column: String,
+    },
+    ParameterCount {
+        expected: usize,
+        given: usize,
+    },
+    ParameterType {
+        index: usize,
+        name: String,
+        expected: CqlType,
+    },
+    // An error from the driver while binding the values.
+    Driver(Box<dyn Error + Send + Sync>),
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            QueryError::Syntax(ref message) => write!(f, "{}", message),
+            QueryError::UnknownTable(ref table) => write!(f, "no table {}", table),
+            QueryError::UnknownColumn { ref table, ref column } => {
+                write!(f, "no column {} in {}", column, table)
+            }
+            QueryError::ParameterCount { expected, given } => {
+                write!(f, "{} bind markers but {} values", expected, given)
+            }
+            QueryError::ParameterType { index, ref name, ref expected } => {
+                write!(f, "value {} ({}) can't be bound as {}", index, name, expected)
+            }
+            QueryError::Driver(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for QueryError {
+    fn description(&self) -> &str {
+        match *self {
+            QueryError::Syntax(ref message) => message,
+            QueryError::UnknownTable(_) => "unknown table",
+            QueryError::UnknownColumn { .. } => "unknown column",
+            QueryError::ParameterCount { .. } => "wrong number of values",
+            QueryError::ParameterType { .. } => "value of the wrong type",
+            QueryError::Driver(ref err) => err.description(),
+        }
+    }
+}
+
+impl From<DdlError> for QueryError {
+    fn from(err: DdlError) -> QueryError {
+        QueryError::Syntax(err.to_string())
+    }
+}
+
+// Both positional (?) and named (:name) markers.
+pub fn count_bind_markers(query: &str) -> Result<usize, QueryError> {
+    let tokens = try!(tokenize("<query>", query));
+    let mut markers = 0;
+    // A colon inside braces separates a map key from its value.
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Symbol('{') => depth += 1,
+            TokenKind::Symbol('}') => depth -= 1,
+            TokenKind::Symbol('?') => markers += 1,
+            TokenKind::Symbol(':') if depth == 0 && is_name(tokens.get(i + 1)) => markers += 1,
+            _ => {}
+        }
+    }
+    Ok(markers)
+}
+
+fn is_name(token: Option<&Token>) -> bool {
+    match token.map(|token| &token.kind) {
+        Some(&TokenKind::Identifier(_)) | Some(&TokenKind::QuotedIdentifier(_)) => true,
+        _ => false,
+    }
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct CheckedQuery {
+    pub query: String,
+    pub keyspace: String,
+    pub table: String,
+    // One for each bind marker, in order, named the way the server names them:
+    // by the column the value goes to or is compared with, by the marker's own
+    // name for :name markers, or [ttl], [timestamp], [limit] or [json].
+    pub parameters: Vec<(String, CqlType)>,
+    // What a SELECT returns. The type is None for calls to functions other than
+    // count, ttl, writetime and token.
+    pub columns: Vec<(String, Option<CqlType>)>,
+}
+
+impl SchemaSnapshot {
+    // Checks a SELECT, INSERT, UPDATE or DELETE. Tables have to be named with
+    // their keyspace.
+    pub fn check_query(&self, query: &str) -> Result<CheckedQuery, QueryError> {
+        let mut parser = QueryParser {
+            schema: self,
+            tokens: try!(tokenize("<query>", query)),
+            pos: 0,
+            table: String::new(),
+            columns: &[],
+            checked: CheckedQuery {
+                query: query.to_owned(),
+                keyspace: String::new(),
+                table: String::new(),
+                parameters: Vec::new(),
+                columns: Vec::new(),
+            },
+        };
+        try!(parser.parse_statement());
+        Ok(parser.checked)
+    }
+}
+
+fn tuple_name(columns: &[(String, CqlType)]) -> String {
+    let names: Vec<&str> = columns.iter().map(|&(ref name, _)| &name[..]).collect();
+    format!("({})", names.join(","))
+}
+
+fn tuple_type(columns: &[(String, CqlType)]) -> CqlType {
+    CqlType::Tuple(columns.iter().map(|&(_, ref cql_type)| cql_type.clone()).collect())
+}
+
+struct QueryParser<'a> {
+    schema: &'a SchemaSnapshot,
+    tokens: Vec<Token>,
+    pos: usize,
+    // ks.table, for errors.
+    table: String,
+    columns: &'a [ColumnMeta],
+    checked: CheckedQuery,
+}
+
+impl<'a> QueryParser<'a> {
+    fn parse_statement(&mut self) -> Result<(), QueryError> {
+        if self.accept_keyword("select") {
+            try!(self.parse_select());
+        } else if self.accept_keyword("insert") {
+            try!(self.parse_insert());
+        } else if self.accept_keyword("update") {
+            try!(self.parse_update());
+        } else if self.accept_keyword("delete") {
+            try!(self.parse_delete());
+        } else {
+            return Err(self.error("expected SELECT, INSERT, UPDATE or DELETE"));
+        }
+        self.accept_symbol(';');
+        if self.peek().is_some() {
+            return Err(self.error("unexpected text after the statement"));
+        }
+        Ok(())
+    }
+
+    fn parse_select(&mut self) -> Result<(), QueryError> {
+        // The selection names columns of the table that comes after it.
+        let selection = self.pos;
+        try!(self.skip_to_from());
+        try!(self.parse_table());
+        let end = self.pos;
+        self.pos = selection;
+        if self.accept_keyword("json") {
+            self.checked.columns.push(("[json]".to_owned(), Some(CqlType::Native(CassValueType::TEXT))));
+            try!(self.parse_selectors(false));
+        } else {
+            self.accept_keyword("distinct");
+            try!(self.parse_selectors(true));
+        }
+        self.pos = end;
+        if self.accept_keyword("where") {
+            try!(self.parse_conditions());
+        }
+        if self.accept_keyword("group") {
+            try!(self.expect_keyword("by"));
+            loop {
+                try!(self.column());
+                if !self.accept_symbol(',') {
+                    break;
+                }
+            }
+        }
+        if self.accept_keyword("order") {
+            try!(self.expect_keyword("by"));
+            loop {
+                try!(self.column());
+                let _ = self.accept_keyword("asc") || self.accept_keyword("desc");
+                if !self.accept_symbol(',') {
+                    break;
+                }
+            }
+        }
+        if self.accept_keyword("per") {
+            try!(self.expect_keyword("partition"));
+            try!(self.expect_keyword("limit"));
+            try!(self.term("[limit]", CqlType::Native(CassValueType::INT)));
+        }
+        if self.accept_keyword("limit") {
+            try!(self.term("[limit]", CqlType::Native(CassValueType::INT)));
+        }
+        if self.accept_keyword("allow") {
+            try!(self.expect_keyword("filtering"));
+        }
+        Ok(())
+    }
+
+    fn parse_selectors(&mut self, record: bool) -> Result<(), QueryError> {
+        if self.accept_symbol('*') {
+            if record {
+                for column in self.columns {
+                    self.checked
+                        .columns
+                        .push((column.name.clone(), Some(column.data_type.clone())));
+                }
+            }
+        } else {
+            loop {
+                let (name, cql_type) = try!(self.selector());
+                let name = if self.accept_keyword("as") {
+                    try!(self.name())
+                } else {
+                    name
+                };
+                if record {
+                    self.checked.columns.push((name, cql_type));
+                }
+                if !self.accept_symbol(',') {
+                    break;
+                }
+            }
+        }
+        try!(self.expect_keyword("from"));
+        Ok(())
+    }
+
+    fn selector(&mut self) -> Result<(String, Option<CqlType>), QueryError> {
+        let name = try!(self.name());
+        if !self.accept_symbol('(') {
+            let cql_type = try!(self.column_type(&name));
+            return Ok((name, Some(cql_type)));
+        }
+        let mut arguments = Vec::new();
+        if self.accept_symbol('*') {
+            arguments.push("*".to_owned());
+        } else if !self.accept_symbol(')') {
+            loop {
+                let (argument, _) = try!(self.selector());
+                arguments.push(argument);
+                if !self.accept_symbol(',') {
+                    break;
+                }
+            }
+        } else {
+            return Ok((format!("{}()", name), None));
+        }
+        try!(self.expect_symbol(')'));
+        let cql_type = match &name[..] {
+            "count" | "writetime" | "token" => Some(CqlType::Native(CassValueType::BIGINT)),
+            "ttl" => Some(CqlType::Native(CassValueType::INT)),
+            _ => None,
+        };
+        Ok((format!("{}({})", name, arguments.join(", ")), cql_type))
+    }
+
+    fn parse_insert(&mut self) -> Result<(), QueryError> {
+        try!(self.expect_keyword("into"));
+        try!(self.parse_table());
+        if self.accept_keyword("json") {
+            try!(self.term("[json]", CqlType::Native(CassValueType::TEXT)));
+            let _ = self.accept_keyword("default") &&
+                    (self.accept_keyword("null") || self.accept_keyword("unset"));
+        } else {
+            try!(self.expect_symbol('('));
+            let mut columns = Vec::new();
+            loop {
+                columns.push(try!(self.column()));
+                if !self.accept_symbol(',') {
+                    break;
+                }
+            }
+            try!(self.expect_symbol(')'));
+            try!(self.expect_keyword("values"));
+            try!(self.expect_symbol('('));
+            for (i, (name, cql_type)) in columns.into_iter().enumerate() {
+                if i > 0 {
+                    try!(self.expect_symbol(','));
+                }
+                try!(self.term(&name, cql_type));
+            }
+            try!(self.expect_symbol(')'));
+        }
+        loop {
+            if self.accept_keyword("if") {
+                try!(self.expect_keyword("not"));
+                try!(self.expect_keyword("exists"));
+            } else if !try!(self.parse_using()) {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_update(&mut self) -> Result<(), QueryError> {
+        try!(self.parse_table());
+        try!(self.parse_using());
+        try!(self.expect_keyword("set"));
+        loop {
+            try!(self.parse_assignment());
+            if !self.accept_symbol(',') {
+                break;
+            }
+        }
+        try!(self.expect_keyword("where"));
+        try!(self.parse_conditions());
+        self.parse_if()
+    }
+
+    fn parse_assignment(&mut self) -> Result<(), QueryError> {
+        let (name, cql_type) = try!(self.column());
+        if self.accept_symbol('[') {
+            let (key, value) = match cql_type.unfrozen().clone() {
+                CqlType::Map(key, value) => (*key, *value),
+                CqlType::List(item) => (CqlType::Native(CassValueType::INT), *item),
+                _ => return Err(self.error(&format!("{} is not a map or list", name))),
+            };
+            try!(self.term(&format!("key({})", name), key));
+            try!(self.expect_symbol(']'));
+            try!(self.expect_symbol('='));
+            return self.term(&format!("value({})", name), value);
+        }
+        try!(self.expect_symbol('='));
+        let is_column = match self.peek() {
+            Some(&TokenKind::Identifier(ref other)) |
+            Some(&TokenKind::QuotedIdentifier(ref other)) => *other == name,
+            _ => false,
+        };
+        if is_column {
+            // c = c + ? and c = c - ?; removing from a map takes a set of keys.
+            self.pos += 1;
+            let removing = self.accept_symbol('-');
+            if !removing {
+                try!(self.expect_symbol('+'));
+            }
+            let operand = match cql_type.unfrozen().clone() {
+                CqlType::Map(key, _) if removing => CqlType::Set(key),
+                other => other,
+            };
+            return self.term(&name, operand);
+        }
+        try!(self.term(&name, cql_type));
+        // c = ? + c prepends to a list.
+        if self.accept_symbol('+') {
+            try!(self.column());
+        }
+        Ok(())
+    }
+
+    fn parse_delete(&mut self) -> Result<(), QueryError> {
+        let selection = self.pos;
+        try!(self.skip_to_from());
+        try!(self.parse_table());
+        let end = self.pos;
+        self.pos = selection;
+        while !self.accept_keyword("from") {
+            let (name, cql_type) = try!(self.column());
+            if self.accept_symbol('[') {
+                let key = match cql_type.unfrozen().clone() {
+                    CqlType::Map(key, _) => *key,
+                    CqlType::List(_) => CqlType::Native(CassValueType::INT),
+                    _ => return Err(self.error(&format!("{} is not a map or list", name))),
+                };
+                try!(self.term(&format!("key({})", name), key));
+                try!(self.expect_symbol(']'));
+            }
+            self.accept_symbol(',');
+        }
+        self.pos = end;
+        try!(self.parse_using());
+        try!(self.expect_keyword("where"));
+        try!(self.parse_conditions());
+        self.parse_if()
+    }
+
+    fn parse_if(&mut self) -> Result<(), QueryError> {
+        if self.accept_keyword("if") && !self.accept_keyword("exists") {
+            try!(self.parse_conditions());
+        }
+        Ok(())
+    }
+
+    // Whether there was a USING clause.
+    fn parse_using(&mut self) -> Result<bool, QueryError> {
+        if !self.accept_keyword("using") {
+            return Ok(false);
+        }
+        loop {
+            if self.accept_keyword("ttl") {
+                try!(self.term("[ttl]", CqlType::Native(CassValueType::INT)));
+            } else if self.accept_keyword("timestamp") {
+                try!(self.term("[timestamp]", CqlType::Native(CassValueType::BIGINT)));
+            } else {
+                return Err(self.error("expected TTL or TIMESTAMP after USING"));
+            }
+            if !self.accept_keyword("and") {
+                return Ok(true);
+            }
+        }
+    }
+
+    fn parse_conditions(&mut self) -> Result<(), QueryError> {
+        loop {
+            try!(self.parse_condition());
+            if !self.accept_keyword("and") {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_condition(&mut self) -> Result<(), QueryError> {
+        if self.accept_keyword("token") {
+            try!(self.expect_symbol('('));
+            loop {
+                try!(self.column());
+                if !self.accept_symbol(',') {
+                    break;
+                }
+            }
+            try!(self.expect_symbol(')'));
+            try!(self.operator());
+            return self.term("partition key token", CqlType::Native(CassValueType::BIGINT));
+        }
+        if self.accept_symbol('(') {
+            // (a, b) > (?, ?) and (a, b) IN ((?, ?), (?, ?)) on clustering
+            // columns.
+            let mut columns = Vec::new();
+            loop {
+                columns.push(try!(self.column()));
+                if !self.accept_symbol(',') {
+                    break;
+                }
+            }
+            try!(self.expect_symbol(')'));
+            if self.accept_keyword("in") {
+                if !self.accept_symbol('(') {
+                    let name = tuple_name(&columns);
+                    return self.term(&name, CqlType::List(Box::new(tuple_type(&columns))));
+                }
+                if self.accept_symbol(')') {
+                    return Ok(());
+                }
+                loop {
+                    try!(self.tuple_term(&columns));
+                    if !self.accept_symbol(',') {
+                        return self.expect_symbol(')');
+                    }
+                }
+            }
+            try!(self.operator());
+            return self.tuple_term(&columns);
+        }
+        let (name, cql_type) = try!(self.column());
+        let (name, cql_type) = if self.accept_symbol('[') {
+            // m[?] = ? in IF conditions.
+            let (key, value) = match cql_type.unfrozen().clone() {
+                CqlType::Map(key, value) => (*key, *value),
+                CqlType::List(item) => (CqlType::Native(CassValueType::INT), *item),
+                _ => return Err(self.error(&format!("{} is not a map or list", name))),
+            };
+            try!(self.term(&format!("key({})", name), key));
+            try!(self.expect_symbol(']'));
+            (format!("value({})", name), value)
+        } else {
+            (name, cql_type)
+        };
+        if self.accept_keyword("in") {
+            if !self.accept_symbol('(') {
+                return self.term(&name, CqlType::List(Box::new(cql_type)));
+            }
+            if self.accept_symbol(')') {
+                return Ok(());
+            }
+            loop {
+                try!(self.term(&name, cql_type.clone()));
+                if !self.accept_symbol(',') {
+                    return self.expect_symbol(')');
+                }
+            }
+        }
+        if self.accept_keyword("contains") {
+            let key = self.accept_keyword("key");
+            let item = match cql_type.unfrozen().clone() {
+                CqlType::Map(map_key, _) if key => *map_key,
+                CqlType::Map(_, value) => *value,
+                CqlType::List(item) | CqlType::Set(item) if !key => *item,
+                _ => return Err(self.error(&format!("{} can't be searched with CONTAINS", name))),
+            };
+            return self.term(&name, item);
+        }
+        if self.accept_keyword("like") {
+            return self.term(&name, cql_type);
+        }
+        if self.accept_keyword("is") {
+            try!(self.expect_keyword("not"));
+            return self.expect_keyword("null");
+        }
+        try!(self.operator());
+        self.term(&name, cql_type)
+    }
+
+    // = < > <= >= != and <>.
+    fn operator(&mut self) -> Result<(), QueryError> {
+        if self.accept_symbol('=') {
+            return Ok(());
+        }
+        if self.accept_symbol('<') || self.accept_symbol('>') {
+            let _ = self.accept_symbol('=') || self.accept_symbol('>');
+            return Ok(());
+        }
+        if self.accept_symbol('!') {
+            return self.expect_symbol('=');
+        }
+        Err(self.error("expected an operator"))
+    }
+
+    // A tuple of values for the columns, each of which may be a bind marker,
+    // or a bind marker for the whole tuple.
+    fn tuple_term(&mut self, columns: &[(String, CqlType)]) -> Result<(), QueryError> {
+        if !self.accept_symbol('(') {
+            return self.term(&tuple_name(columns), tuple_type(columns));
+        }
+        for (i, &(ref name, ref cql_type)) in columns.iter().enumerate() {
+            if i > 0 {
+                try!(self.expect_symbol(','));
+            }
+            try!(self.term(name, cql_type.clone()));
+        }
+        self.expect_symbol(')')
+    }
+
+    // A bind marker, which becomes a parameter, or a literal, which isn't
+    // checked.
+    fn term(&mut self, name: &str, cql_type: CqlType) -> Result<(), QueryError> {
+        if self.accept_symbol('?') {
+            self.checked.parameters.push((name.to_owned(), cql_type));
+            return Ok(());
+        }
+        if self.accept_symbol(':') {
+            let marker = try!(self.name());
+            self.checked.parameters.push((marker, cql_type));
+            return Ok(());
+        }
+        match self.next() {
+            Some(TokenKind::Str(_)) |
+            Some(TokenKind::Number(_)) => Ok(()),
+            Some(TokenKind::Symbol('(')) |
+            Some(TokenKind::Symbol('[')) |
+            Some(TokenKind::Symbol('{')) => self.skip_nested(),
+            Some(TokenKind::Identifier(_)) => {
+                // true, null, a uuid or a call like now().
+                if self.accept_symbol('(') {
+                    self.skip_nested()
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(self.error("expected a value or bind marker")),
+        }
+    }
+
+    // Past the bracket closing one that has just been read.
+    fn skip_nested(&mut self) -> Result<(), QueryError> {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next() {
+                Some(TokenKind::Symbol('(')) |
+                Some(TokenKind::Symbol('[')) |
+                Some(TokenKind::Symbol('{')) => depth += 1,
+                Some(TokenKind::Symbol(')')) |
+                Some(TokenKind::Symbol(']')) |
+                Some(TokenKind::Symbol('}')) => depth -= 1,
+                Some(TokenKind::Symbol('?')) => {
+                    return Err(self.error("bind markers inside literals aren't supported"))
+                }
+                Some(_) => {}
+                None => return Err(self.error("unclosed bracket")),
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_to_from(&mut self) -> Result<(), QueryError> {
+        let mut depth = 0;
+        while let Some(kind) = self.next() {
+            match kind {
+                TokenKind::Symbol('(') => depth += 1,
+                TokenKind::Symbol(')') => depth -= 1,
+                TokenKind::Identifier(ref word) if depth == 0 && word == "from" => return Ok(()),
+                _ => {}
+            }
+        }
+        Err(self.error("expected FROM"))
+    }
+
+    fn parse_table(&mut self) -> Result<(), QueryError> {
+        let keyspace = try!(self.name());
+        if !self.accept_symbol('.') {
+            return Err(self.error(&format!("table {} needs its keyspace", keyspace)));
+        }
+        let name = try!(self.name());
+        self.table = format!("{}.{}", keyspace, name);
+        let schema = self.schema;
+        self.columns = match schema.keyspace(&keyspace) {
+            Some(meta) => {
+                match (meta.tables.get(&name), meta.views.get(&name)) {
+                    (Some(table), _) => &table.columns,
+                    (None, Some(view)) => &view.columns,
+                    (None, None) => return Err(QueryError::UnknownTable(self.table.clone())),
+                }
+            }
+            None => return Err(QueryError::UnknownTable(self.table.clone())),
+        };
+        self.checked.keyspace = keyspace;
+        self.checked.table = name;
+        Ok(())
+    }
+
+    fn column(&mut self) -> Result<(String, CqlType), QueryError> {
+        let name = try!(self.name());
+        let cql_type = try!(self.column_type(&name));
+        Ok((name, cql_type))
+    }
+
+    fn column_type(&self, name: &str) -> Result<CqlType, QueryError> {
+        match self.columns.iter().find(|column| column.name == name) {
+            Some(column) => Ok(column.data_type.clone()),
+            None => {
+                Err(QueryError::UnknownColumn {
+                    table: self.table.clone(),
+                    column: name.to_owned(),
+                })
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, QueryError> {
+        match self.next() {
+            Some(TokenKind::Identifier(name)) |
+            Some(TokenKind::QuotedIdentifier(name)) => Ok(name),
+            _ => Err(self.error("expected a name")),
+        }
+    }
+
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|token| &token.kind)
+    }
+
+    fn next(&mut self) -> Option<TokenKind> {
+        let kind = self.tokens.get(self.pos).map(|token| token.kind.clone());
+        if kind.is_some() {
+            self.pos += 1;
+        }
+        kind
+    }
+
+    fn accept_keyword(&mut self, keyword: &str) -> bool {
+        let found = match self.peek() {
+            Some(&TokenKind::Identifier(ref word)) => word == keyword,
+            _ => false,
+        };
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), QueryError> {
+        if self.accept_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {}", keyword.to_uppercase())))
+        }
+    }
+
+    fn accept_symbol(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&TokenKind::Symbol(symbol)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), QueryError> {
+        if self.accept_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {:?}", symbol)))
+        }
+    }
+
+    fn error(&self, message: &str) -> QueryError {
+        let position = match self.tokens.get(self.pos) {
+            Some(token) => format!("line {}", token.line),
+            None => "the end".to_owned(),
+        };
+        QueryError::Syntax(format!("{} at {}", message, position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &'static str = "
+        CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy', \
+                                               'replication_factor': 1};
+        CREATE TABLE ks.t (k int, c1 int, c2 text, v text, m map<text, int>, s set<uuid>,
+                           l list<bigint>, PRIMARY KEY (k, c1, c2));";
+
+    const KEYS: &'static str = "WHERE k = ? AND c1 = ? AND c2 = ?";
+
+    fn parameters(snapshot: &SchemaSnapshot, query: &str) -> Vec<(String, String)> {
+        match snapshot.check_query(query) {
+            Ok(checked) => {
+                checked.parameters
+                       .iter()
+                       .map(|&(ref name, ref cql_type)| (name.clone(), cql_type.to_string()))
+                       .collect()
+            }
+            Err(err) => panic!("{}: {}", query, err),
+        }
+    }
+
+    #[test]
+    fn parameters_are_named_and_typed_by_where_they_are_bound() {
+        let key = [("k", "int"), ("c1", "int"), ("c2", "text")];
+        let cases: Vec<(String, Vec<(&str, &str)>)> = vec![
+            ("SELECT v FROM ks.t WHERE k IN ?".to_owned(), vec![("k", "list<int>")]),
+            ("SELECT v FROM ks.t WHERE k IN (?, 3, ?)".to_owned(),
+             vec![("k", "int"), ("k", "int")]),
+            ("SELECT v FROM ks.t WHERE k = ? AND m CONTAINS KEY ? ALLOW FILTERING".to_owned(),
+             vec![("k", "int"), ("m", "text")]),
+            ("SELECT v FROM ks.t WHERE m CONTAINS ? AND s CONTAINS ? ALLOW FILTERING".to_owned(),
+             vec![("m", "int"), ("s", "uuid")]),
+            (format!("UPDATE ks.t SET m[?] = ? {}", KEYS),
+             [("key(m)", "text"), ("value(m)", "int")].iter().chain(&key).cloned().collect()),
+            (format!("UPDATE ks.t SET l[?] = ? {}", KEYS),
+             [("key(l)", "int"), ("value(l)", "bigint")].iter().chain(&key).cloned().collect()),
+            (format!("UPDATE ks.t SET m = m - ? {}", KEYS),
+             [("m", "set<text>")].iter().chain(&key).cloned().collect()),
+            (format!("UPDATE ks.t SET m = m + ? {}", KEYS),
+             [("m", "map<text, int>")].iter().chain(&key).cloned().collect()),
+            (format!("UPDATE ks.t SET l = ? + l {}", KEYS),
+             [("l", "list<bigint>")].iter().chain(&key).cloned().collect()),
+            (format!("UPDATE ks.t SET v = ? {} IF m[?] = ?", KEYS),
+             [("v", "text")]
+                 .iter()
+                 .chain(&key)
+                 .chain(&[("key(m)", "text"), ("value(m)", "int")])
+                 .cloned()
+                 .collect()),
+            ("INSERT INTO ks.t (k, c1, c2, v) VALUES (?, ?, ?, ?) USING TTL ? AND TIMESTAMP ?"
+                 .to_owned(),
+             key.iter()
+                .chain(&[("v", "text"), ("[ttl]", "int"), ("[timestamp]", "bigint")])
+                .cloned()
+                .collect()),
+            (format!("UPDATE ks.t USING TIMESTAMP ? AND TTL ? SET v = ? {}", KEYS),
+             [("[timestamp]", "bigint"), ("[ttl]", "int"), ("v", "text")]
+                 .iter()
+                 .chain(&key)
+                 .cloned()
+                 .collect()),
+            (format!("DELETE m[?] FROM ks.t USING TIMESTAMP ? {} IF v = ?", KEYS),
+             [("key(m)", "text"), ("[timestamp]", "bigint")]
+                 .iter()
+                 .chain(&key)
+                 .chain(&[("v", "text")])
+                 .cloned()
+                 .collect()),
+            ("SELECT v FROM ks.t WHERE k = ? AND (c1, c2) > (?, ?)".to_owned(),
+             vec![("k", "int"), ("c1", "int"), ("c2", "text")]),
+            ("SELECT v FROM ks.t WHERE k = ? AND (c1, c2) <= ?".to_owned(),
+             vec![("k", "int"), ("(c1,c2)", "tuple<int, text>")]),
+            ("SELECT v FROM ks.t WHERE k = ? AND (c1, c2) IN ((?, ?), (1, ?))".to_owned(),
+             vec![("k", "int"), ("c1", "int"), ("c2", "text"), ("c2", "text")]),
+            ("SELECT v FROM ks.t WHERE k = ? AND (c1, c2) IN (?, (1, 'a'))".to_owned(),
+             vec![("k", "int"), ("(c1,c2)", "tuple<int, text>")]),
+            ("SELECT v FROM ks.t WHERE k = ? AND (c1, c2) IN ?".to_owned(),
+             vec![("k", "int"), ("(c1,c2)", "list<tuple<int, text>>")]),
+            ("SELECT v FROM ks.t WHERE k = ? AND (c1, c2) IN ()".to_owned(),
+             vec![("k", "int")]),
+            ("SELECT v FROM ks.t WHERE token(k) > ?".to_owned(),
+             vec![("partition key token", "bigint")]),
+            ("SELECT v FROM ks.t WHERE k = ? PER PARTITION LIMIT ? LIMIT ?".to_owned(),
+             vec![("k", "int"), ("[limit]", "int"), ("[limit]", "int")]),
+            ("SELECT v FROM ks.t WHERE k = :key AND c1 >= :lowest".to_owned(),
+             vec![("key", "int"), ("lowest", "int")]),
+        ];
+        let snapshot = SchemaSnapshot::from_cql(SCHEMA).unwrap();
+        for (query, expected) in cases {
+            let expected: Vec<(String, String)> = expected.into_iter()
+                                                          .map(|(name, cql_type)| {
+                                                              (name.to_owned(),
+                                                               cql_type.to_owned())
+                                                          })
+                                                          .collect();
+            assert_eq!(parameters(&snapshot, &query), expected, "{}", query);
+        }
+    }
+
+    #[test]
+    fn multi_column_relations_are_checked_against_their_columns() {
+        let snapshot = SchemaSnapshot::from_cql(SCHEMA).unwrap();
+        for query in &["SELECT v FROM ks.t WHERE k = ? AND (c1, nope) IN ((?, ?))",
+                       "SELECT v FROM ks.t WHERE k = ? AND (c1, c2) IN ((?, ?, ?))",
+                       "SELECT v FROM ks.t WHERE k = ? AND (c1, c2) IN ((?, ?)"] {
+            assert!(snapshot.check_query(query).is_err(), "{}", query);
+        }
+    }
+}
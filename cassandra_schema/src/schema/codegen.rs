@@ -0,0 +1,612 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use schema::cql_type::CqlType;
+use schema::cql_type::quote_identifier;
+use schema::metadata::ColumnKind;
+use schema::metadata::ColumnMeta;
+use schema::metadata::KeyspaceMeta;
+use schema::metadata::TableMeta;
+use schema::metadata::UserTypeMeta;
+use schema::value_type::CassValueType;
+
+// Rust source for a keyspace: a struct per user type and table, statement
+// constants to insert, select and delete a table's rows by primary key, and the
+// functions that bind and read them. The source expects `cassandra` to be an
+// extern crate of the crate it is included in.
+//
+// Columns and fields are read with FromCql, so every type it knows has a Rust
+// type here; those it doesn't (varint, decimal, custom types) are read as a
+// CqlValue. What can't be bound that way (those, tuples, and user types or
+// blobs inside collections) gets no bind function, and the statement constants
+// are still there to bind by hand.
+//
+// Names are made into Rust identifiers, and ones that come out the same (a
+// table and a user type both called address, or columns "a b" and a_b) get a
+// number, as do types named like one the source uses.
+
+// Types and values the generated source names, which its own types mustn't hide.
+static USED_NAMES: &'static [&'static str] = &["Self", "Option", "Some", "None", "Result", "Ok",
+                                               "Err", "Vec", "String", "Default", "Debug",
+                                               "Clone", "PartialEq", "BTreeMap", "IpAddr",
+                                               "CassValue", "CassError", "CassRow",
+                                               "CassStatement", "CassSchema", "CassUserType",
+                                               "CassList", "CassSet", "CassMap", "CassUuid",
+                                               "CqlDuration", "CqlValue", "FromCql"];
+
+// The Rust names of a keyspace's user types, and the struct and constant
+// prefix of each of its tables.
+struct Names {
+    user_types: BTreeMap<String, String>,
+    tables: BTreeMap<String, (String, String)>,
+}
+
+impl Names {
+    fn new(keyspace: &KeyspaceMeta) -> Names {
+        let mut taken: BTreeSet<String> = USED_NAMES.iter()
+                                                    .map(|name| (*name).to_owned())
+                                                    .collect();
+        let mut user_types = BTreeMap::new();
+        for user_type in keyspace.user_types.values() {
+            let name = unique(type_name(&user_type.name), &mut taken);
+            user_types.insert(user_type.name.clone(), name);
+        }
+        let mut constants = BTreeSet::new();
+        let mut tables = BTreeMap::new();
+        for table in keyspace.tables.values() {
+            let mut name = type_name(&table.name);
+            if taken.contains(&name) {
+                name.push_str("Row");
+            }
+            let name = unique(name, &mut taken);
+            let constant = unique(constant_name(&table.name), &mut constants);
+            tables.insert(table.name.clone(), (name, constant));
+        }
+        Names {
+            user_types: user_types,
+            tables: tables,
+        }
+    }
+
+    fn user_type(&self, name: &str) -> String {
+        self.user_types.get(name).cloned().unwrap_or_else(|| type_name(name))
+    }
+}
+
+// The name, or failing that the name with the first number from 2 that makes
+// it one not yet taken.
+fn unique(name: String, taken: &mut BTreeSet<String>) -> String {
+    let mut unique = name.clone();
+    let mut number = 2;
+    while taken.contains(&unique) {
+        unique = format!("{}{}", name, number);
+        number += 1;
+    }
+    taken.insert(unique.clone());
+    unique
+}
+
+// Field names for a struct's columns or fields, in their order, with those
+// given already taken.
+fn field_names<'a, I>(names: I, taken: &[&str]) -> Vec<String>
+    where I: Iterator<Item = &'a String>
+{
+    let mut taken: BTreeSet<String> = taken.iter().map(|name| (*name).to_owned()).collect();
+    names.map(|name| unique(field_name(name), &mut taken)).collect()
+}
+
+impl KeyspaceMeta {
+    pub fn to_rust(&self) -> String {
+        let mut rust = format!("// Generated by cassandra-codegen from keyspace {}; edits will be \
+                                lost.\n\n",
+                               self.name);
+        rust.push_str("#![allow(dead_code)]\n\n");
+        rust.push_str("use std::collections::BTreeMap;\nuse std::net::IpAddr;\n\n");
+        rust.push_str("use cassandra::*;\n");
+        let names = Names::new(self);
+        for user_type in self.user_types_in_dependency_order() {
+            rust.push('\n');
+            rust.push_str(&user_type.to_rust(&names));
+        }
+        for table in self.tables.values() {
+            rust.push('\n');
+            rust.push_str(&table.to_rust(&names));
+        }
+        rust
+    }
+}
+
+impl UserTypeMeta {
+    fn to_rust(&self, names: &Names) -> String {
+        let name = names.user_type(&self.name);
+        let fields = field_names(self.fields.iter().map(|&(ref field, _)| field), &[]);
+        let mut rust = String::new();
+        let _ = writeln!(rust, "#[derive(Debug,Clone,PartialEq,Default)]");
+        let _ = writeln!(rust, "pub struct {} {{", name);
+        for (&(_, ref cql_type), field) in self.fields.iter().zip(&fields) {
+            let _ = writeln!(rust,
+                             "    pub {}: Option<{}>,",
+                             field,
+                             rust_type(cql_type, names));
+        }
+        rust.push_str("}\n\n");
+
+        let _ = writeln!(rust, "impl<'a> FromCql<'a> for {} {{", name);
+        let _ = writeln!(rust,
+                         "    fn from_cql(value: &CassValue<'a>) -> Result<{}, CassError> {{",
+                         name);
+        let _ = writeln!(rust, "        let mut user_type = {}::default();", name);
+        rust.push_str("        let mut fields = try!(value.as_user_type_iterator());\n");
+        rust.push_str("        while let Some((name, field)) = fields.next() {\n");
+        rust.push_str("            match &name[..] {\n");
+        for (&(ref cql_name, ref cql_type), field) in self.fields.iter().zip(&fields) {
+            let _ = writeln!(rust,
+                             "                {:?} => user_type.{} = {},",
+                             cql_name,
+                             field,
+                             read_expression("field", cql_type, true));
+        }
+        rust.push_str("                _ => {}\n            }\n        }\n        Ok(user_type)\n    }\n}\n");
+
+        if self.fields.iter().all(|&(_, ref cql_type)| writable(cql_type, names)) {
+            let _ = writeln!(rust, "\nimpl {} {{", name);
+            rust.push_str("    pub fn to_user_type(&self, schema: &CassSchema) -> Result<CassUserType, \
+                           CassError> {\n");
+            let _ = writeln!(rust,
+                             "        let mut user_type = CassUserType::new(try!(schema.get_udt({:?}, \
+                              {:?})));",
+                             self.keyspace,
+                             self.name);
+            for (i, (&(_, ref cql_type), field)) in self.fields.iter().zip(&fields).enumerate() {
+                let _ = writeln!(rust, "        if let Some(ref value) = self.{} {{", field);
+                let _ = writeln!(rust,
+                                 "            try!({});",
+                                 set_expression("user_type", "set", i, "value", cql_type));
+                rust.push_str("        }\n");
+            }
+            rust.push_str("        Ok(user_type)\n    }\n}\n");
+        }
+        rust
+    }
+}
+
+impl TableMeta {
+    fn to_rust(&self, names: &Names) -> String {
+        let (ref name, ref constant) = names.tables[&self.name];
+        // bind_key's parameters are named after the key's fields
+        let fields = field_names(self.columns.iter().map(|column| &column.name),
+                                 &["statement", "schema"]);
+        let table = format!("{}.{}",
+                            quote_identifier(&self.keyspace),
+                            quote_identifier(&self.name));
+        let columns: Vec<String> = self.columns
+                                       .iter()
+                                       .map(|column| quote_identifier(&column.name))
+                                       .collect();
+        let key: Vec<(&ColumnMeta, &String)> = self.columns
+                                                   .iter()
+                                                   .zip(&fields)
+                                                   .filter(|&(column, _)| is_key(column))
+                                                   .collect();
+        let key_condition: Vec<String> = key.iter()
+                                            .map(|&(column, _)| {
+                                                format!("{} = ?", quote_identifier(&column.name))
+                                            })
+                                            .collect();
+        let markers: Vec<&str> = self.columns.iter().map(|_| "?").collect();
+
+        let mut rust = String::new();
+        let _ = writeln!(rust,
+                         "pub const {}_INSERT: &'static str = {:?};",
+                         constant,
+                         format!("INSERT INTO {} ({}) VALUES ({})",
+                                 table,
+                                 columns.join(", "),
+                                 markers.join(", ")));
+        let _ = writeln!(rust,
+                         "pub const {}_SELECT: &'static str = {:?};",
+                         constant,
+                         format!("SELECT {} FROM {} WHERE {}",
+                                 columns.join(", "),
+                                 table,
+                                 key_condition.join(" AND ")));
+        let _ = writeln!(rust,
+                         "pub const {}_DELETE: &'static str = {:?};",
+                         constant,
+                         format!("DELETE FROM {} WHERE {}", table, key_condition.join(" AND ")));
+
+        rust.push_str("\n#[derive(Debug,Clone,PartialEq)]\n");
+        let _ = writeln!(rust, "pub struct {} {{", name);
+        for (column, field) in self.columns.iter().zip(&fields) {
+            let cql_type = &column.data_type;
+            let rust_type = if is_key(column) {
+                rust_type(cql_type, names)
+            } else {
+                format!("Option<{}>", rust_type(cql_type, names))
+            };
+            let _ = writeln!(rust, "    pub {}: {},", field, rust_type);
+        }
+        rust.push_str("}\n\n");
+
+        let _ = writeln!(rust, "impl {} {{", name);
+        let _ = writeln!(rust,
+                         "    // Reads a row of {}_SELECT, or of any select of the same columns in \
+                          the\n    // same order.",
+                         constant);
+        let _ = writeln!(rust,
+                         "    pub fn from_row(row: &CassRow) -> Result<{}, CassError> {{",
+                         name);
+        let _ = writeln!(rust, "        Ok({} {{", name);
+        for (i, (column, field)) in self.columns.iter().zip(&fields).enumerate() {
+            let _ = writeln!(rust,
+                             "            {}: {},",
+                             field,
+                             read_expression(&format!("try!(row.get_column({}))", i),
+                                             &column.data_type,
+                                             !is_key(column)));
+        }
+        rust.push_str("        })\n    }\n");
+
+        let needs_schema = |columns: &[&ColumnMeta]| {
+            columns.iter().any(|column| has_user_type(&column.data_type))
+        };
+        let schema_parameter = |columns: &[&ColumnMeta]| {
+            if needs_schema(columns) {
+                ", schema: &CassSchema"
+            } else {
+                ""
+            }
+        };
+        let all: Vec<&ColumnMeta> = self.columns.iter().collect();
+        if all.iter().all(|column| writable(&column.data_type, names)) {
+            let _ = writeln!(rust,
+                             "\n    // Binds every column, in the order of {}_INSERT.",
+                             constant);
+            let _ = writeln!(rust,
+                             "    pub fn bind_insert(&self, statement: &mut CassStatement{}) -> \
+                              Result<(), CassError> {{",
+                             schema_parameter(&all));
+            for (i, (column, field)) in self.columns.iter().zip(&fields).enumerate() {
+                let cql_type = &column.data_type;
+                if is_key(column) {
+                    let _ = writeln!(rust, "        let value = &self.{};", field);
+                    let _ = writeln!(rust,
+                                     "        try!({});",
+                                     set_expression("statement", "bind", i, "value", cql_type));
+                } else {
+                    let _ = writeln!(rust, "        match self.{} {{", field);
+                    let _ = writeln!(rust,
+                                     "            Some(ref value) => try!({}),",
+                                     set_expression("statement", "bind", i, "value", cql_type));
+                    let _ = writeln!(rust,
+                                     "            None => try!(statement.bind_null({})),",
+                                     i);
+                    rust.push_str("        };\n");
+                }
+            }
+            rust.push_str("        Ok(())\n    }\n");
+        }
+        if key.iter().all(|&(column, _)| writable(&column.data_type, names)) {
+            let parameters: Vec<String> = key.iter()
+                                             .map(|&(column, field)| {
+                                                 format!("{}: &{}",
+                                                         field,
+                                                         rust_type(&column.data_type, names))
+                                             })
+                                             .collect();
+            let key_columns: Vec<&ColumnMeta> = key.iter().map(|&(column, _)| column).collect();
+            let _ = writeln!(rust,
+                             "\n    // Binds the primary key of {}_SELECT and {}_DELETE.",
+                             constant,
+                             constant);
+            let _ = writeln!(rust,
+                             "    pub fn bind_key(statement: &mut CassStatement, {}{}) -> \
+                              Result<(), CassError> {{",
+                             parameters.join(", "),
+                             schema_parameter(&key_columns));
+            for (i, &(column, field)) in key.iter().enumerate() {
+                let _ = writeln!(rust,
+                                 "        try!({});",
+                                 set_expression("statement", "bind", i, field, &column.data_type));
+            }
+            rust.push_str("        Ok(())\n    }\n");
+        }
+        rust.push_str("}\n");
+        rust
+    }
+}
+
+fn is_key(column: &ColumnMeta) -> bool {
+    column.kind == ColumnKind::PartitionKey || column.kind == ColumnKind::Clustering
+}
+
+// user_events -> UserEvents
+fn type_name(name: &str) -> String {
+    let mut rust_name = String::new();
+    let mut upper = true;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            upper = true;
+        } else if upper {
+            rust_name.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            rust_name.push(c);
+        }
+    }
+    if rust_name.is_empty() {
+        rust_name.push_str("Unnamed");
+    } else if rust_name.starts_with(|c: char| c.is_numeric()) {
+        rust_name.insert(0, '_');
+    }
+    rust_name
+}
+
+// Lower case, with anything that isn't an ASCII letter or digit replaced by an
+// underscore, and a trailing underscore on keywords and on _ itself.
+fn field_name(name: &str) -> String {
+    let mut rust_name = identifier(name).to_lowercase();
+    if is_keyword(&rust_name) || rust_name == "_" {
+        rust_name.push('_');
+    }
+    rust_name
+}
+
+// "1st place" -> _1ST_PLACE, to which _INSERT, _SELECT and _DELETE are added.
+fn constant_name(name: &str) -> String {
+    identifier(name).to_uppercase()
+}
+
+fn identifier(name: &str) -> String {
+    let mut rust_name: String = name.chars()
+                                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                                    .collect();
+    if rust_name.is_empty() || rust_name.starts_with(|c: char| c.is_numeric()) {
+        rust_name.insert(0, '_');
+    }
+    rust_name
+}
+
+fn is_keyword(name: &str) -> bool {
+    match name {
+        "abstract" | "as" | "async" | "await" | "become" | "box" | "break" | "const" |
+        "continue" | "crate" | "do" | "dyn" | "else" | "enum" | "extern" | "false" | "final" |
+        "fn" | "for" | "if" | "impl" | "in" | "let" | "loop" | "macro" | "match" | "mod" |
+        "move" | "mut" | "override" | "priv" | "pub" | "ref" | "return" | "self" | "static" |
+        "struct" | "super" | "trait" | "true" | "try" | "type" | "typeof" | "unsafe" |
+        "unsized" | "use" | "virtual" | "where" | "while" | "yield" => true,
+        _ => false,
+    }
+}
+
+fn rust_type(cql_type: &CqlType, names: &Names) -> String {
+    match *cql_type {
+        CqlType::Native(value_type) => {
+            native_rust_type(value_type).unwrap_or("CqlValue").to_owned()
+        }
+        CqlType::List(ref item) | CqlType::Set(ref item) => {
+            if readable_in_collection(item, names) {
+                format!("Vec<{}>", rust_type(item, names))
+            } else {
+                "CqlValue".to_owned()
+            }
+        }
+        CqlType::Map(ref key, ref value) => {
+            if readable_in_collection(key, names) && readable_in_collection(value, names) &&
+               orderable(key) {
+                format!("BTreeMap<{}, {}>", rust_type(key, names), rust_type(value, names))
+            } else {
+                "CqlValue".to_owned()
+            }
+        }
+        CqlType::Tuple(ref items) => {
+            if !items.is_empty() && items.len() <= 16 &&
+               items.iter().all(|item| readable_in_collection(item, names)) {
+                let items: Vec<String> = items.iter()
+                                              .map(|item| {
+                                                  format!("Option<{}>", rust_type(item, names))
+                                              })
+                                              .collect();
+                if items.len() == 1 {
+                    format!("({},)", items[0])
+                } else {
+                    format!("({})", items.join(", "))
+                }
+            } else {
+                "CqlValue".to_owned()
+            }
+        }
+        CqlType::Udt { ref name, .. } => names.user_type(name),
+        CqlType::Frozen(ref inner) => rust_type(inner, names),
+        CqlType::Custom(_) => "CqlValue".to_owned(),
+    }
+}
+
+fn native_rust_type(value_type: CassValueType) -> Option<&'static str> {
+    match value_type {
+        CassValueType::ASCII | CassValueType::TEXT | CassValueType::VARCHAR => Some("String"),
+        CassValueType::BIGINT | CassValueType::COUNTER | CassValueType::TIMESTAMP |
+        CassValueType::TIME => Some("i64"),
+        CassValueType::INT => Some("i32"),
+        CassValueType::SMALL_INT => Some("i16"),
+        CassValueType::TINY_INT => Some("i8"),
+        CassValueType::DATE => Some("u32"),
+        CassValueType::FLOAT => Some("f32"),
+        CassValueType::DOUBLE => Some("f64"),
+        CassValueType::BOOLEAN => Some("bool"),
+        CassValueType::UUID | CassValueType::TIMEUUID => Some("CassUuid"),
+        CassValueType::INET => Some("IpAddr"),
+        CassValueType::DURATION => Some("CqlDuration"),
+        CassValueType::BLOB => Some("Vec<u8>"),
+        _ => None,
+    }
+}
+
+// Blobs are read as a byte slice and copied, which FromCql can't do for the
+// items of a collection.
+fn readable_in_collection(cql_type: &CqlType, names: &Names) -> bool {
+    match *cql_type.unfrozen() {
+        CqlType::Native(CassValueType::BLOB) => false,
+        CqlType::Native(value_type) => native_rust_type(value_type).is_some(),
+        CqlType::List(_) | CqlType::Set(_) | CqlType::Map(..) | CqlType::Tuple(_) => {
+            rust_type(cql_type, names) != "CqlValue"
+        }
+        CqlType::Udt { .. } => true,
+        CqlType::Custom(_) | CqlType::Frozen(_) => false,
+    }
+}
+
+fn orderable(cql_type: &CqlType) -> bool {
+    match *cql_type.unfrozen() {
+        CqlType::Native(CassValueType::FLOAT) |
+        CqlType::Native(CassValueType::DOUBLE) |
+        CqlType::Native(CassValueType::DURATION) |
+        CqlType::Udt { .. } |
+        CqlType::Tuple(_) => false,
+        CqlType::List(ref item) | CqlType::Set(ref item) => orderable(item),
+        CqlType::Map(ref key, ref value) => orderable(key) && orderable(value),
+        _ => true,
+    }
+}
+
+fn read_expression(value: &str, cql_type: &CqlType, optional: bool) -> String {
+    match *cql_type.unfrozen() {
+        CqlType::Native(CassValueType::BLOB) => {
+            if optional {
+                format!("try!({}.get::<Option<&[u8]>>()).map(|bytes| bytes.to_vec())", value)
+            } else {
+                format!("try!({}.get::<&[u8]>()).to_vec()", value)
+            }
+        }
+        _ => format!("try!({}.get())", value),
+    }
+}
+
+// Whether set_expression can bind or set a value of the type.
+fn writable(cql_type: &CqlType, names: &Names) -> bool {
+    match *cql_type.unfrozen() {
+        CqlType::Native(value_type) => native_rust_type(value_type).is_some(),
+        CqlType::List(ref item) | CqlType::Set(ref item) => appendable(item, names),
+        CqlType::Map(ref key, ref value) => {
+            rust_type(cql_type, names) != "CqlValue" && appendable(key, names) &&
+            appendable(value, names)
+        }
+        CqlType::Udt { .. } => true,
+        _ => false,
+    }
+}
+
+// Whether the Rust type of a collection item is ToCql.
+fn appendable(cql_type: &CqlType, names: &Names) -> bool {
+    match *cql_type.unfrozen() {
+        CqlType::Native(CassValueType::BLOB) => false,
+        CqlType::Native(value_type) => native_rust_type(value_type).is_some(),
+        CqlType::List(_) | CqlType::Set(_) | CqlType::Map(..) => writable(cql_type, names),
+        _ => false,
+    }
+}
+
+fn has_user_type(cql_type: &CqlType) -> bool {
+    match *cql_type.unfrozen() {
+        CqlType::Udt { .. } => true,
+        _ => false,
+    }
+}
+
+// A call binding (on a statement) or setting (on a user type) the value at
+// the index, where value is a reference to the Rust type.
+fn set_expression(target: &str, prefix: &str, index: usize, value: &str, cql_type: &CqlType) -> String {
+    let (method, argument) = match *cql_type.unfrozen() {
+        CqlType::Native(value_type) => {
+            match value_type {
+                CassValueType::ASCII | CassValueType::TEXT | CassValueType::VARCHAR => {
+                    if prefix == "bind" {
+                        ("string", format!("&{}[..]", value))
+                    } else {
+                        ("stringl", format!("&{}[..]", value))
+                    }
+                }
+                CassValueType::BIGINT | CassValueType::COUNTER | CassValueType::TIMESTAMP |
+                CassValueType::TIME => ("int64", format!("*{}", value)),
+                CassValueType::INT => ("int32", format!("*{}", value)),
+                CassValueType::SMALL_INT => ("int16", format!("*{}", value)),
+                CassValueType::TINY_INT => ("int8", format!("*{}", value)),
+                CassValueType::DATE => ("uint32", format!("*{}", value)),
+                CassValueType::FLOAT => ("float", format!("*{}", value)),
+                CassValueType::DOUBLE => ("double", format!("*{}", value)),
+                CassValueType::BOOLEAN => ("bool", format!("*{}", value)),
+                CassValueType::UUID | CassValueType::TIMEUUID => ("uuid", format!("*{}", value)),
+                CassValueType::INET => ("inet", format!("*{}", value)),
+                CassValueType::DURATION => ("duration", format!("*{}", value)),
+                _ => ("bytes", format!("&{}[..]", value)),
+            }
+        }
+        CqlType::List(_) => collection_method(prefix, "list", "CassList", value),
+        CqlType::Set(_) => collection_method(prefix, "set", "CassSet", value),
+        CqlType::Map(..) => collection_method(prefix, "map", "CassMap", value),
+        _ => ("user_type", format!("try!({}.to_user_type(schema))", value)),
+    };
+    format!("{}.{}_{}({}, {})", target, prefix, method, index, argument)
+}
+
+// Statements bind each kind of collection with a method of its own, user
+// types set them all with set_collection.
+fn collection_method(prefix: &str,
+                     kind: &'static str,
+                     collection: &str,
+                     value: &str)
+                     -> (&'static str, String) {
+    let method = if prefix == "bind" { kind } else { "collection" };
+    (method, format!("try!({}::from_iter({}))", collection, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use schema::metadata::SchemaSnapshot;
+
+    const SCHEMA: &'static str = "
+        CREATE KEYSPACE shop WITH replication = {'class': 'SimpleStrategy',
+            'replication_factor': 1};
+        CREATE TYPE shop.address (street text, \"Street\" text, \"_\" int);
+        CREATE TYPE shop.\"String\" (value text);
+        CREATE TABLE shop.address (id int PRIMARY KEY, home frozen<address>);
+        CREATE TABLE shop.\"1st place\" (\"type\" int PRIMARY KEY, \"a b\" text, a_b text);
+        CREATE TABLE shop.\"1ST PLACE\" (statement int, schema int,
+            PRIMARY KEY (statement, schema));
+        CREATE TABLE shop.\"String\" (id int PRIMARY KEY, name frozen<\"String\">);
+    ";
+
+    fn rust() -> String {
+        SchemaSnapshot::from_cql(SCHEMA).unwrap().keyspace("shop").unwrap().to_rust()
+    }
+
+    #[test]
+    fn tables_and_user_types_get_their_own_names() {
+        let rust = rust();
+        assert!(rust.contains("pub struct Address {"));
+        assert!(rust.contains("pub struct AddressRow {"));
+        assert!(rust.contains("pub home: Option<Address>,"));
+        // String is taken by the source itself
+        assert!(rust.contains("pub struct String2 {"));
+        assert!(rust.contains("pub struct StringRow {"));
+        assert!(rust.contains("pub name: Option<String2>,"));
+        assert!(rust.contains("pub value: Option<String>,"));
+    }
+
+    #[test]
+    fn names_are_identifiers() {
+        let rust = rust();
+        assert!(rust.contains("pub const _1ST_PLACE_INSERT: &'static str"));
+        assert!(rust.contains("pub const _1ST_PLACE2_INSERT: &'static str"));
+        assert!(rust.contains("pub struct _1stPlace {"));
+        assert!(rust.contains("pub struct _1STPLACE {"));
+        assert!(rust.contains("pub type_: i32,"));
+        assert!(rust.contains("pub a_b: Option<String>,\n    pub a_b2: Option<String>,"));
+        assert!(rust.contains("pub street: Option<String>,\n    pub street2: Option<String>,"));
+        assert!(rust.contains("pub __: Option<i32>,"));
+        assert!(rust.contains("\"_\" => user_type.__ = "));
+        assert!(rust.contains("pub fn bind_key(statement: &mut CassStatement, statement2: &i32, \
+                               schema2: &i32)"));
+    }
+}
@@ -0,0 +1,616 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
+use std::str::FromStr;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use schema::value_type::CassValueType;
+use schema::error::ValueError;
+
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+pub enum CqlType {
+    Native(CassValueType),
+    List(Box<CqlType>),
+    Set(Box<CqlType>),
+    Map(Box<CqlType>, Box<CqlType>),
+    Tuple(Vec<CqlType>),
+    Udt {
+        keyspace: Option<String>,
+        name: String,
+    },
+    Custom(String),
+    Frozen(Box<CqlType>),
+}
+
+impl CqlType {
+    pub fn parse(type_string: &str) -> Result<CqlType, ValueError> {
+        let mut parser = TypeParser { tokens: try!(tokenize(type_string)), pos: 0 };
+        let parsed = try!(parser.parse_type());
+        match parser.next() {
+            None => Ok(parsed),
+            Some(_) => Err(bad_type_string()),
+        }
+    }
+
+    // Cassandra 2.x keeps column types in the schema tables as the class names
+    // of their marshal types, e.g.
+    // org.apache.cassandra.db.marshal.ListType(org.apache.cassandra.db.marshal.Int32Type).
+    // Reversed types come back as the type they reverse, and anything without a
+    // CQL equivalent as a custom type.
+    pub fn from_class_name(class_name: &str) -> Result<CqlType, ValueError> {
+        let class_name = class_name.trim();
+        let (name, parameters) = match class_name.find('(') {
+            Some(open) => {
+                if !class_name.ends_with(')') {
+                    return Err(bad_type_string());
+                }
+                let parameters = try!(split_class_parameters(&class_name[open + 1..class_name.len() - 1]));
+                (&class_name[..open], parameters)
+            }
+            None => (class_name, Vec::new()),
+        };
+        let short_name = name.trim_start_matches(MARSHAL_PACKAGE);
+        let parameter_types = || -> Result<Vec<CqlType>, ValueError> {
+            parameters.iter().map(|parameter| CqlType::from_class_name(parameter)).collect()
+        };
+        match (short_name, parameters.len()) {
+            ("ListType", 1) => Ok(CqlType::List(Box::new(try!(parameter_types()).remove(0)))),
+            ("SetType", 1) => Ok(CqlType::Set(Box::new(try!(parameter_types()).remove(0)))),
+            ("MapType", 2) => {
+                let mut pair = try!(parameter_types());
+                let value = pair.remove(1);
+                Ok(CqlType::Map(Box::new(pair.remove(0)), Box::new(value)))
+            }
+            ("TupleType", _) if !parameters.is_empty() => Ok(CqlType::Tuple(try!(parameter_types()))),
+            ("FrozenType", 1) => Ok(CqlType::Frozen(Box::new(try!(parameter_types()).remove(0)))),
+            ("ReversedType", 1) => CqlType::from_class_name(&parameters[0]),
+            // UserType(keyspace,hex name,hex field name:field type,...)
+            ("UserType", _) if parameters.len() >= 2 => {
+                Ok(CqlType::Udt {
+                    keyspace: Some(parameters[0].clone()),
+                    name: try!(decode_hex_name(&parameters[1])),
+                })
+            }
+            (_, 0) => {
+                match native_type_from_class_name(short_name) {
+                    Some(value_type) => Ok(CqlType::Native(value_type)),
+                    None => Ok(CqlType::Custom(class_name.to_owned())),
+                }
+            }
+            _ => Ok(CqlType::Custom(class_name.to_owned())),
+        }
+    }
+
+    pub fn is_reversed_class_name(class_name: &str) -> bool {
+        let class_name = class_name.trim();
+        class_name.trim_start_matches(MARSHAL_PACKAGE).starts_with("ReversedType(")
+    }
+
+    pub fn value_type(&self) -> CassValueType {
+        match *self {
+            CqlType::Native(value_type) => value_type,
+            CqlType::List(_) => CassValueType::LIST,
+            CqlType::Set(_) => CassValueType::SET,
+            CqlType::Map(..) => CassValueType::MAP,
+            CqlType::Tuple(_) => CassValueType::TUPLE,
+            CqlType::Udt { .. } => CassValueType::UDT,
+            CqlType::Custom(_) => CassValueType::CUSTOM,
+            CqlType::Frozen(ref inner) => inner.value_type(),
+        }
+    }
+
+    pub fn unfrozen(&self) -> &CqlType {
+        match *self {
+            CqlType::Frozen(ref inner) => inner.unfrozen(),
+            ref other => other,
+        }
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        match *self {
+            CqlType::Frozen(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for CqlType {
+    type Err = ValueError;
+    fn from_str(s: &str) -> Result<CqlType, ValueError> {
+        CqlType::parse(s)
+    }
+}
+
+impl Display for CqlType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            CqlType::Native(value_type) => {
+                match native_type_name(value_type) {
+                    Some(name) => write!(f, "{}", name),
+                    None => write!(f, "{:?}", value_type),
+                }
+            }
+            CqlType::List(ref item) => write!(f, "list<{}>", item),
+            CqlType::Set(ref item) => write!(f, "set<{}>", item),
+            CqlType::Map(ref key, ref value) => write!(f, "map<{}, {}>", key, value),
+            CqlType::Tuple(ref items) => {
+                try!(write!(f, "tuple<"));
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        try!(write!(f, ", "));
+                    }
+                    try!(write!(f, "{}", item));
+                }
+                write!(f, ">")
+            }
+            CqlType::Udt { ref keyspace, ref name } => {
+                if let Some(ref keyspace) = *keyspace {
+                    try!(write!(f, "{}.", quote_identifier(keyspace)));
+                }
+                write!(f, "{}", quote_identifier(name))
+            }
+            CqlType::Custom(ref class_name) => write!(f, "'{}'", class_name.replace("'", "''")),
+            CqlType::Frozen(ref inner) => write!(f, "frozen<{}>", inner),
+        }
+    }
+}
+
+pub fn native_type_name(value_type: CassValueType) -> Option<&'static str> {
+    match value_type {
+        CassValueType::ASCII => Some("ascii"),
+        CassValueType::BIGINT => Some("bigint"),
+        CassValueType::BLOB => Some("blob"),
+        CassValueType::BOOLEAN => Some("boolean"),
+        CassValueType::COUNTER => Some("counter"),
+        CassValueType::DECIMAL => Some("decimal"),
+        CassValueType::DOUBLE => Some("double"),
+        CassValueType::FLOAT => Some("float"),
+        CassValueType::INT => Some("int"),
+        CassValueType::TEXT => Some("text"),
+        CassValueType::TIMESTAMP => Some("timestamp"),
+        CassValueType::UUID => Some("uuid"),
+        CassValueType::VARCHAR => Some("varchar"),
+        CassValueType::VARINT => Some("varint"),
+        CassValueType::TIMEUUID => Some("timeuuid"),
+        CassValueType::INET => Some("inet"),
+        CassValueType::DATE => Some("date"),
+        CassValueType::TIME => Some("time"),
+        CassValueType::SMALL_INT => Some("smallint"),
+        CassValueType::TINY_INT => Some("tinyint"),
+        CassValueType::DURATION => Some("duration"),
+        _ => None,
+    }
+}
+
+pub fn native_type_from_name(name: &str) -> Option<CassValueType> {
+    match name {
+        "ascii" => Some(CassValueType::ASCII),
+        "bigint" => Some(CassValueType::BIGINT),
+        "blob" => Some(CassValueType::BLOB),
+        "boolean" => Some(CassValueType::BOOLEAN),
+        "counter" => Some(CassValueType::COUNTER),
+        "decimal" => Some(CassValueType::DECIMAL),
+        "double" => Some(CassValueType::DOUBLE),
+        "float" => Some(CassValueType::FLOAT),
+        "int" => Some(CassValueType::INT),
+        "text" => Some(CassValueType::TEXT),
+        "timestamp" => Some(CassValueType::TIMESTAMP),
+        "uuid" => Some(CassValueType::UUID),
+        "varchar" => Some(CassValueType::VARCHAR),
+        "varint" => Some(CassValueType::VARINT),
+        "timeuuid" => Some(CassValueType::TIMEUUID),
+        "inet" => Some(CassValueType::INET),
+        "date" => Some(CassValueType::DATE),
+        "time" => Some(CassValueType::TIME),
+        "smallint" => Some(CassValueType::SMALL_INT),
+        "tinyint" => Some(CassValueType::TINY_INT),
+        "duration" => Some(CassValueType::DURATION),
+        _ => None,
+    }
+}
+
+const MARSHAL_PACKAGE: &'static str = "org.apache.cassandra.db.marshal.";
+
+fn native_type_from_class_name(short_name: &str) -> Option<CassValueType> {
+    match short_name {
+        "AsciiType" => Some(CassValueType::ASCII),
+        "LongType" => Some(CassValueType::BIGINT),
+        "BytesType" => Some(CassValueType::BLOB),
+        "BooleanType" => Some(CassValueType::BOOLEAN),
+        "CounterColumnType" => Some(CassValueType::COUNTER),
+        "DecimalType" => Some(CassValueType::DECIMAL),
+        "DoubleType" => Some(CassValueType::DOUBLE),
+        "FloatType" => Some(CassValueType::FLOAT),
+        "Int32Type" => Some(CassValueType::INT),
+        "UTF8Type" => Some(CassValueType::TEXT),
+        "TimestampType" | "DateType" => Some(CassValueType::TIMESTAMP),
+        "UUIDType" => Some(CassValueType::UUID),
+        "IntegerType" => Some(CassValueType::VARINT),
+        "TimeUUIDType" => Some(CassValueType::TIMEUUID),
+        "InetAddressType" => Some(CassValueType::INET),
+        "SimpleDateType" => Some(CassValueType::DATE),
+        "TimeType" => Some(CassValueType::TIME),
+        "ShortType" => Some(CassValueType::SMALL_INT),
+        "ByteType" => Some(CassValueType::TINY_INT),
+        "DurationType" => Some(CassValueType::DURATION),
+        _ => None,
+    }
+}
+
+// Splits the parameters of a marshal type on the commas that are not nested in
+// another type's parentheses.
+fn split_class_parameters(parameters: &str) -> Result<Vec<String>, ValueError> {
+    let mut split = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in parameters.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Err(bad_type_string()),
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                split.push(parameters[start..i].trim().to_owned());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err(bad_type_string());
+    }
+    split.push(parameters[start..].trim().to_owned());
+    Ok(split)
+}
+
+fn decode_hex_name(hex: &str) -> Result<String, ValueError> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return Err(bad_type_string());
+    }
+    let mut bytes = Vec::with_capacity(hex.len() / 2);
+    for i in 0..hex.len() / 2 {
+        match u8::from_str_radix(&hex[2 * i..2 * i + 2], 16) {
+            Ok(byte) => bytes.push(byte),
+            Err(_) => return Err(bad_type_string()),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| bad_type_string())
+}
+
+// Identifiers that are not plain lower case CQL identifiers, or that would be
+// read back as a keyword, have to be double quoted.
+pub fn quote_identifier(identifier: &str) -> String {
+    let starts_with_letter = match identifier.chars().next() {
+        Some(first) => first.is_ascii_lowercase(),
+        None => false,
+    };
+    let plain = identifier.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if starts_with_letter && plain && !is_keyword(identifier) {
+        identifier.to_owned()
+    } else {
+        format!("\"{}\"", identifier.replace("\"", "\"\""))
+    }
+}
+
+// Cassandra's reserved keywords, with key and the type names, which are
+// quoted to be safe anywhere an identifier is written.
+static RESERVED: &'static [&'static str] =
+    &["add", "allow", "alter", "and", "apply", "asc", "authorize", "batch", "begin", "by",
+      "columnfamily", "create", "default", "delete", "desc", "describe", "drop", "entries",
+      "execute", "from", "full", "grant", "if", "in", "index", "infinity", "insert", "into", "is",
+      "key", "keyspace", "limit", "materialized", "mbean", "mbeans", "modify", "nan",
+      "norecursive", "not", "null", "of", "on", "or", "order", "primary", "rename", "replace",
+      "revoke", "schema", "select", "set", "table", "to", "token", "truncate", "unlogged",
+      "unset", "update", "use", "using", "view", "where", "with"];
+
+fn is_keyword(identifier: &str) -> bool {
+    match identifier {
+        "frozen" | "list" | "map" | "tuple" => true,
+        other => RESERVED.contains(&other) || native_type_from_name(other).is_some(),
+    }
+}
+
+fn bad_type_string() -> ValueError {
+    ValueError::BadParams
+}
+
+#[derive(Debug,Clone,PartialEq)]
+enum Token {
+    Identifier(String),
+    QuotedIdentifier(String),
+    StringLiteral(String),
+    Open,
+    Close,
+    Comma,
+    Dot,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ValueError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '<' => {
+                chars.next();
+                tokens.push(Token::Open)
+            }
+            '>' => {
+                chars.next();
+                tokens.push(Token::Close)
+            }
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma)
+            }
+            '.' => {
+                chars.next();
+                tokens.push(Token::Dot)
+            }
+            '"' => tokens.push(Token::QuotedIdentifier(try!(read_quoted(&mut chars, '"')))),
+            '\'' => tokens.push(Token::StringLiteral(try!(read_quoted(&mut chars, '\'')))),
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut identifier = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' {
+                        identifier.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Identifier(identifier.to_lowercase()))
+            }
+            _ => return Err(bad_type_string()),
+        }
+    }
+    Ok(tokens)
+}
+
+// Reads a quoted token, where a doubled quote character stands for itself.
+fn read_quoted(chars: &mut Peekable<Chars>, quote: char) -> Result<String, ValueError> {
+    chars.next();
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some(c) if c == quote => {
+                if chars.peek() == Some(&quote) {
+                    chars.next();
+                    value.push(quote);
+                } else {
+                    return Ok(value);
+                }
+            }
+            Some(c) => value.push(c),
+            None => return Err(bad_type_string()),
+        }
+    }
+}
+
+struct TypeParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl TypeParser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ValueError> {
+        match self.next() {
+            Some(ref token) if *token == expected => Ok(()),
+            _ => Err(bad_type_string()),
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<CqlType, ValueError> {
+        match self.next() {
+            Some(Token::StringLiteral(class_name)) => Ok(CqlType::Custom(class_name)),
+            Some(Token::QuotedIdentifier(name)) => self.parse_udt(name),
+            Some(Token::Identifier(name)) => {
+                let parameterized = self.peek() == Some(&Token::Open);
+                match (&name[..], parameterized) {
+                    ("frozen", true) => {
+                        let inner = try!(self.parse_parameters(1));
+                        Ok(CqlType::Frozen(Box::new(inner[0].clone())))
+                    }
+                    ("list", true) => {
+                        let item = try!(self.parse_parameters(1));
+                        Ok(CqlType::List(Box::new(item[0].clone())))
+                    }
+                    ("set", true) => {
+                        let item = try!(self.parse_parameters(1));
+                        Ok(CqlType::Set(Box::new(item[0].clone())))
+                    }
+                    ("map", true) => {
+                        let pair = try!(self.parse_parameters(2));
+                        Ok(CqlType::Map(Box::new(pair[0].clone()), Box::new(pair[1].clone())))
+                    }
+                    ("tuple", true) => {
+                        let items = try!(self.parse_parameters(0));
+                        Ok(CqlType::Tuple(items))
+                    }
+                    (_, true) => Err(bad_type_string()),
+                    (native, false) => {
+                        match native_type_from_name(native) {
+                            Some(value_type) if self.peek() != Some(&Token::Dot) => {
+                                Ok(CqlType::Native(value_type))
+                            }
+                            _ => self.parse_udt(name.clone()),
+                        }
+                    }
+                }
+            }
+            _ => Err(bad_type_string()),
+        }
+    }
+
+    // Parses `<type, type, ...>`. An expected count of zero accepts any
+    // non-empty list.
+    fn parse_parameters(&mut self, expected: usize) -> Result<Vec<CqlType>, ValueError> {
+        try!(self.expect(Token::Open));
+        let mut parameters = vec![try!(self.parse_type())];
+        loop {
+            match self.next() {
+                Some(Token::Comma) => parameters.push(try!(self.parse_type())),
+                Some(Token::Close) => break,
+                _ => return Err(bad_type_string()),
+            }
+        }
+        if expected != 0 && parameters.len() != expected {
+            return Err(bad_type_string());
+        }
+        Ok(parameters)
+    }
+
+    fn parse_udt(&mut self, first: String) -> Result<CqlType, ValueError> {
+        if self.peek() != Some(&Token::Dot) {
+            return Ok(CqlType::Udt { keyspace: None, name: first });
+        }
+        self.next();
+        match self.next() {
+            Some(Token::Identifier(name)) | Some(Token::QuotedIdentifier(name)) => {
+                Ok(CqlType::Udt { keyspace: Some(first), name: name })
+            }
+            _ => Err(bad_type_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schema::value_type::CassValueType;
+
+    fn round_trip(type_string: &str) -> CqlType {
+        let parsed = CqlType::parse(type_string).unwrap();
+        let displayed = parsed.to_string();
+        assert_eq!(CqlType::parse(&displayed).unwrap(), parsed, "{} -> {}", type_string, displayed);
+        parsed
+    }
+
+    #[test]
+    fn native_types_round_trip() {
+        for name in &["ascii", "bigint", "blob", "boolean", "counter", "decimal", "double", "float",
+                      "int", "text", "timestamp", "uuid", "varchar", "varint", "timeuuid", "inet",
+                      "date", "time", "smallint", "tinyint", "duration"] {
+            let parsed = round_trip(name);
+            assert_eq!(parsed.to_string(), *name);
+        }
+        assert_eq!(round_trip("BigInt"), CqlType::Native(CassValueType::BIGINT));
+    }
+
+    #[test]
+    fn collections_round_trip() {
+        assert_eq!(round_trip("list<int>").to_string(), "list<int>");
+        assert_eq!(round_trip("set < text >").to_string(), "set<text>");
+        assert_eq!(round_trip("map<text,list<int>>").to_string(), "map<text, list<int>>");
+        assert_eq!(round_trip("tuple<int, text, blob>").to_string(), "tuple<int, text, blob>");
+    }
+
+    #[test]
+    fn frozen_and_nested_types_round_trip() {
+        let parsed = round_trip("map<frozen<tuple<int, frozen<list<text>>>>, frozen<set<uuid>>>");
+        assert_eq!(parsed.to_string(),
+                   "map<frozen<tuple<int, frozen<list<text>>>>, frozen<set<uuid>>>");
+        assert!(round_trip("frozen<address>").is_frozen());
+        assert_eq!(round_trip("frozen<list<int>>").unfrozen(),
+                   &CqlType::List(Box::new(CqlType::Native(CassValueType::INT))));
+        assert_eq!(round_trip("frozen<map<int, int>>").value_type(), CassValueType::MAP);
+    }
+
+    #[test]
+    fn user_types_round_trip() {
+        assert_eq!(round_trip("address"),
+                   CqlType::Udt { keyspace: None, name: "address".to_owned() });
+        assert_eq!(round_trip("shop.address"),
+                   CqlType::Udt {
+                       keyspace: Some("shop".to_owned()),
+                       name: "address".to_owned(),
+                   });
+        let quoted = round_trip("\"Shop\".\"Home \"\"Address\"\"\"");
+        assert_eq!(quoted,
+                   CqlType::Udt {
+                       keyspace: Some("Shop".to_owned()),
+                       name: "Home \"Address\"".to_owned(),
+                   });
+        assert_eq!(quoted.to_string(), "\"Shop\".\"Home \"\"Address\"\"\"");
+        // a user type named as a keyword is written quoted to be read back
+        let keyword = CqlType::Udt { keyspace: None, name: "select".to_owned() };
+        assert_eq!(keyword.to_string(), "\"select\"");
+        assert_eq!(CqlType::parse(&keyword.to_string()).unwrap(), keyword);
+        let native = CqlType::Udt { keyspace: None, name: "int".to_owned() };
+        assert_eq!(CqlType::parse(&native.to_string()).unwrap(), native);
+    }
+
+    #[test]
+    fn custom_types_round_trip() {
+        let parsed = round_trip("'org.example.It''s'");
+        assert_eq!(parsed, CqlType::Custom("org.example.It's".to_owned()));
+        assert_eq!(parsed.to_string(), "'org.example.It''s'");
+    }
+
+    #[test]
+    fn bad_type_strings() {
+        for bad in &["", "list<int", "list<>", "map<int>", "set<int, int>", "int>", "list<int>>",
+                     "\"unterminated", "int int", "a.", "foo<int>"] {
+            assert!(CqlType::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn class_names() {
+        let class_name = "org.apache.cassandra.db.marshal.MapType(\
+                          org.apache.cassandra.db.marshal.UTF8Type,\
+                          org.apache.cassandra.db.marshal.FrozenType(\
+                          org.apache.cassandra.db.marshal.ListType(\
+                          org.apache.cassandra.db.marshal.Int32Type)))";
+        assert_eq!(CqlType::from_class_name(class_name).unwrap().to_string(),
+                   "map<text, frozen<list<int>>>");
+        let reversed = "org.apache.cassandra.db.marshal.ReversedType(\
+                        org.apache.cassandra.db.marshal.TimestampType)";
+        assert!(CqlType::is_reversed_class_name(reversed));
+        assert_eq!(CqlType::from_class_name(reversed).unwrap().to_string(), "timestamp");
+        // UserType(keyspace, hex name, hex field name:field type...)
+        let user_type = "org.apache.cassandra.db.marshal.UserType(shop,61646472657373,\
+                         737472656574:org.apache.cassandra.db.marshal.UTF8Type)";
+        assert_eq!(CqlType::from_class_name(user_type).unwrap(),
+                   CqlType::Udt {
+                       keyspace: Some("shop".to_owned()),
+                       name: "address".to_owned(),
+                   });
+        let tuple = "org.apache.cassandra.db.marshal.TupleType(\
+                     org.apache.cassandra.db.marshal.Int32Type,\
+                     org.apache.cassandra.db.marshal.BytesType)";
+        assert_eq!(CqlType::from_class_name(tuple).unwrap().to_string(), "tuple<int, blob>");
+        assert_eq!(CqlType::from_class_name("com.example.PointType").unwrap(),
+                   CqlType::Custom("com.example.PointType".to_owned()));
+        assert!(CqlType::from_class_name("org.apache.cassandra.db.marshal.ListType(").is_err());
+        assert!(CqlType::from_class_name("org.apache.cassandra.db.marshal.UserType(shop,6)")
+                    .is_err());
+    }
+
+    #[test]
+    fn quoted_identifiers() {
+        assert_eq!(quote_identifier("users"), "users");
+        assert_eq!(quote_identifier("user_2"), "user_2");
+        for keyword in &["select", "from", "table", "order", "key", "token", "where", "set",
+                         "frozen", "text"] {
+            assert_eq!(quote_identifier(keyword), format!("\"{}\"", keyword));
+        }
+        assert_eq!(quote_identifier("Users"), "\"Users\"");
+        assert_eq!(quote_identifier("2fa"), "\"2fa\"");
+        assert_eq!(quote_identifier("first name"), "\"first name\"");
+        assert_eq!(quote_identifier("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(quote_identifier(""), "\"\"");
+    }
+}
@@ -0,0 +1,323 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
+use std::net::IpAddr;
+
+use schema::uuid::CassUuid;
+use schema::cql_type::quote_identifier;
+use schema::time::{CqlDuration, date_to_string, time_to_string};
+
+#[derive(Debug,Clone,PartialEq)]
+pub enum CqlValue {
+    Ascii(String),
+    Text(String),
+    Varchar(String),
+    BigInt(i64),
+    Blob(Vec<u8>),
+    Boolean(bool),
+    Counter(i64),
+    Decimal {
+        unscaled: Vec<u8>,
+        scale: i32,
+    },
+    Double(f64),
+    Float(f32),
+    Int(i32),
+    Timestamp(i64),
+    Date(u32),
+    Time(i64),
+    SmallInt(i16),
+    TinyInt(i8),
+    Duration(CqlDuration),
+    Uuid(CassUuid),
+    TimeUuid(CassUuid),
+    Inet(IpAddr),
+    Varint(Vec<u8>),
+    Custom(Vec<u8>),
+    List(Vec<CqlValue>),
+    Set(Vec<CqlValue>),
+    Map(Vec<(CqlValue, CqlValue)>),
+    Tuple(Vec<CqlValue>),
+    Udt(Vec<(String, CqlValue)>),
+    Null,
+}
+
+impl CqlValue {
+    pub fn is_null(&self) -> bool {
+        match *self {
+            CqlValue::Null => true,
+            _ => false,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            CqlValue::Ascii(ref s) | CqlValue::Text(ref s) | CqlValue::Varchar(ref s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl Display for CqlValue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            CqlValue::Ascii(ref s) | CqlValue::Text(ref s) | CqlValue::Varchar(ref s) => {
+                write!(f, "'{}'", s.replace("'", "''"))
+            }
+            CqlValue::BigInt(v) | CqlValue::Counter(v) | CqlValue::Timestamp(v) => {
+                write!(f, "{}", v)
+            }
+            CqlValue::Int(v) => write!(f, "{}", v),
+            CqlValue::SmallInt(v) => write!(f, "{}", v),
+            CqlValue::TinyInt(v) => write!(f, "{}", v),
+            CqlValue::Date(v) => write!(f, "'{}'", date_to_string(v)),
+            CqlValue::Time(v) => write!(f, "'{}'", time_to_string(v)),
+            CqlValue::Duration(v) => write!(f, "{}", v),
+            CqlValue::Blob(ref bytes) | CqlValue::Custom(ref bytes) => {
+                try!(write!(f, "0x"));
+                for byte in bytes {
+                    try!(write!(f, "{:02x}", byte));
+                }
+                Ok(())
+            }
+            CqlValue::Boolean(v) => write!(f, "{}", v),
+            CqlValue::Double(v) => write_float(f, v),
+            CqlValue::Float(v) => write_float(f, v as f64),
+            CqlValue::Decimal { ref unscaled, scale } => {
+                write!(f, "{}", decimal_to_string(unscaled, scale))
+            }
+            CqlValue::Varint(ref bytes) => write!(f, "{}", varint_to_string(bytes)),
+            CqlValue::Uuid(uuid) | CqlValue::TimeUuid(uuid) => write!(f, "{}", uuid),
+            CqlValue::Inet(addr) => write!(f, "'{}'", addr),
+            CqlValue::List(ref items) => write_items(f, "[", items.iter(), "]"),
+            CqlValue::Set(ref items) => write_items(f, "{", items.iter(), "}"),
+            CqlValue::Tuple(ref items) => write_items(f, "(", items.iter(), ")"),
+            CqlValue::Map(ref entries) => {
+                try!(write!(f, "{{"));
+                for (i, &(ref key, ref value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        try!(write!(f, ", "));
+                    }
+                    try!(write!(f, "{}: {}", key, value));
+                }
+                write!(f, "}}")
+            }
+            CqlValue::Udt(ref fields) => {
+                try!(write!(f, "{{"));
+                for (i, &(ref name, ref value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        try!(write!(f, ", "));
+                    }
+                    try!(write!(f, "{}: {}", quote_identifier(name), value));
+                }
+                write!(f, "}}")
+            }
+            CqlValue::Null => write!(f, "null"),
+        }
+    }
+}
+
+fn write_items<'a, I>(f: &mut Formatter, open: &str, items: I, close: &str) -> fmt::Result
+    where I: Iterator<Item = &'a CqlValue>
+{
+    try!(write!(f, "{}", open));
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            try!(write!(f, ", "));
+        }
+        try!(write!(f, "{}", item));
+    }
+    write!(f, "{}", close)
+}
+
+fn write_float(f: &mut Formatter, v: f64) -> fmt::Result {
+    if v.is_nan() {
+        write!(f, "NaN")
+    } else if v.is_infinite() {
+        write!(f, "{}Infinity", if v < 0.0 { "-" } else { "" })
+    } else {
+        write!(f, "{:?}", v)
+    }
+}
+
+// Whether a big-endian two's complement integer is negative, and its
+// magnitude as big-endian unsigned bytes.
+pub fn varint_magnitude(bytes: &[u8]) -> (bool, Vec<u8>) {
+    let negative = bytes.first().map_or(false, |b| b & 0x80 != 0);
+    let magnitude: Vec<u8> = if negative {
+        let mut inverted: Vec<u8> = bytes.iter().map(|b| !b).collect();
+        for byte in inverted.iter_mut().rev() {
+            let (sum, overflow) = byte.overflowing_add(1);
+            *byte = sum;
+            if !overflow {
+                break;
+            }
+        }
+        inverted
+    } else {
+        bytes.to_vec()
+    };
+    (negative, magnitude)
+}
+
+// Formats a big-endian two's complement integer in base 10.
+pub fn varint_to_string(bytes: &[u8]) -> String {
+    let (negative, mut magnitude) = varint_magnitude(bytes);
+
+    let mut digits = Vec::new();
+    while magnitude.iter().any(|&b| b != 0) {
+        let mut remainder = 0u32;
+        for byte in magnitude.iter_mut() {
+            let current = (remainder << 8) | *byte as u32;
+            *byte = (current / 10) as u8;
+            remainder = current % 10;
+        }
+        digits.push((b'0' + remainder as u8) as char);
+    }
+    if digits.is_empty() {
+        digits.push('0');
+    }
+    if negative {
+        digits.push('-');
+    }
+    digits.iter().rev().cloned().collect()
+}
+
+// The way Java's BigDecimal.toString writes it, as Cassandra does: in plain
+// notation unless the scale is negative or there would be more than six zeros
+// after the point, and with an exponent otherwise, so no scale makes it long.
+pub fn decimal_to_string(unscaled: &[u8], scale: i32) -> String {
+    let unscaled = varint_to_string(unscaled);
+    let (sign, digits) = if unscaled.starts_with('-') {
+        ("-", &unscaled[1..])
+    } else {
+        ("", &unscaled[..])
+    };
+    if scale == 0 {
+        return unscaled.clone();
+    }
+    let adjusted = digits.len() as i64 - 1 - scale as i64;
+    if scale > 0 && adjusted >= -6 {
+        let scale = scale as usize;
+        if digits.len() > scale {
+            let point = digits.len() - scale;
+            return format!("{}{}.{}", sign, &digits[..point], &digits[point..]);
+        }
+        let zeros: String = (0..scale - digits.len()).map(|_| '0').collect();
+        return format!("{}0.{}{}", sign, zeros, digits);
+    }
+    let point = if digits.len() > 1 { "." } else { "" };
+    format!("{}{}{}{}E{}{}",
+            sign,
+            &digits[..1],
+            point,
+            &digits[1..],
+            if adjusted > 0 { "+" } else { "" },
+            adjusted)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    #[test]
+    fn varints_are_written_in_base_ten() {
+        assert_eq!(varint_to_string(&[]), "0");
+        assert_eq!(varint_to_string(&[0x00]), "0");
+        assert_eq!(varint_to_string(&[0x7f]), "127");
+        assert_eq!(varint_to_string(&[0x80]), "-128");
+        assert_eq!(varint_to_string(&[0xff]), "-1");
+        assert_eq!(varint_to_string(&[0x00, 0x80]), "128");
+        assert_eq!(varint_to_string(&[0xff, 0x7f]), "-129");
+        assert_eq!(varint_to_string(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+                   "18446744073709551616");
+        assert_eq!(varint_to_string(&[0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+                   "-18446744073709551616");
+    }
+
+    #[test]
+    fn decimals_are_written_like_java_big_decimals() {
+        // -12345
+        let unscaled = [0xcf, 0xc7];
+        assert_eq!(decimal_to_string(&unscaled, 0), "-12345");
+        assert_eq!(decimal_to_string(&unscaled, 2), "-123.45");
+        assert_eq!(decimal_to_string(&unscaled, 5), "-0.12345");
+        assert_eq!(decimal_to_string(&unscaled, 7), "-0.0012345");
+        assert_eq!(decimal_to_string(&unscaled, 10), "-0.0000012345");
+        assert_eq!(decimal_to_string(&unscaled, 11), "-1.2345E-7");
+        assert_eq!(decimal_to_string(&unscaled, -2), "-1.2345E+6");
+        assert_eq!(decimal_to_string(&[0x05], -3), "5E+3");
+        assert_eq!(decimal_to_string(&[0x64], 2), "1.00");
+        assert_eq!(decimal_to_string(&[], 0), "0");
+        assert_eq!(decimal_to_string(&[0x00], 3), "0.000");
+        assert_eq!(decimal_to_string(&[0x00], -3), "0E+3");
+        assert_eq!(decimal_to_string(&[0x01], i32::MAX), "1E-2147483647");
+        assert_eq!(decimal_to_string(&[0x01], i32::MIN), "1E+2147483648");
+        assert_eq!(decimal_to_string(&unscaled, i32::MIN), "-1.2345E+2147483652");
+    }
+
+    #[test]
+    fn strings_and_identifiers_are_quoted() {
+        assert_eq!(CqlValue::Text("it's".to_owned()).to_string(), "'it''s'");
+        assert_eq!(CqlValue::Ascii("''".to_owned()).to_string(), "''''''");
+        let udt = CqlValue::Udt(vec![("plain".to_owned(), CqlValue::Int(1)),
+                                     ("Mixed".to_owned(), CqlValue::Int(2)),
+                                     ("say \"hi\"".to_owned(), CqlValue::Null)]);
+        assert_eq!(udt.to_string(),
+                   "{plain: 1, \"Mixed\": 2, \"say \"\"hi\"\"\": null}");
+    }
+
+    #[test]
+    fn values_are_written_as_literals() {
+        assert_eq!(CqlValue::Blob(vec![0x00, 0xab]).to_string(), "0x00ab");
+        assert_eq!(CqlValue::Blob(vec![]).to_string(), "0x");
+        assert_eq!(CqlValue::Boolean(true).to_string(), "true");
+        assert_eq!(CqlValue::Double(1.0).to_string(), "1.0");
+        assert_eq!(CqlValue::Float(-0.5).to_string(), "-0.5");
+        assert_eq!(CqlValue::Double(::std::f64::NAN).to_string(), "NaN");
+        assert_eq!(CqlValue::Double(::std::f64::NEG_INFINITY).to_string(), "-Infinity");
+        assert_eq!(CqlValue::Inet(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))).to_string(),
+                   "'10.0.0.1'");
+        assert_eq!(CqlValue::Date(1 << 31).to_string(), "'1970-01-01'");
+        assert_eq!(CqlValue::Varint(vec![0xff, 0x7f]).to_string(), "-129");
+        assert_eq!(CqlValue::Decimal {
+                           unscaled: vec![0xcf, 0xc7],
+                           scale: 2,
+                       }
+                       .to_string(),
+                   "-123.45");
+    }
+
+    #[test]
+    fn collections_tuples_and_user_types_are_written_as_literals() {
+        let text = |s: &str| CqlValue::Text(s.to_owned());
+        assert_eq!(CqlValue::List(vec![CqlValue::Int(1), CqlValue::Int(2)]).to_string(),
+                   "[1, 2]");
+        assert_eq!(CqlValue::List(vec![]).to_string(), "[]");
+        assert_eq!(CqlValue::Set(vec![text("a"), text("b")]).to_string(), "{'a', 'b'}");
+        assert_eq!(CqlValue::Map(vec![(text("k"), CqlValue::List(vec![CqlValue::Null]))])
+                       .to_string(),
+                   "{'k': [null]}");
+        assert_eq!(CqlValue::Map(vec![]).to_string(), "{}");
+        assert_eq!(CqlValue::Tuple(vec![CqlValue::Int(1), text("x"), CqlValue::Null]).to_string(),
+                   "(1, 'x', null)");
+        let address = CqlValue::Udt(vec![("street".to_owned(), text("Main")),
+                                         ("zip".to_owned(), CqlValue::Int(12345))]);
+        assert_eq!(CqlValue::Set(vec![address]).to_string(),
+                   "{{street: 'Main', zip: 12345}}");
+    }
+
+    #[test]
+    fn values_compare_by_type_and_contents() {
+        assert_eq!(CqlValue::Int(1), CqlValue::Int(1));
+        assert!(CqlValue::Int(1) != CqlValue::BigInt(1));
+        assert!(CqlValue::Text("a".to_owned()) != CqlValue::Varchar("a".to_owned()));
+        assert!(CqlValue::List(vec![CqlValue::Int(1)]) != CqlValue::Set(vec![CqlValue::Int(1)]));
+        assert!(CqlValue::Null.is_null());
+        assert_eq!(CqlValue::Varchar("a".to_owned()).as_str(), Some("a"));
+        assert_eq!(CqlValue::Int(1).as_str(), None);
+    }
+}
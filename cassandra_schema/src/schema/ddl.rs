@@ -0,0 +1,1236 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use schema::cql_type::CqlType;
+use schema::cql_type::quote_identifier;
+use schema::cql_value::CqlValue;
+use schema::metadata::AggregateMeta;
+use schema::metadata::ClusteringOrder;
+use schema::metadata::ColumnKind;
+use schema::metadata::ColumnMeta;
+use schema::metadata::FunctionMeta;
+use schema::metadata::IndexKind;
+use schema::metadata::IndexMeta;
+use schema::metadata::KeyspaceMeta;
+use schema::metadata::Replication;
+use schema::metadata::ReplicationStrategy;
+use schema::metadata::SchemaSnapshot;
+use schema::metadata::TableMeta;
+use schema::metadata::UserTypeMeta;
+use schema::metadata::ViewMeta;
+use schema::metadata::sort_columns;
+
+#[derive(Debug)]
+pub enum DdlError {
+    Io(PathBuf, io::Error),
+    Syntax {
+        source: String,
+        line: usize,
+        message: String,
+    },
+}
+
+impl Display for DdlError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            DdlError::Io(ref path, ref err) => write!(f, "{}: {}", path.display(), err),
+            DdlError::Syntax { ref source, line, ref message } => {
+                write!(f, "{}:{}: {}", source, line, message)
+            }
+        }
+    }
+}
+
+impl Error for DdlError {
+    fn description(&self) -> &str {
+        match *self {
+            DdlError::Io(_, ref err) => err.description(),
+            DdlError::Syntax { ref message, .. } => message,
+        }
+    }
+}
+
+// Reads the schema a set of CREATE statements describe, without a cluster.
+// Besides CREATE KEYSPACE, TYPE, TABLE, INDEX, FUNCTION, AGGREGATE and
+// MATERIALIZED VIEW only USE is accepted; the statements describe a schema
+// rather than change one.
+impl SchemaSnapshot {
+    pub fn from_cql(cql: &str) -> Result<SchemaSnapshot, DdlError> {
+        let mut snapshot = SchemaSnapshot::default();
+        try!(snapshot.apply_cql("<cql>", cql));
+        Ok(snapshot)
+    }
+
+    // Every .cql file in the directory, in file name order.
+    pub fn from_directory<P: AsRef<Path>>(directory: P) -> Result<SchemaSnapshot, DdlError> {
+        let mut snapshot = SchemaSnapshot::default();
+        for path in try!(cql_files(directory.as_ref())) {
+            let cql = try!(fs::read_to_string(&path).map_err(|err| DdlError::Io(path.clone(), err)));
+            try!(snapshot.apply_cql(&path.display().to_string(), &cql));
+        }
+        Ok(snapshot)
+    }
+
+    pub fn apply_cql(&mut self, source: &str, cql: &str) -> Result<(), DdlError> {
+        let tokens = try!(tokenize(source, cql));
+        let mut parser = DdlParser {
+            source: source,
+            tokens: tokens,
+            pos: 0,
+            keyspace: None,
+            snapshot: self,
+        };
+        parser.parse_statements()
+    }
+}
+
+pub fn cql_files(directory: &Path) -> Result<Vec<PathBuf>, DdlError> {
+    let entries = try!(fs::read_dir(directory)
+                           .map_err(|err| DdlError::Io(directory.to_path_buf(), err)));
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = try!(entry.map_err(|err| DdlError::Io(directory.to_path_buf(), err))).path();
+        if path.is_file() && path.extension().map_or(false, |extension| extension == "cql") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+// Splits a script into statements on the semicolons that are not inside a
+// string, quoted name or comment. Each statement is returned as written, without
+// the comments around it.
+pub fn split_statements(source: &str, cql: &str) -> Result<Vec<String>, DdlError> {
+    let tokens = try!(tokenize(source, cql));
+    let mut statements = Vec::new();
+    let mut first: Option<&Token> = None;
+    let mut last: Option<&Token> = None;
+    for token in &tokens {
+        if token.kind == TokenKind::Symbol(';') {
+            if let (Some(first), Some(last)) = (first, last) {
+                statements.push(cql[first.start..last.end].to_owned());
+            }
+            first = None;
+            last = None;
+        } else {
+            first = first.or(Some(token));
+            last = Some(token);
+        }
+    }
+    if let (Some(first), Some(last)) = (first, last) {
+        statements.push(cql[first.start..last.end].to_owned());
+    }
+    Ok(statements)
+}
+
+// Puts a clause like a view's WHERE clause in a canonical form, so that
+// differences in spacing or keyword case don't count.
+pub fn normalize_clause(clause: &str) -> String {
+    match tokenize("<clause>", clause) {
+        Ok(tokens) => {
+            let tokens: Vec<&Token> = tokens.iter().collect();
+            tokens_to_cql(&tokens)
+        }
+        Err(_) => clause.split_whitespace().collect::<Vec<_>>().join(" "),
+    }
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub enum TokenKind {
+    // Unquoted identifiers and keywords, lower cased.
+    Identifier(String),
+    QuotedIdentifier(String),
+    Str(String),
+    Number(String),
+    Symbol(char),
+}
+
+#[derive(Debug,Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub line: usize,
+    // Where the token is in the text it was read from, in bytes.
+    start: usize,
+    end: usize,
+}
+
+fn tokens_to_cql(tokens: &[&Token]) -> String {
+    let mut cql = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let text = match token.kind {
+            TokenKind::Identifier(ref name) => name.clone(),
+            TokenKind::QuotedIdentifier(ref name) => format!("\"{}\"", name.replace("\"", "\"\"")),
+            TokenKind::Str(ref value) => CqlValue::Text(value.clone()).to_string(),
+            TokenKind::Number(ref number) => number.clone(),
+            TokenKind::Symbol(c) => c.to_string(),
+        };
+        let tight = match token.kind {
+            TokenKind::Symbol('.') | TokenKind::Symbol(',') | TokenKind::Symbol(')') => true,
+            _ => {
+                i > 0 &&
+                match tokens[i - 1].kind {
+                    TokenKind::Symbol('.') | TokenKind::Symbol('(') => true,
+                    _ => false,
+                }
+            }
+        };
+        if i > 0 && !tight {
+            cql.push(' ');
+        }
+        cql.push_str(&text);
+    }
+    cql
+}
+
+pub fn tokenize(source: &str, cql: &str) -> Result<Vec<Token>, DdlError> {
+    let mut tokens = Vec::new();
+    let mut scanner = Scanner { cql: cql, pos: 0, line: 1 };
+    let error = |line: usize, message: &str| {
+        DdlError::Syntax {
+            source: source.to_owned(),
+            line: line,
+            message: message.to_owned(),
+        }
+    };
+    while let Some(c) = scanner.peek() {
+        let start = scanner.pos;
+        let line = scanner.line;
+        let kind = match c {
+            c if c.is_whitespace() => {
+                scanner.next();
+                continue;
+            }
+            '-' | '/' if scanner.peek_second() == Some(c) => {
+                while scanner.next().map_or(false, |c| c != '\n') {}
+                continue;
+            }
+            '/' if scanner.peek_second() == Some('*') => {
+                scanner.next();
+                scanner.next();
+                let mut previous = ' ';
+                loop {
+                    match scanner.next() {
+                        Some('/') if previous == '*' => break,
+                        Some(c) => previous = c,
+                        None => return Err(error(line, "unterminated comment")),
+                    }
+                }
+                continue;
+            }
+            '$' if scanner.peek_second() == Some('$') => {
+                scanner.next();
+                scanner.next();
+                let mut value = String::new();
+                loop {
+                    match scanner.next() {
+                        Some('$') if scanner.peek() == Some('$') => {
+                            scanner.next();
+                            break;
+                        }
+                        Some(c) => value.push(c),
+                        None => return Err(error(line, "unterminated $$ string")),
+                    }
+                }
+                TokenKind::Str(value)
+            }
+            '\'' | '"' => {
+                let value = match scanner.quoted(c) {
+                    Some(value) => value,
+                    None => return Err(error(line, "unterminated quoted string")),
+                };
+                if c == '"' {
+                    TokenKind::QuotedIdentifier(value)
+                } else {
+                    TokenKind::Str(value)
+                }
+            }
+            c if c.is_ascii_digit() ||
+                 (c == '-' && scanner.peek_second().map_or(false, |c| c.is_ascii_digit())) => {
+                let mut number = String::new();
+                number.push(c);
+                scanner.next();
+                while let Some(c) = scanner.peek() {
+                    let exponent_sign = (c == '-' || c == '+') &&
+                                        (number.ends_with('e') || number.ends_with('E'));
+                    if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign {
+                        number.push(c);
+                        scanner.next();
+                    } else {
+                        break;
+                    }
+                }
+                TokenKind::Number(number)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut identifier = String::new();
+                while let Some(c) = scanner.peek() {
+                    if c.is_alphanumeric() || c == '_' {
+                        identifier.push(c);
+                        scanner.next();
+                    } else {
+                        break;
+                    }
+                }
+                TokenKind::Identifier(identifier.to_lowercase())
+            }
+            '(' | ')' | ',' | ';' | '.' | '=' | '{' | '}' | ':' | '<' | '>' | '[' | ']' | '*' |
+            '?' | '+' | '-' | '!' => {
+                scanner.next();
+                TokenKind::Symbol(c)
+            }
+            other => return Err(error(line, &format!("unexpected character {:?}", other))),
+        };
+        tokens.push(Token {
+            kind: kind,
+            line: line,
+            start: start,
+            end: scanner.pos,
+        });
+    }
+    Ok(tokens)
+}
+
+struct Scanner<'a> {
+    cql: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn peek(&self) -> Option<char> {
+        self.cql[self.pos..].chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.cql[self.pos..].chars().nth(1)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if let Some(c) = c {
+            self.pos += c.len_utf8();
+            if c == '\n' {
+                self.line += 1;
+            }
+        }
+        c
+    }
+
+    // A doubled quote stands for the quote itself.
+    fn quoted(&mut self, quote: char) -> Option<String> {
+        self.next();
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some(c) if c == quote => {
+                    if self.peek() == Some(quote) {
+                        self.next();
+                        value.push(quote);
+                    } else {
+                        return Some(value);
+                    }
+                }
+                Some(c) => value.push(c),
+                None => return None,
+            }
+        }
+    }
+}
+
+type ClusteringOrders = BTreeMap<String, ClusteringOrder>;
+
+struct DdlParser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    // Set by USE, for names that don't say which keyspace they are in.
+    keyspace: Option<String>,
+    snapshot: &'a mut SchemaSnapshot,
+}
+
+impl<'a> DdlParser<'a> {
+    fn parse_statements(&mut self) -> Result<(), DdlError> {
+        while self.peek().is_some() {
+            if self.accept_symbol(';') {
+                continue;
+            }
+            try!(self.parse_statement());
+            if self.peek().is_some() {
+                try!(self.expect_symbol(';'));
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_statement(&mut self) -> Result<(), DdlError> {
+        if self.accept_keyword("use") {
+            self.keyspace = Some(try!(self.name()));
+            return Ok(());
+        }
+        try!(self.expect_keyword("create"));
+        if self.accept_keyword("or") {
+            try!(self.expect_keyword("replace"));
+        }
+        if self.accept_keyword("keyspace") || self.accept_keyword("schema") {
+            self.parse_keyspace()
+        } else if self.accept_keyword("type") {
+            self.parse_type()
+        } else if self.accept_keyword("table") || self.accept_keyword("columnfamily") {
+            self.parse_table()
+        } else if self.accept_keyword("custom") {
+            try!(self.expect_keyword("index"));
+            self.parse_index(true)
+        } else if self.accept_keyword("index") {
+            self.parse_index(false)
+        } else if self.accept_keyword("function") {
+            self.parse_function()
+        } else if self.accept_keyword("aggregate") {
+            self.parse_aggregate()
+        } else if self.accept_keyword("materialized") {
+            try!(self.expect_keyword("view"));
+            self.parse_view()
+        } else {
+            Err(self.error("expected KEYSPACE, TYPE, TABLE, INDEX, FUNCTION, AGGREGATE or \
+                            MATERIALIZED VIEW after CREATE"))
+        }
+    }
+
+    fn parse_keyspace(&mut self) -> Result<(), DdlError> {
+        let if_not_exists = try!(self.if_not_exists());
+        let name = try!(self.name());
+        try!(self.expect_keyword("with"));
+        let mut replication = None;
+        let mut durable_writes = true;
+        loop {
+            let option = try!(self.name());
+            try!(self.expect_symbol('='));
+            let value = try!(self.literal());
+            match &option[..] {
+                "replication" => {
+                    let mut options = literal_string_map(&value);
+                    let class_name = match options.remove("class") {
+                        Some(class_name) => class_name,
+                        None => return Err(self.error("replication has no class")),
+                    };
+                    replication = Some(Replication {
+                        strategy: ReplicationStrategy::from_class_name(&class_name),
+                        options: options,
+                    });
+                }
+                "durable_writes" => {
+                    durable_writes = match value {
+                        CqlValue::Boolean(value) => value,
+                        _ => return Err(self.error("durable_writes must be true or false")),
+                    }
+                }
+                other => return Err(self.error(&format!("unknown keyspace option {}", other))),
+            }
+            if !self.accept_keyword("and") {
+                break;
+            }
+        }
+        let replication = match replication {
+            Some(replication) => replication,
+            None => return Err(self.error("a keyspace needs a replication strategy")),
+        };
+        if self.snapshot.keyspaces.contains_key(&name) {
+            return self.already_defined(if_not_exists, "keyspace", &name);
+        }
+        let mut keyspace = KeyspaceMeta::new(&name, replication);
+        keyspace.durable_writes = durable_writes;
+        self.snapshot.keyspaces.insert(name, keyspace);
+        Ok(())
+    }
+
+    fn parse_type(&mut self) -> Result<(), DdlError> {
+        let if_not_exists = try!(self.if_not_exists());
+        let (keyspace, name) = try!(self.qualified_name());
+        try!(self.expect_symbol('('));
+        let mut fields = Vec::new();
+        loop {
+            let field = try!(self.name());
+            fields.push((field, try!(self.data_type())));
+            if !self.accept_symbol(',') {
+                break;
+            }
+        }
+        try!(self.expect_symbol(')'));
+        let user_type = UserTypeMeta {
+            keyspace: keyspace.clone(),
+            name: name.clone(),
+            fields: fields,
+        };
+        let exists = try!(self.keyspace_mut(&keyspace)).user_types.contains_key(&name);
+        if exists {
+            return self.already_defined(if_not_exists, "type", &name);
+        }
+        try!(self.keyspace_mut(&keyspace)).user_types.insert(name, user_type);
+        Ok(())
+    }
+
+    fn parse_table(&mut self) -> Result<(), DdlError> {
+        let if_not_exists = try!(self.if_not_exists());
+        let (keyspace, name) = try!(self.qualified_name());
+        try!(self.expect_symbol('('));
+        let mut definitions = Vec::new();
+        let mut key = None;
+        loop {
+            if self.accept_keyword("primary") {
+                try!(self.expect_keyword("key"));
+                key = Some(try!(self.primary_key()));
+            } else {
+                let column = try!(self.name());
+                let data_type = try!(self.data_type());
+                let is_static = self.accept_keyword("static");
+                if self.accept_keyword("primary") {
+                    try!(self.expect_keyword("key"));
+                    key = Some((vec![column.clone()], Vec::new()));
+                }
+                definitions.push((column, data_type, is_static));
+            }
+            if !self.accept_symbol(',') {
+                break;
+            }
+        }
+        try!(self.expect_symbol(')'));
+        let (partition_key, clustering_key) = match key {
+            Some(key) => key,
+            None => return Err(self.error(&format!("table {} has no primary key", name))),
+        };
+        let (clustering_order, options) = try!(self.table_options());
+
+        let mut columns = Vec::new();
+        for (column, data_type, is_static) in definitions {
+            let (kind, position) = if let Some(position) = partition_key.iter().position(|c| *c == column) {
+                (ColumnKind::PartitionKey, position)
+            } else if let Some(position) = clustering_key.iter().position(|c| *c == column) {
+                (ColumnKind::Clustering, position)
+            } else if is_static {
+                (ColumnKind::Static, 0)
+            } else {
+                (ColumnKind::Regular, 0)
+            };
+            let order = match kind {
+                ColumnKind::Clustering => {
+                    clustering_order.get(&column).cloned().unwrap_or(ClusteringOrder::Asc)
+                }
+                _ => ClusteringOrder::None,
+            };
+            columns.push(ColumnMeta {
+                name: column,
+                kind: kind,
+                position: position as i32,
+                data_type: data_type,
+                clustering_order: order,
+            });
+        }
+        for column in partition_key.iter().chain(clustering_key.iter()) {
+            if !columns.iter().any(|c| c.name == *column) {
+                return Err(self.error(&format!("primary key column {} is not defined", column)));
+            }
+        }
+        sort_columns(&mut columns);
+        let table = TableMeta {
+            keyspace: keyspace.clone(),
+            name: name.clone(),
+            columns: columns,
+            options: options,
+            indexes: BTreeMap::new(),
+        };
+        let exists = try!(self.keyspace_mut(&keyspace)).tables.contains_key(&name);
+        if exists {
+            return self.already_defined(if_not_exists, "table", &name);
+        }
+        try!(self.keyspace_mut(&keyspace)).tables.insert(name, table);
+        Ok(())
+    }
+
+    // `(a, b)`, `((a, b), c, d)` or `(a, c, d)`, after PRIMARY KEY.
+    fn primary_key(&mut self) -> Result<(Vec<String>, Vec<String>), DdlError> {
+        try!(self.expect_symbol('('));
+        let partition_key = if self.accept_symbol('(') {
+            let names = try!(self.names());
+            try!(self.expect_symbol(')'));
+            names
+        } else {
+            vec![try!(self.name())]
+        };
+        let mut clustering_key = Vec::new();
+        while self.accept_symbol(',') {
+            clustering_key.push(try!(self.name()));
+        }
+        try!(self.expect_symbol(')'));
+        Ok((partition_key, clustering_key))
+    }
+
+    fn table_options(&mut self) -> Result<(ClusteringOrders, BTreeMap<String, CqlValue>), DdlError> {
+        let mut clustering_order = BTreeMap::new();
+        let mut options = BTreeMap::new();
+        if !self.accept_keyword("with") {
+            return Ok((clustering_order, options));
+        }
+        loop {
+            if self.accept_keyword("clustering") {
+                try!(self.expect_keyword("order"));
+                try!(self.expect_keyword("by"));
+                try!(self.expect_symbol('('));
+                loop {
+                    let column = try!(self.name());
+                    let order = if self.accept_keyword("desc") {
+                        ClusteringOrder::Desc
+                    } else {
+                        self.accept_keyword("asc");
+                        ClusteringOrder::Asc
+                    };
+                    clustering_order.insert(column, order);
+                    if !self.accept_symbol(',') {
+                        break;
+                    }
+                }
+                try!(self.expect_symbol(')'));
+            } else if self.accept_keyword("compact") {
+                return Err(self.error("COMPACT STORAGE tables are not supported"));
+            } else {
+                let option = try!(self.name());
+                try!(self.expect_symbol('='));
+                options.insert(option, try!(self.literal()));
+            }
+            if !self.accept_keyword("and") {
+                break;
+            }
+        }
+        Ok((clustering_order, options))
+    }
+
+    fn parse_index(&mut self, custom: bool) -> Result<(), DdlError> {
+        let if_not_exists = try!(self.if_not_exists());
+        let name = if self.peek_keyword("on") {
+            None
+        } else {
+            Some(try!(self.name()))
+        };
+        try!(self.expect_keyword("on"));
+        let (keyspace, table) = try!(self.qualified_name());
+        try!(self.expect_symbol('('));
+        let first = try!(self.name());
+        let (target, column) = if self.accept_symbol('(') {
+            let column = try!(self.name());
+            try!(self.expect_symbol(')'));
+            (format!("{}({})", first, quote_identifier(&column)), column)
+        } else {
+            (quote_identifier(&first), first)
+        };
+        try!(self.expect_symbol(')'));
+        let mut options = BTreeMap::new();
+        if custom {
+            try!(self.expect_keyword("using"));
+            options.insert("class_name".to_owned(), try!(self.string()));
+            if self.accept_keyword("with") {
+                try!(self.expect_keyword("options"));
+                try!(self.expect_symbol('='));
+                options.extend(literal_string_map(&try!(self.literal())));
+            }
+        }
+        // Cassandra drops anything but letters, digits and underscores from the
+        // names it makes up.
+        let name = name.unwrap_or_else(|| {
+            format!("{}_{}_idx", table, column)
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+                .collect()
+        });
+        let index = IndexMeta {
+            name: name.clone(),
+            table: table.clone(),
+            kind: if custom { IndexKind::Custom } else { IndexKind::Composites },
+            target: target,
+            options: options,
+        };
+        let exists = match try!(self.keyspace_mut(&keyspace)).tables.get(&table) {
+            Some(table) => table.indexes.contains_key(&name),
+            None => return Err(self.error(&format!("table {} is not defined", table))),
+        };
+        if exists {
+            return self.already_defined(if_not_exists, "index", &name);
+        }
+        if let Some(table) = try!(self.keyspace_mut(&keyspace)).tables.get_mut(&table) {
+            table.indexes.insert(name, index);
+        }
+        Ok(())
+    }
+
+    fn parse_function(&mut self) -> Result<(), DdlError> {
+        try!(self.if_not_exists());
+        let (keyspace, name) = try!(self.qualified_name());
+        try!(self.expect_symbol('('));
+        let mut arguments = Vec::new();
+        if !self.accept_symbol(')') {
+            loop {
+                let argument = try!(self.name());
+                arguments.push((argument, try!(self.data_type())));
+                if !self.accept_symbol(',') {
+                    break;
+                }
+            }
+            try!(self.expect_symbol(')'));
+        }
+        let called_on_null_input = if self.accept_keyword("called") {
+            true
+        } else {
+            try!(self.expect_keyword("returns"));
+            try!(self.expect_keyword("null"));
+            false
+        };
+        try!(self.expect_keyword("on"));
+        try!(self.expect_keyword("null"));
+        try!(self.expect_keyword("input"));
+        try!(self.expect_keyword("returns"));
+        let return_type = try!(self.data_type());
+        try!(self.expect_keyword("language"));
+        let language = try!(self.name());
+        try!(self.expect_keyword("as"));
+        let body = try!(self.string());
+        let function = FunctionMeta {
+            keyspace: keyspace.clone(),
+            name: name,
+            arguments: arguments,
+            return_type: return_type,
+            language: language,
+            body: body,
+            called_on_null_input: called_on_null_input,
+        };
+        let keyspace = try!(self.keyspace_mut(&keyspace));
+        keyspace.functions.retain(|existing| existing.signature() != function.signature());
+        keyspace.functions.push(function);
+        Ok(())
+    }
+
+    fn parse_aggregate(&mut self) -> Result<(), DdlError> {
+        try!(self.if_not_exists());
+        let (keyspace, name) = try!(self.qualified_name());
+        try!(self.expect_symbol('('));
+        let mut argument_types = Vec::new();
+        if !self.accept_symbol(')') {
+            loop {
+                argument_types.push(try!(self.data_type()));
+                if !self.accept_symbol(',') {
+                    break;
+                }
+            }
+            try!(self.expect_symbol(')'));
+        }
+        try!(self.expect_keyword("sfunc"));
+        let state_function = try!(self.name());
+        try!(self.expect_keyword("stype"));
+        let state_type = try!(self.data_type());
+        let final_function = if self.accept_keyword("finalfunc") {
+            Some(try!(self.name()))
+        } else {
+            None
+        };
+        let initial_condition = if self.accept_keyword("initcond") {
+            Some(try!(self.literal()).to_string())
+        } else {
+            None
+        };
+        // Without a final function the state is the result; with one the
+        // result is whatever the final function returns.
+        let return_type = match final_function {
+            Some(ref final_function) => {
+                let found = try!(self.keyspace_mut(&keyspace))
+                                .functions
+                                .iter()
+                                .find(|function| function.name == *final_function)
+                                .map(|function| function.return_type.clone());
+                match found {
+                    Some(return_type) => return_type,
+                    None => {
+                        return Err(self.error(&format!("function {} is not defined",
+                                                       final_function)))
+                    }
+                }
+            }
+            None => state_type.clone(),
+        };
+        let aggregate = AggregateMeta {
+            keyspace: keyspace.clone(),
+            name: name,
+            return_type: return_type,
+            argument_types: argument_types,
+            state_function: state_function,
+            state_type: state_type,
+            final_function: final_function,
+            initial_condition: initial_condition,
+        };
+        let keyspace = try!(self.keyspace_mut(&keyspace));
+        keyspace.aggregates.retain(|existing| existing.signature() != aggregate.signature());
+        keyspace.aggregates.push(aggregate);
+        Ok(())
+    }
+
+    fn parse_view(&mut self) -> Result<(), DdlError> {
+        let if_not_exists = try!(self.if_not_exists());
+        let (keyspace, name) = try!(self.qualified_name());
+        try!(self.expect_keyword("as"));
+        try!(self.expect_keyword("select"));
+        let selected = if self.accept_symbol('*') {
+            None
+        } else {
+            Some(try!(self.names()))
+        };
+        try!(self.expect_keyword("from"));
+        let (base_keyspace, base_table) = try!(self.qualified_name());
+        if base_keyspace != keyspace {
+            return Err(self.error("a view must be in the keyspace of its base table"));
+        }
+        let mut where_tokens = Vec::new();
+        if self.accept_keyword("where") {
+            while !self.peek_keyword("primary") {
+                match self.next() {
+                    Some(token) => where_tokens.push(token),
+                    None => return Err(self.error("expected PRIMARY KEY")),
+                }
+            }
+        }
+        try!(self.expect_keyword("primary"));
+        try!(self.expect_keyword("key"));
+        let (partition_key, clustering_key) = try!(self.primary_key());
+        let (clustering_order, options) = try!(self.table_options());
+
+        let base_columns = match try!(self.keyspace_mut(&keyspace)).tables.get(&base_table) {
+            Some(table) => table.columns.clone(),
+            None => return Err(self.error(&format!("table {} is not defined", base_table))),
+        };
+        let mut columns = Vec::new();
+        for base_column in base_columns {
+            let selected_column = match selected {
+                Some(ref names) => names.contains(&base_column.name),
+                None => true,
+            };
+            let partition_position = partition_key.iter().position(|c| *c == base_column.name);
+            let clustering_position = clustering_key.iter().position(|c| *c == base_column.name);
+            if !selected_column && partition_position.is_none() && clustering_position.is_none() {
+                continue;
+            }
+            let (kind, position, order) = match (partition_position, clustering_position) {
+                (Some(position), _) => (ColumnKind::PartitionKey, position, ClusteringOrder::None),
+                (None, Some(position)) => {
+                    let order = clustering_order.get(&base_column.name)
+                                                .cloned()
+                                                .unwrap_or(ClusteringOrder::Asc);
+                    (ColumnKind::Clustering, position, order)
+                }
+                (None, None) => (ColumnKind::Regular, 0, ClusteringOrder::None),
+            };
+            columns.push(ColumnMeta {
+                name: base_column.name,
+                kind: kind,
+                position: position as i32,
+                data_type: base_column.data_type,
+                clustering_order: order,
+            });
+        }
+        sort_columns(&mut columns);
+        let where_tokens: Vec<&Token> = where_tokens.iter().collect();
+        let view = ViewMeta {
+            keyspace: keyspace.clone(),
+            name: name.clone(),
+            base_table: base_table,
+            include_all_columns: selected.is_none(),
+            where_clause: tokens_to_cql(&where_tokens),
+            columns: columns,
+            options: options,
+        };
+        let exists = try!(self.keyspace_mut(&keyspace)).views.contains_key(&name);
+        if exists {
+            return self.already_defined(if_not_exists, "materialized view", &name);
+        }
+        try!(self.keyspace_mut(&keyspace)).views.insert(name, view);
+        Ok(())
+    }
+
+    fn if_not_exists(&mut self) -> Result<bool, DdlError> {
+        if self.accept_keyword("if") {
+            try!(self.expect_keyword("not"));
+            try!(self.expect_keyword("exists"));
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn already_defined(&self, if_not_exists: bool, what: &str, name: &str) -> Result<(), DdlError> {
+        if if_not_exists {
+            Ok(())
+        } else {
+            Err(self.error(&format!("{} {} is already defined", what, name)))
+        }
+    }
+
+    fn keyspace_mut(&mut self, name: &str) -> Result<&mut KeyspaceMeta, DdlError> {
+        if !self.snapshot.keyspaces.contains_key(name) {
+            return Err(self.error(&format!("keyspace {} is not defined", name)));
+        }
+        Ok(self.snapshot.keyspaces.get_mut(name).unwrap())
+    }
+
+    // A name with an optional keyspace, which otherwise is the one last USEd.
+    fn qualified_name(&mut self) -> Result<(String, String), DdlError> {
+        let first = try!(self.name());
+        if self.accept_symbol('.') {
+            Ok((first, try!(self.name())))
+        } else {
+            match self.keyspace {
+                Some(ref keyspace) => Ok((keyspace.clone(), first)),
+                None => Err(self.error(&format!("{} has no keyspace and there was no USE", first))),
+            }
+        }
+    }
+
+    fn names(&mut self) -> Result<Vec<String>, DdlError> {
+        let mut names = vec![try!(self.name())];
+        while self.accept_symbol(',') {
+            names.push(try!(self.name()));
+        }
+        Ok(names)
+    }
+
+    fn name(&mut self) -> Result<String, DdlError> {
+        match self.next().map(|token| token.kind) {
+            Some(TokenKind::Identifier(name)) | Some(TokenKind::QuotedIdentifier(name)) => Ok(name),
+            _ => Err(self.error("expected a name")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, DdlError> {
+        match self.next().map(|token| token.kind) {
+            Some(TokenKind::Str(value)) => Ok(value),
+            _ => Err(self.error("expected a string")),
+        }
+    }
+
+    // Rebuilds the type from its tokens and hands it to the type parser.
+    fn data_type(&mut self) -> Result<CqlType, DdlError> {
+        let mut type_string = String::new();
+        let mut depth = 0;
+        loop {
+            let token = match self.next() {
+                Some(token) => token,
+                None => return Err(self.error("expected a type")),
+            };
+            match token.kind {
+                TokenKind::Identifier(ref name) => type_string.push_str(name),
+                TokenKind::QuotedIdentifier(ref name) => {
+                    type_string.push_str(&format!("\"{}\"", name.replace("\"", "\"\"")))
+                }
+                TokenKind::Str(ref class_name) => {
+                    type_string.push_str(&CqlValue::Text(class_name.clone()).to_string())
+                }
+                TokenKind::Symbol('<') => {
+                    depth += 1;
+                    type_string.push('<')
+                }
+                TokenKind::Symbol('>') if depth > 0 => {
+                    depth -= 1;
+                    type_string.push('>')
+                }
+                TokenKind::Symbol(c @ ',') | TokenKind::Symbol(c @ '.') => type_string.push(c),
+                _ => return Err(self.error("expected a type")),
+            }
+            let more = match self.peek() {
+                Some(&TokenKind::Symbol('<')) | Some(&TokenKind::Symbol('.')) => true,
+                _ => depth > 0 || token.kind == TokenKind::Symbol('.'),
+            };
+            if !more {
+                break;
+            }
+        }
+        match CqlType::parse(&type_string) {
+            Ok(cql_type) => Ok(cql_type),
+            Err(_) => Err(self.error(&format!("{} is not a type", type_string))),
+        }
+    }
+
+    fn literal(&mut self) -> Result<CqlValue, DdlError> {
+        match self.next().map(|token| token.kind) {
+            Some(TokenKind::Str(value)) => Ok(CqlValue::Text(value)),
+            Some(TokenKind::Number(number)) => {
+                if let Ok(value) = number.parse::<i32>() {
+                    Ok(CqlValue::Int(value))
+                } else if let Ok(value) = number.parse::<i64>() {
+                    Ok(CqlValue::BigInt(value))
+                } else if let Ok(value) = number.parse::<f64>() {
+                    Ok(CqlValue::Double(value))
+                } else {
+                    Err(self.error(&format!("{} is not a number", number)))
+                }
+            }
+            Some(TokenKind::Identifier(ref word)) if word == "true" => Ok(CqlValue::Boolean(true)),
+            Some(TokenKind::Identifier(ref word)) if word == "false" => Ok(CqlValue::Boolean(false)),
+            Some(TokenKind::Identifier(ref word)) if word == "null" => Ok(CqlValue::Null),
+            Some(TokenKind::Symbol('{')) => {
+                if self.accept_symbol('}') {
+                    return Ok(CqlValue::Map(Vec::new()));
+                }
+                let first = try!(self.literal());
+                if self.accept_symbol(':') {
+                    let mut entries = vec![(first, try!(self.literal()))];
+                    while self.accept_symbol(',') {
+                        let key = try!(self.literal());
+                        try!(self.expect_symbol(':'));
+                        entries.push((key, try!(self.literal())));
+                    }
+                    try!(self.expect_symbol('}'));
+                    Ok(CqlValue::Map(entries))
+                } else {
+                    let items = try!(self.literals_after(first, '}'));
+                    Ok(CqlValue::Set(items))
+                }
+            }
+            Some(TokenKind::Symbol('[')) => {
+                if self.accept_symbol(']') {
+                    return Ok(CqlValue::List(Vec::new()));
+                }
+                let first = try!(self.literal());
+                Ok(CqlValue::List(try!(self.literals_after(first, ']'))))
+            }
+            Some(TokenKind::Symbol('(')) => {
+                let first = try!(self.literal());
+                Ok(CqlValue::Tuple(try!(self.literals_after(first, ')'))))
+            }
+            _ => Err(self.error("expected a literal")),
+        }
+    }
+
+    fn literals_after(&mut self, first: CqlValue, close: char) -> Result<Vec<CqlValue>, DdlError> {
+        let mut items = vec![first];
+        while self.accept_symbol(',') {
+            items.push(try!(self.literal()));
+        }
+        try!(self.expect_symbol(close));
+        Ok(items)
+    }
+
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|token| &token.kind)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(&TokenKind::Identifier(ref word)) => word == keyword,
+            _ => false,
+        }
+    }
+
+    fn accept_keyword(&mut self, keyword: &str) -> bool {
+        let matches = self.peek_keyword(keyword);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), DdlError> {
+        if self.accept_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {}", keyword.to_uppercase())))
+        }
+    }
+
+    fn accept_symbol(&mut self, symbol: char) -> bool {
+        let matches = self.peek() == Some(&TokenKind::Symbol(symbol));
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), DdlError> {
+        if self.accept_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {:?}", symbol)))
+        }
+    }
+
+    // Points at the token just read, or at the last one at the end of input.
+    fn error(&self, message: &str) -> DdlError {
+        let index = if self.pos > 0 { self.pos - 1 } else { 0 };
+        let line = self.tokens
+                       .get(index)
+                       .or_else(|| self.tokens.last())
+                       .map_or(1, |token| token.line);
+        DdlError::Syntax {
+            source: self.source.to_owned(),
+            line: line,
+            message: message.to_owned(),
+        }
+    }
+}
+
+// Map literal entries as text, with numbers as they were written.
+fn literal_string_map(value: &CqlValue) -> BTreeMap<String, String> {
+    match *value {
+        CqlValue::Map(ref entries) => {
+            entries.iter()
+                   .map(|&(ref key, ref value)| (literal_string(key), literal_string(value)))
+                   .collect()
+        }
+        _ => BTreeMap::new(),
+    }
+}
+
+fn literal_string(value: &CqlValue) -> String {
+    match value.as_str() {
+        Some(value) => value.to_owned(),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schema::cql_type::CqlType;
+    use schema::cql_value::CqlValue;
+    use schema::metadata::ClusteringOrder;
+    use schema::metadata::ColumnKind;
+    use schema::metadata::SchemaSnapshot;
+    use super::*;
+
+    const SCHEMA: &'static str = "
+        -- a line comment; with a semicolon
+        CREATE KEYSPACE IF NOT EXISTS ks WITH replication = {'class': 'SimpleStrategy',
+            'replication_factor': 1} AND durable_writes = false;
+        USE ks;
+        /* a block comment;
+           over two lines */
+        CREATE TYPE \"Home Address\" (street text, \"Zip\" int);
+        CREATE TABLE \"Mixed Case\" (
+            key int,                                // a keyword as a name
+            \"Select\" text,
+            \"1st\" int,
+            tags map<text, frozen<set<int>>> static,
+            home frozen<\"Home Address\">,
+            pairs frozen<list<frozen<tuple<int, text>>>>,
+            PRIMARY KEY ((key), \"1st\")
+        ) WITH CLUSTERING ORDER BY (\"1st\" DESC) AND comment = 'a; b';
+        CREATE TABLE IF NOT EXISTS \"Mixed Case\" (key int PRIMARY KEY);
+        CREATE INDEX ON \"Mixed Case\" (\"Select\");
+    ";
+
+    #[test]
+    fn statements_split_outside_strings_and_comments() {
+        let statements = split_statements("<test>", SCHEMA).unwrap();
+        assert_eq!(statements.len(), 6);
+        assert!(statements[0].starts_with("CREATE KEYSPACE IF NOT EXISTS ks"));
+        assert_eq!(statements[1], "USE ks");
+        assert!(statements[3].ends_with("comment = 'a; b'"));
+        assert_eq!(statements[5], "CREATE INDEX ON \"Mixed Case\" (\"Select\")");
+        assert!(split_statements("<test>", "SELECT 'unterminated").is_err());
+        assert!(split_statements("<test>", "/* unterminated").is_err());
+    }
+
+    #[test]
+    fn parses_keywords_quoted_names_and_nested_types() {
+        let snapshot = SchemaSnapshot::from_cql(SCHEMA).unwrap();
+        let keyspace = &snapshot.keyspaces["ks"];
+        assert!(!keyspace.durable_writes);
+        assert_eq!(keyspace.user_types["Home Address"].fields[1].0, "Zip");
+
+        let table = snapshot.table("ks", "Mixed Case").unwrap();
+        let names: Vec<&str> = table.columns.iter().map(|column| &column.name[..]).collect();
+        assert_eq!(names, ["key", "1st", "tags", "Select", "home", "pairs"]);
+        assert_eq!(table.partition_key()[0].name, "key");
+        let first = table.column("1st").unwrap();
+        assert_eq!(first.kind, ColumnKind::Clustering);
+        assert_eq!(first.clustering_order, ClusteringOrder::Desc);
+        let tags = table.column("tags").unwrap();
+        assert_eq!(tags.kind, ColumnKind::Static);
+        assert_eq!(tags.data_type.to_string(), "map<text, frozen<set<int>>>");
+        assert_eq!(table.column("home").unwrap().data_type,
+                   CqlType::Frozen(Box::new(CqlType::Udt {
+                       keyspace: None,
+                       name: "Home Address".to_owned(),
+                   })));
+        assert_eq!(table.column("pairs").unwrap().data_type.to_string(),
+                   "frozen<list<frozen<tuple<int, text>>>>");
+        assert_eq!(table.options["comment"], CqlValue::Text("a; b".to_owned()));
+        assert!(table.indexes.contains_key("MixedCase_Select_idx"));
+    }
+
+    #[test]
+    fn definitions_must_be_new_without_if_not_exists() {
+        let twice = "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy', \
+                     'replication_factor': 1};
+                     CREATE TABLE ks.t (id int PRIMARY KEY);
+                     CREATE TABLE ks.t (id int PRIMARY KEY);";
+        match SchemaSnapshot::from_cql(twice) {
+            Err(DdlError::Syntax { line, ref message, .. }) => {
+                assert_eq!(line, 3);
+                assert_eq!(message, "table t is already defined");
+            }
+            _ => panic!("a table defined twice is an error"),
+        }
+        assert!(SchemaSnapshot::from_cql("CREATE TABLE t (id int PRIMARY KEY)").is_err());
+        assert!(SchemaSnapshot::from_cql("DROP TABLE ks.t").is_err());
+    }
+
+    // What describing a snapshot writes reads back to the same snapshot.
+    #[test]
+    fn described_schemas_read_back_the_same() {
+        let schema = "
+            CREATE KEYSPACE shop WITH replication = {'class': 'NetworkTopologyStrategy', \
+                                                     'dc1': '3'}
+                AND durable_writes = false;
+            CREATE TYPE shop.zone (code int, \"Name\" text);
+            CREATE TYPE shop.address (street text, zones list<frozen<zone>>);
+            CREATE TABLE shop.customers (
+                region text,
+                id timeuuid,
+                name text,
+                home frozen<address>,
+                tags set<text>,
+                PRIMARY KEY ((region), id)
+            ) WITH CLUSTERING ORDER BY (id DESC) AND comment = 'it''s';
+            CREATE INDEX customers_name_idx ON shop.customers (name);
+            CREATE INDEX ON shop.customers (tags);
+            CREATE FUNCTION shop.count_state(state tuple<int, bigint>, value int)
+                CALLED ON NULL INPUT RETURNS tuple<int, bigint> LANGUAGE java
+                AS $$ if (value != null) { state.setInt(0, state.getInt(0) + 1); } return state; $$;
+            CREATE FUNCTION shop.count_final(state tuple<int, bigint>)
+                RETURNS NULL ON NULL INPUT RETURNS int LANGUAGE java AS 'return state.getInt(0);';
+            CREATE AGGREGATE shop.count_values(int) SFUNC count_state STYPE tuple<int, bigint>
+                FINALFUNC count_final INITCOND (0, 0);
+            CREATE MATERIALIZED VIEW shop.customers_by_name AS
+                SELECT region, id, name FROM shop.customers
+                WHERE name IS NOT NULL AND region IS NOT NULL AND id IS NOT NULL
+                PRIMARY KEY (name, region, id);
+        ";
+        let snapshot = SchemaSnapshot::from_cql(schema).unwrap();
+        let shop = &snapshot.keyspaces["shop"];
+        assert_eq!(shop.user_types.len(), 2);
+        assert_eq!(shop.tables["customers"].indexes.len(), 2);
+        assert_eq!(shop.functions.len(), 2);
+        assert_eq!(shop.aggregates.len(), 1);
+        assert_eq!(shop.views.len(), 1);
+
+        let cql = snapshot.to_cql();
+        let described = SchemaSnapshot::from_cql(&cql).unwrap();
+        assert_eq!(described, snapshot);
+        assert_eq!(described.to_cql(), cql);
+    }
+}
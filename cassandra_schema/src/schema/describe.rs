@@ -0,0 +1,558 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use schema::cql_type::CqlType;
+use schema::cql_type::quote_identifier;
+use schema::cql_value::CqlValue;
+use schema::metadata::AggregateMeta;
+use schema::metadata::ClusteringOrder;
+use schema::metadata::ColumnKind;
+use schema::metadata::ColumnMeta;
+use schema::metadata::FunctionMeta;
+use schema::metadata::IndexKind;
+use schema::metadata::IndexMeta;
+use schema::metadata::KeyspaceMeta;
+use schema::metadata::SchemaSnapshot;
+use schema::metadata::TableMeta;
+use schema::metadata::UserTypeMeta;
+use schema::metadata::ViewMeta;
+use schema::value_type::CassValueType;
+
+// DDL in the spirit of cqlsh's DESCRIBE. The output only depends on the
+// snapshot, so two snapshots of the same schema describe to the same text, and
+// statements come in an order that can be run as is against an empty cluster.
+
+impl SchemaSnapshot {
+    // Every keyspace but the ones Cassandra creates for itself.
+    pub fn to_cql(&self) -> String {
+        let keyspaces: Vec<String> = self.keyspaces
+                                         .values()
+                                         .filter(|keyspace| !keyspace.is_system())
+                                         .map(KeyspaceMeta::to_cql)
+                                         .collect();
+        keyspaces.join("\n")
+    }
+}
+
+impl KeyspaceMeta {
+    pub fn is_system(&self) -> bool {
+        match &self.name[..] {
+            "system" | "system_schema" | "system_auth" | "system_distributed" |
+            "system_traces" | "system_views" | "system_virtual_schema" => true,
+            _ => false,
+        }
+    }
+
+    // The keyspace, then its user types with every type after the ones it uses,
+    // its tables each followed by their indexes, functions, aggregates and
+    // finally views.
+    pub fn to_cql(&self) -> String {
+        let mut statements = vec![self.create_statement()];
+        for user_type in self.user_types_in_dependency_order() {
+            statements.push(user_type.to_cql());
+        }
+        for table in self.tables.values() {
+            statements.push(table.to_cql());
+        }
+        for function in &self.functions {
+            statements.push(function.to_cql());
+        }
+        for aggregate in &self.aggregates {
+            statements.push(aggregate.to_cql());
+        }
+        for view in self.views.values() {
+            statements.push(view.to_cql());
+        }
+        let mut cql = statements.join("\n\n");
+        cql.push('\n');
+        cql
+    }
+
+    pub fn create_statement(&self) -> String {
+        format!("CREATE KEYSPACE {} WITH {};",
+                quote_identifier(&self.name),
+                self.keyspace_options())
+    }
+
+    pub fn alter_statement(&self) -> String {
+        format!("ALTER KEYSPACE {} WITH {};",
+                quote_identifier(&self.name),
+                self.keyspace_options())
+    }
+
+    fn keyspace_options(&self) -> String {
+        let mut replication = BTreeMap::new();
+        replication.insert("class".to_owned(),
+                           self.replication.strategy.class_name().to_owned());
+        for (name, value) in &self.replication.options {
+            replication.insert(name.clone(), value.clone());
+        }
+        format!("replication = {} AND durable_writes = {}",
+                string_map(&replication),
+                self.durable_writes)
+    }
+
+    // Types are taken in name order, except that a type is held back until
+    // every type it has a field of has been emitted.
+    pub fn user_types_in_dependency_order(&self) -> Vec<&UserTypeMeta> {
+        let mut ordered = Vec::new();
+        let mut emitted = BTreeSet::new();
+        while ordered.len() < self.user_types.len() {
+            let ready = self.user_types.values().find(|user_type| {
+                !emitted.contains(&user_type.name) &&
+                user_type.referenced_types()
+                         .iter()
+                         .all(|name| name == &user_type.name || emitted.contains(name) ||
+                                     !self.user_types.contains_key(name))
+            });
+            // A cycle can't be created in CQL; should one show up anyway the
+            // rest are emitted in name order rather than looping forever.
+            let next = ready.or_else(|| {
+                self.user_types.values().find(|user_type| !emitted.contains(&user_type.name))
+            });
+            match next {
+                Some(user_type) => {
+                    emitted.insert(user_type.name.clone());
+                    ordered.push(user_type);
+                }
+                None => break,
+            }
+        }
+        ordered
+    }
+}
+
+impl UserTypeMeta {
+    pub fn to_cql(&self) -> String {
+        let fields: Vec<String> = self.fields
+                                      .iter()
+                                      .map(|&(ref name, ref data_type)| {
+                                          format!("    {} {}", quote_identifier(name), data_type)
+                                      })
+                                      .collect();
+        format!("CREATE TYPE {}.{} (\n{}\n);",
+                quote_identifier(&self.keyspace),
+                quote_identifier(&self.name),
+                fields.join(",\n"))
+    }
+
+    // The names of the user types this type has fields of.
+    pub fn referenced_types(&self) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        for &(_, ref data_type) in &self.fields {
+            collect_user_types(data_type, &mut names);
+        }
+        names
+    }
+}
+
+impl TableMeta {
+    // The table followed by its indexes.
+    pub fn to_cql(&self) -> String {
+        let mut statements = vec![self.create_statement()];
+        for index in self.indexes.values() {
+            statements.push(index.to_cql(&self.keyspace));
+        }
+        statements.join("\n")
+    }
+
+    pub fn create_statement(&self) -> String {
+        let mut cql = format!("CREATE TABLE {}.{} (\n",
+                              quote_identifier(&self.keyspace),
+                              quote_identifier(&self.name));
+        for column in &self.columns {
+            let _ = write!(cql, "    {} {}", quote_identifier(&column.name), column.data_type);
+            if column.kind == ColumnKind::Static {
+                cql.push_str(" static");
+            }
+            cql.push_str(",\n");
+        }
+        let _ = write!(cql,
+                       "    {}\n)",
+                       primary_key(&self.partition_key(), &self.clustering_key()));
+        cql.push_str(&with_clause(&self.clustering_key(), &self.options));
+        cql.push(';');
+        cql
+    }
+
+    // The user types the table's columns are declared with.
+    pub fn referenced_types(&self) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        for column in &self.columns {
+            collect_user_types(&column.data_type, &mut names);
+        }
+        names
+    }
+}
+
+impl IndexMeta {
+    pub fn to_cql(&self, keyspace: &str) -> String {
+        let on = format!("{} ON {}.{} ({})",
+                         quote_identifier(&self.name),
+                         quote_identifier(keyspace),
+                         quote_identifier(&self.table),
+                         self.target);
+        match self.kind {
+            IndexKind::Custom => {
+                let mut options = self.options.clone();
+                let class_name = options.remove("class_name").unwrap_or_default();
+                let mut cql = format!("CREATE CUSTOM INDEX {} USING {}",
+                                      on,
+                                      CqlValue::Text(class_name));
+                if !options.is_empty() {
+                    let _ = write!(cql, " WITH OPTIONS = {}", string_map(&options));
+                }
+                cql.push(';');
+                cql
+            }
+            _ => format!("CREATE INDEX {};", on),
+        }
+    }
+}
+
+impl FunctionMeta {
+    // Functions are overloaded on their argument types, so the name alone
+    // doesn't tell which one is meant. This is also how DROP FUNCTION names it.
+    pub fn signature(&self) -> String {
+        let argument_types: Vec<String> = self.arguments
+                                              .iter()
+                                              .map(|&(_, ref data_type)| {
+                                                  comparable_type(data_type).to_string()
+                                              })
+                                              .collect();
+        format!("{}.{}({})",
+                quote_identifier(&self.keyspace),
+                quote_identifier(&self.name),
+                argument_types.join(", "))
+    }
+
+    pub fn to_cql(&self) -> String {
+        let arguments: Vec<String> = self.arguments
+                                         .iter()
+                                         .map(|&(ref name, ref data_type)| {
+                                             format!("{} {}", quote_identifier(name), data_type)
+                                         })
+                                         .collect();
+        format!("CREATE FUNCTION {}.{}({})\n    {}\n    RETURNS {}\n    LANGUAGE {}\n    AS {};",
+                quote_identifier(&self.keyspace),
+                quote_identifier(&self.name),
+                arguments.join(", "),
+                if self.called_on_null_input {
+                    "CALLED ON NULL INPUT"
+                } else {
+                    "RETURNS NULL ON NULL INPUT"
+                },
+                self.return_type,
+                self.language,
+                function_body(&self.body))
+    }
+}
+
+impl AggregateMeta {
+    pub fn signature(&self) -> String {
+        let argument_types: Vec<String> = self.argument_types
+                                              .iter()
+                                              .map(|data_type| comparable_type(data_type).to_string())
+                                              .collect();
+        format!("{}.{}({})",
+                quote_identifier(&self.keyspace),
+                quote_identifier(&self.name),
+                argument_types.join(", "))
+    }
+
+    pub fn to_cql(&self) -> String {
+        let argument_types: Vec<String> = self.argument_types
+                                              .iter()
+                                              .map(|data_type| data_type.to_string())
+                                              .collect();
+        let mut cql = format!("CREATE AGGREGATE {}.{}({})\n    SFUNC {}\n    STYPE {}",
+                              quote_identifier(&self.keyspace),
+                              quote_identifier(&self.name),
+                              argument_types.join(", "),
+                              quote_identifier(&self.state_function),
+                              self.state_type);
+        if let Some(ref final_function) = self.final_function {
+            let _ = write!(cql, "\n    FINALFUNC {}", quote_identifier(final_function));
+        }
+        if let Some(ref initial_condition) = self.initial_condition {
+            let _ = write!(cql, "\n    INITCOND {}", initial_condition);
+        }
+        cql.push(';');
+        cql
+    }
+}
+
+impl ViewMeta {
+    pub fn to_cql(&self) -> String {
+        let selected = if self.include_all_columns {
+            "*".to_owned()
+        } else {
+            let names: Vec<String> = self.columns
+                                         .iter()
+                                         .map(|column| quote_identifier(&column.name))
+                                         .collect();
+            names.join(", ")
+        };
+        let mut cql = format!("CREATE MATERIALIZED VIEW {}.{} AS\n    SELECT {}\n    FROM {}.{}\n",
+                              quote_identifier(&self.keyspace),
+                              quote_identifier(&self.name),
+                              selected,
+                              quote_identifier(&self.keyspace),
+                              quote_identifier(&self.base_table));
+        if !self.where_clause.is_empty() {
+            let _ = writeln!(cql, "    WHERE {}", self.where_clause);
+        }
+        let _ = write!(cql,
+                       "    {}",
+                       primary_key(&self.partition_key(), &self.clustering_key()));
+        cql.push_str(&with_clause(&self.clustering_key(), &self.options));
+        cql.push(';');
+        cql
+    }
+}
+
+fn primary_key(partition_key: &[&ColumnMeta], clustering_key: &[&ColumnMeta]) -> String {
+    let partition_names: Vec<String> = partition_key.iter()
+                                                    .map(|column| quote_identifier(&column.name))
+                                                    .collect();
+    let mut key = if partition_names.len() == 1 {
+        partition_names[0].clone()
+    } else {
+        format!("({})", partition_names.join(", "))
+    };
+    for column in clustering_key {
+        let _ = write!(key, ", {}", quote_identifier(&column.name));
+    }
+    format!("PRIMARY KEY ({})", key)
+}
+
+fn with_clause(clustering_key: &[&ColumnMeta], options: &BTreeMap<String, CqlValue>) -> String {
+    let mut clauses = Vec::new();
+    if !clustering_key.is_empty() {
+        let order: Vec<String> = clustering_key.iter()
+                                               .map(|column| {
+                                                   let direction = match column.clustering_order {
+                                                       ClusteringOrder::Desc => "DESC",
+                                                       _ => "ASC",
+                                                   };
+                                                   format!("{} {}",
+                                                           quote_identifier(&column.name),
+                                                           direction)
+                                               })
+                                               .collect();
+        clauses.push(format!("CLUSTERING ORDER BY ({})", order.join(", ")));
+    }
+    for (name, value) in options {
+        clauses.push(format!("{} = {}", name, value));
+    }
+    if clauses.is_empty() {
+        String::new()
+    } else {
+        format!(" WITH {}", clauses.join("\n    AND "))
+    }
+}
+
+fn string_map(map: &BTreeMap<String, String>) -> String {
+    let entries: Vec<String> = map.iter()
+                                  .map(|(key, value)| {
+                                      format!("{}: {}",
+                                              CqlValue::Text(key.clone()),
+                                              CqlValue::Text(value.clone()))
+                                  })
+                                  .collect();
+    format!("{{{}}}", entries.join(", "))
+}
+
+// Bodies are dollar quoted unless they contain `$$` themselves.
+fn function_body(body: &str) -> String {
+    if body.contains("$$") {
+        CqlValue::Text(body.to_owned()).to_string()
+    } else {
+        format!("$${}$$", body)
+    }
+}
+
+fn collect_user_types(cql_type: &CqlType, names: &mut BTreeSet<String>) {
+    match *cql_type {
+        CqlType::Udt { ref name, .. } => {
+            names.insert(name.clone());
+        }
+        CqlType::List(ref item) | CqlType::Set(ref item) | CqlType::Frozen(ref item) => {
+            collect_user_types(item, names)
+        }
+        CqlType::Map(ref key, ref value) => {
+            collect_user_types(key, names);
+            collect_user_types(value, names);
+        }
+        CqlType::Tuple(ref items) => {
+            for item in items {
+                collect_user_types(item, names);
+            }
+        }
+        CqlType::Native(_) | CqlType::Custom(_) => {}
+    }
+}
+
+// The type with what doesn't change its meaning taken out: varchar is text, and
+// user types are named without their keyspace, which is always the keyspace of
+// whatever uses them.
+pub(crate) fn comparable_type(data_type: &CqlType) -> CqlType {
+    comparable(data_type.clone())
+}
+
+fn comparable(cql_type: CqlType) -> CqlType {
+    match cql_type {
+        CqlType::Native(CassValueType::VARCHAR) => CqlType::Native(CassValueType::TEXT),
+        CqlType::List(item) => CqlType::List(Box::new(comparable(*item))),
+        CqlType::Set(item) => CqlType::Set(Box::new(comparable(*item))),
+        CqlType::Map(key, value) => {
+            CqlType::Map(Box::new(comparable(*key)), Box::new(comparable(*value)))
+        }
+        CqlType::Tuple(items) => CqlType::Tuple(items.into_iter().map(comparable).collect()),
+        CqlType::Udt { name, .. } => CqlType::Udt { keyspace: None, name: name },
+        CqlType::Frozen(inner) => CqlType::Frozen(Box::new(comparable(*inner))),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schema::metadata::Replication;
+    use schema::metadata::ReplicationStrategy;
+    use super::*;
+
+    fn cql_type(type_string: &str) -> CqlType {
+        CqlType::parse(type_string).unwrap()
+    }
+
+    fn column(name: &str, kind: ColumnKind, data_type: &str) -> ColumnMeta {
+        ColumnMeta {
+            name: name.to_owned(),
+            kind: kind,
+            position: 0,
+            data_type: cql_type(data_type),
+            clustering_order: match kind {
+                ColumnKind::Clustering => ClusteringOrder::Desc,
+                _ => ClusteringOrder::None,
+            },
+        }
+    }
+
+    fn index(name: &str, target: &str) -> IndexMeta {
+        IndexMeta {
+            name: name.to_owned(),
+            table: "customers".to_owned(),
+            kind: IndexKind::Composites,
+            target: target.to_owned(),
+            options: BTreeMap::new(),
+        }
+    }
+
+    // "address" sorts before the "zone" it uses, and the table, index, view and
+    // aggregate each depend on something defined before them.
+    fn shop() -> SchemaSnapshot {
+        let mut options = BTreeMap::new();
+        options.insert("dc1".to_owned(), "3".to_owned());
+        let mut shop = KeyspaceMeta::new("shop",
+                                         Replication {
+                                             strategy: ReplicationStrategy::NetworkTopology,
+                                             options: options,
+                                         });
+        shop.durable_writes = false;
+        shop.user_types.insert("zone".to_owned(),
+                               UserTypeMeta {
+                                   keyspace: "shop".to_owned(),
+                                   name: "zone".to_owned(),
+                                   fields: vec![("code".to_owned(), cql_type("int")),
+                                                ("Name".to_owned(), cql_type("text"))],
+                               });
+        shop.user_types.insert("address".to_owned(),
+                               UserTypeMeta {
+                                   keyspace: "shop".to_owned(),
+                                   name: "address".to_owned(),
+                                   fields: vec![("street".to_owned(), cql_type("text")),
+                                                ("zones".to_owned(),
+                                                 cql_type("list<frozen<zone>>"))],
+                               });
+        let mut table = TableMeta {
+            keyspace: "shop".to_owned(),
+            name: "customers".to_owned(),
+            columns: vec![column("region", ColumnKind::PartitionKey, "text"),
+                          column("id", ColumnKind::Clustering, "timeuuid"),
+                          column("home", ColumnKind::Regular, "frozen<address>"),
+                          column("name", ColumnKind::Regular, "text"),
+                          column("tags", ColumnKind::Regular, "set<text>")],
+            options: BTreeMap::new(),
+            indexes: BTreeMap::new(),
+        };
+        table.options.insert("comment".to_owned(), CqlValue::Text("it's".to_owned()));
+        table.indexes.insert("customers_name_idx".to_owned(), index("customers_name_idx", "name"));
+        table.indexes.insert("customers_tags_idx".to_owned(),
+                             index("customers_tags_idx", "values(tags)"));
+        shop.tables.insert("customers".to_owned(), table);
+        shop.functions.push(FunctionMeta {
+            keyspace: "shop".to_owned(),
+            name: "count_state".to_owned(),
+            arguments: vec![("state".to_owned(), cql_type("tuple<int, bigint>")),
+                            ("value".to_owned(), cql_type("int"))],
+            return_type: cql_type("tuple<int, bigint>"),
+            language: "java".to_owned(),
+            body: "return state;".to_owned(),
+            called_on_null_input: true,
+        });
+        shop.aggregates.push(AggregateMeta {
+            keyspace: "shop".to_owned(),
+            name: "count_values".to_owned(),
+            argument_types: vec![cql_type("int")],
+            state_function: "count_state".to_owned(),
+            state_type: cql_type("tuple<int, bigint>"),
+            final_function: None,
+            initial_condition: Some("(0, 0)".to_owned()),
+            return_type: cql_type("tuple<int, bigint>"),
+        });
+        shop.views.insert("customers_by_name".to_owned(),
+                          ViewMeta {
+                              keyspace: "shop".to_owned(),
+                              name: "customers_by_name".to_owned(),
+                              base_table: "customers".to_owned(),
+                              include_all_columns: false,
+                              where_clause: "name IS NOT NULL AND region IS NOT NULL AND id IS \
+                                             NOT NULL"
+                                                .to_owned(),
+                              columns: vec![column("name", ColumnKind::PartitionKey, "text"),
+                                            column("region", ColumnKind::Clustering, "text"),
+                                            column("id", ColumnKind::Clustering, "timeuuid")],
+                              options: BTreeMap::new(),
+                          });
+        let mut snapshot = SchemaSnapshot::default();
+        snapshot.keyspaces.insert("shop".to_owned(), shop);
+        snapshot
+    }
+
+    #[test]
+    fn statements_come_after_what_they_use() {
+        let cql = shop().to_cql();
+        let position = |statement: &str| {
+            cql.find(statement).unwrap_or_else(|| panic!("{} is missing from\n{}", statement, cql))
+        };
+        assert!(position("CREATE TYPE shop.zone") < position("CREATE TYPE shop.address"));
+        assert!(position("CREATE TYPE shop.address") < position("CREATE TABLE shop.customers"));
+        assert!(position("CREATE TABLE shop.customers") <
+                position("CREATE INDEX customers_name_idx"));
+        assert!(position("CREATE FUNCTION shop.count_state") <
+                position("CREATE AGGREGATE shop.count_values"));
+        assert!(position("CREATE TABLE shop.customers") <
+                position("CREATE MATERIALIZED VIEW shop.customers_by_name"));
+    }
+
+    #[test]
+    fn names_and_options_are_quoted() {
+        let cql = shop().to_cql();
+        assert!(cql.starts_with("CREATE KEYSPACE shop WITH replication = {'class': \
+                                 'NetworkTopologyStrategy', 'dc1': '3'} AND durable_writes = \
+                                 false;\n"));
+        assert!(cql.contains("    \"Name\" text\n"));
+        assert!(cql.contains(") WITH CLUSTERING ORDER BY (id DESC)\n    AND comment = 'it''s';"));
+    }
+}
@@ -0,0 +1,803 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
+use std::path::Path;
+
+use schema::cql_type::CqlType;
+use schema::cql_type::quote_identifier;
+use schema::cql_value::CqlValue;
+use schema::ddl::DdlError;
+use schema::ddl::normalize_clause;
+use schema::ddl::split_statements;
+use schema::describe::comparable_type;
+use schema::metadata::ColumnKind;
+use schema::metadata::ColumnMeta;
+use schema::metadata::IndexKind;
+use schema::metadata::IndexMeta;
+use schema::metadata::KeyspaceMeta;
+use schema::metadata::SchemaSnapshot;
+use schema::metadata::TableMeta;
+use schema::metadata::ViewMeta;
+
+// What it takes to go from one schema to another. Only what the desired schema
+// says counts: an option it leaves out may have any value, and keyspaces it
+// doesn't mention are left alone when comparing against a directory of .cql
+// files.
+#[derive(Debug,Clone,PartialEq,Default)]
+pub struct SchemaDiff {
+    pub differences: Vec<Difference>,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct Difference {
+    pub kind: DiffKind,
+    pub object: SchemaObject,
+    // The dotted name of what changed, e.g. keyspace.table.column.
+    pub path: String,
+    pub detail: String,
+    // Empty for changes that can't be made in place.
+    pub statements: Vec<String>,
+    // False for changes Cassandra can't make to an existing object, like a new
+    // primary key; those need the object dropped and created again, data and
+    // all.
+    pub in_place: bool,
+    phase: Phase,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum SchemaObject {
+    Keyspace,
+    Type,
+    Field,
+    Table,
+    Column,
+    Index,
+    Function,
+    Aggregate,
+    View,
+    Option,
+}
+
+// Statements run in this order, so that everything exists before what uses it
+// is created, and is only dropped once nothing uses it.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,PartialOrd,Ord)]
+enum Phase {
+    Keyspaces,
+    Types,
+    Tables,
+    Indexes,
+    Functions,
+    Aggregates,
+    Views,
+    DropViews,
+    DropAggregates,
+    DropFunctions,
+    DropIndexes,
+    DropColumns,
+    DropTables,
+    DropTypes,
+    DropKeyspaces,
+}
+
+impl SchemaDiff {
+    // System keyspaces are never compared. Fails if an added keyspace's
+    // definition can't be split back into statements.
+    pub fn between(current: &SchemaSnapshot,
+                   desired: &SchemaSnapshot)
+                   -> Result<SchemaDiff, DdlError> {
+        let mut diff = SchemaDiff::default();
+        for (name, keyspace) in &desired.keyspaces {
+            if keyspace.is_system() {
+                continue;
+            }
+            match current.keyspaces.get(name) {
+                Some(current) => diff.keyspace(current, keyspace),
+                None => {
+                    let statements = try!(split_statements(name, &keyspace.to_cql()))
+                                         .into_iter()
+                                         .map(|statement| format!("{};", statement))
+                                         .collect();
+                    diff.push(DiffKind::Added,
+                              SchemaObject::Keyspace,
+                              name.clone(),
+                              String::new(),
+                              Phase::Keyspaces,
+                              statements)
+                }
+            }
+        }
+        for (name, keyspace) in &current.keyspaces {
+            if !keyspace.is_system() && !desired.keyspaces.contains_key(name) {
+                diff.push(DiffKind::Removed,
+                          SchemaObject::Keyspace,
+                          name.clone(),
+                          String::new(),
+                          Phase::DropKeyspaces,
+                          vec![format!("DROP KEYSPACE {};", quote_identifier(name))]);
+            }
+        }
+        Ok(diff)
+    }
+
+    // A live schema, as LoadSchema::load reads it, against the one the .cql
+    // files in a directory describe. Keyspaces that are not in any of the files
+    // are not compared.
+    pub fn against_directory<P: AsRef<Path>>(current: &SchemaSnapshot,
+                                             directory: P)
+                                             -> Result<SchemaDiff, DdlError> {
+        let desired = try!(SchemaSnapshot::from_directory(directory));
+        let mut current = current.clone();
+        current.keyspaces.retain(|name, _| desired.keyspaces.contains_key(name));
+        SchemaDiff::between(&current, &desired)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    // Every statement, in an order that can be run as is.
+    pub fn statements(&self) -> Vec<String> {
+        let mut differences: Vec<&Difference> = self.differences.iter().collect();
+        differences.sort_by_key(|difference| difference.phase);
+        differences.iter()
+                   .flat_map(|difference| difference.statements.iter().cloned())
+                   .collect()
+    }
+
+    // The changes no statement can make.
+    pub fn blocking(&self) -> Vec<&Difference> {
+        self.differences.iter().filter(|difference| !difference.in_place).collect()
+    }
+
+    fn push(&mut self,
+            kind: DiffKind,
+            object: SchemaObject,
+            path: String,
+            detail: String,
+            phase: Phase,
+            statements: Vec<String>) {
+        self.differences.push(Difference {
+            kind: kind,
+            object: object,
+            path: path,
+            detail: detail,
+            statements: statements,
+            in_place: true,
+            phase: phase,
+        });
+    }
+
+    fn push_blocking(&mut self, object: SchemaObject, path: String, detail: String) {
+        self.differences.push(Difference {
+            kind: DiffKind::Changed,
+            object: object,
+            path: path,
+            detail: detail,
+            statements: Vec::new(),
+            in_place: false,
+            phase: Phase::Keyspaces,
+        });
+    }
+
+    fn keyspace(&mut self, current: &KeyspaceMeta, desired: &KeyspaceMeta) {
+        let replication_changed =
+            short_class_name(current.replication.strategy.class_name()) !=
+            short_class_name(desired.replication.strategy.class_name()) ||
+            current.replication.options.len() != desired.replication.options.len() ||
+            desired.replication.options.iter().any(|(name, value)| {
+                current.replication
+                       .options
+                       .get(name)
+                       .map_or(true, |existing| !same_text(name, existing, value))
+            });
+        if replication_changed || current.durable_writes != desired.durable_writes {
+            self.push(DiffKind::Changed,
+                      SchemaObject::Keyspace,
+                      desired.name.clone(),
+                      "replication or durable_writes".to_owned(),
+                      Phase::Keyspaces,
+                      vec![desired.alter_statement()]);
+        }
+        self.user_types(current, desired);
+        for (name, table) in &desired.tables {
+            match current.tables.get(name) {
+                Some(current) => self.table(current, table),
+                None => {
+                    let mut statements = vec![table.create_statement()];
+                    for index in table.indexes.values() {
+                        statements.push(index.to_cql(&table.keyspace));
+                    }
+                    self.push(DiffKind::Added,
+                              SchemaObject::Table,
+                              path(&[&desired.name, name]),
+                              String::new(),
+                              Phase::Tables,
+                              statements)
+                }
+            }
+        }
+        for name in current.tables.keys() {
+            if !desired.tables.contains_key(name) {
+                self.push(DiffKind::Removed,
+                          SchemaObject::Table,
+                          path(&[&desired.name, name]),
+                          String::new(),
+                          Phase::DropTables,
+                          vec![format!("DROP TABLE {}.{};",
+                                       quote_identifier(&desired.name),
+                                       quote_identifier(name))]);
+            }
+        }
+        self.functions(current, desired);
+        self.aggregates(current, desired);
+        self.views(current, desired);
+    }
+
+    // Fields can only be added at the end of a type; anything else means
+    // dropping the type and every column of it.
+    fn user_types(&mut self, current: &KeyspaceMeta, desired: &KeyspaceMeta) {
+        for user_type in desired.user_types_in_dependency_order() {
+            let type_path = path(&[&desired.name, &user_type.name]);
+            let existing = match current.user_types.get(&user_type.name) {
+                Some(existing) => existing,
+                None => {
+                    self.push(DiffKind::Added,
+                              SchemaObject::Type,
+                              type_path,
+                              String::new(),
+                              Phase::Types,
+                              vec![user_type.to_cql()]);
+                    continue;
+                }
+            };
+            for (i, &(ref name, ref data_type)) in user_type.fields.iter().enumerate() {
+                let field_path = format!("{}.{}", type_path, name);
+                match existing.fields.get(i) {
+                    Some(&(ref existing_name, ref existing_type)) => {
+                        if existing_name != name {
+                            self.push_blocking(SchemaObject::Field,
+                                               field_path,
+                                               format!("field {} is {} in the current schema",
+                                                       i + 1,
+                                                       existing_name));
+                        } else if !same_type(existing_type, data_type) {
+                            self.push_blocking(SchemaObject::Field,
+                                               field_path,
+                                               type_change(existing_type, data_type));
+                        }
+                    }
+                    None => {
+                        self.push(DiffKind::Added,
+                                  SchemaObject::Field,
+                                  field_path,
+                                  data_type.to_string(),
+                                  Phase::Types,
+                                  vec![format!("ALTER TYPE {}.{} ADD {} {};",
+                                               quote_identifier(&desired.name),
+                                               quote_identifier(&user_type.name),
+                                               quote_identifier(name),
+                                               data_type)])
+                    }
+                }
+            }
+            for &(ref name, _) in existing.fields.iter().skip(user_type.fields.len()) {
+                self.push_blocking(SchemaObject::Field,
+                                   format!("{}.{}", type_path, name),
+                                   "fields can't be dropped from a type".to_owned());
+            }
+        }
+        for user_type in current.user_types_in_dependency_order().into_iter().rev() {
+            if !desired.user_types.contains_key(&user_type.name) {
+                self.push(DiffKind::Removed,
+                          SchemaObject::Type,
+                          path(&[&desired.name, &user_type.name]),
+                          String::new(),
+                          Phase::DropTypes,
+                          vec![format!("DROP TYPE {}.{};",
+                                       quote_identifier(&desired.name),
+                                       quote_identifier(&user_type.name))]);
+            }
+        }
+    }
+
+    fn table(&mut self, current: &TableMeta, desired: &TableMeta) {
+        let table_path = path(&[&desired.keyspace, &desired.name]);
+        let table_name = format!("{}.{}",
+                                 quote_identifier(&desired.keyspace),
+                                 quote_identifier(&desired.name));
+        if !same_key(&current.partition_key(), &desired.partition_key()) ||
+           !same_key(&current.clustering_key(), &desired.clustering_key()) {
+            // The table has to be created again, so altering its columns,
+            // options or indexes first would be wasted, or fail.
+            self.push_blocking(SchemaObject::Table,
+                               table_path,
+                               "the primary key or clustering order changed".to_owned());
+            return;
+        }
+        for column in &desired.columns {
+            let column_path = format!("{}.{}", table_path, column.name);
+            let is_key = column.kind == ColumnKind::PartitionKey ||
+                         column.kind == ColumnKind::Clustering;
+            match current.column(&column.name) {
+                // Key columns are covered by the primary key check.
+                _ if is_key => {}
+                Some(existing) => {
+                    if !same_type(&existing.data_type, &column.data_type) {
+                        self.push_blocking(SchemaObject::Column,
+                                           column_path,
+                                           type_change(&existing.data_type, &column.data_type));
+                    } else if existing.kind != column.kind {
+                        self.push_blocking(SchemaObject::Column,
+                                           column_path,
+                                           format!("{:?} in the current schema, {:?} wanted",
+                                                   existing.kind,
+                                                   column.kind));
+                    }
+                }
+                None => {
+                    let statement = format!("ALTER TABLE {} ADD {} {}{};",
+                                            table_name,
+                                            quote_identifier(&column.name),
+                                            column.data_type,
+                                            if column.kind == ColumnKind::Static {
+                                                " static"
+                                            } else {
+                                                ""
+                                            });
+                    self.push(DiffKind::Added,
+                              SchemaObject::Column,
+                              column_path,
+                              column.data_type.to_string(),
+                              Phase::Tables,
+                              vec![statement])
+                }
+            }
+        }
+        for column in &current.columns {
+            let is_key = column.kind == ColumnKind::PartitionKey ||
+                         column.kind == ColumnKind::Clustering;
+            if !is_key && desired.column(&column.name).is_none() {
+                self.push(DiffKind::Removed,
+                          SchemaObject::Column,
+                          format!("{}.{}", table_path, column.name),
+                          String::new(),
+                          Phase::DropColumns,
+                          vec![format!("ALTER TABLE {} DROP {};",
+                                       table_name,
+                                       quote_identifier(&column.name))]);
+            }
+        }
+        self.options(&table_path,
+                     &format!("ALTER TABLE {}", table_name),
+                     &current.options,
+                     &desired.options);
+        self.indexes(current, desired);
+    }
+
+    // An index can't be altered, so a changed one is dropped and created
+    // again.
+    fn indexes(&mut self, current: &TableMeta, desired: &TableMeta) {
+        for (name, index) in &desired.indexes {
+            let index_path = path(&[&desired.keyspace, name]);
+            match current.indexes.get(name) {
+                Some(existing) => {
+                    if !same_index(existing, index) {
+                        self.push(DiffKind::Changed,
+                                  SchemaObject::Index,
+                                  index_path,
+                                  format!("on {}", index.target),
+                                  Phase::Indexes,
+                                  vec![drop_index(&desired.keyspace, name),
+                                       index.to_cql(&desired.keyspace)]);
+                    }
+                }
+                None => {
+                    self.push(DiffKind::Added,
+                              SchemaObject::Index,
+                              index_path,
+                              format!("on {}", index.target),
+                              Phase::Indexes,
+                              vec![index.to_cql(&desired.keyspace)])
+                }
+            }
+        }
+        for name in current.indexes.keys() {
+            if !desired.indexes.contains_key(name) {
+                self.push(DiffKind::Removed,
+                          SchemaObject::Index,
+                          path(&[&desired.keyspace, name]),
+                          String::new(),
+                          Phase::DropIndexes,
+                          vec![drop_index(&desired.keyspace, name)]);
+            }
+        }
+    }
+
+    // The return type of a function or aggregate is the one thing CREATE OR
+    // REPLACE can't change.
+    fn functions(&mut self, current: &KeyspaceMeta, desired: &KeyspaceMeta) {
+        for function in &desired.functions {
+            let signature = function.signature();
+            let existing = current.functions
+                                  .iter()
+                                  .find(|existing| existing.signature() == signature);
+            match existing {
+                Some(existing) => {
+                    if !same_type(&existing.return_type, &function.return_type) {
+                        self.push_blocking(SchemaObject::Function,
+                                           signature,
+                                           type_change(&existing.return_type,
+                                                       &function.return_type));
+                    } else if existing.body.trim() != function.body.trim() ||
+                              !existing.language.eq_ignore_ascii_case(&function.language) ||
+                              existing.called_on_null_input != function.called_on_null_input {
+                        self.push(DiffKind::Changed,
+                                  SchemaObject::Function,
+                                  signature,
+                                  String::new(),
+                                  Phase::Functions,
+                                  vec![or_replace(&function.to_cql())]);
+                    }
+                }
+                None => {
+                    self.push(DiffKind::Added,
+                              SchemaObject::Function,
+                              signature,
+                              String::new(),
+                              Phase::Functions,
+                              vec![function.to_cql()])
+                }
+            }
+        }
+        for function in &current.functions {
+            let signature = function.signature();
+            if !desired.functions.iter().any(|wanted| wanted.signature() == signature) {
+                self.push(DiffKind::Removed,
+                          SchemaObject::Function,
+                          signature.clone(),
+                          String::new(),
+                          Phase::DropFunctions,
+                          vec![format!("DROP FUNCTION {};", signature)]);
+            }
+        }
+    }
+
+    fn aggregates(&mut self, current: &KeyspaceMeta, desired: &KeyspaceMeta) {
+        for aggregate in &desired.aggregates {
+            let signature = aggregate.signature();
+            let existing = current.aggregates
+                                  .iter()
+                                  .find(|existing| existing.signature() == signature);
+            match existing {
+                Some(existing) => {
+                    if !same_type(&existing.return_type, &aggregate.return_type) {
+                        self.push_blocking(SchemaObject::Aggregate,
+                                           signature,
+                                           type_change(&existing.return_type,
+                                                       &aggregate.return_type));
+                    } else if existing.state_function != aggregate.state_function ||
+                              !same_type(&existing.state_type, &aggregate.state_type) ||
+                              existing.final_function != aggregate.final_function ||
+                              existing.initial_condition.as_ref().map(|c| normalize_clause(c)) !=
+                              aggregate.initial_condition.as_ref().map(|c| normalize_clause(c)) {
+                        self.push(DiffKind::Changed,
+                                  SchemaObject::Aggregate,
+                                  signature,
+                                  String::new(),
+                                  Phase::Aggregates,
+                                  vec![or_replace(&aggregate.to_cql())]);
+                    }
+                }
+                None => {
+                    self.push(DiffKind::Added,
+                              SchemaObject::Aggregate,
+                              signature,
+                              String::new(),
+                              Phase::Aggregates,
+                              vec![aggregate.to_cql()])
+                }
+            }
+        }
+        for aggregate in &current.aggregates {
+            let signature = aggregate.signature();
+            if !desired.aggregates.iter().any(|wanted| wanted.signature() == signature) {
+                self.push(DiffKind::Removed,
+                          SchemaObject::Aggregate,
+                          signature.clone(),
+                          String::new(),
+                          Phase::DropAggregates,
+                          vec![format!("DROP AGGREGATE {};", signature)]);
+            }
+        }
+    }
+
+    // Only a view's options can be altered.
+    fn views(&mut self, current: &KeyspaceMeta, desired: &KeyspaceMeta) {
+        for (name, view) in &desired.views {
+            let view_path = path(&[&desired.name, name]);
+            match current.views.get(name) {
+                Some(existing) => {
+                    if !same_view_definition(existing, view) {
+                        self.push_blocking(SchemaObject::View,
+                                           view_path.clone(),
+                                           "the select, where clause or primary key changed"
+                                               .to_owned());
+                    }
+                    self.options(&view_path,
+                                 &format!("ALTER MATERIALIZED VIEW {}.{}",
+                                          quote_identifier(&desired.name),
+                                          quote_identifier(name)),
+                                 &existing.options,
+                                 &view.options);
+                }
+                None => {
+                    self.push(DiffKind::Added,
+                              SchemaObject::View,
+                              view_path,
+                              String::new(),
+                              Phase::Views,
+                              vec![view.to_cql()])
+                }
+            }
+        }
+        for name in current.views.keys() {
+            if !desired.views.contains_key(name) {
+                self.push(DiffKind::Removed,
+                          SchemaObject::View,
+                          path(&[&desired.name, name]),
+                          String::new(),
+                          Phase::DropViews,
+                          vec![format!("DROP MATERIALIZED VIEW {}.{};",
+                                       quote_identifier(&desired.name),
+                                       quote_identifier(name))]);
+            }
+        }
+    }
+
+    fn options(&mut self,
+               object_path: &str,
+               alter: &str,
+               current: &BTreeMap<String, CqlValue>,
+               desired: &BTreeMap<String, CqlValue>) {
+        let phase = if alter.starts_with("ALTER TABLE") {
+            Phase::Tables
+        } else {
+            Phase::Views
+        };
+        for (name, value) in desired {
+            let matches = current.get(name)
+                                 .map_or(false, |existing| same_option(name, existing, value));
+            if !matches {
+                let detail = match current.get(name) {
+                    Some(existing) => format!("{}: {} -> {}", name, existing, value),
+                    None => format!("{}: {}", name, value),
+                };
+                self.push(DiffKind::Changed,
+                          SchemaObject::Option,
+                          object_path.to_owned(),
+                          detail,
+                          phase,
+                          vec![format!("{} WITH {} = {};", alter, name, value)]);
+            }
+        }
+    }
+}
+
+impl Display for SchemaDiff {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for difference in &self.differences {
+            try!(writeln!(f, "{}", difference));
+        }
+        Ok(())
+    }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let sign = match self.kind {
+            DiffKind::Added => '+',
+            DiffKind::Removed => '-',
+            DiffKind::Changed => '~',
+        };
+        try!(write!(f, "{} {:?} {}", sign, self.object, self.path));
+        if !self.detail.is_empty() {
+            try!(write!(f, ": {}", self.detail));
+        }
+        if !self.in_place {
+            try!(write!(f, " (can't be done in place)"));
+        }
+        Ok(())
+    }
+}
+
+fn path(names: &[&str]) -> String {
+    names.join(".")
+}
+
+fn drop_index(keyspace: &str, name: &str) -> String {
+    format!("DROP INDEX {}.{};", quote_identifier(keyspace), quote_identifier(name))
+}
+
+fn or_replace(create: &str) -> String {
+    create.replacen("CREATE ", "CREATE OR REPLACE ", 1)
+}
+
+fn same_type(a: &CqlType, b: &CqlType) -> bool {
+    comparable_type(a) == comparable_type(b)
+}
+
+fn type_change(current: &CqlType, desired: &CqlType) -> String {
+    format!("{} -> {}", current, desired)
+}
+
+fn same_key(a: &[&ColumnMeta], b: &[&ColumnMeta]) -> bool {
+    a.len() == b.len() &&
+    a.iter().zip(b.iter()).all(|(a, b)| {
+        a.name == b.name && a.clustering_order == b.clustering_order &&
+        same_type(&a.data_type, &b.data_type)
+    })
+}
+
+fn same_index(a: &IndexMeta, b: &IndexMeta) -> bool {
+    let custom = |index: &IndexMeta| index.kind == IndexKind::Custom;
+    normalize_clause(&a.target) == normalize_clause(&b.target) && custom(a) == custom(b) &&
+    b.options.iter().all(|(name, value)| {
+        a.options.get(name).map_or(false, |existing| same_text(name, existing, value))
+    })
+}
+
+fn same_view_definition(a: &ViewMeta, b: &ViewMeta) -> bool {
+    let names = |view: &ViewMeta| -> Vec<String> {
+        view.columns.iter().map(|column| column.name.clone()).collect()
+    };
+    a.base_table == b.base_table &&
+    normalize_clause(&a.where_clause) == normalize_clause(&b.where_clause) &&
+    same_key(&a.partition_key(), &b.partition_key()) &&
+    same_key(&a.clustering_key(), &b.clustering_key()) &&
+    (a.include_all_columns && b.include_all_columns || names(a) == names(b))
+}
+
+// Maps only need the entries the desired schema gives.
+fn same_option(name: &str, current: &CqlValue, desired: &CqlValue) -> bool {
+    match (current, desired) {
+        (&CqlValue::Map(ref current), &CqlValue::Map(ref desired)) => {
+            let current: BTreeMap<String, &CqlValue> = current.iter()
+                                                              .map(|&(ref key, ref value)| {
+                                                                  (option_text(key), value)
+                                                              })
+                                                              .collect();
+            desired.iter().all(|&(ref key, ref value)| {
+                let key = option_text(key);
+                current.get(&key).map_or(false, |existing| same_option(&key, existing, value))
+            })
+        }
+        _ => same_text(name, &option_text(current), &option_text(desired)),
+    }
+}
+
+// Numbers may be written as text ('3') or as floats (0.10 for 0.1), and
+// classes with or without their package. Class names and the settings Cassandra
+// reads as enums, like caching's 'ALL' or speculative_retry's '99PERCENTILE',
+// may differ in case; anything else, like a comment, has to match exactly.
+fn same_text(name: &str, current: &str, desired: &str) -> bool {
+    if let (Ok(current), Ok(desired)) = (current.parse::<f64>(), desired.parse::<f64>()) {
+        return current == desired;
+    }
+    match name {
+        "class" | "class_name" | "sstable_compression" => {
+            short_class_name(current).eq_ignore_ascii_case(short_class_name(desired))
+        }
+        "caching" | "keys" | "rows_per_partition" | "speculative_retry" |
+        "additional_write_policy" => current.eq_ignore_ascii_case(desired),
+        _ => current == desired,
+    }
+}
+
+fn option_text(value: &CqlValue) -> String {
+    match value.as_str() {
+        Some(text) => text.to_owned(),
+        None => value.to_string(),
+    }
+}
+
+fn short_class_name(class_name: &str) -> &str {
+    match class_name.rfind('.') {
+        Some(dot) => &class_name[dot + 1..],
+        None => class_name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schema::metadata::SchemaSnapshot;
+    use super::*;
+
+    fn snapshot(tables: &str) -> SchemaSnapshot {
+        SchemaSnapshot::from_cql(&format!("CREATE KEYSPACE ks WITH replication = \
+                                           {{'class': 'SimpleStrategy', \
+                                           'replication_factor': 1}};
+                                           USE ks;
+                                           {}",
+                                          tables))
+            .unwrap()
+    }
+
+    #[test]
+    fn added_keyspaces_are_created_whole() {
+        let desired = snapshot("CREATE TYPE \"Point\" (x int, y int);
+                                CREATE TABLE t (id int PRIMARY KEY, p frozen<\"Point\">);");
+        let diff = SchemaDiff::between(&SchemaSnapshot::default(), &desired).unwrap();
+        let statements = diff.statements();
+        assert_eq!(statements.len(), 3);
+        assert!(statements[0].starts_with("CREATE KEYSPACE ks"));
+        assert!(statements[1].starts_with("CREATE TYPE ks.\"Point\""));
+        assert!(statements[2].starts_with("CREATE TABLE ks.t"));
+        assert!(statements.iter().all(|statement| statement.ends_with(';')));
+    }
+
+    #[test]
+    fn columns_are_added_and_dropped() {
+        let current = snapshot("CREATE TABLE t (id int PRIMARY KEY, old text, \
+                                nested frozen<map<varchar, frozen<list<int>>>>);");
+        let desired = snapshot("CREATE TABLE t (id int PRIMARY KEY, \"New\" set<text>, \
+                                nested frozen<map<text, frozen<list<int>>>>);");
+        let diff = SchemaDiff::between(&current, &desired).unwrap();
+        assert!(diff.blocking().is_empty());
+        assert_eq!(diff.statements(),
+                   ["ALTER TABLE ks.t ADD \"New\" set<text>;", "ALTER TABLE ks.t DROP old;"]);
+        assert!(SchemaDiff::between(&desired, &desired).unwrap().is_empty());
+    }
+
+    #[test]
+    fn changed_column_types_are_blocking() {
+        let current = snapshot("CREATE TABLE t (id int PRIMARY KEY, v frozen<list<int>>);");
+        let desired = snapshot("CREATE TABLE t (id int PRIMARY KEY, v frozen<set<int>>);");
+        let diff = SchemaDiff::between(&current, &desired).unwrap();
+        assert!(diff.statements().is_empty());
+        let blocking = diff.blocking();
+        assert_eq!(blocking.len(), 1);
+        assert_eq!(blocking[0].path, "ks.t.v");
+        assert_eq!(blocking[0].detail, "frozen<list<int>> -> frozen<set<int>>");
+    }
+
+    #[test]
+    fn new_primary_keys_leave_the_table_alone() {
+        let current = snapshot("CREATE TABLE t (id int, c int, old text, PRIMARY KEY (id, c)) \
+                                WITH comment = 'before';
+                                CREATE TABLE u (id int PRIMARY KEY);");
+        let desired = snapshot("CREATE TABLE t (id int, c int, new text, PRIMARY KEY (id, c)) \
+                                WITH CLUSTERING ORDER BY (c DESC) AND comment = 'after';
+                                CREATE INDEX ON t (new);
+                                CREATE TABLE u (id int PRIMARY KEY, added int);");
+        let diff = SchemaDiff::between(&current, &desired).unwrap();
+        let blocking = diff.blocking();
+        assert_eq!(blocking.len(), 1);
+        assert_eq!(blocking[0].object, SchemaObject::Table);
+        assert_eq!(blocking[0].path, "ks.t");
+        assert_eq!(diff.statements(), ["ALTER TABLE ks.u ADD added int;"]);
+    }
+
+    #[test]
+    fn only_class_names_and_enum_options_ignore_case() {
+        let current = snapshot("CREATE TABLE t (id int PRIMARY KEY) WITH comment = 'Orders' \
+                                AND caching = {'keys': 'ALL', 'rows_per_partition': 'NONE'} \
+                                AND speculative_retry = '99PERCENTILE' \
+                                AND compaction = {'class': 'org.apache.cassandra.db.compaction.\
+                                SizeTieredCompactionStrategy', 'min_threshold': '4'};");
+        let desired = snapshot("CREATE TABLE t (id int PRIMARY KEY) WITH comment = 'orders' \
+                                AND caching = {'keys': 'all', 'rows_per_partition': 'none'} \
+                                AND speculative_retry = '99percentile' \
+                                AND compaction = {'class': 'sizetieredcompactionstrategy', \
+                                'min_threshold': 4};");
+        let diff = SchemaDiff::between(&current, &desired).unwrap();
+        assert_eq!(diff.statements(), ["ALTER TABLE ks.t WITH comment = 'orders';"]);
+    }
+}
@@ -0,0 +1,28 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
+
+// What goes wrong reading a type name, a uuid or a time without the driver.
+// The cassandra crate turns these into the driver's LIB_BAD_PARAMS and
+// LIB_INVALID_VALUE_TYPE.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum ValueError {
+    BadParams,
+    InvalidValueType,
+}
+
+impl Display for ValueError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+impl Error for ValueError {
+    fn description(&self) -> &str {
+        match *self {
+            ValueError::BadParams => "Bad parameters",
+            ValueError::InvalidValueType => "Invalid value type",
+        }
+    }
+}
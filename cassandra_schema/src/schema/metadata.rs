@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+
+use schema::cql_type::CqlType;
+use schema::cql_value::CqlValue;
+
+// An owned copy of the schema. The cassandra crate reads one from the driver's
+// schema metadata or straight from the system schema tables, understanding both
+// the Cassandra 2.x (system.schema_*) and 3.x (system_schema.*) layouts.
+#[derive(Debug,Clone,PartialEq,Default)]
+pub struct SchemaSnapshot {
+    pub keyspaces: BTreeMap<String, KeyspaceMeta>,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct KeyspaceMeta {
+    pub name: String,
+    pub replication: Replication,
+    pub durable_writes: bool,
+    pub tables: BTreeMap<String, TableMeta>,
+    pub views: BTreeMap<String, ViewMeta>,
+    pub user_types: BTreeMap<String, UserTypeMeta>,
+    pub functions: Vec<FunctionMeta>,
+    pub aggregates: Vec<AggregateMeta>,
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Replication {
+    pub strategy: ReplicationStrategy,
+    // Everything but the class, e.g. replication_factor or one entry per data
+    // center.
+    pub options: BTreeMap<String, String>,
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum ReplicationStrategy {
+    Simple,
+    NetworkTopology,
+    Local,
+    Everywhere,
+    Other(String),
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct TableMeta {
+    pub keyspace: String,
+    pub name: String,
+    // The partition key and clustering columns in key order, then the static
+    // and regular columns by name.
+    pub columns: Vec<ColumnMeta>,
+    // Table options under their CQL names, e.g. compaction or gc_grace_seconds.
+    pub options: BTreeMap<String, CqlValue>,
+    pub indexes: BTreeMap<String, IndexMeta>,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub enum ColumnKind {
+    PartitionKey,
+    Clustering,
+    Static,
+    Regular,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum ClusteringOrder {
+    Asc,
+    Desc,
+    None,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct ColumnMeta {
+    pub name: String,
+    pub kind: ColumnKind,
+    // The position within the partition or clustering key, 0 for other columns.
+    pub position: i32,
+    pub data_type: CqlType,
+    pub clustering_order: ClusteringOrder,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum IndexKind {
+    Keys,
+    Composites,
+    Custom,
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct IndexMeta {
+    pub name: String,
+    pub table: String,
+    pub kind: IndexKind,
+    // The indexed column as it appears in CREATE INDEX, e.g. `name`, `keys(tags)`
+    // or `entries(attributes)`.
+    pub target: String,
+    // Any other index options; a custom index has its class under class_name.
+    pub options: BTreeMap<String, String>,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct UserTypeMeta {
+    pub keyspace: String,
+    pub name: String,
+    pub fields: Vec<(String, CqlType)>,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct FunctionMeta {
+    pub keyspace: String,
+    pub name: String,
+    pub arguments: Vec<(String, CqlType)>,
+    pub return_type: CqlType,
+    pub language: String,
+    pub body: String,
+    pub called_on_null_input: bool,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct AggregateMeta {
+    pub keyspace: String,
+    pub name: String,
+    pub argument_types: Vec<CqlType>,
+    pub state_function: String,
+    pub state_type: CqlType,
+    pub final_function: Option<String>,
+    // The initial state as a CQL literal.
+    pub initial_condition: Option<String>,
+    pub return_type: CqlType,
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct ViewMeta {
+    pub keyspace: String,
+    pub name: String,
+    pub base_table: String,
+    pub include_all_columns: bool,
+    pub where_clause: String,
+    pub columns: Vec<ColumnMeta>,
+    pub options: BTreeMap<String, CqlValue>,
+}
+
+impl SchemaSnapshot {
+    pub fn keyspace(&self, name: &str) -> Option<&KeyspaceMeta> {
+        self.keyspaces.get(name)
+    }
+
+    pub fn table(&self, keyspace: &str, table: &str) -> Option<&TableMeta> {
+        self.keyspace(keyspace).and_then(|keyspace| keyspace.tables.get(table))
+    }
+}
+
+impl KeyspaceMeta {
+    pub fn new(name: &str, replication: Replication) -> KeyspaceMeta {
+        KeyspaceMeta {
+            name: name.to_owned(),
+            replication: replication,
+            durable_writes: true,
+            tables: BTreeMap::new(),
+            views: BTreeMap::new(),
+            user_types: BTreeMap::new(),
+            functions: Vec::new(),
+            aggregates: Vec::new(),
+        }
+    }
+}
+
+impl ReplicationStrategy {
+    pub fn from_class_name(class_name: &str) -> ReplicationStrategy {
+        match class_name.trim_start_matches("org.apache.cassandra.locator.") {
+            "SimpleStrategy" => ReplicationStrategy::Simple,
+            "NetworkTopologyStrategy" => ReplicationStrategy::NetworkTopology,
+            "LocalStrategy" => ReplicationStrategy::Local,
+            "EverywhereStrategy" => ReplicationStrategy::Everywhere,
+            _ => ReplicationStrategy::Other(class_name.to_owned()),
+        }
+    }
+
+    pub fn class_name(&self) -> &str {
+        match *self {
+            ReplicationStrategy::Simple => "SimpleStrategy",
+            ReplicationStrategy::NetworkTopology => "NetworkTopologyStrategy",
+            ReplicationStrategy::Local => "LocalStrategy",
+            ReplicationStrategy::Everywhere => "EverywhereStrategy",
+            ReplicationStrategy::Other(ref class_name) => class_name,
+        }
+    }
+}
+
+impl TableMeta {
+    pub fn column(&self, name: &str) -> Option<&ColumnMeta> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    pub fn partition_key(&self) -> Vec<&ColumnMeta> {
+        columns_of_kind(&self.columns, ColumnKind::PartitionKey)
+    }
+
+    pub fn clustering_key(&self) -> Vec<&ColumnMeta> {
+        columns_of_kind(&self.columns, ColumnKind::Clustering)
+    }
+
+    pub fn static_columns(&self) -> Vec<&ColumnMeta> {
+        columns_of_kind(&self.columns, ColumnKind::Static)
+    }
+
+    pub fn regular_columns(&self) -> Vec<&ColumnMeta> {
+        columns_of_kind(&self.columns, ColumnKind::Regular)
+    }
+}
+
+impl ViewMeta {
+    pub fn column(&self, name: &str) -> Option<&ColumnMeta> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    pub fn partition_key(&self) -> Vec<&ColumnMeta> {
+        columns_of_kind(&self.columns, ColumnKind::PartitionKey)
+    }
+
+    pub fn clustering_key(&self) -> Vec<&ColumnMeta> {
+        columns_of_kind(&self.columns, ColumnKind::Clustering)
+    }
+}
+
+fn columns_of_kind(columns: &[ColumnMeta], kind: ColumnKind) -> Vec<&ColumnMeta> {
+    columns.iter().filter(|column| column.kind == kind).collect()
+}
+
+pub fn sort_columns(columns: &mut [ColumnMeta]) {
+    columns.sort_by(|a, b| (a.kind, a.position, &a.name).cmp(&(b.kind, b.position, &b.name)));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use schema::value_type::CassValueType;
+    use super::*;
+
+    // Snapshots hold no driver objects, so they can be built, cloned and shared
+    // across threads without libcassandra.
+    #[test]
+    fn snapshots_are_plain_data() {
+        let snapshot = SchemaSnapshot::from_cql("CREATE KEYSPACE ks WITH replication = \
+                                                 {'class': 'SimpleStrategy', \
+                                                 'replication_factor': 1};
+                                                 CREATE TYPE ks.address (street text, \
+                                                 zip int);
+                                                 CREATE TABLE ks.users (id uuid PRIMARY KEY, \
+                                                 home frozen<address>);")
+                           .unwrap();
+        let copy = snapshot.clone();
+        let users = thread::spawn(move || copy.table("ks", "users").cloned())
+                        .join()
+                        .unwrap()
+                        .unwrap();
+        assert_eq!(users.column("id").unwrap().data_type,
+                   CqlType::Native(CassValueType::UUID));
+        assert_eq!(users.column("home").unwrap().data_type.to_string(),
+                   "frozen<address>");
+        assert_eq!(snapshot.keyspace("ks").unwrap().user_types["address"].fields,
+                   vec![("street".to_owned(), CqlType::Native(CassValueType::TEXT)),
+                        ("zip".to_owned(), CqlType::Native(CassValueType::INT))]);
+    }
+
+    // A 2.x aggregate row, with the initial condition serialized as the state
+}
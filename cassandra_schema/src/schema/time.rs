@@ -0,0 +1,193 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+// A timestamp is milliseconds since the unix epoch. A date is a day count
+// centered on the epoch at 2^31, and a time is nanoseconds since midnight.
+const DATE_EPOCH: i64 = 1 << 31;
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const NANOS_PER_DAY: i64 = 86_400 * NANOS_PER_SECOND;
+
+#[derive(Debug,Copy,Clone,PartialEq,Eq,Hash,Default)]
+pub struct CqlDuration {
+    pub months: i32,
+    pub days: i32,
+    pub nanos: i64,
+}
+
+impl CqlDuration {
+    pub fn new(months: i32, days: i32, nanos: i64) -> CqlDuration {
+        CqlDuration { months: months, days: days, nanos: nanos }
+    }
+}
+
+// Formats the duration in the CQL literal syntax, e.g. 1y2mo3d4h5m6s.
+// Cassandra only has durations whose parts share a sign; any other is written
+// with a sign on each negative part, e.g. 1mo-2d, so that nothing is lost.
+impl Display for CqlDuration {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let negative = self.months < 0 || self.days < 0 || self.nanos < 0;
+        let positive = self.months > 0 || self.days > 0 || self.nanos > 0;
+        let mixed = negative && positive;
+        if negative && !mixed {
+            try!(write!(f, "-"));
+        }
+        let months = self.months.unsigned_abs() as u64;
+        let days = self.days.unsigned_abs() as u64;
+        let nanos = self.nanos.unsigned_abs();
+        let parts = [(self.months < 0, vec![(months / 12, "y"), (months % 12, "mo")]),
+                     (self.days < 0, vec![(days, "d")]),
+                     (self.nanos < 0,
+                      vec![(nanos / 3_600_000_000_000, "h"),
+                           (nanos / 60_000_000_000 % 60, "m"),
+                           (nanos / 1_000_000_000 % 60, "s"),
+                           (nanos / 1_000_000 % 1000, "ms"),
+                           (nanos / 1000 % 1000, "us"),
+                           (nanos % 1000, "ns")])];
+        for &(negative, ref units) in parts.iter() {
+            if mixed && negative {
+                try!(write!(f, "-"));
+            }
+            for &(amount, unit) in units {
+                if amount != 0 {
+                    try!(write!(f, "{}{}", amount, unit));
+                }
+            }
+        }
+        if !negative && !positive {
+            try!(write!(f, "0s"));
+        }
+        Ok(())
+    }
+}
+
+pub fn timestamp_from_system_time(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs() as i64 * 1000 + since.subsec_millis() as i64,
+        Err(err) => {
+            let before = err.duration();
+            let millis = before.as_secs() as i64 * 1000 + before.subsec_millis() as i64;
+            // Round towards negative infinity so sub-millisecond parts are
+            // truncated the same way on both sides of the epoch.
+            if before.subsec_nanos() % 1_000_000 != 0 {
+                -millis - 1
+            } else {
+                -millis
+            }
+        }
+    }
+}
+
+pub fn system_time_from_timestamp(timestamp: i64) -> SystemTime {
+    if timestamp >= 0 {
+        UNIX_EPOCH + Duration::from_millis(timestamp as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_millis(timestamp.wrapping_neg() as u64)
+    }
+}
+
+pub fn date_from_days(days_since_epoch: i64) -> u32 {
+    (days_since_epoch + DATE_EPOCH) as u32
+}
+
+pub fn days_from_date(date: u32) -> i64 {
+    date as i64 - DATE_EPOCH
+}
+
+// Formats a date as yyyy-mm-dd in the proleptic Gregorian calendar.
+pub fn date_to_string(date: u32) -> String {
+    let (year, month, day) = civil_from_days(days_from_date(date));
+    if year < 0 {
+        format!("-{:04}-{:02}-{:02}", -year, month, day)
+    } else {
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+// Formats a time of day as hh:mm:ss with nanoseconds when present.
+pub fn time_to_string(time: i64) -> String {
+    let seconds = time / NANOS_PER_SECOND;
+    let nanos = time % NANOS_PER_SECOND;
+    let clock = format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if nanos == 0 {
+        clock
+    } else {
+        format!("{}.{:09}", clock, nanos)
+    }
+}
+
+pub fn is_valid_time(time: i64) -> bool {
+    (0..NANOS_PER_DAY).contains(&time)
+}
+
+// Days since 1970-01-01 to (year, month, day), after Howard Hinnant's
+// civil_from_days.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 -
+                       day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use std::time::UNIX_EPOCH;
+    use super::*;
+
+    #[test]
+    fn durations_display_as_literals() {
+        let hour = 3_600_000_000_000;
+        assert_eq!(CqlDuration::new(14, 3, 4 * hour + 5_000_000_000).to_string(), "1y2mo3d4h5s");
+        assert_eq!(CqlDuration::new(0, 0, 1_001_001).to_string(), "1ms1us1ns");
+        assert_eq!(CqlDuration::new(-1, -2, -hour).to_string(), "-1mo2d1h");
+        assert_eq!(CqlDuration::new(0, -1, 0).to_string(), "-1d");
+        assert_eq!(CqlDuration::default().to_string(), "0s");
+        assert_eq!(CqlDuration::new(0, 0, i64::MIN).to_string(), "-2562047h47m16s854ms775us808ns");
+    }
+
+    #[test]
+    fn mixed_sign_durations_keep_each_sign() {
+        let hour = 3_600_000_000_000;
+        assert_eq!(CqlDuration::new(1, -2, 0).to_string(), "1mo-2d");
+        assert_eq!(CqlDuration::new(-13, 2, -hour).to_string(), "-1y1mo2d-1h");
+        assert_eq!(CqlDuration::new(0, 1, -1).to_string(), "1d-1ns");
+    }
+
+    #[test]
+    fn timestamps() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_500_000_000_123);
+        assert_eq!(timestamp_from_system_time(time), 1_500_000_000_123);
+        assert_eq!(system_time_from_timestamp(1_500_000_000_123), time);
+        let before = UNIX_EPOCH - Duration::from_millis(1500);
+        assert_eq!(timestamp_from_system_time(before), -1500);
+        assert_eq!(system_time_from_timestamp(-1500), before);
+        // sub-millisecond parts are truncated towards the past
+        assert_eq!(timestamp_from_system_time(UNIX_EPOCH - Duration::new(0, 1)), -1);
+    }
+
+    #[test]
+    fn dates_and_times() {
+        assert_eq!(date_from_days(0), 1 << 31);
+        assert_eq!(days_from_date(date_from_days(-1)), -1);
+        assert_eq!(date_to_string(date_from_days(0)), "1970-01-01");
+        assert_eq!(date_to_string(date_from_days(-1)), "1969-12-31");
+        assert_eq!(date_to_string(date_from_days(19_723)), "2024-01-01");
+        assert_eq!(date_to_string(date_from_days(11_016)), "2000-02-29");
+        assert_eq!(time_to_string(0), "00:00:00");
+        assert_eq!(time_to_string(45_296_000_000_001), "12:34:56.000000001");
+        assert!(is_valid_time(NANOS_PER_DAY - 1));
+        assert!(!is_valid_time(NANOS_PER_DAY));
+        assert!(!is_valid_time(-1));
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt::Formatter;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Debug;
+use std::str::FromStr;
+use std::cmp::Ordering;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use schema::error::ValueError;
+
+// The driver keeps the 60 bit timestamp of a version 1 uuid in the low bits of
+// time_and_version, with the version in the top nibble.
+const TIMESTAMP_MASK:u64 = 0x0FFFFFFFFFFFFFFF;
+const TIME_VERSION:u64 = 1 << 60;
+
+// That timestamp counts 100ns ticks since the start of the gregorian calendar,
+// this many ticks before the unix epoch.
+const GREGORIAN_OFFSET:u64 = 0x01B21DD213814000;
+const TICKS_PER_SECOND:u64 = 10_000_000;
+const TICKS_PER_MILLISECOND:u64 = 10_000;
+
+// The lsb of the time uuids that sort first and last among those of a
+// timestamp, as Cassandra compares their bytes as signed values.
+const MIN_CLOCK_SEQ_AND_NODE:u64 = 0x8080808080808080;
+const MAX_CLOCK_SEQ_AND_NODE:u64 = 0x7f7f7f7f7f7f7f7f;
+
+// Laid out as the driver's CassUuid, which the cassandra crate converts to and
+// from when it passes uuids to the driver.
+#[derive(Copy,Clone,Default,PartialEq,Eq,Hash)]
+pub struct CassUuid {
+    pub time_and_version: u64,
+    pub clock_seq_and_node: u64,
+}
+
+impl Debug for CassUuid {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+impl Display for CassUuid {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let (msb, lsb) = (self.msb(), self.clock_seq_and_node);
+        write!(f,
+               "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+               msb >> 32,
+               msb >> 16 & 0xFFFF,
+               msb & 0xFFFF,
+               lsb >> 48,
+               lsb & 0xFFFFFFFFFFFF)
+    }
+}
+
+// Only the standard form, 32 hex digits grouped 8-4-4-4-12.
+impl FromStr for CassUuid {
+    type Err = ValueError;
+
+    fn from_str(value: &str) -> Result<CassUuid, ValueError> {
+        let bytes = value.as_bytes();
+        if bytes.len() != 36 {
+            return Err(ValueError::BadParams);
+        }
+        let mut halves = [0u64; 2];
+        let mut digits = 0;
+        for (i, &byte) in bytes.iter().enumerate() {
+            match i {
+                8 | 13 | 18 | 23 if byte == b'-' => continue,
+                8 | 13 | 18 | 23 => return Err(ValueError::BadParams),
+                _ => {}
+            }
+            let digit = match (byte as char).to_digit(16) {
+                Some(digit) => digit as u64,
+                None => return Err(ValueError::BadParams),
+            };
+            halves[digits / 16] = halves[digits / 16] << 4 | digit;
+            digits += 1;
+        }
+        Ok(CassUuid::from_msb_lsb(halves[0], halves[1]))
+    }
+}
+
+// Follows Cassandra's UUIDType: uuids of different versions sort by version,
+// time uuids by timestamp and then by the remaining bytes compared as signed
+// bytes, and all other uuids by their bytes compared unsigned.
+impl Ord for CassUuid {
+    fn cmp(&self, other: &CassUuid) -> Ordering {
+        match self.version().cmp(&other.version()) {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
+        if self.version() == 1 {
+            (self.time_and_version & TIMESTAMP_MASK)
+                .cmp(&(other.time_and_version & TIMESTAMP_MASK))
+                .then(self.signed_lsb().cmp(&other.signed_lsb()))
+        } else {
+            (self.msb(), self.clock_seq_and_node).cmp(&(other.msb(), other.clock_seq_and_node))
+        }
+    }
+}
+
+impl PartialOrd for CassUuid {
+    fn partial_cmp(&self, other: &CassUuid) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<::uuid::Uuid> for CassUuid {
+    fn from(uuid: ::uuid::Uuid) -> CassUuid {
+        let value = uuid.as_u128();
+        CassUuid::from_msb_lsb((value >> 64) as u64, value as u64)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<CassUuid> for ::uuid::Uuid {
+    fn from(uuid: CassUuid) -> ::uuid::Uuid {
+        ::uuid::Uuid::from_u128((uuid.msb() as u128) << 64 | uuid.clock_seq_and_node as u128)
+    }
+}
+
+impl CassUuid {
+    // The first time uuid of a millisecond since the unix epoch, as the driver
+    // computes it.
+    pub fn min_from_time(&mut self, time: u64) {
+        self.time_and_version = from_unix_millis(time);
+        self.clock_seq_and_node = MIN_CLOCK_SEQ_AND_NODE;
+    }
+
+    pub fn max_from_time(&mut self, time: u64) {
+        self.time_and_version = from_unix_millis(time);
+        self.clock_seq_and_node = MAX_CLOCK_SEQ_AND_NODE;
+    }
+
+    // Milliseconds since the unix epoch of a version 1 uuid.
+    pub fn timestamp(&self) -> u64 {
+        (self.time_and_version & TIMESTAMP_MASK).wrapping_sub(GREGORIAN_OFFSET) /
+        TICKS_PER_MILLISECOND
+    }
+
+    pub fn version(&self) -> u8 {
+        (self.time_and_version >> 60) as u8
+    }
+
+    // The full 100ns precision time of a version 1 uuid.
+    pub fn system_time(&self) -> Result<SystemTime, ValueError> {
+        if self.version() != 1 {
+            return Err(ValueError::InvalidValueType);
+        }
+        let ticks = self.time_and_version & TIMESTAMP_MASK;
+        if ticks >= GREGORIAN_OFFSET {
+            Ok(UNIX_EPOCH + ticks_to_duration(ticks - GREGORIAN_OFFSET))
+        } else {
+            // Some platforms have no times before the epoch.
+            UNIX_EPOCH.checked_sub(ticks_to_duration(GREGORIAN_OFFSET - ticks))
+                      .ok_or(ValueError::InvalidValueType)
+        }
+    }
+
+    // The 16 bytes of the uuid as it is written, most significant first.
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let (msb, lsb) = (self.msb(), self.clock_seq_and_node);
+        let mut bytes = [0; 16];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            let half = if i < 8 { msb } else { lsb };
+            *byte = (half >> (56 - i % 8 * 8)) as u8;
+        }
+        bytes
+    }
+
+    // The uuid whose first and last eight bytes, as written, are msb and lsb.
+    pub fn from_msb_lsb(msb: u64, lsb: u64) -> CassUuid {
+        CassUuid {
+            time_and_version: (msb & 0xFFFF) << 48 | (msb >> 16 & 0xFFFF) << 32 | msb >> 32,
+            clock_seq_and_node: lsb,
+        }
+    }
+
+    // The first eight bytes in the order they appear in the string form and on
+    // the wire, rather than the time-first layout the driver keeps.
+    fn msb(&self) -> u64 {
+        let time_and_version = self.time_and_version;
+        (time_and_version & 0xFFFFFFFF) << 32 | (time_and_version >> 32 & 0xFFFF) << 16 |
+        time_and_version >> 48
+    }
+
+    // Flipping the sign bit of every byte but the first makes an i64 compare
+    // the same way as the bytes would compare one at a time as signed values.
+    fn signed_lsb(&self) -> i64 {
+        (self.clock_seq_and_node ^ 0x0080808080808080) as i64
+    }
+}
+
+fn from_unix_millis(millis: u64) -> u64 {
+    millis.wrapping_mul(TICKS_PER_MILLISECOND).wrapping_add(GREGORIAN_OFFSET) & TIMESTAMP_MASK |
+    TIME_VERSION
+}
+
+fn ticks_to_duration(ticks: u64) -> Duration {
+    Duration::new(ticks / TICKS_PER_SECOND,
+                  (ticks % TICKS_PER_SECOND) as u32 * 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::*;
+
+    fn uuid(time_and_version: u64, clock_seq_and_node: u64) -> CassUuid {
+        CassUuid {
+            time_and_version: time_and_version,
+            clock_seq_and_node: clock_seq_and_node,
+        }
+    }
+
+    #[test]
+    fn time_uuids_order_by_timestamp_then_signed_bytes() {
+        let time = TIME_VERSION | 1000;
+        let positive = uuid(time, 0x0000000000000000);
+        let negative = uuid(time, 0x8000000000000000);
+        // the same timestamp, so the first byte of the lsb decides, and 0x80
+        // is -128 as a signed byte
+        assert_eq!(negative.cmp(&positive), Ordering::Less);
+        assert!(uuid(time, 0x7f00000000000000) > positive);
+        // and so does every byte after it
+        assert!(uuid(time, 0x0080000000000000) < positive);
+        assert!(uuid(time, 0x00000000000000ff) < positive);
+        assert!(uuid(time, 0x0000000000000001) > positive);
+        // the timestamp comes first, whatever the lsb
+        assert!(uuid(time + 1, 0x8000000000000000) > uuid(time, 0x7fffffffffffffff));
+        // the timestamp's high bits are at the top of time_and_version but at the
+        // end of the uuid's bytes
+        assert!(uuid(TIME_VERSION | 1 << 32, 0) > uuid(TIME_VERSION | 0xffffffff, 0));
+        assert_eq!(positive.cmp(&uuid(time, 0)), Ordering::Equal);
+    }
+
+    #[test]
+    fn other_uuids_order_by_version_then_unsigned_bytes() {
+        let random = 4 << 60;
+        // unsigned, so 0x80 is after 0x7f
+        assert!(uuid(random, 0x8000000000000000) > uuid(random, 0x7f00000000000000));
+        // the msb in its written order: time_low first
+        assert!(uuid(random | 1, 0) > uuid(random | 0xffff << 32, 0));
+        assert!(uuid(random, 0) > uuid(TIME_VERSION | TIMESTAMP_MASK, 0));
+        assert!(uuid(random, 0) < uuid(5 << 60, 0));
+    }
+
+    #[test]
+    fn versions_are_read_from_the_top_nibble() {
+        assert_eq!(uuid(TIME_VERSION | TIMESTAMP_MASK, 0).version(), 1);
+        assert_eq!(uuid(4 << 60, 0).version(), 4);
+        assert_eq!(CassUuid::default().version(), 0);
+    }
+
+    #[test]
+    fn uuids_are_written_and_parsed_in_the_standard_form() {
+        let text = "00112233-4455-1677-8899-aabbccddeeff";
+        let parsed: CassUuid = text.parse().unwrap();
+        assert_eq!(parsed, uuid(0x1677445500112233, 0x8899aabbccddeeff));
+        assert_eq!(parsed.to_string(), text);
+        assert_eq!(parsed.version(), 1);
+        assert_eq!(parsed.to_bytes(),
+                   [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x16, 0x77, 0x88, 0x99, 0xaa, 0xbb,
+                    0xcc, 0xdd, 0xee, 0xff]);
+        let upper: CassUuid = "00112233-4455-1677-8899-AABBCCDDEEFF".parse().unwrap();
+        assert_eq!(upper, parsed);
+        assert_eq!(CassUuid::default().to_string(), "00000000-0000-0000-0000-000000000000");
+        assert!("00112233-4455-1677-8899-aabbccddeef".parse::<CassUuid>().is_err());
+        assert!("00112233-4455-1677-8899-aabbccddeefg".parse::<CassUuid>().is_err());
+        assert!("001122334-455-1677-8899-aabbccddeeff".parse::<CassUuid>().is_err());
+        assert!("+0112233-4455-1677-8899-aabbccddeeff".parse::<CassUuid>().is_err());
+        assert!("".parse::<CassUuid>().is_err());
+        assert!("00112233-4455-1677-8899-aabbccddeeff\0".parse::<CassUuid>().is_err());
+    }
+
+    #[test]
+    fn time_bounds_are_the_first_and_last_uuids_of_a_millisecond() {
+        let ticks = GREGORIAN_OFFSET + 1500 * TICKS_PER_MILLISECOND;
+        let mut min = CassUuid::default();
+        let mut max = CassUuid::default();
+        min.min_from_time(1500);
+        max.max_from_time(1500);
+        assert_eq!(min, uuid(TIME_VERSION | ticks, MIN_CLOCK_SEQ_AND_NODE));
+        assert_eq!(max, uuid(TIME_VERSION | ticks, MAX_CLOCK_SEQ_AND_NODE));
+        assert_eq!(min.timestamp(), 1500);
+        assert_eq!(uuid(TIME_VERSION | ticks + TICKS_PER_MILLISECOND - 1, 0).timestamp(), 1500);
+        for &lsb in &[0, 0x8000000000000000, 0x8080808080808081, 0x7f7f7f7f7f7f7f7e] {
+            assert!(min < uuid(TIME_VERSION | ticks, lsb));
+            assert!(max > uuid(TIME_VERSION | ticks, lsb));
+        }
+    }
+
+    fn at_millis(millis: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn time_uuids_keep_their_full_precision_time() {
+        let time = uuid(TIME_VERSION | GREGORIAN_OFFSET + 15_000_001, 0);
+        assert_eq!(time.system_time().unwrap(),
+                   at_millis(1500) + Duration::new(0, 100));
+        let before = uuid(TIME_VERSION | GREGORIAN_OFFSET - 10_000_000, 0);
+        if let Some(second_before) = UNIX_EPOCH.checked_sub(Duration::from_secs(1)) {
+            assert_eq!(before.system_time().unwrap(), second_before);
+        }
+        assert_eq!(uuid(4 << 60, 0).system_time(), Err(ValueError::InvalidValueType));
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuids_convert_to_and_from_the_uuid_crate() {
+        let bytes = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x16, 0x77, 0x88, 0x99, 0xaa, 0xbb,
+                     0xcc, 0xdd, 0xee, 0xff];
+        let other = ::uuid::Uuid::from_bytes(bytes);
+        let converted = CassUuid::from(other);
+        assert_eq!(converted, uuid(0x1677445500112233, 0x8899aabbccddeeff));
+        assert_eq!(converted.to_bytes(), bytes);
+        assert_eq!(::uuid::Uuid::from(converted), other);
+        let msb = 0x0011223344551677;
+        let lsb = 0x8899aabbccddeeff;
+        assert_eq!(CassUuid::from_msb_lsb(msb, lsb).msb(), msb);
+        assert_eq!(CassUuid::from_msb_lsb(msb, lsb).clock_seq_and_node, lsb);
+    }
+}
@@ -0,0 +1,72 @@
+// The driver's value types, numbered as the native protocol numbers them.
+#[derive(Debug,Copy,Clone,PartialEq,Eq,Hash)]
+pub enum CassValueType {
+    UNKNOWN = 0xFFFF,
+    CUSTOM = 0x0000,
+    ASCII = 0x0001,
+    BIGINT = 0x0002,
+    BLOB = 0x0003,
+    BOOLEAN = 0x0004,
+    COUNTER = 0x0005,
+    DECIMAL = 0x0006,
+    DOUBLE = 0x0007,
+    FLOAT = 0x0008,
+    INT = 0x0009,
+    TEXT = 0x000A,
+    TIMESTAMP = 0x000B,
+    UUID = 0x000C,
+    VARCHAR = 0x000D,
+    VARINT = 0x000E,
+    TIMEUUID = 0x000F,
+    INET = 0x0010,
+    DATE = 0x0011,
+    TIME = 0x0012,
+    SMALL_INT = 0x0013,
+    TINY_INT = 0x0014,
+    DURATION = 0x0015,
+    LIST = 0x0020,
+    MAP = 0x0021,
+    SET = 0x0022,
+    UDT = 0x0030,
+    TUPLE = 0x0031,
+    LASTENTRY = 0x0032,
+}
+
+impl CassValueType {
+    pub fn build(_type: u32) -> Self {
+        match _type {
+            0xFFFF => CassValueType::UNKNOWN,
+            0x0000 => CassValueType::CUSTOM,
+            0x0001 => CassValueType::ASCII,
+            0x0002 => CassValueType::BIGINT,
+            0x0003 => CassValueType::BLOB,
+            0x0004 => CassValueType::BOOLEAN,
+            0x0005 => CassValueType::COUNTER,
+            0x0006 => CassValueType::DECIMAL,
+            0x0007 => CassValueType::DOUBLE,
+            0x0008 => CassValueType::FLOAT,
+            0x0009 => CassValueType::INT,
+            0x000A => CassValueType::TEXT,
+            0x000B => CassValueType::TIMESTAMP,
+            0x000C => CassValueType::UUID,
+            0x000D => CassValueType::VARCHAR,
+            0x000E => CassValueType::VARINT,
+            0x000F => CassValueType::TIMEUUID,
+            0x0010 => CassValueType::INET,
+            0x0011 => CassValueType::DATE,
+            0x0012 => CassValueType::TIME,
+            0x0013 => CassValueType::SMALL_INT,
+            0x0014 => CassValueType::TINY_INT,
+            0x0015 => CassValueType::DURATION,
+            0x0020 => CassValueType::LIST,
+            0x0021 => CassValueType::MAP,
+            0x0022 => CassValueType::SET,
+            0x0030 => CassValueType::UDT,
+            0x0031 => CassValueType::TUPLE,
+            unknown => {
+                warn!("unknown value type {}", unknown);
+                CassValueType::UNKNOWN
+            }
+        }
+    }
+}
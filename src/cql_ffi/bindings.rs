@@ -0,0 +1,137 @@
+// Driver functions cql_bindgen 0.1 has no bindings for, as it was generated
+// from an older cassandra.h. They are in the libcassandra build.rs links; the
+// duration ones need version 2.10 or later, the node ones 2.16.
+
+use libc::c_char;
+
+use cql_bindgen::CassCollection;
+use cql_bindgen::CassDataType;
+use cql_bindgen::CassError;
+use cql_bindgen::CassFuture;
+use cql_bindgen::CassStatement;
+use cql_bindgen::CassTuple;
+use cql_bindgen::CassUserType;
+use cql_bindgen::CassValue;
+use cql_bindgen::CassValueType;
+use cql_bindgen::cass_bool_t;
+use cql_bindgen::cass_int8_t;
+use cql_bindgen::cass_int16_t;
+use cql_bindgen::cass_int32_t;
+use cql_bindgen::cass_int64_t;
+use cql_bindgen::cass_uint32_t;
+use cql_bindgen::size_t;
+
+pub const CASS_VALUE_TYPE_DATE: CassValueType = 0x0011;
+pub const CASS_VALUE_TYPE_TIME: CassValueType = 0x0012;
+pub const CASS_VALUE_TYPE_SMALL_INT: CassValueType = 0x0013;
+pub const CASS_VALUE_TYPE_TINY_INT: CassValueType = 0x0014;
+pub const CASS_VALUE_TYPE_DURATION: CassValueType = 0x0015;
+
+pub enum CassNode {}
+
+#[link(name = "cassandra")]
+extern "C" {
+    pub fn cass_data_type_is_frozen(data_type: *const CassDataType) -> cass_bool_t;
+    pub fn cass_data_type_sub_type_count(data_type: *const CassDataType) -> size_t;
+
+    pub fn cass_future_coordinator(future: *mut CassFuture) -> *const CassNode;
+    pub fn cass_statement_set_node(statement: *mut CassStatement,
+                                   node: *const CassNode)
+                                   -> CassError;
+
+    pub fn cass_statement_bind_int8(statement: *mut CassStatement,
+                                    index: size_t,
+                                    value: cass_int8_t)
+                                    -> CassError;
+    pub fn cass_statement_bind_int8_by_name(statement: *mut CassStatement,
+                                            name: *const c_char,
+                                            value: cass_int8_t)
+                                            -> CassError;
+    pub fn cass_statement_bind_int16(statement: *mut CassStatement,
+                                     index: size_t,
+                                     value: cass_int16_t)
+                                     -> CassError;
+    pub fn cass_statement_bind_int16_by_name(statement: *mut CassStatement,
+                                             name: *const c_char,
+                                             value: cass_int16_t)
+                                             -> CassError;
+    pub fn cass_statement_bind_uint32(statement: *mut CassStatement,
+                                      index: size_t,
+                                      value: cass_uint32_t)
+                                      -> CassError;
+    pub fn cass_statement_bind_uint32_by_name(statement: *mut CassStatement,
+                                              name: *const c_char,
+                                              value: cass_uint32_t)
+                                              -> CassError;
+    pub fn cass_statement_bind_duration(statement: *mut CassStatement,
+                                        index: size_t,
+                                        months: cass_int32_t,
+                                        days: cass_int32_t,
+                                        nanos: cass_int64_t)
+                                        -> CassError;
+    pub fn cass_statement_bind_duration_by_name(statement: *mut CassStatement,
+                                                name: *const c_char,
+                                                months: cass_int32_t,
+                                                days: cass_int32_t,
+                                                nanos: cass_int64_t)
+                                                -> CassError;
+
+    pub fn cass_collection_append_int8(collection: *mut CassCollection,
+                                       value: cass_int8_t)
+                                       -> CassError;
+    pub fn cass_collection_append_int16(collection: *mut CassCollection,
+                                        value: cass_int16_t)
+                                        -> CassError;
+    pub fn cass_collection_append_uint32(collection: *mut CassCollection,
+                                         value: cass_uint32_t)
+                                         -> CassError;
+    pub fn cass_collection_append_duration(collection: *mut CassCollection,
+                                           months: cass_int32_t,
+                                           days: cass_int32_t,
+                                           nanos: cass_int64_t)
+                                           -> CassError;
+
+    pub fn cass_tuple_set_int8(tuple: *mut CassTuple, index: size_t, value: cass_int8_t) -> CassError;
+    pub fn cass_tuple_set_int16(tuple: *mut CassTuple,
+                                index: size_t,
+                                value: cass_int16_t)
+                                -> CassError;
+    pub fn cass_tuple_set_uint32(tuple: *mut CassTuple,
+                                 index: size_t,
+                                 value: cass_uint32_t)
+                                 -> CassError;
+    pub fn cass_tuple_set_duration(tuple: *mut CassTuple,
+                                   index: size_t,
+                                   months: cass_int32_t,
+                                   days: cass_int32_t,
+                                   nanos: cass_int64_t)
+                                   -> CassError;
+
+    pub fn cass_user_type_set_int8(user_type: *mut CassUserType,
+                                   index: size_t,
+                                   value: cass_int8_t)
+                                   -> CassError;
+    pub fn cass_user_type_set_int16(user_type: *mut CassUserType,
+                                    index: size_t,
+                                    value: cass_int16_t)
+                                    -> CassError;
+    pub fn cass_user_type_set_uint32(user_type: *mut CassUserType,
+                                     index: size_t,
+                                     value: cass_uint32_t)
+                                     -> CassError;
+    pub fn cass_user_type_set_duration(user_type: *mut CassUserType,
+                                       index: size_t,
+                                       months: cass_int32_t,
+                                       days: cass_int32_t,
+                                       nanos: cass_int64_t)
+                                       -> CassError;
+
+    pub fn cass_value_get_int8(value: *const CassValue, output: *mut cass_int8_t) -> CassError;
+    pub fn cass_value_get_int16(value: *const CassValue, output: *mut cass_int16_t) -> CassError;
+    pub fn cass_value_get_uint32(value: *const CassValue, output: *mut cass_uint32_t) -> CassError;
+    pub fn cass_value_get_duration(value: *const CassValue,
+                                   months: *mut cass_int32_t,
+                                   days: *mut cass_int32_t,
+                                   nanos: *mut cass_int64_t)
+                                   -> CassError;
+}
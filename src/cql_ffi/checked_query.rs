@@ -0,0 +1,346 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::net::IpAddr;
+use std::time::SystemTime;
+
+use cassandra_schema::{CassUuid, CassValueType, CheckedQuery, CqlDuration, CqlType, QueryError,
+                       count_bind_markers};
+use cql_ffi::collection::collection::ToCql;
+use cql_ffi::collection::list::CassList;
+use cql_ffi::collection::map::CassMap;
+use cql_ffi::collection::set::CassSet;
+use cql_ffi::error::CassError;
+use cql_ffi::result::CassResult;
+use cql_ffi::row::CassRow;
+use cql_ffi::statement::CassStatement;
+
+// Binding the values of queries checked with SchemaSnapshot::check_query. BindAs,
+// bind_as and TypedStatement are for the code the cql! macro expands to.
+
+impl From<CassError> for QueryError {
+    fn from(err: CassError) -> QueryError {
+        QueryError::Driver(Box::new(err))
+    }
+}
+
+// A value that can be bound to a statement parameter.
+pub trait BindCql {
+    fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError>;
+    // Whether the value can be bound to a parameter of the type. Collections
+    // only check that the parameter is a collection of the same kind.
+    fn accepts(&self, cql_type: &CqlType) -> bool;
+}
+
+macro_rules! impl_bind_cql {
+    ($t:ty, $bind:ident, $($value_type:ident)|*) => {
+        impl BindCql for $t {
+            fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError> {
+                statement.$bind(index, *self).map(|_| ())
+            }
+
+            fn accepts(&self, cql_type: &CqlType) -> bool {
+                match *cql_type.unfrozen() {
+                    $(CqlType::Native(CassValueType::$value_type))|* => true,
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+impl_bind_cql!(i8, bind_int8, TINY_INT);
+impl_bind_cql!(i16, bind_int16, SMALL_INT);
+impl_bind_cql!(i32, bind_int32, INT);
+impl_bind_cql!(i64, bind_int64, BIGINT | COUNTER | TIMESTAMP | TIME);
+impl_bind_cql!(u32, bind_uint32, DATE);
+impl_bind_cql!(f32, bind_float, FLOAT);
+impl_bind_cql!(f64, bind_double, DOUBLE);
+impl_bind_cql!(bool, bind_bool, BOOLEAN);
+impl_bind_cql!(CassUuid, bind_uuid, UUID | TIMEUUID);
+impl_bind_cql!(IpAddr, bind_inet, INET);
+impl_bind_cql!(CqlDuration, bind_duration, DURATION);
+impl_bind_cql!(SystemTime, bind_system_time, TIMESTAMP);
+
+impl BindCql for str {
+    fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError> {
+        statement.bind_string(index, self).map(|_| ())
+    }
+
+    fn accepts(&self, cql_type: &CqlType) -> bool {
+        match *cql_type.unfrozen() {
+            CqlType::Native(CassValueType::ASCII) |
+            CqlType::Native(CassValueType::TEXT) |
+            CqlType::Native(CassValueType::VARCHAR) => true,
+            _ => false,
+        }
+    }
+}
+
+impl BindCql for String {
+    fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError> {
+        self[..].bind_to(statement, index)
+    }
+
+    fn accepts(&self, cql_type: &CqlType) -> bool {
+        self[..].accepts(cql_type)
+    }
+}
+
+impl BindCql for [u8] {
+    fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError> {
+        statement.bind_bytes(index, self).map(|_| ())
+    }
+
+    fn accepts(&self, cql_type: &CqlType) -> bool {
+        *cql_type.unfrozen() == CqlType::Native(CassValueType::BLOB)
+    }
+}
+
+impl<'a, T: BindCql + ?Sized> BindCql for &'a T {
+    fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError> {
+        (**self).bind_to(statement, index)
+    }
+
+    fn accepts(&self, cql_type: &CqlType) -> bool {
+        (**self).accepts(cql_type)
+    }
+}
+
+// None is bound as null, which any parameter takes.
+impl<T: BindCql> BindCql for Option<T> {
+    fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError> {
+        match *self {
+            Some(ref value) => value.bind_to(statement, index),
+            None => statement.bind_null(index).map(|_| ()),
+        }
+    }
+
+    fn accepts(&self, cql_type: &CqlType) -> bool {
+        match *self {
+            Some(ref value) => value.accepts(cql_type),
+            None => true,
+        }
+    }
+}
+
+// Lists and sets are written the same way, so a Vec binds to either.
+impl<T: ToCql> BindCql for Vec<T> {
+    fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError> {
+        statement.bind_list(index, try!(CassList::from_iter(self))).map(|_| ())
+    }
+
+    fn accepts(&self, cql_type: &CqlType) -> bool {
+        match *cql_type.unfrozen() {
+            CqlType::List(_) | CqlType::Set(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: ToCql + Eq + Hash> BindCql for HashSet<T> {
+    fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError> {
+        statement.bind_set(index, try!(CassSet::from_iter(self))).map(|_| ())
+    }
+
+    fn accepts(&self, cql_type: &CqlType) -> bool {
+        match *cql_type.unfrozen() {
+            CqlType::Set(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: ToCql + Ord> BindCql for BTreeSet<T> {
+    fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError> {
+        statement.bind_set(index, try!(CassSet::from_iter(self))).map(|_| ())
+    }
+
+    fn accepts(&self, cql_type: &CqlType) -> bool {
+        match *cql_type.unfrozen() {
+            CqlType::Set(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<K: ToCql + Eq + Hash, V: ToCql> BindCql for HashMap<K, V> {
+    fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError> {
+        statement.bind_map(index, try!(CassMap::from_iter(self))).map(|_| ())
+    }
+
+    fn accepts(&self, cql_type: &CqlType) -> bool {
+        match *cql_type.unfrozen() {
+            CqlType::Map(..) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<K: ToCql + Ord, V: ToCql> BindCql for BTreeMap<K, V> {
+    fn bind_to(&self, statement: &mut CassStatement, index: u64) -> Result<(), CassError> {
+        statement.bind_map(index, try!(CassMap::from_iter(self))).map(|_| ())
+    }
+
+    fn accepts(&self, cql_type: &CqlType) -> bool {
+        match *cql_type.unfrozen() {
+            CqlType::Map(..) => true,
+            _ => false,
+        }
+    }
+}
+
+// A value cql! binds to a parameter whose CQL type it reads as T: String for
+// text, SystemTime for timestamps, Vec<T>, BTreeSet<T> and BTreeMap<K, V> for
+// lists, sets and maps of T, and otherwise the type FromCql reads. A value
+// that isn't BindAs the parameter's T is a compile error.
+pub trait BindAs<T: ?Sized> {}
+
+macro_rules! impl_bind_as {
+    ($t:ty: $($value:ty),*) => {
+        $(impl BindAs<$t> for $value {})*
+    }
+}
+
+impl_bind_as!(i8: i8);
+impl_bind_as!(i16: i16);
+impl_bind_as!(i32: i32);
+impl_bind_as!(i64: i64);
+impl_bind_as!(u32: u32);
+impl_bind_as!(f32: f32);
+impl_bind_as!(f64: f64);
+impl_bind_as!(bool: bool);
+impl_bind_as!(String: str, String);
+impl_bind_as!([u8]: [u8]);
+impl_bind_as!(CassUuid: CassUuid);
+impl_bind_as!(IpAddr: IpAddr);
+impl_bind_as!(CqlDuration: CqlDuration);
+impl_bind_as!(SystemTime: SystemTime, i64);
+
+impl<'a, T: ?Sized, V: BindAs<T> + ?Sized> BindAs<T> for &'a V {}
+
+impl<T: ?Sized, V: BindAs<T>> BindAs<T> for Option<V> {}
+
+impl<T, V: BindAs<T> + ToCql> BindAs<Vec<T>> for Vec<V> {}
+
+impl<T, V: BindAs<T> + ToCql> BindAs<BTreeSet<T>> for Vec<V> {}
+
+impl<T, V: BindAs<T> + ToCql + Eq + Hash> BindAs<BTreeSet<T>> for HashSet<V> {}
+
+impl<T, V: BindAs<T> + ToCql + Ord> BindAs<BTreeSet<T>> for BTreeSet<V> {}
+
+impl<K, V, KV, VV> BindAs<BTreeMap<K, V>> for HashMap<KV, VV>
+    where KV: BindAs<K> + ToCql + Eq + Hash,
+          VV: BindAs<V> + ToCql
+{
+}
+
+impl<K, V, KV, VV> BindAs<BTreeMap<K, V>> for BTreeMap<KV, VV>
+    where KV: BindAs<K> + ToCql + Ord,
+          VV: BindAs<V> + ToCql
+{
+}
+
+// The value, if it binds to a parameter read as T.
+pub fn bind_as<T: ?Sized, V: BindCql + BindAs<T>>(value: &V) -> &dyn BindCql {
+    value
+}
+
+// A statement for the query with the values bound in order, once there is one
+// for each bind marker. Nothing checks their types; see CheckedQuery for that.
+pub fn bound_statement(query: &str, values: &[&dyn BindCql]) -> Result<CassStatement, QueryError> {
+    let markers = try!(count_bind_markers(query));
+    if markers != values.len() {
+        return Err(QueryError::ParameterCount {
+            expected: markers,
+            given: values.len(),
+        });
+    }
+    let mut statement = try!(CassStatement::new(query, values.len() as u64));
+    for (i, value) in values.iter().enumerate() {
+        try!(value.bind_to(&mut statement, i as u64));
+    }
+    Ok(statement)
+}
+
+// Checks the values against the query's parameters before binding them.
+pub trait CheckedStatement {
+    fn check_values(&self, values: &[&dyn BindCql]) -> Result<(), QueryError>;
+    fn statement(&self, values: &[&dyn BindCql]) -> Result<CassStatement, QueryError>;
+}
+
+impl CheckedStatement for CheckedQuery {
+    fn check_values(&self, values: &[&dyn BindCql]) -> Result<(), QueryError> {
+        if values.len() != self.parameters.len() {
+            return Err(QueryError::ParameterCount {
+                expected: self.parameters.len(),
+                given: values.len(),
+            });
+        }
+        for (i, (value, &(ref name, ref cql_type))) in values.iter()
+                                                             .zip(&self.parameters)
+                                                             .enumerate() {
+            if !value.accepts(cql_type) {
+                return Err(QueryError::ParameterType {
+                    index: i,
+                    name: name.clone(),
+                    expected: cql_type.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn statement(&self, values: &[&dyn BindCql]) -> Result<CassStatement, QueryError> {
+        try!(self.check_values(values));
+        bound_statement(&self.query, values)
+    }
+}
+
+// A struct a row is read into, as cql! generates for a SELECT.
+pub trait FromRow: Sized {
+    fn from_row(row: &CassRow) -> Result<Self, CassError>;
+}
+
+// The rows of statements other than SELECTs, which have no columns.
+impl FromRow for () {
+    fn from_row(_: &CassRow) -> Result<(), CassError> {
+        Ok(())
+    }
+}
+
+// What cql! returns: a statement with its values bound, and the type its rows
+// are read into.
+pub struct TypedStatement<R> {
+    statement: CassStatement,
+    row: PhantomData<R>,
+}
+
+impl<R: FromRow> TypedStatement<R> {
+    pub fn new(query: &str, values: &[&dyn BindCql]) -> Result<TypedStatement<R>, QueryError> {
+        Ok(TypedStatement {
+            statement: try!(bound_statement(query, values)),
+            row: PhantomData,
+        })
+    }
+
+    pub fn statement(&self) -> &CassStatement {
+        &self.statement
+    }
+
+    pub fn statement_mut(&mut self) -> &mut CassStatement {
+        &mut self.statement
+    }
+
+    pub fn rows(&self, result: &CassResult) -> Result<Vec<R>, CassError> {
+        let mut rows = result.iter();
+        let mut read = Vec::new();
+        while let Some(row) = rows.next() {
+            read.push(try!(R::from_row(&row)));
+        }
+        Ok(read)
+    }
+}
@@ -57,7 +57,7 @@ impl CassCluster {
         where S: Into<String>
     {
         unsafe {
-            let s = CString::new(contact_points.into()).unwrap();
+            let s = try!(CString::new(contact_points.into()));
             let err = CassError::build(cass_cluster_set_contact_points(self.0, s.as_ptr()));
             err.wrap(self)
         }
@@ -219,7 +219,7 @@ impl CassCluster {
     {
         unsafe {
             CassError::build({
-            let local_dc = CString::new(local_dc.into()).unwrap();
+            let local_dc = try!(CString::new(local_dc.into()));
             cass_cluster_set_load_balance_dc_aware(
                 self.0,local_dc.as_ptr(),
                 used_hosts_per_remote_dc,
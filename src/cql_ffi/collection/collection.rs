@@ -1,15 +1,57 @@
-//use cql_bindgen::cass_collection_new_from_data_type;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::convert::Into;
+use std::ffi::CString;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::net::IpAddr;
+use std::time::SystemTime;
+
+use cql_bindgen::CassCollection as _CassCollection;
+use cql_bindgen::CassIterator as _CassIterator;
+use cql_bindgen::cass_iterator_free;
+use cql_bindgen::cass_iterator_next;
+use cql_bindgen::cass_iterator_get_value;
+use cql_bindgen::cass_collection_new;
+use cql_bindgen::cass_collection_new_from_data_type;
 //use cql_bindgen::cass_collection_data_type;
-//use cql_bindgen::cass_collection_append_collection;
-//use cql_bindgen::cass_collection_append_tuple;
-//use cql_bindgen::cass_collection_append_user_type;
+use cql_bindgen::cass_collection_append_int32;
+use cql_bindgen::cass_collection_append_int64;
+use cql_bindgen::cass_collection_append_float;
+use cql_bindgen::cass_collection_append_double;
+use cql_bindgen::cass_collection_append_bool;
+use cql_bindgen::cass_collection_append_string;
+use cql_bindgen::cass_collection_append_bytes;
+use cql_bindgen::cass_collection_append_uuid;
+use cql_bindgen::cass_collection_append_inet;
+use cql_bindgen::cass_collection_append_collection;
+use cql_bindgen::cass_collection_append_tuple;
+use cql_bindgen::cass_collection_append_user_type;
 
+use cql_ffi::collection::list::CassList;
+use cql_ffi::collection::map::CassMap;
+use cql_ffi::collection::set::CassSet;
+use cql_ffi::error::CassError;
+use cql_ffi::error::CassErrorTypes;
+use cql_ffi::inet::CassInet;
+use cassandra_schema::CqlDuration;
+use cassandra_schema::timestamp_from_system_time;
+use cql_ffi::tuple::CassTuple;
+use cql_ffi::udt::CassDataType;
+use cql_ffi::udt::CassUserType;
+use cassandra_schema::CassUuid;
+use cql_ffi::uuid::to_driver;
+use cql_ffi::value::CassValue;
+use cassandra_schema::CassValueType;
+use cql_ffi::bindings::cass_collection_append_int16;
+use cql_ffi::bindings::cass_collection_append_int8;
+use cql_ffi::bindings::cass_collection_append_uint32;
+use cql_ffi::bindings::cass_collection_append_duration;
 
 
 #[repr(C)]
-
-use std::convert::Into;
-
 #[derive(Debug,Copy,Clone)]
 pub enum CassCollectionType {
     LIST = 32,
@@ -22,3 +64,196 @@ impl Into<i64> for CassCollectionType {
         self as i64
     }
 }
+
+// Lists, sets and maps are all the same driver collection underneath, which is
+// what values are appended to.
+pub trait CassCollection {
+    fn inner(&self) -> *mut _CassCollection;
+}
+
+impl CassCollection for CassList {
+    fn inner(&self) -> *mut _CassCollection {
+        self.0
+    }
+}
+
+impl CassCollection for CassSet {
+    fn inner(&self) -> *mut _CassCollection {
+        self.0
+    }
+}
+
+impl CassCollection for CassMap {
+    fn inner(&self) -> *mut _CassCollection {
+        self.0
+    }
+}
+
+// A value that can be appended to a list, set or map. Nested Rust collections
+// are built into a driver collection of their own and appended as a whole.
+pub trait ToCql {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError>;
+}
+
+macro_rules! impl_to_cql {
+    ($t:ty, |$value:ident| $append:ident($($arg:expr),*)) => {
+        impl ToCql for $t {
+            fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+                let $value = self;
+                unsafe {
+                    CassError::build($append(collection.inner(), $($arg),*)).wrap(())
+                }
+            }
+        }
+    }
+}
+
+impl_to_cql!(i8, |value| cass_collection_append_int8(*value));
+impl_to_cql!(i16, |value| cass_collection_append_int16(*value));
+impl_to_cql!(i32, |value| cass_collection_append_int32(*value));
+impl_to_cql!(i64, |value| cass_collection_append_int64(*value));
+impl_to_cql!(u32, |value| cass_collection_append_uint32(*value));
+impl_to_cql!(f32, |value| cass_collection_append_float(*value));
+impl_to_cql!(f64, |value| cass_collection_append_double(*value));
+impl_to_cql!(bool, |value| cass_collection_append_bool(if *value {1} else {0}));
+impl_to_cql!([u8], |value| cass_collection_append_bytes(value.as_ptr(), value.len() as u64));
+impl_to_cql!(CassUuid, |value| cass_collection_append_uuid(to_driver(*value)));
+impl_to_cql!(CassInet, |value| cass_collection_append_inet(value.0));
+impl_to_cql!(IpAddr, |value| cass_collection_append_inet(CassInet::from(*value).0));
+impl_to_cql!(CqlDuration,
+             |value| cass_collection_append_duration(value.months, value.days, value.nanos));
+impl_to_cql!(SystemTime,
+             |value| cass_collection_append_int64(timestamp_from_system_time(*value)));
+impl_to_cql!(CassList, |value| cass_collection_append_collection(value.0));
+impl_to_cql!(CassSet, |value| cass_collection_append_collection(value.0));
+impl_to_cql!(CassMap, |value| cass_collection_append_collection(value.0));
+impl_to_cql!(CassTuple, |value| cass_collection_append_tuple(value.0));
+impl_to_cql!(CassUserType, |value| cass_collection_append_user_type(value.0));
+
+impl ToCql for str {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        let value = try!(CString::new(self));
+        unsafe {
+            CassError::build(cass_collection_append_string(collection.inner(), value.as_ptr()))
+                .wrap(())
+        }
+    }
+}
+
+impl ToCql for String {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        self.as_str().append_to(collection)
+    }
+}
+
+impl<'a, T: ToCql + ?Sized> ToCql for &'a T {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        (**self).append_to(collection)
+    }
+}
+
+impl<T: ToCql> ToCql for Vec<T> {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        try!(CassList::from_iter(self)).append_to(collection)
+    }
+}
+
+impl<T: ToCql + Eq + Hash> ToCql for HashSet<T> {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        try!(CassSet::from_iter(self)).append_to(collection)
+    }
+}
+
+impl<T: ToCql + Ord> ToCql for BTreeSet<T> {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        try!(CassSet::from_iter(self)).append_to(collection)
+    }
+}
+
+impl<K: ToCql + Eq + Hash, V: ToCql> ToCql for HashMap<K, V> {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        try!(CassMap::from_iter(self)).append_to(collection)
+    }
+}
+
+impl<K: ToCql + Ord, V: ToCql> ToCql for BTreeMap<K, V> {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        try!(CassMap::from_iter(self)).append_to(collection)
+    }
+}
+
+// With a data type the driver checks every item against the declared element
+// types as it is appended, and fails with LIB_INVALID_VALUE_TYPE.
+pub(crate) fn new_collection(collection_type: CassCollectionType,
+                      data_type: Option<&CassDataType>,
+                      item_count: u64)
+                      -> Result<*mut _CassCollection, CassError> {
+    unsafe {
+        match data_type {
+            None => Ok(cass_collection_new(collection_type as u32, item_count)),
+            Some(data_type) => {
+                if data_type.get_type() as u32 != collection_type as u32 {
+                    return Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32));
+                }
+                Ok(cass_collection_new_from_data_type(data_type.0, item_count))
+            }
+        }
+    }
+}
+
+// Walks the items of a list, set, map or tuple, together with the type each
+// item was declared with. Map keys and values come out in turn, key first, and
+// each is lent until the next call.
+pub struct CollectionIterator<'a> {
+    iterator: *mut _CassIterator,
+    kind: CassValueType,
+    item_types: (CassValueType, CassValueType),
+    position: u64,
+    _value: PhantomData<CassValue<'a>>,
+}
+
+impl<'a> CollectionIterator<'a> {
+    pub(crate) fn new(iterator: *mut _CassIterator, value: &CassValue<'a>) -> CollectionIterator<'a> {
+        CollectionIterator {
+            iterator: iterator,
+            kind: value.get_type(),
+            item_types: (value.primary_sub_type(), value.secondary_sub_type()),
+            position: 0,
+            _value: PhantomData,
+        }
+    }
+
+    pub fn kind(&self) -> CassValueType {
+        self.kind
+    }
+}
+
+impl<'a> Drop for CollectionIterator<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            cass_iterator_free(self.iterator)
+        }
+    }
+}
+
+impl<'a> CollectionIterator<'a> {
+    pub fn next(&mut self) -> Option<(CassValueType, CassValue<'_>)> {
+        unsafe {
+            match cass_iterator_next(self.iterator) {
+                0 => None,
+                _ => {
+                    let value = CassValue::new(cass_iterator_get_value(self.iterator));
+                    // Tuple items each have a type of their own, which the value
+                    // carries even when it is null.
+                    let item_type = match self.kind {
+                        CassValueType::TUPLE => value.get_type(),
+                        CassValueType::MAP if self.position % 2 == 1 => self.item_types.1,
+                        _ => self.item_types.0,
+                    };
+                    self.position += 1;
+                    Some((item_type, value))
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::ffi::CString;
+use std::marker::PhantomData;
 
 use cql_bindgen::cass_iterator_free;
 use cql_bindgen::cass_iterator_next;
@@ -7,21 +7,14 @@ use cql_bindgen::CassIterator as _CassIterator;
 use cql_bindgen::CassCollection as _CassCollection;
 use cql_bindgen::cass_collection_new;
 use cql_bindgen::cass_collection_free;
-use cql_bindgen::cass_collection_append_int32;
-use cql_bindgen::cass_collection_append_int64;
-use cql_bindgen::cass_collection_append_float;
-use cql_bindgen::cass_collection_append_double;
-use cql_bindgen::cass_collection_append_bool;
-use cql_bindgen::cass_collection_append_bytes;
-use cql_bindgen::cass_collection_append_uuid;
-use cql_bindgen::cass_collection_append_string;
-use cql_bindgen::cass_collection_append_inet;
-//use cql_bindgen::cass_collection_append_decimal;
 
 use cql_ffi::value::CassValue;
 use cql_ffi::collection::collection::CassCollectionType;
+use cql_ffi::collection::collection::ToCql;
+use cql_ffi::collection::collection::new_collection;
+use cql_ffi::udt::CassDataType;
 use cql_ffi::error::CassError;
-use cql_ffi::uuid::CassUuid;
+use cassandra_schema::CassUuid;
 use cql_ffi::inet::CassInet;
 
 pub struct CassList(pub *mut _CassCollection);
@@ -42,75 +35,95 @@ impl CassList {
         }
     }
 
-    pub fn append_int32(&mut self, value: i32) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_int32(self.0,value)).wrap(self)
+    pub fn new_from_data_type(data_type: &CassDataType, item_count: u64) -> Result<CassList, CassError> {
+        new_collection(CassCollectionType::LIST, Some(data_type), item_count).map(CassList)
+    }
+
+    pub fn from_iter<I>(items: I) -> Result<CassList, CassError>
+        where I: IntoIterator,
+              I::Item: ToCql
+    {
+        CassList::collect(None, items)
+    }
+
+    pub fn from_iter_with_type<I>(data_type: &CassDataType, items: I) -> Result<CassList, CassError>
+        where I: IntoIterator,
+              I::Item: ToCql
+    {
+        CassList::collect(Some(data_type), items)
+    }
+
+    // The items are gathered first since the driver wants the count up front.
+    fn collect<I>(data_type: Option<&CassDataType>, items: I) -> Result<CassList, CassError>
+        where I: IntoIterator,
+              I::Item: ToCql
+    {
+        let items: Vec<I::Item> = items.into_iter().collect();
+        let mut collection = CassList(try!(new_collection(CassCollectionType::LIST,
+                                                    data_type,
+                                                    items.len() as u64)));
+        for item in &items {
+            try!(item.append_to(&mut collection));
         }
+        Ok(collection)
     }
 
+    pub fn append<T: ToCql + ?Sized>(&mut self, value: &T) -> Result<&Self, CassError> {
+        try!(value.append_to(self));
+        Ok(self)
+    }
+
+    #[deprecated(note = "use append")]
+    pub fn append_int32(&mut self, value: i32) -> Result<&Self, CassError> {
+        self.append(&value)
+    }
+
+    #[deprecated(note = "use append")]
     pub fn append_int64(&mut self, value: i64) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_int64(self.0,value)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_float(&mut self, value: f32) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_float(self.0,value)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_double(&mut self, value: f64) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_double(self.0,value)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_bool(&mut self, value: bool) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_bool(self.0,if value {1} else {0})).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_string(&mut self, value: &str) -> Result<&Self, CassError> {
-        unsafe {
-            let cstr = CString::new(value).unwrap();
-            let result = cass_collection_append_string(self.0, cstr.as_ptr());
-            CassError::build(result).wrap(self)
-        }
+        self.append(value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_bytes(&mut self, value: Vec<u8>) -> Result<&Self, CassError> {
-        unsafe {
-            let bytes = cass_collection_append_bytes(self.0,
-                                                     value[..].as_ptr(),
-                                                     value.len() as u64);
-            CassError::build(bytes).wrap(self)
-        }
+        self.append(&value[..])
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_uuid(&mut self, value: CassUuid) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_uuid(self.0,value.0)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_inet(&mut self, value: CassInet) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_inet(self.0,value.0)).wrap(self)
-        }
-    }
-
-//FIXME rust doesn't have good decimal support yet
-//    pub fn append_decimal<'a>(&'a mut self, value: String) -> Result<&'a Self,CassError> {unsafe{
-//        CassError::build(cass_collection_append_decimal(self.0,value)).wrap(self)
-//    }}
-
+        self.append(&value)
     }
+}
 
-pub struct ListIterator(pub *mut _CassIterator);
+// Like the other value iterators, it lends each item only until the next
+// call, since the driver decodes items into storage the iterator owns.
+pub struct ListIterator<'a>(pub(crate) *mut _CassIterator, pub(crate) PhantomData<&'a ()>);
 
-impl Drop for ListIterator {
+impl<'a> Drop for ListIterator<'a> {
     fn drop(&mut self) {
         unsafe {
             cass_iterator_free(self.0)
@@ -118,9 +131,8 @@ impl Drop for ListIterator {
     }
 }
 
-impl Iterator for ListIterator {
-    type Item = CassValue;
-    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+impl<'a> ListIterator<'a> {
+    pub fn next(&mut self) -> Option<CassValue<'_>> {
         unsafe {
             match cass_iterator_next(self.0) {
                 0 => None,
@@ -128,10 +140,8 @@ impl Iterator for ListIterator {
             }
         }
     }
-}
 
-impl ListIterator {
-    pub fn get_value(&mut self) -> CassValue {
+    pub fn get_value(&mut self) -> CassValue<'_> {
         unsafe {
             CassValue::new(cass_iterator_get_value(self.0))
         }
@@ -1,31 +1,24 @@
-use std::ffi::CString;
 use std::iter::Iterator;
+use std::marker::PhantomData;
 
 use cql_bindgen::CassCollection as _CassCollection;
 use cql_bindgen::cass_collection_new;
 use cql_bindgen::cass_collection_free;
-use cql_bindgen::cass_collection_append_int32;
-use cql_bindgen::cass_collection_append_int64;
-use cql_bindgen::cass_collection_append_float;
-use cql_bindgen::cass_collection_append_double;
-use cql_bindgen::cass_collection_append_bool;
-use cql_bindgen::cass_collection_append_bytes;
-use cql_bindgen::cass_collection_append_uuid;
-use cql_bindgen::cass_collection_append_string;
-use cql_bindgen::cass_collection_append_inet;
 use cql_bindgen::CassIterator as _CassIterator;
 use cql_bindgen::cass_iterator_free;
 use cql_bindgen::cass_iterator_next;
 use cql_bindgen::cass_iterator_get_map_value;
 use cql_bindgen::cass_iterator_get_map_key;
 
-//use cql_bindgen::cass_collection_append_decimal;
 
 use cql_ffi::value::CassValue;
 use cql_ffi::error::CassError;
-use cql_ffi::uuid::CassUuid;
+use cassandra_schema::CassUuid;
 use cql_ffi::inet::CassInet;
 use cql_ffi::collection::collection::CassCollectionType;
+use cql_ffi::collection::collection::ToCql;
+use cql_ffi::collection::collection::new_collection;
+use cql_ffi::udt::CassDataType;
 
 pub struct CassMap(pub *mut _CassCollection);
 
@@ -45,106 +38,143 @@ impl CassMap {
         }
     }
 
-    pub fn append_int32(&mut self, value: i32) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_int32(self.0,value)).wrap(self)
+    pub fn new_from_data_type(data_type: &CassDataType, item_count: u64) -> Result<CassMap, CassError> {
+        new_collection(CassCollectionType::MAP, Some(data_type), item_count).map(CassMap)
+    }
+
+    pub fn from_iter<I, K, V>(pairs: I) -> Result<CassMap, CassError>
+        where I: IntoIterator<Item = (K, V)>,
+              K: ToCql,
+              V: ToCql
+    {
+        CassMap::collect(None, pairs)
+    }
+
+    pub fn from_iter_with_type<I, K, V>(data_type: &CassDataType, pairs: I) -> Result<CassMap, CassError>
+        where I: IntoIterator<Item = (K, V)>,
+              K: ToCql,
+              V: ToCql
+    {
+        CassMap::collect(Some(data_type), pairs)
+    }
+
+    // Keys and values go into the driver's collection as separate items, so a
+    // map of n pairs holds 2n of them.
+    fn collect<I, K, V>(data_type: Option<&CassDataType>, pairs: I) -> Result<CassMap, CassError>
+        where I: IntoIterator<Item = (K, V)>,
+              K: ToCql,
+              V: ToCql
+    {
+        let pairs: Vec<(K, V)> = pairs.into_iter().collect();
+        let mut map = CassMap(try!(new_collection(CassCollectionType::MAP,
+                                                  data_type,
+                                                  2 * pairs.len() as u64)));
+        for pair in &pairs {
+            try!(map.append_pair(&pair.0, &pair.1));
         }
+        Ok(map)
+    }
+
+    pub fn append_pair<K, V>(&mut self, key: &K, value: &V) -> Result<&Self, CassError>
+        where K: ToCql + ?Sized,
+              V: ToCql + ?Sized
+    {
+        try!(key.append_to(self));
+        try!(value.append_to(self));
+        Ok(self)
+    }
+
+    pub fn append<T: ToCql + ?Sized>(&mut self, value: &T) -> Result<&Self, CassError> {
+        try!(value.append_to(self));
+        Ok(self)
+    }
+
+    #[deprecated(note = "use append")]
+    pub fn append_int32(&mut self, value: i32) -> Result<&Self, CassError> {
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_int64(&mut self, value: i64) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_int64(self.0,value)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_float(&mut self, value: f32) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_float(self.0,value)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_double(&mut self, value: f64) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_double(self.0,value)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_bool(&mut self, value: bool) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_bool(self.0,if value {1} else {0})).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_string(&mut self, value: &str) -> Result<&Self, CassError> {
-        unsafe {
-            let str = CString::new(value).unwrap();
-            CassError::build(cass_collection_append_string(self.0,str.as_ptr())).wrap(self)
-        }
+        self.append(value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_bytes(&mut self, value: Vec<u8>) -> Result<&Self, CassError> {
-        unsafe {
-            let bytes = cass_collection_append_bytes(self.0, value.as_ptr(), value.len() as u64);
-            CassError::build(bytes).wrap(self)
-        }
+        self.append(&value[..])
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_uuid(&mut self, value: CassUuid) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_uuid(self.0,value.0)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_inet(&mut self, value: CassInet) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_inet(self.0,value.0)).wrap(self)
-        }
+        self.append(&value)
     }
-
-//    pub fn append_decimal<'a>(&'a mut self, value: String) -> Result<&'a Self,CassError> {unsafe{
-//        CassError::build(cass_collection_append_decimal(self.0,value)).wrap(self)
-//    }}
-
-
 }
 
-pub struct MapIterator(pub *mut _CassIterator);
+// The key and value of each pair are decoded into the iterator, and lent until
+// the next call.
+pub struct MapIterator<'a>(pub(crate) *mut _CassIterator, pub(crate) PhantomData<&'a ()>);
 
-impl MapIterator {
-    pub fn get_key(&mut self) -> CassValue {
+impl<'a> MapIterator<'a> {
+    pub fn next(&mut self) -> Option<(CassValue<'_>, CassValue<'_>)> {
+        unsafe {
+            match cass_iterator_next(self.0) {
+                0 => None,
+                // a pair that can't be read ends the iteration
+                _ => self.get_pair().ok(),
+            }
+        }
+    }
+
+    pub fn get_key(&mut self) -> CassValue<'_> {
         unsafe {
             CassValue::new(cass_iterator_get_map_key(self.0))
         }
     }
-    pub fn get_value(&mut self) -> CassValue {
+    pub fn get_value(&mut self) -> CassValue<'_> {
         unsafe {
             CassValue::new(cass_iterator_get_map_value(self.0))
         }
     }
 
-    pub fn get_pair(&mut self) -> Result<(CassValue, CassValue), CassError> {
-        Ok((self.get_key(), self.get_value()))
+    pub fn get_pair(&mut self) -> Result<(CassValue<'_>, CassValue<'_>), CassError> {
+        unsafe {
+            Ok((CassValue::new(cass_iterator_get_map_key(self.0)),
+                CassValue::new(cass_iterator_get_map_value(self.0))))
+        }
     }
 
 }
 
 
-impl Drop for MapIterator {
+impl<'a> Drop for MapIterator<'a> {
     fn drop(&mut self) {
         unsafe {
             cass_iterator_free(self.0)
         }
     }
 }
-
-impl Iterator for MapIterator {
-    type Item = (CassValue,CassValue);
-    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        unsafe {
-            match cass_iterator_next(self.0) {
-                0 => None,
-                _ => Some(self.get_pair().unwrap()),
-            }
-        }
-    }
-}
@@ -1,17 +1,8 @@
-use std::ffi::CString;
+use std::marker::PhantomData;
 
 use cql_bindgen::CassCollection as _CassCollection;
 use cql_bindgen::cass_collection_new;
 use cql_bindgen::cass_collection_free;
-use cql_bindgen::cass_collection_append_int32;
-use cql_bindgen::cass_collection_append_int64;
-use cql_bindgen::cass_collection_append_float;
-use cql_bindgen::cass_collection_append_double;
-use cql_bindgen::cass_collection_append_bool;
-use cql_bindgen::cass_collection_append_bytes;
-use cql_bindgen::cass_collection_append_uuid;
-use cql_bindgen::cass_collection_append_string;
-use cql_bindgen::cass_collection_append_inet;
 use cql_bindgen::CassIterator as _CassIterator;
 use cql_bindgen::cass_iterator_free;
 use cql_bindgen::cass_iterator_type;
@@ -25,12 +16,14 @@ use cql_ffi::udt::CassUserType;
 use cql_ffi::value::CassValue;
 use cql_ffi::cass_iterator::CassIteratorType;
 use cql_ffi::schema::CassSchemaMetaField;
-use cql_ffi::schema::CassSchemaMeta;//use cql_bindgen::cass_collection_append_decimal;
+use cql_ffi::schema::CassSchemaMeta;
 use cql_ffi::collection::collection::CassCollectionType;
+use cql_ffi::collection::collection::ToCql;
+use cql_ffi::collection::collection::new_collection;
+use cql_ffi::udt::CassDataType;
 use cql_ffi::error::CassError;
-use cql_ffi::uuid::CassUuid;
+use cassandra_schema::CassUuid;
 use cql_ffi::inet::CassInet;
-use cql_bindgen::cass_collection_append_user_type;
 
 
 pub struct CassSet(pub *mut _CassCollection);
@@ -48,84 +41,103 @@ impl CassSet {
         }
     }
 
+    pub fn new_from_data_type(data_type: &CassDataType, item_count: u64) -> Result<CassSet, CassError> {
+        new_collection(CassCollectionType::SET, Some(data_type), item_count).map(CassSet)
+    }
+
+    pub fn from_iter<I>(items: I) -> Result<CassSet, CassError>
+        where I: IntoIterator,
+              I::Item: ToCql
+    {
+        CassSet::collect(None, items)
+    }
+
+    pub fn from_iter_with_type<I>(data_type: &CassDataType, items: I) -> Result<CassSet, CassError>
+        where I: IntoIterator,
+              I::Item: ToCql
+    {
+        CassSet::collect(Some(data_type), items)
+    }
+
+    // The items are gathered first since the driver wants the count up front.
+    fn collect<I>(data_type: Option<&CassDataType>, items: I) -> Result<CassSet, CassError>
+        where I: IntoIterator,
+              I::Item: ToCql
+    {
+        let items: Vec<I::Item> = items.into_iter().collect();
+        let mut collection = CassSet(try!(new_collection(CassCollectionType::SET,
+                                                    data_type,
+                                                    items.len() as u64)));
+        for item in &items {
+            try!(item.append_to(&mut collection));
+        }
+        Ok(collection)
+    }
+
+    pub fn append<T: ToCql + ?Sized>(&mut self, value: &T) -> Result<&Self, CassError> {
+        try!(value.append_to(self));
+        Ok(self)
+    }
+
     fn free(&mut self) {
         unsafe {
             cass_collection_free(self.0)
         }
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_int32(&mut self, value: i32) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_int32(self.0,value)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_int64(&mut self, value: i64) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_int64(self.0,value)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_float(&mut self, value: f32) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_float(self.0,value)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_double(&mut self, value: f64) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_double(self.0,value)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_bool(&mut self, value: bool) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_bool(self.0,if value {1} else {0})).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_string(&mut self, value: &str) -> Result<&Self, CassError> {
-        unsafe {
-            let cstr = CString::new(value).unwrap();
-            let result = cass_collection_append_string(self.0, cstr.as_ptr());
-            CassError::build(result).wrap(self)
-        }
+        self.append(value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_bytes(&mut self, value: Vec<u8>) -> Result<&Self, CassError> {
-        unsafe {
-            let bytes = cass_collection_append_bytes(self.0,
-                                                     value[..].as_ptr(),
-                                                     value.len() as u64);
-            CassError::build(bytes).wrap(self)
-        }
+        self.append(&value[..])
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_uuid(&mut self, value: CassUuid) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_uuid(self.0,value.0)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_inet(&mut self, value: CassInet) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_inet(self.0,value.0)).wrap(self)
-        }
+        self.append(&value)
     }
 
+    #[deprecated(note = "use append")]
     pub fn append_user_type(&mut self, value: CassUserType) -> Result<&Self, CassError> {
-        unsafe {
-            CassError::build(cass_collection_append_user_type(self.0,value.0)).wrap(self)
-        }
+        self.append(&value)
     }
-
-//    pub fn append_decimal(&mut self, value: String) -> Result<&Self,CassError> {unsafe{
-//        CassError::build(cass_collection_append_decimal(self.0,value)).wrap(self)
-//    }}
 }
 
 
-pub struct SetIterator(pub *mut _CassIterator);
+pub struct SetIterator<'a>(pub(crate) *mut _CassIterator, pub(crate) PhantomData<&'a ()>);
 
 //impl<'a> Display for &'a SetIterator {
 //    fn fmt(&self, f:&mut Formatter) -> fmt::Result {
@@ -136,7 +148,7 @@ pub struct SetIterator(pub *mut _CassIterator);
 //    }
 //}
 
-impl Drop for SetIterator {
+impl<'a> Drop for SetIterator<'a> {
     fn drop(&mut self) {
         unsafe {
             cass_iterator_free(self.0)
@@ -144,9 +156,9 @@ impl Drop for SetIterator {
     }
 }
 
-impl Iterator for SetIterator {
-    type Item = CassValue;
-    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+impl<'a> SetIterator<'a> {
+    // Items are lent until the next call, as with ListIterator.
+    pub fn next(&mut self) -> Option<CassValue<'_>> {
         unsafe {
             match cass_iterator_next(self.0) {
                 0 => None,
@@ -154,16 +166,14 @@ impl Iterator for SetIterator {
             }
         }
     }
-}
 
-impl SetIterator {
     pub unsafe fn get_type(&mut self) -> CassIteratorType {
         CassIteratorType::new(cass_iterator_type(self.0))
     }
 
     //~ unsafe fn get_column(&mut self) -> CassColumn {CassColumn(cass_iterator_get_column(self.0))}
 
-    pub fn get_value(&mut self) -> CassValue {
+    pub fn get_value(&mut self) -> CassValue<'_> {
         unsafe {
             CassValue::new(cass_iterator_get_value(self.0))
         }
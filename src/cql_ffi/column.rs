@@ -1,35 +1,39 @@
+use std::borrow::Cow;
 use std::mem;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt;
 use std::str;
-use std::slice;
+use std::marker::PhantomData;
 
-use cql_bindgen::CASS_VALUE_TYPE_ASCII;
-use cql_bindgen::CASS_VALUE_TYPE_VARCHAR;
-use cql_bindgen::CASS_VALUE_TYPE_TEXT;
-use cql_bindgen::CASS_OK;
 use cql_bindgen::cass_value_get_int32;
 use cql_bindgen::cass_value_get_int64;
 use cql_bindgen::cass_value_get_float;
 use cql_bindgen::cass_value_get_double;
 use cql_bindgen::cass_value_get_bool;
 use cql_bindgen::cass_value_get_uuid;
-use cql_bindgen::cass_value_get_string;
-use cql_bindgen::cass_value_get_inet;
 use cql_bindgen::cass_iterator_from_map;
 use cql_bindgen::cass_iterator_from_user_type;
 use cql_bindgen::cass_iterator_from_collection;
 use cql_bindgen::cass_value_type;
 use cql_bindgen::CassValue as _CassValue;
+use cql_bindgen::CassUuid as _CassUuid;
 
-use cql_ffi::uuid::CassUuid;
+use cassandra_schema::CassUuid;
+use cql_ffi::uuid::from_driver;
 //use cql_ffi::udt::CassUserType;
-use cql_ffi::value::CassValueType;
+use cassandra_schema::CassValueType;
+use cql_ffi::value::CassValue;
+use cql_ffi::value::FromCql;
+use cassandra_schema::CqlValue;
+use cassandra_schema::CqlDuration;
+use cql_ffi::collection::collection::CollectionIterator;
+use cql_ffi::collection::list::ListIterator;
 use cql_ffi::collection::set::SetIterator;
 use cql_ffi::inet::CassInet;
 use cql_ffi::collection::map::MapIterator;
+use cql_ffi::tuple::TupleIterator;
 use cql_ffi::udt::UserTypeIterator;
 use cql_ffi::error::CassErrorTypes;
 use cql_ffi::error::CassError;
@@ -45,97 +49,17 @@ pub enum CassColumnType {
     UNKNOWN = 5,
 }
 
-pub struct CassColumn(pub *const _CassValue);
+pub struct CassColumn<'a>(pub(crate) *const _CassValue, pub(crate) PhantomData<&'a ()>);
 
-impl Debug for CassColumn {
+impl<'a> Debug for CassColumn<'a> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match self.get_type() {
-            CassValueType::UNKNOWN => write!(f, "UNKNOWN Cassandra type"),
-            CassValueType::CUSTOM => write!(f, "CUSTOM Cassandra type"),
-            CassValueType::ASCII => write!(f, "ASCII Cassandra type"),
-            CassValueType::BIGINT => write!(f, "BIGINT Cassandra type"),
-            CassValueType::BLOB => write!(f, "BLOB Cassandra type"),
-            CassValueType::BOOLEAN => write!(f, "BOOLEAN Cassandra type"),
-            CassValueType::COUNTER => write!(f, "COUNTER Cassandra type"),
-            CassValueType::DECIMAL => write!(f, "DECIMAL Cassandra type"),
-            CassValueType::DOUBLE => write!(f, "DOUBLE Cassandra type"),
-            CassValueType::FLOAT => write!(f, "FLOAT Cassandra type"),
-            CassValueType::INT => write!(f, "INT Cassandra type"),
-            CassValueType::TEXT => write!(f, "TEXT Cassandra type"),
-            CassValueType::TIMESTAMP => write!(f, "TIMESTAMP Cassandra type"),
-            CassValueType::UUID => write!(f, "UUID Cassandra type"),
-            CassValueType::VARCHAR => write!(f, "VARCHAR: {:?}", self.get_string()),
-            CassValueType::VARINT => Ok(()),
-            CassValueType::TIMEUUID => write!(f, "TIMEUUID Cassandra type"),
-            CassValueType::INET => write!(f, "INET Cassandra type"),
-            CassValueType::LIST => {
-                for item in self.set_iter().unwrap() {
-                    try!(write!(f, "LIST {:?}", item ))
-                }
-                Ok(())
-            }
-            CassValueType::MAP => {
-                for item in self.map_iter().unwrap() {
-                    try!(write!(f, "LIST {:?}", item ))
-                }
-                Ok(())
-            }
-            CassValueType::SET => {
-                for item in self.set_iter().unwrap() {
-                    try!(write!(f, "SET {:?}", item ))
-                }
-                Ok(())
-            }
-            CassValueType::UDT => write!(f, "UDT Cassandra type"),
-            CassValueType::TUPLE => write!(f, "Tuple Cassandra type"),
-            CassValueType::LASTENTRY => write!(f, "LAST_ENTRY Cassandra type"),
-        }
+        write!(f, "{:?}", CassValue::new(self.0))
     }
 }
 
-impl Display for CassColumn {
+impl<'a> Display for CassColumn<'a> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match self.get_type() {
-            CassValueType::UNKNOWN => write!(f, "UNKNOWN Cassandra type"),
-            CassValueType::CUSTOM => write!(f, "CUSTOM Cassandra type"),
-            CassValueType::ASCII => write!(f, "ASCII Cassandra type"),
-            CassValueType::BIGINT => write!(f, "BIGINT Cassandra type"),
-            CassValueType::BLOB => write!(f, "BLOB Cassandra type"),
-            CassValueType::BOOLEAN => write!(f, "BOOLEAN Cassandra type"),
-            CassValueType::COUNTER => write!(f, "COUNTER Cassandra type"),
-            CassValueType::DECIMAL => write!(f, "DECIMAL Cassandra type"),
-            CassValueType::DOUBLE => write!(f, "DOUBLE Cassandra type"),
-            CassValueType::FLOAT => write!(f, "FLOAT Cassandra type"),
-            CassValueType::INT => write!(f, "INT Cassandra type"),
-            CassValueType::TEXT => write!(f, "TEXT Cassandra type"),
-            CassValueType::TIMESTAMP => write!(f, "TIMESTAMP Cassandra type"),
-            CassValueType::UUID => write!(f, "UUID Cassandra type"),
-            CassValueType::VARCHAR => write!(f, "{}", self.get_string().unwrap()),
-            CassValueType::VARINT => Ok(()),
-            CassValueType::TIMEUUID => write!(f, "TIMEUUID Cassandra type"),
-            CassValueType::INET => write!(f, "INET Cassandra type"),
-            CassValueType::LIST => {
-                for item in self.set_iter().unwrap() {
-                    try!(write!(f, "LIST {:?}", item ))
-                }
-                Ok(())
-            }
-            CassValueType::MAP => {
-                for item in self.map_iter().unwrap() {
-                    try!(write!(f, "LIST {:?}", item ))
-                }
-                Ok(())
-            }
-            CassValueType::SET => {
-                for item in self.set_iter().unwrap() {
-                    try!(write!(f, "SET {:?}", item ))
-                }
-                Ok(())
-            }
-            CassValueType::UDT => write!(f, "UDT Cassandra type"),
-            CassValueType::TUPLE => write!(f, "Tuple Cassandra type"),
-            CassValueType::LASTENTRY => write!(f, "LAST_ENTRY Cassandra type"),
-        }
+        write!(f, "{}", CassValue::new(self.0))
     }
 }
 
@@ -151,37 +75,43 @@ impl AsTypedColumn for bool {
     }
 }
 
-impl CassColumn {
+impl<'a> CassColumn<'a> {
     pub fn get_type(&self) -> CassValueType {
         unsafe {
             CassValueType::build(cass_value_type(self.0))
         }
     }
 
-    pub unsafe fn get_inet(&self, mut output: CassInet) -> Result<CassInet, CassError> {
-        CassError::build(cass_value_get_inet(self.0,&mut output.0)).wrap(output)
+    pub fn to_cql_value(&self) -> Result<CqlValue, CassError> {
+        CassValue::new(self.0).to_cql_value()
     }
 
-    pub fn get_string(&self) -> Result<String, CassError> {
-        unsafe {
-            match cass_value_type(self.0) {
-                CASS_VALUE_TYPE_ASCII | CASS_VALUE_TYPE_TEXT | CASS_VALUE_TYPE_VARCHAR => {
-                    let mut message = mem::zeroed();
-                    let mut message_length = mem::zeroed();
-                    match cass_value_get_string(self.0, &mut message, &mut message_length) {
-                        CASS_OK => {
-                            let slice = slice::from_raw_parts(message as *const u8,
-                                                              message_length as usize);
-                            Ok(str::from_utf8(slice).unwrap().to_owned())
-                        }
-                        err => Err(CassError::build(err)),
-                    }
-
-
-                }
-                err => Err(CassError::build(err)),
-            }
-        }
+    pub fn get_decimal_bytes(&self) -> Result<(Vec<u8>, i32), CassError> {
+        CassValue::new(self.0).get_decimal_bytes()
+    }
+
+    pub fn get_varint_bytes(&self) -> Result<Vec<u8>, CassError> {
+        CassValue::new(self.0).get_varint_bytes()
+    }
+
+    pub fn get_inet(&self) -> Result<CassInet, CassError> {
+        CassValue::new(self.0).get_inet()
+    }
+
+    pub fn get_str(&self) -> Result<&'a str, CassError> {
+        CassValue::new(self.0).get_str()
+    }
+
+    pub fn get_str_lossy(&self) -> Result<Cow<'a, str>, CassError> {
+        CassValue::new(self.0).get_str_lossy()
+    }
+
+    pub fn get_string(&self) -> Result<&'a str, CassError> {
+        CassValue::new(self.0).get_string()
+    }
+
+    pub fn get_bytes(&self) -> Result<&'a [u8], CassError> {
+        CassValue::new(self.0).get_bytes()
     }
 
     pub fn get_int32(&self) -> Result<i32, CassError> {
@@ -198,6 +128,22 @@ impl CassColumn {
         }
     }
 
+    pub fn get_int16(&self) -> Result<i16, CassError> {
+        CassValue::new(self.0).get_int16()
+    }
+
+    pub fn get_int8(&self) -> Result<i8, CassError> {
+        CassValue::new(self.0).get_int8()
+    }
+
+    pub fn get_uint32(&self) -> Result<u32, CassError> {
+        CassValue::new(self.0).get_uint32()
+    }
+
+    pub fn get_duration(&self) -> Result<CqlDuration, CassError> {
+        CassValue::new(self.0).get_duration()
+    }
+
     pub fn get_float(&self) -> Result<f32, CassError> {
         unsafe {
             let mut output = mem::zeroed();
@@ -221,33 +167,51 @@ impl CassColumn {
 
     pub fn get_uuid(&self) -> Result<CassUuid, CassError> {
         unsafe {
-            let mut output: CassUuid = mem::zeroed();
-            CassError::build(cass_value_get_uuid(self.0,&mut output.0)).wrap(output)
+            let mut output: _CassUuid = mem::zeroed();
+            CassError::build(cass_value_get_uuid(self.0,&mut output)).wrap(from_driver(output))
         }
     }
 
-    pub fn map_iter(&self) -> Result<MapIterator, CassError> {
+    pub fn map_iter(&self) -> Result<MapIterator<'a>, CassError> {
         unsafe {
             match self.get_type() {
-                CassValueType::MAP => Ok(MapIterator(cass_iterator_from_map(self.0))),
+                CassValueType::MAP => Ok(MapIterator(cass_iterator_from_map(self.0), PhantomData)),
                 _ => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
             }
         }
     }
 
-    pub fn set_iter(&self) -> Result<SetIterator, CassError> {
+    pub fn set_iter(&self) -> Result<SetIterator<'a>, CassError> {
         unsafe {
             match self.get_type() {
-                CassValueType::SET => Ok(SetIterator(cass_iterator_from_collection(self.0))),
+                CassValueType::SET => Ok(SetIterator(cass_iterator_from_collection(self.0), PhantomData)),
                 _ => Err(CassError::build(1)),
             }
         }
     }
 
-    pub fn use_type_iter(&self) -> Result<UserTypeIterator, CassError> {
+    pub fn list_iter(&self) -> Result<ListIterator<'a>, CassError> {
+        CassValue::new(self.0).list_iter()
+    }
+
+    pub fn tuple_iter(&self) -> Result<TupleIterator<'a>, CassError> {
+        CassValue::new(self.0).tuple_iter()
+    }
+
+    pub fn collection_iter(&self) -> Result<CollectionIterator<'a>, CassError> {
+        CassValue::new(self.0).collection_iter()
+    }
+
+    pub fn get<T>(&self) -> Result<T, CassError>
+        where T: FromCql<'a>
+    {
+        CassValue::new(self.0).get()
+    }
+
+    pub fn use_type_iter(&self) -> Result<UserTypeIterator<'a>, CassError> {
         unsafe {
             match self.get_type() {
-                CassValueType::UDT => Ok(UserTypeIterator(cass_iterator_from_user_type(self.0))),
+                CassValueType::UDT => Ok(UserTypeIterator(cass_iterator_from_user_type(self.0), PhantomData)),
                 _ => Err(CassError::build(1)),
             }
         }
@@ -1,10 +1,13 @@
 use std::fmt::{Debug, Display, Formatter};
 use std::fmt;
-use std::str;
 use std::ffi::CStr;
+use std::ffi::NulError;
 use std::error::Error;
+use std::str::Utf8Error;
 use std::str::from_utf8;
 
+use cassandra_schema::ValueError;
+
 use cql_bindgen::cass_error_desc;
 use cql_bindgen::CASS_ERROR_LIB_BAD_PARAMS;
 use cql_bindgen::CASS_ERROR_LIB_NO_STREAMS;
@@ -64,50 +67,66 @@ pub enum CassErrorSource {
     COMPRESSION = 4,
 }
 
-pub struct CassError(_CassError);
+pub enum CassError {
+    // An error code from the driver or the server.
+    Code(_CassError),
+    // A string read from the driver was not valid UTF-8.
+    Utf8(Utf8Error),
+}
 
 impl Error for CassError {
     fn description(&self) -> &str {
-        let c_buf: *const i8 = unsafe {
-            self.desc()
-        };
-        let buf: &[u8] = unsafe {
-            CStr::from_ptr(c_buf).to_bytes()
-        };
-        from_utf8(buf).unwrap()
+        match *self {
+            CassError::Code(_) => {
+                let buf: &[u8] = unsafe {
+                    CStr::from_ptr(self.desc()).to_bytes()
+                };
+                from_utf8(buf).unwrap_or("unknown error")
+            }
+            CassError::Utf8(_) => "invalid UTF-8 in string",
+        }
     }
 }
 
 impl Display for CassError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let c_buf: *const i8 = unsafe {
-            self.desc()
-        };
-        let buf: &[u8] = unsafe {
-            CStr::from_ptr(c_buf).to_bytes()
-        };
-        match str::from_utf8(buf) {
-            Ok(str_slice) => {
-                write!(f, "{}", str_slice)
-            }
-            Err(err) => panic!("unreachable? {:?}", err),
+        match *self {
+            CassError::Code(_) => write!(f, "{}", self.description()),
+            CassError::Utf8(ref err) => write!(f, "invalid UTF-8 in string: {}", err),
         }
     }
 }
 
 impl Debug for CassError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let c_buf: *const i8 = unsafe {
-            self.desc()
-        };
-        let buf: &[u8] = unsafe {
-            CStr::from_ptr(c_buf).to_bytes()
-        };
-        match str::from_utf8(buf) {
-            Ok(str_slice) => {
-                write!(f, "{:?}", str_slice)
+        match *self {
+            CassError::Code(_) => write!(f, "{:?}", self.description()),
+            CassError::Utf8(ref err) => write!(f, "Utf8({:?})", err),
+        }
+    }
+}
+
+impl From<Utf8Error> for CassError {
+    fn from(err: Utf8Error) -> CassError {
+        CassError::Utf8(err)
+    }
+}
+
+// The driver takes NUL terminated strings, so one with a NUL inside it can't be
+// passed on.
+impl From<NulError> for CassError {
+    fn from(_: NulError) -> CassError {
+        CassError::build(CassErrorTypes::LIB_BAD_PARAMS as u32)
+    }
+}
+
+impl From<ValueError> for CassError {
+    fn from(err: ValueError) -> CassError {
+        match err {
+            ValueError::BadParams => CassError::build(CassErrorTypes::LIB_BAD_PARAMS as u32),
+            ValueError::InvalidValueType => {
+                CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)
             }
-            Err(err) => panic!("unreachable? {:?}", err),
         }
     }
 }
@@ -164,63 +183,64 @@ pub enum CassErrorTypes {
 
 impl CassError {
     pub fn wrap<T>(&self, wrappee: T) -> Result<T, CassError> {
-        match self.0 {
-            CASS_OK => Ok(wrappee),
-            err => Err(CassError::build(err)),
+        match *self {
+            CassError::Code(CASS_OK) => Ok(wrappee),
+            CassError::Code(err) => Err(CassError::build(err)),
+            CassError::Utf8(err) => Err(CassError::Utf8(err)),
         }
     }
 
     pub fn build(val: u32) -> CassError {
         match val {
-            0 => CassError(CASS_OK),
-            1 => CassError(CASS_ERROR_LIB_BAD_PARAMS),
-            2 => CassError(CASS_ERROR_LIB_NO_STREAMS),
-            3 => CassError(CASS_ERROR_LIB_UNABLE_TO_INIT),
-            4 => CassError(CASS_ERROR_LIB_MESSAGE_ENCODE),
-            5 => CassError(CASS_ERROR_LIB_HOST_RESOLUTION),
-            6 => CassError(CASS_ERROR_LIB_UNEXPECTED_RESPONSE),
-            7 => CassError(CASS_ERROR_LIB_REQUEST_QUEUE_FULL),
-            8 => CassError(CASS_ERROR_LIB_NO_AVAILABLE_IO_THREAD),
-            9 => CassError(CASS_ERROR_LIB_WRITE_ERROR),
-            10 | 16777226 => CassError(CASS_ERROR_LIB_NO_HOSTS_AVAILABLE),
-            11 => CassError(CASS_ERROR_LIB_INDEX_OUT_OF_BOUNDS),
-            12 => CassError(CASS_ERROR_LIB_INVALID_ITEM_COUNT),
-            13 => CassError(CASS_ERROR_LIB_INVALID_VALUE_TYPE),
-            14 => CassError(CASS_ERROR_LIB_REQUEST_TIMED_OUT),
-            15 => CassError(CASS_ERROR_LIB_UNABLE_TO_SET_KEYSPACE),
-            16 => CassError(CASS_ERROR_LIB_CALLBACK_ALREADY_SET),
-            17 => CassError(CASS_ERROR_LIB_INVALID_STATEMENT_TYPE),
-            18 => CassError(CASS_ERROR_LIB_NAME_DOES_NOT_EXIST),
-            19 => CassError(CASS_ERROR_LIB_UNABLE_TO_DETERMINE_PROTOCOL),
-            20 => CassError(CASS_ERROR_LIB_NULL_VALUE),
-            21 => CassError(CASS_ERROR_LIB_NOT_IMPLEMENTED),
-            22 => CassError(CASS_ERROR_LIB_UNABLE_TO_CONNECT),
-            23 => CassError(CASS_ERROR_LIB_UNABLE_TO_CLOSE),
-            33554432 => CassError(CASS_ERROR_SERVER_SERVER_ERROR),
-            33554442 => CassError(CASS_ERROR_SERVER_PROTOCOL_ERROR),
-            33554688 => CassError(CASS_ERROR_SERVER_BAD_CREDENTIALS),
-            33558528 => CassError(CASS_ERROR_SERVER_UNAVAILABLE),
-            33558529 => CassError(CASS_ERROR_SERVER_OVERLOADED),
-            33558530 => CassError(CASS_ERROR_SERVER_IS_BOOTSTRAPPING),
-            33558531 => CassError(CASS_ERROR_SERVER_TRUNCATE_ERROR),
-            33558784 => CassError(CASS_ERROR_SERVER_WRITE_TIMEOUT),
-            33559040 => CassError(CASS_ERROR_SERVER_READ_TIMEOUT),
-            33562624 => CassError(CASS_ERROR_SERVER_SYNTAX_ERROR),
-            33562880 => CassError(CASS_ERROR_SERVER_UNAUTHORIZED),
-            33563136 => CassError(CASS_ERROR_SERVER_INVALID_QUERY),
-            33563392 => CassError(CASS_ERROR_SERVER_CONFIG_ERROR),
-            33563648 => CassError(CASS_ERROR_SERVER_ALREADY_EXISTS),
-            33563904 => CassError(CASS_ERROR_SERVER_UNPREPARED),
-            50331649 => CassError(CASS_ERROR_SSL_INVALID_CERT),
-            50331650 => CassError(CASS_ERROR_SSL_INVALID_PRIVATE_KEY),
-            50331651 => CassError(CASS_ERROR_SSL_NO_PEER_CERT),
-            50331652 => CassError(CASS_ERROR_SSL_INVALID_PEER_CERT),
-            50331653 => CassError(CASS_ERROR_SSL_IDENTITY_MISMATCH),
-            50331654 => CassError(CASS_ERROR_SSL_PROTOCOL_ERROR),
-            50331655 => CassError(CASS_ERROR_LAST_ENTRY),
+            0 => CassError::Code(CASS_OK),
+            1 => CassError::Code(CASS_ERROR_LIB_BAD_PARAMS),
+            2 => CassError::Code(CASS_ERROR_LIB_NO_STREAMS),
+            3 => CassError::Code(CASS_ERROR_LIB_UNABLE_TO_INIT),
+            4 => CassError::Code(CASS_ERROR_LIB_MESSAGE_ENCODE),
+            5 => CassError::Code(CASS_ERROR_LIB_HOST_RESOLUTION),
+            6 => CassError::Code(CASS_ERROR_LIB_UNEXPECTED_RESPONSE),
+            7 => CassError::Code(CASS_ERROR_LIB_REQUEST_QUEUE_FULL),
+            8 => CassError::Code(CASS_ERROR_LIB_NO_AVAILABLE_IO_THREAD),
+            9 => CassError::Code(CASS_ERROR_LIB_WRITE_ERROR),
+            10 | 16777226 => CassError::Code(CASS_ERROR_LIB_NO_HOSTS_AVAILABLE),
+            11 => CassError::Code(CASS_ERROR_LIB_INDEX_OUT_OF_BOUNDS),
+            12 => CassError::Code(CASS_ERROR_LIB_INVALID_ITEM_COUNT),
+            13 => CassError::Code(CASS_ERROR_LIB_INVALID_VALUE_TYPE),
+            14 => CassError::Code(CASS_ERROR_LIB_REQUEST_TIMED_OUT),
+            15 => CassError::Code(CASS_ERROR_LIB_UNABLE_TO_SET_KEYSPACE),
+            16 => CassError::Code(CASS_ERROR_LIB_CALLBACK_ALREADY_SET),
+            17 => CassError::Code(CASS_ERROR_LIB_INVALID_STATEMENT_TYPE),
+            18 => CassError::Code(CASS_ERROR_LIB_NAME_DOES_NOT_EXIST),
+            19 => CassError::Code(CASS_ERROR_LIB_UNABLE_TO_DETERMINE_PROTOCOL),
+            20 => CassError::Code(CASS_ERROR_LIB_NULL_VALUE),
+            21 => CassError::Code(CASS_ERROR_LIB_NOT_IMPLEMENTED),
+            22 => CassError::Code(CASS_ERROR_LIB_UNABLE_TO_CONNECT),
+            23 => CassError::Code(CASS_ERROR_LIB_UNABLE_TO_CLOSE),
+            33554432 => CassError::Code(CASS_ERROR_SERVER_SERVER_ERROR),
+            33554442 => CassError::Code(CASS_ERROR_SERVER_PROTOCOL_ERROR),
+            33554688 => CassError::Code(CASS_ERROR_SERVER_BAD_CREDENTIALS),
+            33558528 => CassError::Code(CASS_ERROR_SERVER_UNAVAILABLE),
+            33558529 => CassError::Code(CASS_ERROR_SERVER_OVERLOADED),
+            33558530 => CassError::Code(CASS_ERROR_SERVER_IS_BOOTSTRAPPING),
+            33558531 => CassError::Code(CASS_ERROR_SERVER_TRUNCATE_ERROR),
+            33558784 => CassError::Code(CASS_ERROR_SERVER_WRITE_TIMEOUT),
+            33559040 => CassError::Code(CASS_ERROR_SERVER_READ_TIMEOUT),
+            33562624 => CassError::Code(CASS_ERROR_SERVER_SYNTAX_ERROR),
+            33562880 => CassError::Code(CASS_ERROR_SERVER_UNAUTHORIZED),
+            33563136 => CassError::Code(CASS_ERROR_SERVER_INVALID_QUERY),
+            33563392 => CassError::Code(CASS_ERROR_SERVER_CONFIG_ERROR),
+            33563648 => CassError::Code(CASS_ERROR_SERVER_ALREADY_EXISTS),
+            33563904 => CassError::Code(CASS_ERROR_SERVER_UNPREPARED),
+            50331649 => CassError::Code(CASS_ERROR_SSL_INVALID_CERT),
+            50331650 => CassError::Code(CASS_ERROR_SSL_INVALID_PRIVATE_KEY),
+            50331651 => CassError::Code(CASS_ERROR_SSL_NO_PEER_CERT),
+            50331652 => CassError::Code(CASS_ERROR_SSL_INVALID_PEER_CERT),
+            50331653 => CassError::Code(CASS_ERROR_SSL_IDENTITY_MISMATCH),
+            50331654 => CassError::Code(CASS_ERROR_SSL_PROTOCOL_ERROR),
+            50331655 => CassError::Code(CASS_ERROR_LAST_ENTRY),
             err_no => {
                 debug!("unhandled error number: {}", err_no);
-                CassError(err_no)
+                CassError::Code(err_no)
             }
         }
     }
@@ -229,7 +249,10 @@ impl CassError {
 
 impl CassError {
     pub unsafe fn desc(&self) -> *const i8 {
-        cass_error_desc(self.0)
+        match *self {
+            CassError::Code(err) => cass_error_desc(err),
+            CassError::Utf8(_) => b"Invalid UTF-8 in string\0".as_ptr() as *const i8,
+        }
     }
     pub fn debug(&self) {
         println!("{:?}",self)
@@ -1,8 +1,10 @@
+use std::marker::PhantomData;
 use std::mem;
-use std::str;
-use std::slice;
 
+use cql_ffi::bindings::CassNode as _CassNode;
+use cql_ffi::bindings::cass_future_coordinator;
 use cql_ffi::error::CassError;
+use cql_ffi::helpers::str_from_raw_lossy;
 use cql_ffi::result::CassResult;
 use cql_ffi::prepared::CassPrepared;
 
@@ -59,14 +61,14 @@ impl CassFuture {
         }
     }
 
+    // Messages come from the server as well as the driver, so invalid UTF-8 in
+    // one is replaced rather than reported.
     pub fn error_message(&mut self) -> String {
         unsafe {
-            let message = mem::zeroed();
-            let message_length = mem::zeroed();
-            cass_future_error_message(self.0, message, message_length);
-
-            let slice: &[u8] = slice::from_raw_parts(message as *const u8, message_length as usize);
-            str::from_utf8(slice).unwrap().to_owned()
+            let mut message = mem::zeroed();
+            let mut message_length = mem::zeroed();
+            cass_future_error_message(self.0, &mut message, &mut message_length);
+            str_from_raw_lossy(message, message_length).into_owned()
         }
     }
 
@@ -99,23 +101,36 @@ impl ResultFuture {
 
     pub fn error_message(&mut self) -> String {
         unsafe {
-            let message = mem::zeroed();
-            let message_length = mem::zeroed();
-            cass_future_error_message(self.0, message, message_length);
-
-            let slice = slice::from_raw_parts(message as *const u8, message_length as usize);
-            str::from_utf8(slice).unwrap().to_owned()
+            let mut message = mem::zeroed();
+            let mut message_length = mem::zeroed();
+            cass_future_error_message(self.0, &mut message, &mut message_length);
+            str_from_raw_lossy(message, message_length).into_owned()
         }
     }
 
 
     pub fn get(&mut self) -> CassResult {
         unsafe {
-            CassResult(cass_future_get_result(self.0))
+            CassResult::build(cass_future_get_result(self.0))
+        }
+    }
+
+    // The node that coordinated the request, once it has completed.
+    pub fn coordinator(&self) -> Option<CassNode<'_>> {
+        unsafe {
+            let node = cass_future_coordinator(self.0);
+            if node.is_null() {
+                None
+            } else {
+                Some(CassNode(node, PhantomData))
+            }
         }
     }
 }
 
+// A node of the cluster, valid as long as the future it came from.
+pub struct CassNode<'a>(pub(crate) *const _CassNode, PhantomData<&'a ResultFuture>);
+
 
 pub struct PreparedFuture(pub *mut _CassFuture);
 
@@ -144,12 +159,10 @@ impl PreparedFuture {
 
     pub fn error_message(&mut self) -> String {
         unsafe {
-            let message = mem::zeroed();
-            let message_length = mem::zeroed();
-            cass_future_error_message(self.0, message, message_length);
-
-            let slice = slice::from_raw_parts(message as *const u8, message_length as usize);
-            str::from_utf8(slice).unwrap().to_owned()
+            let mut message = mem::zeroed();
+            let mut message_length = mem::zeroed();
+            cass_future_error_message(self.0, &mut message, &mut message_length);
+            str_from_raw_lossy(message, message_length).into_owned()
         }
     }
 
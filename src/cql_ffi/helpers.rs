@@ -1,11 +1,35 @@
+use std::borrow::Cow;
 use std::mem;
 use std::slice;
+use std::str;
+
+use libc::c_char;
+
+use cql_ffi::error::CassError;
 
 #[allow(unused)]
 unsafe fn raw_byte_repr<T>(ptr: &T) -> &[u8] {
     mem::transmute(slice::from_raw_parts(ptr as *const _ as *const u8, mem::size_of::<T>()))
 }
 
+// The driver hands out strings as a pointer and a length. They are not NUL
+// terminated and nothing checks that they hold UTF-8, so that is done here.
+pub(crate) unsafe fn str_from_raw<'a>(data: *const c_char, length: u64) -> Result<&'a str, CassError> {
+    Ok(try!(str::from_utf8(bytes_from_raw(data, length))))
+}
+
+pub(crate) unsafe fn str_from_raw_lossy<'a>(data: *const c_char, length: u64) -> Cow<'a, str> {
+    String::from_utf8_lossy(bytes_from_raw(data, length))
+}
+
+unsafe fn bytes_from_raw<'a>(data: *const c_char, length: u64) -> &'a [u8] {
+    if data.is_null() {
+        &[]
+    } else {
+        slice::from_raw_parts(data as *const u8, length as usize)
+    }
+}
+
 //~ pub fn str_to_ref(mystr:&str) -> *const i8 {
     //~ let s = CString::new(mystr).unwrap();
     //~ s.as_ptr() // s is still alive here }
@@ -2,12 +2,21 @@
 use cql_bindgen::CassInet as _CassInet;
 use cql_bindgen::cass_inet_init_v4;
 use cql_bindgen::cass_inet_init_v6;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::net::IpAddr;
 use std::net::SocketAddr;
 use std::net::Ipv4Addr;
 use std::net::Ipv6Addr;
 use std::default::Default;
 
+use cql_ffi::error::CassError;
+use cql_ffi::error::CassErrorTypes;
+
 #[repr(C)]
+#[derive(Copy,Clone)]
 pub struct CassInet(pub _CassInet);
 
 impl Default for CassInet {
@@ -18,60 +27,100 @@ impl Default for CassInet {
     }
 }
 
+impl Debug for CassInet {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match IpAddr::try_from(*self) {
+            Ok(addr) => write!(f, "{}", addr),
+            Err(_) => write!(f, "invalid inet of length {}", self.0.address_length),
+        }
+    }
+}
+
 pub trait AsCassInet {
     fn as_cass_inet(&self) -> CassInet;
 }
 
 impl AsCassInet for SocketAddr {
     fn as_cass_inet(&self) -> CassInet {
-        match *self {
-            SocketAddr::V4(ipv4_addr) => {
-                unsafe {
-                    CassInet(cass_inet_init_v4(ipv4_addr.ip().octets().as_ptr()))
-                }
-            }
-            SocketAddr::V6(ipv6_addr) => {
-                unsafe {
-                    let seg = ipv6_addr.ip().segments();
-                //FIXME does this really work?
-                    CassInet(cass_inet_init_v6(seg.as_ptr() as *const u8))
-                }
-            }
+        CassInet::from(self.ip())
+    }
+}
+
+impl AsCassInet for IpAddr {
+    fn as_cass_inet(&self) -> CassInet {
+        CassInet::from(*self)
+    }
+}
+
+impl From<Ipv4Addr> for CassInet {
+    fn from(addr: Ipv4Addr) -> CassInet {
+        unsafe {
+            CassInet(cass_inet_init_v4(addr.octets().as_ptr()))
         }
-        //~ let foo:_CassInet = Default::default();
-        //~ CassInet(foo)
     }
 }
 
-pub trait FromCassInet {
-    fn from_cass_inet(inet: CassInet) -> Self;
+// The driver wants the 16 address bytes in network order, not the u16 segments.
+impl From<Ipv6Addr> for CassInet {
+    fn from(addr: Ipv6Addr) -> CassInet {
+        unsafe {
+            CassInet(cass_inet_init_v6(addr.octets().as_ptr()))
+        }
+    }
 }
 
-impl FromCassInet for Ipv4Addr {
-    fn from_cass_inet(inet: CassInet) -> Self {
+impl From<IpAddr> for CassInet {
+    fn from(addr: IpAddr) -> CassInet {
+        match addr {
+            IpAddr::V4(addr) => CassInet::from(addr),
+            IpAddr::V6(addr) => CassInet::from(addr),
+        }
+    }
+}
+
+impl From<SocketAddr> for CassInet {
+    fn from(addr: SocketAddr) -> CassInet {
+        CassInet::from(addr.ip())
+    }
+}
+
+impl TryFrom<CassInet> for IpAddr {
+    type Error = CassError;
+
+    fn try_from(inet: CassInet) -> Result<IpAddr, CassError> {
         let raw_addr: [u8; 16] = inet.0.address;
         match inet.0.address_length {
-            4 => Ipv4Addr::new(raw_addr[0], raw_addr[1], raw_addr[2], raw_addr[3]),
-            16 => panic!(),
-            unsupported => panic!("impossible inet type: {:?}", unsupported),
+            4 => Ok(IpAddr::V4(Ipv4Addr::new(raw_addr[0], raw_addr[1], raw_addr[2], raw_addr[3]))),
+            16 => Ok(IpAddr::V6(Ipv6Addr::from(raw_addr))),
+            _ => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
+        }
+    }
+}
+
+pub trait FromCassInet: Sized {
+    fn from_cass_inet(inet: CassInet) -> Result<Self, CassError>;
+}
+
+impl FromCassInet for IpAddr {
+    fn from_cass_inet(inet: CassInet) -> Result<Self, CassError> {
+        IpAddr::try_from(inet)
+    }
+}
+
+impl FromCassInet for Ipv4Addr {
+    fn from_cass_inet(inet: CassInet) -> Result<Self, CassError> {
+        match try!(IpAddr::try_from(inet)) {
+            IpAddr::V4(addr) => Ok(addr),
+            IpAddr::V6(_) => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
         }
     }
 }
 
 impl FromCassInet for Ipv6Addr {
-    fn from_cass_inet(inet: CassInet) -> Self {
-        let raw_addr: [u8; 16] = inet.0.address;
-        match inet.0.address_length {
-            4 => panic!(),
-            16 => Ipv6Addr::new((raw_addr[1]  as u16) << (8 + raw_addr[0]  as u16),
-                                (raw_addr[3]  as u16) << (8 + raw_addr[2]  as u16),
-                                (raw_addr[5]  as u16) << (8 + raw_addr[4]  as u16),
-                                (raw_addr[7]  as u16) << (8 + raw_addr[6]  as u16),
-                                (raw_addr[9]  as u16) << (8 + raw_addr[8]  as u16),
-                                (raw_addr[11] as u16) << (8 + raw_addr[10] as u16),
-                                (raw_addr[13] as u16) << (8 + raw_addr[12] as u16),
-                                (raw_addr[15] as u16) << (8 + raw_addr[14] as u16)),
-            unsupported => panic!("impossible inet type: {}", unsupported),
+    fn from_cass_inet(inet: CassInet) -> Result<Self, CassError> {
+        match try!(IpAddr::try_from(inet)) {
+            IpAddr::V4(_) => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
+            IpAddr::V6(addr) => Ok(addr),
         }
     }
 }
@@ -89,3 +138,71 @@ impl CassInet {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::net::IpAddr;
+    use std::net::Ipv4Addr;
+    use std::net::Ipv6Addr;
+    use std::net::SocketAddr;
+
+    use super::*;
+
+    fn inet(address: &[u8]) -> CassInet {
+        let mut inet = CassInet::default();
+        inet.0.address[..address.len()].copy_from_slice(address);
+        inet.0.address_length = address.len() as u8;
+        inet
+    }
+
+    #[test]
+    fn ipv4_addresses_round_trip() {
+        let addr = Ipv4Addr::new(192, 168, 1, 20);
+        let converted = CassInet::from(IpAddr::V4(addr));
+        assert_eq!(converted.0.address_length, 4);
+        assert_eq!(&converted.0.address[..4], &[192, 168, 1, 20]);
+        assert_eq!(IpAddr::try_from(converted).unwrap(), IpAddr::V4(addr));
+        assert_eq!(Ipv4Addr::from_cass_inet(converted).unwrap(), addr);
+        assert!(Ipv6Addr::from_cass_inet(converted).is_err());
+    }
+
+    #[test]
+    fn ipv6_addresses_round_trip_in_network_order() {
+        let addr: Ipv6Addr = "2001:db8::ff00:42:8329".parse().unwrap();
+        let converted = CassInet::from(IpAddr::V6(addr));
+        assert_eq!(converted.0.address_length, 16);
+        assert_eq!(converted.0.address,
+                   [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0xff, 0x00, 0x00, 0x42, 0x83, 0x29]);
+        assert_eq!(IpAddr::try_from(converted).unwrap(), IpAddr::V6(addr));
+        assert_eq!(Ipv6Addr::from_cass_inet(converted).unwrap(), addr);
+        assert!(Ipv4Addr::from_cass_inet(converted).is_err());
+
+        let mapped = Ipv4Addr::new(10, 0, 0, 1).to_ipv6_mapped();
+        assert_eq!(IpAddr::try_from(CassInet::from(mapped)).unwrap(), IpAddr::V6(mapped));
+    }
+
+    #[test]
+    fn socket_addresses_convert_by_their_ip() {
+        let addr: SocketAddr = "[::1]:9042".parse().unwrap();
+        assert_eq!(IpAddr::try_from(addr.as_cass_inet()).unwrap(), addr.ip());
+        let addr: SocketAddr = "127.0.0.1:9042".parse().unwrap();
+        assert_eq!(IpAddr::try_from(CassInet::from(addr)).unwrap(), addr.ip());
+    }
+
+    #[test]
+    fn other_lengths_are_errors() {
+        for length in &[0, 1, 5, 15, 17, 255] {
+            let mut invalid = inet(&[1, 2, 3, 4]);
+            invalid.0.address_length = *length;
+            assert!(IpAddr::try_from(invalid).is_err());
+            assert!(IpAddr::from_cass_inet(invalid).is_err());
+            assert!(Ipv4Addr::from_cass_inet(invalid).is_err());
+            assert!(Ipv6Addr::from_cass_inet(invalid).is_err());
+            assert_eq!(format!("{:?}", invalid),
+                       format!("invalid inet of length {}", length));
+        }
+        assert_eq!(IpAddr::try_from(inet(&[127, 0, 0, 1])).unwrap(),
+                   IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+    }
+}
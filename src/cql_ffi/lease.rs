@@ -0,0 +1,461 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use cql_ffi::error::CassError;
+use cql_ffi::error::CassErrorTypes;
+use cql_ffi::result::CassResult;
+use cql_ffi::session::CassSession;
+use cql_ffi::statement::CassStatement;
+
+pub struct Lease {
+    inner: Arc<LeaseInner>,
+    renewer: Option<(Sender<()>, JoinHandle<()>)>,
+}
+
+struct LeaseInner {
+    table: Box<dyn LeaseTable>,
+    name: String,
+    owner: String,
+    ttl: Duration,
+    held: AtomicBool,
+}
+
+// The conditional statements a lease runs against its table, each returning
+// whether it was applied. Leases run them on a session; the tests use a
+// stand-in.
+trait LeaseTable: Send + Sync {
+    fn insert(&self, name: &str, owner: &str, ttl: i32) -> Result<bool, CassError>;
+    fn update(&self, name: &str, owner: &str, ttl: i32) -> Result<bool, CassError>;
+    fn delete(&self, name: &str, owner: &str) -> Result<bool, CassError>;
+}
+
+struct SessionTable {
+    session: Arc<CassSession>,
+    table: String,
+}
+
+pub fn create_lease_table(session: &CassSession, table: &str) -> Result<(), CassError> {
+    let query = format!("CREATE TABLE IF NOT EXISTS {} (name text PRIMARY KEY, owner text)",
+                        table);
+    try!(try!(session.execute(&query, 0)).wait());
+    Ok(())
+}
+
+impl Lease {
+    pub fn acquire(session: Arc<CassSession>,
+                   table: &str,
+                   name: &str,
+                   owner: &str,
+                   ttl: Duration)
+                   -> Result<Option<Lease>, CassError> {
+        let table = SessionTable {
+            session: session,
+            table: table.to_owned(),
+        };
+        Lease::acquire_in(Box::new(table), name, owner, ttl)
+    }
+
+    fn acquire_in(table: Box<dyn LeaseTable>,
+                  name: &str,
+                  owner: &str,
+                  ttl: Duration)
+                  -> Result<Option<Lease>, CassError> {
+        let ttl_secs = try!(ttl_secs(ttl));
+        if !try!(table.insert(name, owner, ttl_secs)) {
+            return Ok(None);
+        }
+        let inner = LeaseInner {
+            table: table,
+            name: name.to_owned(),
+            owner: owner.to_owned(),
+            ttl: ttl,
+            held: AtomicBool::new(true),
+        };
+        Ok(Some(Lease {
+            inner: Arc::new(inner),
+            renewer: None,
+        }))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.inner.owner
+    }
+
+    pub fn is_held(&self) -> bool {
+        self.inner.held.load(Ordering::SeqCst)
+    }
+
+    pub fn renew(&self) -> Result<bool, CassError> {
+        self.inner.renew()
+    }
+
+    // Renews the lease every `interval` on a background thread until the lease
+    // is released, dropped, or lost to another owner. `interval` should be
+    // comfortably shorter than the ttl.
+    pub fn keep_alive(&mut self, interval: Duration) {
+        if self.renewer.is_some() {
+            return;
+        }
+        let (stop, stopped) = channel();
+        let inner = self.inner.clone();
+        let handle = thread::spawn(move || {
+            loop {
+                match stopped.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => {
+                        match inner.renew() {
+                            Ok(true) => {}
+                            Ok(false) => break,
+                            Err(err) => warn!("failed to renew lease {}: {:?}", inner.name, err),
+                        }
+                    }
+                    _ => break,
+                }
+            }
+        });
+        self.renewer = Some((stop, handle));
+    }
+
+    pub fn release(mut self) -> Result<bool, CassError> {
+        self.stop_renewer();
+        self.inner.release()
+    }
+
+    fn stop_renewer(&mut self) {
+        if let Some((stop, handle)) = self.renewer.take() {
+            let _ = stop.send(());
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        self.stop_renewer();
+        if let Err(err) = self.inner.release() {
+            warn!("failed to release lease {}: {:?}", self.inner.name, err);
+        }
+    }
+}
+
+impl LeaseInner {
+    fn renew(&self) -> Result<bool, CassError> {
+        let applied = try!(self.table.update(&self.name, &self.owner, try!(ttl_secs(self.ttl))));
+        self.held.store(applied, Ordering::SeqCst);
+        Ok(applied)
+    }
+
+    fn release(&self) -> Result<bool, CassError> {
+        if !self.held.swap(false, Ordering::SeqCst) {
+            return Ok(false);
+        }
+        self.table.delete(&self.name, &self.owner)
+    }
+}
+
+impl LeaseTable for SessionTable {
+    fn insert(&self, name: &str, owner: &str, ttl: i32) -> Result<bool, CassError> {
+        let query = format!("INSERT INTO {} (name, owner) VALUES (?, ?) IF NOT EXISTS USING TTL ?",
+                            self.table);
+        let mut statement = try!(CassStatement::new(&query, 3));
+        try!(statement.bind_string(0, name));
+        try!(statement.bind_string(1, owner));
+        try!(statement.bind_int32(2, ttl));
+        self.execute_conditional(&statement)
+    }
+
+    fn update(&self, name: &str, owner: &str, ttl: i32) -> Result<bool, CassError> {
+        let query = format!("UPDATE {} USING TTL ? SET owner = ? WHERE name = ? IF owner = ?",
+                            self.table);
+        let mut statement = try!(CassStatement::new(&query, 4));
+        try!(statement.bind_int32(0, ttl));
+        try!(statement.bind_string(1, owner));
+        try!(statement.bind_string(2, name));
+        try!(statement.bind_string(3, owner));
+        self.execute_conditional(&statement)
+    }
+
+    fn delete(&self, name: &str, owner: &str) -> Result<bool, CassError> {
+        let query = format!("DELETE FROM {} WHERE name = ? IF owner = ?", self.table);
+        let mut statement = try!(CassStatement::new(&query, 2));
+        try!(statement.bind_string(0, name));
+        try!(statement.bind_string(1, owner));
+        self.execute_conditional(&statement)
+    }
+}
+
+impl SessionTable {
+    fn execute_conditional(&self, statement: &CassStatement) -> Result<bool, CassError> {
+        let result = try!(self.session.execute_statement(statement).wait());
+        applied(&result)
+    }
+}
+
+// TTLs are whole seconds, and a TTL of 0 means none, so part of a second
+// counts as a whole one.
+fn ttl_secs(ttl: Duration) -> Result<i32, CassError> {
+    let secs = if ttl.subsec_nanos() > 0 {
+        ttl.as_secs().checked_add(1)
+    } else {
+        Some(ttl.as_secs())
+    };
+    match secs {
+        Some(0) => Ok(1),
+        Some(secs) if secs <= i32::MAX as u64 => Ok(secs as i32),
+        _ => Err(CassError::build(CassErrorTypes::LIB_BAD_PARAMS as u32)),
+    }
+}
+
+// Lightweight transactions report their outcome in the "[applied]" column,
+// which is always the first column of the result.
+fn applied(result: &CassResult) -> Result<bool, CassError> {
+    match result.first_row() {
+        Some(row) => try!(row.get_column(0)).get_bool(),
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    use cql_ffi::cluster::CassCluster;
+    use cql_ffi::error::CassError;
+    use cql_ffi::error::CassErrorTypes;
+    use cql_ffi::session::CassSession;
+    use super::*;
+    use super::LeaseTable;
+
+    // Applies the statements the way Cassandra would, without expiring rows.
+    // Clones share their rows, so a test can look at them or take a lease over.
+    #[derive(Clone,Default)]
+    struct StandIn {
+        rows: Arc<Mutex<HashMap<String, (String, i32)>>>,
+        failing: Arc<AtomicBool>,
+    }
+
+    impl StandIn {
+        fn owner(&self, name: &str) -> Option<String> {
+            self.rows.lock().unwrap().get(name).map(|row| row.0.clone())
+        }
+
+        fn ttl(&self, name: &str) -> Option<i32> {
+            self.rows.lock().unwrap().get(name).map(|row| row.1)
+        }
+
+        fn take_over(&self, name: &str, owner: &str) {
+            self.rows.lock().unwrap().insert(name.to_owned(), (owner.to_owned(), 30));
+        }
+
+        fn check(&self) -> Result<(), CassError> {
+            match self.failing.load(Ordering::SeqCst) {
+                true => Err(CassError::build(CassErrorTypes::LIB_REQUEST_TIMED_OUT as u32)),
+                false => Ok(()),
+            }
+        }
+    }
+
+    impl LeaseTable for StandIn {
+        fn insert(&self, name: &str, owner: &str, ttl: i32) -> Result<bool, CassError> {
+            try!(self.check());
+            let mut rows = self.rows.lock().unwrap();
+            if rows.contains_key(name) {
+                return Ok(false);
+            }
+            rows.insert(name.to_owned(), (owner.to_owned(), ttl));
+            Ok(true)
+        }
+
+        fn update(&self, name: &str, owner: &str, ttl: i32) -> Result<bool, CassError> {
+            try!(self.check());
+            match self.rows.lock().unwrap().get_mut(name) {
+                Some(row) if row.0 == owner => {
+                    row.1 = ttl;
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        }
+
+        fn delete(&self, name: &str, owner: &str) -> Result<bool, CassError> {
+            try!(self.check());
+            let mut rows = self.rows.lock().unwrap();
+            if rows.get(name).map(|row| row.0 == owner) != Some(true) {
+                return Ok(false);
+            }
+            rows.remove(name);
+            Ok(true)
+        }
+    }
+
+    fn acquire(table: &StandIn, name: &str, owner: &str, ttl: Duration) -> Option<Lease> {
+        Lease::acquire_in(Box::new(table.clone()), name, owner, ttl).unwrap()
+    }
+
+    #[test]
+    fn ttls_are_whole_seconds() {
+        assert_eq!(ttl_secs(Duration::from_secs(30)).unwrap(), 30);
+        assert_eq!(ttl_secs(Duration::from_millis(1500)).unwrap(), 2);
+        assert_eq!(ttl_secs(Duration::from_millis(1)).unwrap(), 1);
+        assert_eq!(ttl_secs(Duration::from_secs(0)).unwrap(), 1);
+        assert_eq!(ttl_secs(Duration::from_secs(i32::MAX as u64)).unwrap(), i32::MAX);
+        assert!(ttl_secs(Duration::from_secs(i32::MAX as u64 + 1)).is_err());
+        assert!(ttl_secs(Duration::new(i32::MAX as u64, 1)).is_err());
+        assert!(ttl_secs(Duration::from_secs(u64::MAX)).is_err());
+        assert!(ttl_secs(Duration::new(u64::MAX, 1)).is_err());
+    }
+
+    #[test]
+    fn one_owner_at_a_time_in_a_stand_in() {
+        let table = StandIn::default();
+        let ttl = Duration::from_millis(1500);
+        let lease = acquire(&table, "job", "a", ttl).expect("a gets the lease");
+        assert!(lease.is_held());
+        assert_eq!(table.owner("job"), Some("a".to_owned()));
+        assert_eq!(table.ttl("job"), Some(2));
+        assert!(acquire(&table, "job", "b", ttl).is_none());
+        assert!(lease.renew().unwrap());
+        assert!(lease.release().unwrap());
+        assert_eq!(table.owner("job"), None);
+        let lease = acquire(&table, "job", "b", ttl).expect("b gets the released lease");
+        assert_eq!(lease.owner(), "b");
+    }
+
+    #[test]
+    fn a_lease_taken_over_is_lost_and_not_released() {
+        let table = StandIn::default();
+        let lease = acquire(&table, "job", "a", Duration::from_secs(30)).unwrap();
+        // as if a's row had expired and b had inserted its own
+        table.take_over("job", "b");
+        assert!(!lease.renew().unwrap());
+        assert!(!lease.is_held());
+        assert!(!lease.release().unwrap());
+        assert_eq!(table.owner("job"), Some("b".to_owned()));
+    }
+
+    #[test]
+    fn failed_renewals_keep_the_lease() {
+        let table = StandIn::default();
+        let lease = acquire(&table, "job", "a", Duration::from_secs(30)).unwrap();
+        table.failing.store(true, Ordering::SeqCst);
+        assert!(lease.renew().is_err());
+        assert!(lease.is_held());
+        table.failing.store(false, Ordering::SeqCst);
+        assert!(lease.renew().unwrap());
+    }
+
+    #[test]
+    fn dropping_releases_in_a_stand_in() {
+        let table = StandIn::default();
+        {
+            let mut lease = acquire(&table, "job", "a", Duration::from_secs(30)).unwrap();
+            lease.keep_alive(Duration::from_millis(10));
+            ::std::thread::sleep(Duration::from_millis(50));
+            assert!(lease.is_held());
+        }
+        assert_eq!(table.owner("job"), None);
+        assert!(acquire(&table, "job", "b", Duration::from_secs(30)).is_some());
+    }
+
+    #[test]
+    fn keep_alive_stops_once_the_lease_is_lost() {
+        let table = StandIn::default();
+        let mut lease = acquire(&table, "job", "a", Duration::from_secs(30)).unwrap();
+        lease.keep_alive(Duration::from_millis(10));
+        table.take_over("job", "b");
+        ::std::thread::sleep(Duration::from_millis(100));
+        assert!(!lease.is_held());
+        drop(lease);
+        assert_eq!(table.owner("job"), Some("b".to_owned()));
+    }
+
+    #[test]
+    fn bad_ttls_are_rejected_before_any_statement() {
+        let table = StandIn::default();
+        let ttl = Duration::from_secs(i32::MAX as u64 + 1);
+        assert!(Lease::acquire_in(Box::new(table.clone()), "job", "a", ttl).is_err());
+        assert_eq!(table.owner("job"), None);
+    }
+
+    // These need a node on 127.0.0.1: cargo test -- --ignored
+
+    fn session() -> Arc<CassSession> {
+        let mut cluster = CassCluster::new();
+        cluster.set_contact_points("127.0.0.1").unwrap();
+        let session = CassSession::new().connect(&mut cluster).wait().unwrap();
+        session.execute("CREATE KEYSPACE IF NOT EXISTS lease_test WITH replication = \
+                         {'class': 'SimpleStrategy', 'replication_factor': 1}",
+                        0)
+               .unwrap()
+               .wait()
+               .unwrap();
+        create_lease_table(&session, "lease_test.locks").unwrap();
+        Arc::new(session)
+    }
+
+    #[test]
+    #[ignore]
+    fn one_owner_at_a_time() {
+        let session = session();
+        let ttl = Duration::from_secs(30);
+        let lease = Lease::acquire(session.clone(), "lease_test.locks", "one_owner", "a", ttl)
+                        .unwrap()
+                        .expect("a gets the lease");
+        assert!(lease.is_held());
+        assert!(Lease::acquire(session.clone(), "lease_test.locks", "one_owner", "b", ttl)
+                    .unwrap()
+                    .is_none());
+        assert!(lease.renew().unwrap());
+        assert!(lease.release().unwrap());
+        let lease = Lease::acquire(session.clone(), "lease_test.locks", "one_owner", "b", ttl)
+                        .unwrap()
+                        .expect("b gets the released lease");
+        assert_eq!(lease.owner(), "b");
+    }
+
+    #[test]
+    #[ignore]
+    fn dropping_releases() {
+        let session = session();
+        let ttl = Duration::from_secs(30);
+        {
+            let mut lease = Lease::acquire(session.clone(), "lease_test.locks", "dropped", "a", ttl)
+                                .unwrap()
+                                .expect("a gets the lease");
+            lease.keep_alive(Duration::from_millis(100));
+            ::std::thread::sleep(Duration::from_millis(350));
+            assert!(lease.is_held());
+        }
+        assert!(Lease::acquire(session.clone(), "lease_test.locks", "dropped", "b", ttl)
+                    .unwrap()
+                    .is_some());
+    }
+
+    #[test]
+    #[ignore]
+    fn sub_second_ttls_expire() {
+        let session = session();
+        let lease = Lease::acquire(session.clone(),
+                                   "lease_test.locks",
+                                   "short",
+                                   "a",
+                                   Duration::from_millis(200))
+                        .unwrap()
+                        .expect("a gets the lease");
+        ::std::mem::forget(lease);
+        ::std::thread::sleep(Duration::from_millis(2500));
+        assert!(Lease::acquire(session, "lease_test.locks", "short", "b", Duration::from_secs(30))
+                    .unwrap()
+                    .is_some());
+    }
+}
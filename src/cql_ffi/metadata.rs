@@ -0,0 +1,1027 @@
+use std::collections::BTreeMap;
+use std::char;
+use std::iter::Peekable;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+
+use cql_bindgen::CASS_ERROR_SERVER_INVALID_QUERY;
+
+use cassandra_schema::{AggregateMeta, ColumnKind, ColumnMeta, CqlType, CqlValue, CqlDuration,
+                       FunctionMeta, IndexKind, IndexMeta, KeyspaceMeta, Replication,
+                       ReplicationStrategy, SchemaSnapshot, TableMeta, UserTypeMeta, ViewMeta,
+                       ClusteringOrder, quote_identifier, sort_columns, CassUuid, CassValueType};
+use cql_ffi::error::CassError;
+use cql_ffi::error::CassErrorTypes;
+use cql_ffi::schema::CassSchema;
+use cql_ffi::schema::CassSchemaMeta;
+use cql_ffi::session::CassSession;
+
+// Reads a SchemaSnapshot from the driver's schema metadata or straight from the
+// system schema tables. Both Cassandra 2.x (system.schema_*) and 3.x
+// (system_schema.*) table layouts are understood.
+pub trait LoadSchema: Sized {
+    fn from_schema(schema: &CassSchema) -> Result<Self, CassError>;
+    fn load(session: &CassSession) -> Result<Self, CassError>;
+}
+
+impl LoadSchema for SchemaSnapshot {
+    // The driver's schema metadata only covers keyspaces, tables and columns, so
+    // a snapshot built from it has no user types, functions, aggregates or views.
+    fn from_schema(schema: &CassSchema) -> Result<SchemaSnapshot, CassError> {
+        let mut rows = SchemaRows::default();
+        for keyspace in schema.keyspaces() {
+            let keyspace_fields = try!(meta_fields(&keyspace));
+            let keyspace_name = try!(text(&keyspace_fields, "keyspace_name"));
+            for table in keyspace.entries() {
+                let mut table_fields = try!(meta_fields(&table));
+                table_fields.entry("keyspace_name".to_owned())
+                            .or_insert_with(|| CqlValue::Text(keyspace_name.clone()));
+                let table_name = try!(table_name(&table_fields));
+                for column in table.entries() {
+                    let mut column_fields = try!(meta_fields(&column));
+                    column_fields.entry("keyspace_name".to_owned())
+                                 .or_insert_with(|| CqlValue::Text(keyspace_name.clone()));
+                    column_fields.entry("columnfamily_name".to_owned())
+                                 .or_insert_with(|| CqlValue::Text(table_name.clone()));
+                    rows.columns.push(column_fields);
+                }
+                rows.tables.push(table_fields);
+            }
+            rows.keyspaces.push(keyspace_fields);
+        }
+        rows.build()
+    }
+
+    // Reads the system_schema tables, or the Cassandra 2.x system tables when
+    // the server rejects those as unknown. Any other error is returned as is.
+    fn load(session: &CassSession) -> Result<SchemaSnapshot, CassError> {
+        match try!(query_rows_if_known(session, "SELECT * FROM system_schema.keyspaces")) {
+            Some(keyspaces) => {
+                let rows = SchemaRows {
+                    keyspaces: keyspaces,
+                    tables: try!(query_rows(session, "SELECT * FROM system_schema.tables")),
+                    columns: try!(query_rows(session, "SELECT * FROM system_schema.columns")),
+                    indexes: try!(query_rows(session, "SELECT * FROM system_schema.indexes")),
+                    views: try!(query_rows(session, "SELECT * FROM system_schema.views")),
+                    types: try!(query_rows(session, "SELECT * FROM system_schema.types")),
+                    functions: try!(query_rows(session, "SELECT * FROM system_schema.functions")),
+                    aggregates: try!(query_rows(session, "SELECT * FROM system_schema.aggregates")),
+                };
+                rows.build()
+            }
+            None => {
+                // User types came with 2.1 and functions with 2.2.
+                let rows = SchemaRows {
+                    keyspaces: try!(query_rows(session, "SELECT * FROM system.schema_keyspaces")),
+                    tables: try!(query_rows(session, "SELECT * FROM system.schema_columnfamilies")),
+                    columns: try!(query_rows(session, "SELECT * FROM system.schema_columns")),
+                    types: try!(query_rows_if_known(session, "SELECT * FROM system.schema_usertypes"))
+                               .unwrap_or_default(),
+                    functions: try!(query_rows_if_known(session,
+                                                        "SELECT * FROM system.schema_functions"))
+                                   .unwrap_or_default(),
+                    aggregates: try!(query_rows_if_known(session,
+                                                         "SELECT * FROM system.schema_aggregates"))
+                                    .unwrap_or_default(),
+                    ..SchemaRows::default()
+                };
+                rows.build()
+            }
+        }
+    }
+}
+
+fn index_kind(name: &str) -> IndexKind {
+    match &name.to_uppercase()[..] {
+        "KEYS" => IndexKind::Keys,
+        "CUSTOM" => IndexKind::Custom,
+        _ => IndexKind::Composites,
+    }
+}
+
+// The fields of one row of a schema table, by column name.
+type Fields = BTreeMap<String, CqlValue>;
+
+#[derive(Default)]
+struct SchemaRows {
+    keyspaces: Vec<Fields>,
+    tables: Vec<Fields>,
+    columns: Vec<Fields>,
+    indexes: Vec<Fields>,
+    views: Vec<Fields>,
+    types: Vec<Fields>,
+    functions: Vec<Fields>,
+    aggregates: Vec<Fields>,
+}
+
+impl SchemaRows {
+    fn build(self) -> Result<SchemaSnapshot, CassError> {
+        let mut snapshot = SchemaSnapshot::default();
+        for fields in &self.keyspaces {
+            let keyspace = try!(build_keyspace(fields));
+            snapshot.keyspaces.insert(keyspace.name.clone(), keyspace);
+        }
+
+        // Columns of tables and views alike, and the indexes 2.x keeps with them.
+        let mut columns = BTreeMap::new();
+        let mut indexes = Vec::new();
+        for fields in &self.columns {
+            let keyspace = try!(text(fields, "keyspace_name"));
+            let table = try!(table_name(fields));
+            let column = try!(build_column(fields));
+            if let Some(index) = try!(build_column_index(fields, &table, &column.name)) {
+                indexes.push((keyspace.clone(), index));
+            }
+            columns.entry((keyspace, table)).or_insert_with(Vec::new).push(column);
+        }
+        for fields in &self.indexes {
+            indexes.push((try!(text(fields, "keyspace_name")), try!(build_index(fields))));
+        }
+
+        for fields in &self.tables {
+            let keyspace = try!(text(fields, "keyspace_name"));
+            let name = try!(table_name(fields));
+            let mut table_columns = columns.remove(&(keyspace.clone(), name.clone()))
+                                           .unwrap_or_default();
+            sort_columns(&mut table_columns);
+            let table = TableMeta {
+                keyspace: keyspace.clone(),
+                name: name.clone(),
+                columns: table_columns,
+                options: table_options(fields),
+                indexes: BTreeMap::new(),
+            };
+            if let Some(keyspace) = snapshot.keyspaces.get_mut(&keyspace) {
+                keyspace.tables.insert(name, table);
+            }
+        }
+        for (keyspace, index) in indexes {
+            let table = snapshot.keyspaces
+                                .get_mut(&keyspace)
+                                .and_then(|keyspace| keyspace.tables.get_mut(&index.table));
+            if let Some(table) = table {
+                table.indexes.insert(index.name.clone(), index);
+            }
+        }
+
+        for fields in &self.views {
+            let keyspace = try!(text(fields, "keyspace_name"));
+            let name = try!(text(fields, "view_name"));
+            let mut view_columns = columns.remove(&(keyspace.clone(), name.clone()))
+                                          .unwrap_or_default();
+            sort_columns(&mut view_columns);
+            let view = ViewMeta {
+                keyspace: keyspace.clone(),
+                name: name.clone(),
+                base_table: try!(text(fields, "base_table_name")),
+                include_all_columns: flag(fields, "include_all_columns"),
+                where_clause: opt_text(fields, "where_clause").unwrap_or_default(),
+                columns: view_columns,
+                options: table_options(fields),
+            };
+            if let Some(keyspace) = snapshot.keyspaces.get_mut(&keyspace) {
+                keyspace.views.insert(name, view);
+            }
+        }
+        for fields in &self.types {
+            let user_type = try!(build_user_type(fields));
+            if let Some(keyspace) = snapshot.keyspaces.get_mut(&user_type.keyspace) {
+                keyspace.user_types.insert(user_type.name.clone(), user_type);
+            }
+        }
+        for fields in &self.functions {
+            let function = try!(build_function(fields));
+            if let Some(keyspace) = snapshot.keyspaces.get_mut(&function.keyspace) {
+                keyspace.functions.push(function);
+            }
+        }
+        for fields in &self.aggregates {
+            let aggregate = try!(build_aggregate(fields, &snapshot));
+            if let Some(keyspace) = snapshot.keyspaces.get_mut(&aggregate.keyspace) {
+                keyspace.aggregates.push(aggregate);
+            }
+        }
+        for keyspace in snapshot.keyspaces.values_mut() {
+            keyspace.functions.sort_by(|a, b| {
+                (&a.name, signature(&a.arguments)).cmp(&(&b.name, signature(&b.arguments)))
+            });
+            keyspace.aggregates.sort_by(|a, b| {
+                (&a.name, type_names(&a.argument_types)).cmp(&(&b.name, type_names(&b.argument_types)))
+            });
+        }
+        Ok(snapshot)
+    }
+}
+
+fn build_keyspace(fields: &Fields) -> Result<KeyspaceMeta, CassError> {
+    let replication = match field(fields, "replication") {
+        Some(_) => {
+            let mut options = text_map(fields, "replication");
+            let class_name = options.remove("class").unwrap_or_default();
+            Replication {
+                strategy: ReplicationStrategy::from_class_name(&class_name),
+                options: options,
+            }
+        }
+        None => {
+            let options = match opt_text(fields, "strategy_options") {
+                Some(json) => try!(parse_json_object(&json)),
+                None => BTreeMap::new(),
+            };
+            Replication {
+                strategy: ReplicationStrategy::from_class_name(&try!(text(fields, "strategy_class"))),
+                options: options,
+            }
+        }
+    };
+    let mut keyspace = KeyspaceMeta::new(&try!(text(fields, "keyspace_name")), replication);
+    keyspace.durable_writes = match field(fields, "durable_writes") {
+        Some(_) => flag(fields, "durable_writes"),
+        None => true,
+    };
+    Ok(keyspace)
+}
+
+fn build_column(fields: &Fields) -> Result<ColumnMeta, CassError> {
+    let name = try!(text(fields, "column_name"));
+    match opt_text(fields, "kind") {
+        Some(kind) => {
+            let kind = column_kind(&kind);
+            let clustering_order = match &opt_text(fields, "clustering_order").unwrap_or_default()[..] {
+                "desc" => ClusteringOrder::Desc,
+                "asc" => ClusteringOrder::Asc,
+                _ => ClusteringOrder::None,
+            };
+            Ok(ColumnMeta {
+                name: name,
+                kind: kind,
+                position: key_position(kind, int(fields, "position")),
+                data_type: try!(parse_type(&try!(text(fields, "type")))),
+                clustering_order: clustering_order,
+            })
+        }
+        // Cassandra 2.x: the kind is under type and the data type is a marshal
+        // class, reversed for descending clustering columns.
+        None => {
+            let kind = column_kind(&try!(text(fields, "type")));
+            let validator = try!(text(fields, "validator"));
+            let clustering_order = match kind {
+                ColumnKind::Clustering if CqlType::is_reversed_class_name(&validator) => {
+                    ClusteringOrder::Desc
+                }
+                ColumnKind::Clustering => ClusteringOrder::Asc,
+                _ => ClusteringOrder::None,
+            };
+            Ok(ColumnMeta {
+                name: name,
+                kind: kind,
+                position: key_position(kind, int(fields, "component_index")),
+                data_type: try!(parse_type(&validator)),
+                clustering_order: clustering_order,
+            })
+        }
+    }
+}
+
+fn column_kind(kind: &str) -> ColumnKind {
+    match kind {
+        "partition_key" => ColumnKind::PartitionKey,
+        "clustering" | "clustering_key" => ColumnKind::Clustering,
+        "static" => ColumnKind::Static,
+        _ => ColumnKind::Regular,
+    }
+}
+
+fn key_position(kind: ColumnKind, position: Option<i32>) -> i32 {
+    match kind {
+        ColumnKind::PartitionKey | ColumnKind::Clustering => position.unwrap_or(0),
+        _ => 0,
+    }
+}
+
+// Cassandra 2.x keeps an index with the column it indexes.
+fn build_column_index(fields: &Fields,
+                      table: &str,
+                      column: &str)
+                      -> Result<Option<IndexMeta>, CassError> {
+    let name = match opt_text(fields, "index_name") {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+    let mut options = match opt_text(fields, "index_options") {
+        Some(json) => try!(parse_json_object(&json)),
+        None => BTreeMap::new(),
+    };
+    let column = quote_identifier(column);
+    let target = if options.remove("index_keys").is_some() {
+        format!("keys({})", column)
+    } else if options.remove("index_keys_and_values").is_some() {
+        format!("entries({})", column)
+    } else {
+        options.remove("index_values");
+        column
+    };
+    Ok(Some(IndexMeta {
+        name: name,
+        table: table.to_owned(),
+        kind: index_kind(&opt_text(fields, "index_type").unwrap_or_default()),
+        target: target,
+        options: options,
+    }))
+}
+
+fn build_index(fields: &Fields) -> Result<IndexMeta, CassError> {
+    let mut options = text_map(fields, "options");
+    Ok(IndexMeta {
+        name: try!(text(fields, "index_name")),
+        table: try!(text(fields, "table_name")),
+        kind: index_kind(&opt_text(fields, "kind").unwrap_or_default()),
+        target: options.remove("target").unwrap_or_default(),
+        options: options,
+    })
+}
+
+fn build_user_type(fields: &Fields) -> Result<UserTypeMeta, CassError> {
+    let names = text_list(fields, "field_names");
+    let types = text_list(fields, "field_types");
+    let mut user_type_fields = Vec::with_capacity(names.len());
+    for (name, field_type) in names.into_iter().zip(types.iter()) {
+        user_type_fields.push((name, try!(parse_type(field_type))));
+    }
+    Ok(UserTypeMeta {
+        keyspace: try!(text(fields, "keyspace_name")),
+        name: try!(text(fields, "type_name")),
+        fields: user_type_fields,
+    })
+}
+
+fn build_function(fields: &Fields) -> Result<FunctionMeta, CassError> {
+    let names = text_list(fields, "argument_names");
+    let types = text_list(fields, "argument_types");
+    let mut arguments = Vec::with_capacity(names.len());
+    for (name, argument_type) in names.into_iter().zip(types.iter()) {
+        arguments.push((name, try!(parse_type(argument_type))));
+    }
+    Ok(FunctionMeta {
+        keyspace: try!(text(fields, "keyspace_name")),
+        name: try!(text(fields, "function_name")),
+        arguments: arguments,
+        return_type: try!(parse_type(&try!(text(fields, "return_type")))),
+        language: try!(text(fields, "language")),
+        body: try!(text(fields, "body")),
+        called_on_null_input: flag(fields, "called_on_null_input"),
+    })
+}
+
+fn build_aggregate(fields: &Fields, snapshot: &SchemaSnapshot) -> Result<AggregateMeta, CassError> {
+    let mut argument_types = Vec::new();
+    for argument_type in text_list(fields, "argument_types") {
+        argument_types.push(try!(parse_type(&argument_type)));
+    }
+    let keyspace = try!(text(fields, "keyspace_name"));
+    let state_type = try!(parse_type(&try!(text(fields, "state_type"))));
+    // 3.x keeps the initial condition as a CQL literal, 2.x as the serialized
+    // state value.
+    let initial_condition = match field(fields, "initcond") {
+        Some(&CqlValue::Blob(ref bytes)) => {
+            let decoder = ValueDecoder { snapshot: snapshot, keyspace: &keyspace };
+            Some(try!(decoder.decode(&state_type, bytes)).to_string())
+        }
+        _ => opt_text(fields, "initcond"),
+    };
+    Ok(AggregateMeta {
+        keyspace: keyspace,
+        name: try!(text(fields, "aggregate_name")),
+        argument_types: argument_types,
+        state_function: try!(text(fields, "state_func")),
+        state_type: state_type,
+        final_function: opt_text(fields, "final_func"),
+        initial_condition: initial_condition,
+        return_type: try!(parse_type(&try!(text(fields, "return_type")))),
+    })
+}
+
+// Reads a value serialized the way Cassandra stores it, as in the native
+// protocol v3 and later. User types are looked up in the snapshot for their
+// field names.
+struct ValueDecoder<'a> {
+    snapshot: &'a SchemaSnapshot,
+    keyspace: &'a str,
+}
+
+impl<'a> ValueDecoder<'a> {
+    fn decode(&self, cql_type: &CqlType, bytes: &[u8]) -> Result<CqlValue, CassError> {
+        match *cql_type {
+            CqlType::Native(value_type) => decode_native(value_type, bytes),
+            CqlType::List(ref item) => Ok(CqlValue::List(try!(self.decode_items(item, bytes)))),
+            CqlType::Set(ref item) => Ok(CqlValue::Set(try!(self.decode_items(item, bytes)))),
+            CqlType::Map(ref key, ref value) => {
+                let mut input = bytes;
+                let count = try!(read_count(&mut input));
+                let mut entries = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let key = try!(self.decode_item(key, &mut input));
+                    entries.push((key, try!(self.decode_item(value, &mut input))));
+                }
+                try!(expect_end(input));
+                Ok(CqlValue::Map(entries))
+            }
+            // Tuples and user types may hold fewer items than their type has,
+            // and the rest are null.
+            CqlType::Tuple(ref item_types) => {
+                let mut input = bytes;
+                let mut items = Vec::with_capacity(item_types.len());
+                for item_type in item_types {
+                    items.push(if input.is_empty() {
+                        CqlValue::Null
+                    } else {
+                        try!(self.decode_item(item_type, &mut input))
+                    });
+                }
+                try!(expect_end(input));
+                Ok(CqlValue::Tuple(items))
+            }
+            CqlType::Udt { ref keyspace, ref name } => {
+                let keyspace = keyspace.as_ref().map(String::as_str).unwrap_or(self.keyspace);
+                let user_type = try!(self.snapshot
+                                         .keyspace(keyspace)
+                                         .and_then(|keyspace| keyspace.user_types.get(name))
+                                         .ok_or_else(missing_field));
+                let mut input = bytes;
+                let mut fields = Vec::with_capacity(user_type.fields.len());
+                for &(ref field_name, ref field_type) in &user_type.fields {
+                    let value = if input.is_empty() {
+                        CqlValue::Null
+                    } else {
+                        try!(self.decode_item(field_type, &mut input))
+                    };
+                    fields.push((field_name.clone(), value));
+                }
+                try!(expect_end(input));
+                Ok(CqlValue::Udt(fields))
+            }
+            CqlType::Custom(_) => Ok(CqlValue::Custom(bytes.to_vec())),
+            CqlType::Frozen(ref inner) => self.decode(inner, bytes),
+        }
+    }
+
+    fn decode_items(&self, item_type: &CqlType, bytes: &[u8]) -> Result<Vec<CqlValue>, CassError> {
+        let mut input = bytes;
+        let count = try!(read_count(&mut input));
+        let mut items = Vec::with_capacity(count);
+        for _ in 0..count {
+            items.push(try!(self.decode_item(item_type, &mut input)));
+        }
+        try!(expect_end(input));
+        Ok(items)
+    }
+
+    // An item is its length followed by its bytes, or a negative length for null.
+    fn decode_item(&self, cql_type: &CqlType, input: &mut &[u8]) -> Result<CqlValue, CassError> {
+        let length = try!(read_int(input));
+        if length < 0 {
+            return Ok(CqlValue::Null);
+        }
+        let bytes = try!(take(input, length as usize));
+        self.decode(cql_type, bytes)
+    }
+}
+
+fn decode_native(value_type: CassValueType, bytes: &[u8]) -> Result<CqlValue, CassError> {
+    // Only strings and blobs have an empty value of their own.
+    if bytes.is_empty() {
+        match value_type {
+            CassValueType::ASCII | CassValueType::TEXT | CassValueType::VARCHAR |
+            CassValueType::BLOB => {}
+            _ => return Ok(CqlValue::Null),
+        }
+    }
+    let mut input = bytes;
+    let value = match value_type {
+        CassValueType::ASCII => CqlValue::Ascii(try!(read_text(&mut input))),
+        CassValueType::TEXT => CqlValue::Text(try!(read_text(&mut input))),
+        CassValueType::VARCHAR => CqlValue::Varchar(try!(read_text(&mut input))),
+        CassValueType::BLOB => CqlValue::Blob(read_rest(&mut input).to_vec()),
+        CassValueType::CUSTOM => CqlValue::Custom(read_rest(&mut input).to_vec()),
+        CassValueType::VARINT => CqlValue::Varint(read_rest(&mut input).to_vec()),
+        CassValueType::BOOLEAN => CqlValue::Boolean(try!(read_be(&mut input, 1)) != 0),
+        CassValueType::TINY_INT => CqlValue::TinyInt(try!(read_be(&mut input, 1)) as i8),
+        CassValueType::SMALL_INT => CqlValue::SmallInt(try!(read_be(&mut input, 2)) as i16),
+        CassValueType::INT => CqlValue::Int(try!(read_int(&mut input))),
+        CassValueType::DATE => CqlValue::Date(try!(read_be(&mut input, 4)) as u32),
+        CassValueType::FLOAT => {
+            CqlValue::Float(f32::from_bits(try!(read_be(&mut input, 4)) as u32))
+        }
+        CassValueType::BIGINT => CqlValue::BigInt(try!(read_be(&mut input, 8)) as i64),
+        CassValueType::COUNTER => CqlValue::Counter(try!(read_be(&mut input, 8)) as i64),
+        CassValueType::TIMESTAMP => CqlValue::Timestamp(try!(read_be(&mut input, 8)) as i64),
+        CassValueType::TIME => CqlValue::Time(try!(read_be(&mut input, 8)) as i64),
+        CassValueType::DOUBLE => CqlValue::Double(f64::from_bits(try!(read_be(&mut input, 8)))),
+        CassValueType::DECIMAL => {
+            let scale = try!(read_int(&mut input));
+            CqlValue::Decimal { unscaled: read_rest(&mut input).to_vec(), scale: scale }
+        }
+        CassValueType::UUID | CassValueType::TIMEUUID => {
+            let msb = try!(read_be(&mut input, 8));
+            let uuid = CassUuid::from_msb_lsb(msb, try!(read_be(&mut input, 8)));
+            match value_type {
+                CassValueType::UUID => CqlValue::Uuid(uuid),
+                _ => CqlValue::TimeUuid(uuid),
+            }
+        }
+        CassValueType::INET => {
+            let address = read_rest(&mut input);
+            match address.len() {
+                4 => {
+                    CqlValue::Inet(IpAddr::V4(Ipv4Addr::new(address[0],
+                                                            address[1],
+                                                            address[2],
+                                                            address[3])))
+                }
+                16 => {
+                    let mut octets = [0; 16];
+                    octets.copy_from_slice(address);
+                    CqlValue::Inet(IpAddr::V6(Ipv6Addr::from(octets)))
+                }
+                _ => return Err(missing_field()),
+            }
+        }
+        CassValueType::DURATION => {
+            let months = try!(read_vint(&mut input));
+            let days = try!(read_vint(&mut input));
+            let nanos = try!(read_vint(&mut input));
+            CqlValue::Duration(CqlDuration::new(months as i32, days as i32, nanos))
+        }
+        _ => return Err(missing_field()),
+    };
+    try!(expect_end(input));
+    Ok(value)
+}
+
+fn read_rest<'b>(input: &mut &'b [u8]) -> &'b [u8] {
+    let rest = *input;
+    *input = &[];
+    rest
+}
+
+fn read_text(input: &mut &[u8]) -> Result<String, CassError> {
+    String::from_utf8(read_rest(input).to_vec()).map_err(|_| missing_field())
+}
+
+fn take<'b>(input: &mut &'b [u8], length: usize) -> Result<&'b [u8], CassError> {
+    if input.len() < length {
+        return Err(missing_field());
+    }
+    let (taken, rest) = input.split_at(length);
+    *input = rest;
+    Ok(taken)
+}
+
+fn read_be(input: &mut &[u8], length: usize) -> Result<u64, CassError> {
+    let bytes = try!(take(input, length));
+    Ok(bytes.iter().fold(0, |value, &byte| value << 8 | byte as u64))
+}
+
+fn read_int(input: &mut &[u8]) -> Result<i32, CassError> {
+    read_be(input, 4).map(|value| value as i32)
+}
+
+fn read_count(input: &mut &[u8]) -> Result<usize, CassError> {
+    match try!(read_int(input)) {
+        count if count < 0 => Err(missing_field()),
+        count => Ok(count as usize),
+    }
+}
+
+// Durations are three zigzag encoded variable length integers, where the
+// leading one bits of the first byte count the bytes that follow.
+fn read_vint(input: &mut &[u8]) -> Result<i64, CassError> {
+    let first = try!(take(input, 1))[0];
+    let extra = (!first).leading_zeros() as usize;
+    let mut value = (first as u64) & (0xff >> extra);
+    for &byte in try!(take(input, extra)) {
+        value = value << 8 | byte as u64;
+    }
+    Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+}
+
+fn expect_end(input: &[u8]) -> Result<(), CassError> {
+    if input.is_empty() { Ok(()) } else { Err(missing_field()) }
+}
+
+const TABLE_OPTIONS: &'static [&'static str] = &["bloom_filter_fp_chance",
+                                                 "caching",
+                                                 "cdc",
+                                                 "comment",
+                                                 "compaction",
+                                                 "compression",
+                                                 "crc_check_chance",
+                                                 "dclocal_read_repair_chance",
+                                                 "default_time_to_live",
+                                                 "gc_grace_seconds",
+                                                 "max_index_interval",
+                                                 "memtable_flush_period_in_ms",
+                                                 "min_index_interval",
+                                                 "read_repair_chance",
+                                                 "speculative_retry"];
+
+// Picks the options CREATE TABLE accepts out of a table's fields. Cassandra 2.x
+// splits compaction into a class and JSON options and keeps compression and
+// caching as JSON, which are turned into the maps 3.x has.
+fn table_options(fields: &Fields) -> BTreeMap<String, CqlValue> {
+    let mut options = BTreeMap::new();
+    for &name in TABLE_OPTIONS {
+        if let Some(value) = field(fields, name) {
+            options.insert(name.to_owned(), normalize(value));
+        }
+    }
+    if let Some(class_name) = opt_text(fields, "compaction_strategy_class") {
+        let mut compaction = opt_text(fields, "compaction_strategy_options")
+                                 .and_then(|json| parse_json_object(&json).ok())
+                                 .unwrap_or_default();
+        compaction.insert("class".to_owned(), class_name);
+        options.insert("compaction".to_owned(), text_map_value(compaction));
+    }
+    if let Some(json) = opt_text(fields, "compression_parameters") {
+        if let Ok(compression) = parse_json_object(&json) {
+            options.insert("compression".to_owned(), text_map_value(compression));
+        }
+    }
+    if let Some(json) = opt_text(fields, "caching") {
+        if let Ok(caching) = parse_json_object(&json) {
+            options.insert("caching".to_owned(), text_map_value(caching));
+        }
+    }
+    if let Some(value) = field(fields, "local_read_repair_chance") {
+        options.insert("dclocal_read_repair_chance".to_owned(), normalize(value));
+    }
+    options
+}
+
+// Text comes back as ascii, text or varchar depending on the column; options
+// are compared by value, so they are all kept as text.
+fn normalize(value: &CqlValue) -> CqlValue {
+    match *value {
+        CqlValue::Ascii(ref s) | CqlValue::Varchar(ref s) => CqlValue::Text(s.clone()),
+        CqlValue::List(ref items) => CqlValue::List(items.iter().map(normalize).collect()),
+        CqlValue::Set(ref items) => CqlValue::Set(items.iter().map(normalize).collect()),
+        CqlValue::Map(ref entries) => {
+            CqlValue::Map(entries.iter()
+                                 .map(|&(ref key, ref value)| (normalize(key), normalize(value)))
+                                 .collect())
+        }
+        ref other => other.clone(),
+    }
+}
+
+fn text_map_value(map: BTreeMap<String, String>) -> CqlValue {
+    CqlValue::Map(map.into_iter()
+                     .map(|(key, value)| (CqlValue::Text(key), CqlValue::Text(value)))
+                     .collect())
+}
+
+fn signature(arguments: &[(String, CqlType)]) -> Vec<String> {
+    arguments.iter().map(|&(_, ref data_type)| data_type.to_string()).collect()
+}
+
+fn type_names(types: &[CqlType]) -> Vec<String> {
+    types.iter().map(|data_type| data_type.to_string()).collect()
+}
+
+// 2.x stores marshal class names where 3.x stores CQL.
+fn parse_type(type_string: &str) -> Result<CqlType, CassError> {
+    if type_string.starts_with("org.apache.cassandra.") {
+        Ok(try!(CqlType::from_class_name(type_string)))
+    } else {
+        Ok(try!(CqlType::parse(type_string)))
+    }
+}
+
+fn meta_fields(meta: &CassSchemaMeta) -> Result<Fields, CassError> {
+    let mut fields = Fields::new();
+    for field in meta.fields() {
+        let value = try!(field.get_value().to_cql_value());
+        fields.insert(try!(field.get_name()), value);
+    }
+    Ok(fields)
+}
+
+fn query_rows(session: &CassSession, query: &str) -> Result<Vec<Fields>, CassError> {
+    let result = try!(try!(session.execute(query, 0)).wait());
+    let names = try!(result.column_names());
+    let mut rows = Vec::new();
+    let mut result_rows = result.iter();
+    while let Some(row) = result_rows.next() {
+        let mut fields = Fields::new();
+        for (index, name) in names.iter().enumerate() {
+            fields.insert(name.clone(), try!(try!(row.get_column(index as u64)).to_cql_value()));
+        }
+        rows.push(fields);
+    }
+    Ok(rows)
+}
+
+// None when the server doesn't know the table.
+fn query_rows_if_known(session: &CassSession, query: &str) -> Result<Option<Vec<Fields>>, CassError> {
+    match query_rows(session, query) {
+        Ok(rows) => Ok(Some(rows)),
+        Err(CassError::Code(CASS_ERROR_SERVER_INVALID_QUERY)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn missing_field() -> CassError {
+    CassError::build(CassErrorTypes::LIB_UNEXPECTED_RESPONSE as u32)
+}
+
+fn field<'f>(fields: &'f Fields, name: &str) -> Option<&'f CqlValue> {
+    match fields.get(name) {
+        Some(&CqlValue::Null) | None => None,
+        Some(value) => Some(value),
+    }
+}
+
+fn text(fields: &Fields, name: &str) -> Result<String, CassError> {
+    opt_text(fields, name).ok_or_else(missing_field)
+}
+
+fn opt_text(fields: &Fields, name: &str) -> Option<String> {
+    field(fields, name).and_then(CqlValue::as_str).map(str::to_owned)
+}
+
+fn table_name(fields: &Fields) -> Result<String, CassError> {
+    opt_text(fields, "table_name")
+        .or_else(|| opt_text(fields, "columnfamily_name"))
+        .ok_or_else(missing_field)
+}
+
+fn flag(fields: &Fields, name: &str) -> bool {
+    match field(fields, name) {
+        Some(&CqlValue::Boolean(value)) => value,
+        _ => false,
+    }
+}
+
+fn int(fields: &Fields, name: &str) -> Option<i32> {
+    match field(fields, name) {
+        Some(&CqlValue::Int(value)) => Some(value),
+        _ => None,
+    }
+}
+
+fn text_list(fields: &Fields, name: &str) -> Vec<String> {
+    match field(fields, name) {
+        Some(&CqlValue::List(ref items)) | Some(&CqlValue::Set(ref items)) => {
+            items.iter().filter_map(CqlValue::as_str).map(str::to_owned).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn text_map(fields: &Fields, name: &str) -> BTreeMap<String, String> {
+    match field(fields, name) {
+        Some(&CqlValue::Map(ref entries)) => {
+            entries.iter()
+                   .filter_map(|&(ref key, ref value)| match (key.as_str(), value.as_str()) {
+                       (Some(key), Some(value)) => Some((key.to_owned(), value.to_owned())),
+                       _ => None,
+                   })
+                   .collect()
+        }
+        _ => BTreeMap::new(),
+    }
+}
+
+// Cassandra 2.x keeps replication, compaction, compression and index options as
+// flat JSON objects. Numbers, booleans and null are kept as their JSON text.
+fn parse_json_object(json: &str) -> Result<BTreeMap<String, String>, CassError> {
+    let mut object = BTreeMap::new();
+    let mut chars = json.trim().chars().peekable();
+    if chars.next() != Some('{') {
+        return Err(missing_field());
+    }
+    loop {
+        skip_whitespace(&mut chars);
+        match chars.next() {
+            Some('}') if object.is_empty() => return Ok(object),
+            Some('"') => {}
+            _ => return Err(missing_field()),
+        }
+        let key = try!(read_json_string(&mut chars));
+        skip_whitespace(&mut chars);
+        if chars.next() != Some(':') {
+            return Err(missing_field());
+        }
+        skip_whitespace(&mut chars);
+        let value = match chars.peek() {
+            Some(&'"') => {
+                chars.next();
+                try!(read_json_string(&mut chars))
+            }
+            _ => {
+                let mut value = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c == ',' || c == '}' || c.is_whitespace() {
+                        break;
+                    }
+                    value.push(c);
+                    chars.next();
+                }
+                value
+            }
+        };
+        object.insert(key, value);
+        skip_whitespace(&mut chars);
+        match chars.next() {
+            Some(',') => {}
+            Some('}') => return Ok(object),
+            _ => return Err(missing_field()),
+        }
+    }
+}
+
+fn skip_whitespace<I: Iterator<Item = char>>(chars: &mut Peekable<I>) {
+    while chars.peek().map_or(false, |c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+// Reads up to and including the closing quote of a string whose opening quote
+// has already been read.
+fn read_json_string<I: Iterator<Item = char>>(chars: &mut I) -> Result<String, CassError> {
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(value),
+            Some('\\') => {
+                match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some('b') => value.push('\u{8}'),
+                    Some('f') => value.push('\u{c}'),
+                    Some('u') => {
+                        let hex: String = chars.by_ref().take(4).collect();
+                        match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                            Some(c) => value.push(c),
+                            None => return Err(missing_field()),
+                        }
+                    }
+                    Some(c) => value.push(c),
+                    None => return Err(missing_field()),
+                }
+            }
+            Some(c) => value.push(c),
+            None => return Err(missing_field()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn types_from_either_layout() {
+        assert_eq!(parse_type("frozen<map<text, list<int>>>").unwrap(),
+                   parse_type("org.apache.cassandra.db.marshal.FrozenType(org.apache.cassandra.db.\
+                               marshal.MapType(org.apache.cassandra.db.marshal.UTF8Type,\
+                               org.apache.cassandra.db.marshal.ListType(org.apache.cassandra.db.\
+                               marshal.Int32Type)))")
+                       .unwrap());
+        assert_eq!(parse_type("bigint").unwrap(),
+                   CqlType::Native(CassValueType::BIGINT));
+        assert!(parse_type("list<").is_err());
+    }
+
+    fn row(columns: &[(&str, CqlValue)]) -> Fields {
+        columns.iter().map(|&(name, ref value)| (name.to_owned(), value.clone())).collect()
+    }
+
+    // A keyspace ks with a user type address (street text, zip int) and a table
+    // users (id uuid PRIMARY KEY, home frozen<address>), as 3.x schema rows.
+    fn snapshot() -> SchemaSnapshot {
+        let text = |s: &str| CqlValue::Text(s.to_owned());
+        let list = |items: &[&str]| CqlValue::List(items.iter().map(|item| text(item)).collect());
+        let column = |name: &str, kind: &str, position: i32, cql_type: &str| {
+            row(&[("keyspace_name", text("ks")),
+                  ("table_name", text("users")),
+                  ("column_name", text(name)),
+                  ("kind", text(kind)),
+                  ("position", CqlValue::Int(position)),
+                  ("clustering_order", text("none")),
+                  ("type", text(cql_type))])
+        };
+        let rows = SchemaRows {
+            keyspaces: vec![row(&[("keyspace_name", text("ks")),
+                                  ("durable_writes", CqlValue::Boolean(true)),
+                                  ("replication",
+                                   CqlValue::Map(vec![(text("class"),
+                                                       text("org.apache.cassandra.locator.\
+                                                             SimpleStrategy")),
+                                                      (text("replication_factor"), text("1"))]))])],
+            types: vec![row(&[("keyspace_name", text("ks")),
+                              ("type_name", text("address")),
+                              ("field_names", list(&["street", "zip"])),
+                              ("field_types", list(&["text", "int"]))])],
+            tables: vec![row(&[("keyspace_name", text("ks")), ("table_name", text("users"))])],
+            columns: vec![column("id", "partition_key", 0, "uuid"),
+                          column("home", "regular", -1, "frozen<address>")],
+            ..SchemaRows::default()
+        };
+        rows.build().unwrap()
+    }
+
+    // A 2.x aggregate row, with the initial condition serialized as the state
+    // type and the types as marshal class names.
+    fn aggregate_row(state_type: &str, initcond: CqlValue) -> Fields {
+        let text = |s: &str| CqlValue::Text(s.to_owned());
+        let mut fields = Fields::new();
+        fields.insert("keyspace_name".to_owned(), text("ks"));
+        fields.insert("aggregate_name".to_owned(), text("agg"));
+        fields.insert("argument_types".to_owned(),
+                      CqlValue::List(vec![text("org.apache.cassandra.db.marshal.Int32Type")]));
+        fields.insert("state_func".to_owned(), text("acc"));
+        fields.insert("state_type".to_owned(), text(state_type));
+        fields.insert("return_type".to_owned(), text(state_type));
+        fields.insert("initcond".to_owned(), initcond);
+        fields
+    }
+
+    fn initial_condition(state_type: &str, initcond: CqlValue) -> Result<Option<String>, CassError> {
+        let snapshot = snapshot();
+        build_aggregate(&aggregate_row(state_type, initcond), &snapshot)
+            .map(|aggregate| aggregate.initial_condition)
+    }
+
+    #[test]
+    fn serialized_initial_conditions_are_written_as_literals() {
+        let marshal = |name: &str| format!("org.apache.cassandra.db.marshal.{}", name);
+        let blob = |bytes: &[u8]| CqlValue::Blob(bytes.to_vec());
+        assert_eq!(initial_condition(&marshal("Int32Type"), blob(&[0, 0, 0, 42])).unwrap(),
+                   Some("42".to_owned()));
+        assert_eq!(initial_condition(&marshal("LongType"), blob(&[0xff; 8])).unwrap(),
+                   Some("-1".to_owned()));
+        assert_eq!(initial_condition(&marshal("UTF8Type"), blob(b"it's")).unwrap(),
+                   Some("'it''s'".to_owned()));
+        assert_eq!(initial_condition(&marshal("DoubleType"),
+                                     blob(&[0x3f, 0xf8, 0, 0, 0, 0, 0, 0]))
+                       .unwrap(),
+                   Some("1.5".to_owned()));
+        assert_eq!(initial_condition(&marshal("DecimalType"), blob(&[0, 0, 0, 2, 0x04, 0xd2]))
+                       .unwrap(),
+                   Some("12.34".to_owned()));
+        assert_eq!(initial_condition(&marshal("DurationType"), blob(&[2, 4, 6])).unwrap(),
+                   Some("1mo2d3ns".to_owned()));
+        assert_eq!(initial_condition(&marshal("InetAddressType"), blob(&[10, 0, 0, 1]))
+                       .unwrap(),
+                   Some("'10.0.0.1'".to_owned()));
+        // An empty value of a type that has no empty form is null.
+        assert_eq!(initial_condition(&marshal("Int32Type"), blob(&[])).unwrap(),
+                   Some("null".to_owned()));
+    }
+
+    #[test]
+    fn serialized_collections_tuples_and_user_types_are_decoded() {
+        let marshal = |name: &str| format!("org.apache.cassandra.db.marshal.{}", name);
+        let blob = |bytes: &[u8]| CqlValue::Blob(bytes.to_vec());
+        let map = format!("{}({},{})", marshal("MapType"), marshal("UTF8Type"), marshal("Int32Type"));
+        assert_eq!(initial_condition(&map,
+                                     blob(&[0, 0, 0, 2, 0, 0, 0, 1, b'a', 0, 0, 0, 4, 0, 0, 0, 1,
+                                            0, 0, 0, 1, b'b', 0xff, 0xff, 0xff, 0xff]))
+                       .unwrap(),
+                   Some("{'a': 1, 'b': null}".to_owned()));
+        let list = format!("{}({})", marshal("ListType"), marshal("Int32Type"));
+        assert_eq!(initial_condition(&list, blob(&[0, 0, 0, 0])).unwrap(),
+                   Some("[]".to_owned()));
+        let tuple = format!("{}({},{})", marshal("TupleType"), marshal("Int32Type"), marshal("UTF8Type"));
+        assert_eq!(initial_condition(&tuple, blob(&[0, 0, 0, 4, 0, 0, 0, 7])).unwrap(),
+                   Some("(7, null)".to_owned()));
+        // UserType(keyspace, hex name, hex field name:field type, ...)
+        let address = format!("{}(ks,61646472657373,737472656574:{},7a6970:{})",
+                              marshal("UserType"),
+                              marshal("UTF8Type"),
+                              marshal("Int32Type"));
+        assert_eq!(initial_condition(&address,
+                                     blob(&[0, 0, 0, 4, b'M', b'a', b'i', b'n', 0, 0, 0, 4, 0, 0,
+                                            0x30, 0x39]))
+                       .unwrap(),
+                   Some("{street: 'Main', zip: 12345}".to_owned()));
+    }
+
+    #[test]
+    fn initial_conditions_are_kept_or_rejected() {
+        let int = "org.apache.cassandra.db.marshal.Int32Type";
+        // 3.x already keeps the literal.
+        assert_eq!(initial_condition("int", CqlValue::Text("0".to_owned())).unwrap(),
+                   Some("0".to_owned()));
+        assert_eq!(initial_condition("int", CqlValue::Null).unwrap(), None);
+        // Values that are short, or have bytes left over, are not the state type.
+        assert!(initial_condition(int, CqlValue::Blob(vec![0, 0, 1])).is_err());
+        assert!(initial_condition(int, CqlValue::Blob(vec![0, 0, 0, 0, 1])).is_err());
+        let list = "org.apache.cassandra.db.marshal.ListType(org.apache.cassandra.db.marshal.\
+                    Int32Type)";
+        assert!(initial_condition(list, CqlValue::Blob(vec![0, 0, 0, 1, 0, 0, 0, 4])).is_err());
+    }
+}
@@ -0,0 +1,864 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use cassandra_schema::quote_identifier;
+use cassandra_schema::DdlError;
+use cassandra_schema::TokenKind;
+use cassandra_schema::split_statements;
+use cassandra_schema::tokenize;
+use cql_ffi::error::CassError;
+use cql_ffi::result::CassResult;
+use cql_ffi::session::CassSession;
+use cql_ffi::statement::CassStatement;
+
+// Versioned CQL scripts, named like Flyway's: V001__create_users.cql is version
+// 1, "create users". Which versions have been applied is kept in a
+// schema_migrations table, written with lightweight transactions so that two
+// runners started at once can't both apply the same script.
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Cass(CassError),
+    Io(PathBuf, io::Error),
+    Ddl(DdlError),
+    // A .cql file whose name isn't V<version>__<description>.cql.
+    BadName(PathBuf),
+    DuplicateVersion(u32),
+    // An applied script was edited afterwards.
+    ChecksumMismatch {
+        version: u32,
+        applied: u32,
+        script: u32,
+    },
+    // A script was started and never finished; what it did has to be checked
+    // by hand and its row deleted before migrating again.
+    Incomplete(u32),
+    // Another runner claimed the version first.
+    Conflict(u32),
+    Statement {
+        version: u32,
+        statement: String,
+        error: CassError,
+    },
+    SchemaDisagreement(u32),
+    // Baselining only makes sense before anything was applied.
+    AlreadyMigrated,
+}
+
+impl Display for MigrationError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            MigrationError::Cass(ref err) => write!(f, "{}", err),
+            MigrationError::Io(ref path, ref err) => write!(f, "{}: {}", path.display(), err),
+            MigrationError::Ddl(ref err) => write!(f, "{}", err),
+            MigrationError::BadName(ref path) => {
+                write!(f,
+                       "{}: migration scripts are named V<version>__<description>.cql",
+                       path.display())
+            }
+            MigrationError::DuplicateVersion(version) => {
+                write!(f, "more than one script for version {}", version)
+            }
+            MigrationError::ChecksumMismatch { version, applied, script } => {
+                write!(f,
+                       "version {} was applied with checksum {} but the script's is now {}",
+                       version,
+                       applied,
+                       script)
+            }
+            MigrationError::Incomplete(version) => {
+                write!(f,
+                       "version {} was started but never finished; check what it did and \
+                        delete its row from schema_migrations",
+                       version)
+            }
+            MigrationError::Conflict(version) => {
+                write!(f, "version {} is being applied by someone else", version)
+            }
+            MigrationError::Statement { version, ref statement, ref error } => {
+                write!(f, "version {} failed at {:?}: {}", version, statement, error)
+            }
+            MigrationError::SchemaDisagreement(version) => {
+                write!(f, "the schema did not settle while applying version {}", version)
+            }
+            MigrationError::AlreadyMigrated => {
+                write!(f, "can't baseline a keyspace with migrations already applied")
+            }
+        }
+    }
+}
+
+impl Error for MigrationError {
+    fn description(&self) -> &str {
+        match *self {
+            MigrationError::Cass(ref err) => err.description(),
+            MigrationError::Io(_, ref err) => err.description(),
+            MigrationError::Ddl(ref err) => err.description(),
+            MigrationError::BadName(_) => "badly named migration script",
+            MigrationError::DuplicateVersion(_) => "duplicate migration version",
+            MigrationError::ChecksumMismatch { .. } => "applied migration script changed",
+            MigrationError::Incomplete(_) => "incomplete migration",
+            MigrationError::Conflict(_) => "concurrent migration",
+            MigrationError::Statement { .. } => "migration statement failed",
+            MigrationError::SchemaDisagreement(_) => "schema agreement timed out",
+            MigrationError::AlreadyMigrated => "migrations already applied",
+        }
+    }
+}
+
+impl From<CassError> for MigrationError {
+    fn from(err: CassError) -> MigrationError {
+        MigrationError::Cass(err)
+    }
+}
+
+impl From<DdlError> for MigrationError {
+    fn from(err: DdlError) -> MigrationError {
+        MigrationError::Ddl(err)
+    }
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct Migration {
+    pub version: u32,
+    pub description: String,
+    pub path: PathBuf,
+    pub script: String,
+    pub checksum: u32,
+}
+
+// A row of schema_migrations.
+#[derive(Debug,Clone,PartialEq)]
+pub struct AppliedMigration {
+    pub version: u32,
+    pub description: String,
+    pub checksum: u32,
+    // Milliseconds since the epoch.
+    pub installed_on: i64,
+    pub execution_ms: i64,
+    pub success: bool,
+    pub baseline: bool,
+}
+
+impl Migration {
+    pub fn new(version: u32, description: &str, script: &str) -> Migration {
+        Migration {
+            version: version,
+            description: description.to_owned(),
+            path: PathBuf::new(),
+            script: script.to_owned(),
+            checksum: checksum(script),
+        }
+    }
+
+    // Every .cql file in the directory, in version order. Other files are
+    // ignored.
+    pub fn load_directory<P: AsRef<Path>>(directory: P) -> Result<Vec<Migration>, MigrationError> {
+        let directory = directory.as_ref();
+        let entries = try!(fs::read_dir(directory)
+                               .map_err(|err| MigrationError::Io(directory.to_path_buf(), err)));
+        let mut migrations = BTreeMap::new();
+        for entry in entries {
+            let path = try!(entry.map_err(|err| MigrationError::Io(directory.to_path_buf(), err)))
+                           .path();
+            if !path.is_file() || !path.extension().map_or(false, |extension| extension == "cql") {
+                continue;
+            }
+            let (version, description) = match parse_file_name(&path) {
+                Some(parsed) => parsed,
+                None => return Err(MigrationError::BadName(path)),
+            };
+            let script = try!(fs::read_to_string(&path)
+                                  .map_err(|err| MigrationError::Io(path.clone(), err)));
+            let mut migration = Migration::new(version, &description, &script);
+            migration.path = path;
+            if migrations.insert(version, migration).is_some() {
+                return Err(MigrationError::DuplicateVersion(version));
+            }
+        }
+        Ok(migrations.into_iter().map(|(_, migration)| migration).collect())
+    }
+
+    pub fn statements(&self) -> Result<Vec<String>, MigrationError> {
+        let source = self.path.display().to_string();
+        Ok(try!(split_statements(&source, &self.script)))
+    }
+}
+
+// V<digits>__<description>.cql, with underscores in the description read as
+// spaces. Versions are kept in an int column, so can't be over i32::MAX.
+fn parse_file_name(path: &Path) -> Option<(u32, String)> {
+    let stem = match path.file_stem().and_then(|stem| stem.to_str()) {
+        Some(stem) => stem,
+        None => return None,
+    };
+    if !stem.starts_with('V') {
+        return None;
+    }
+    let separator = match stem.find("__") {
+        Some(separator) => separator,
+        None => return None,
+    };
+    let digits = &stem[1..separator];
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let version = match digits.parse::<u32>() {
+        Ok(version) if version <= i32::MAX as u32 => version,
+        _ => return None,
+    };
+    Some((version, stem[separator + 2..].replace('_', " ")))
+}
+
+// CRC-32 of the script, with Windows line endings read as Unix ones so a
+// checkout on another platform doesn't look like an edit.
+pub fn checksum(script: &str) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in script.replace("\r\n", "\n").as_bytes() {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+pub struct Migrator<'a> {
+    table: Box<dyn MigrationTable + 'a>,
+    dry_run: bool,
+    schema_agreement_timeout: Duration,
+}
+
+// What a migrator does with schema_migrations and the cluster. Migrators run it
+// on a session; the tests use a stand-in.
+trait MigrationTable {
+    fn exists(&self) -> Result<bool, CassError>;
+    fn create(&self) -> Result<(), CassError>;
+    fn rows(&self) -> Result<Vec<AppliedMigration>, CassError>;
+    // Inserts the row if there is none for its version, returning whether it did.
+    fn insert(&self, row: &AppliedMigration) -> Result<bool, CassError>;
+    // Marks the version finished unless it already is, returning whether it did.
+    fn finish(&self, version: u32, execution_ms: i64) -> Result<bool, CassError>;
+    fn execute(&self, statement: &str) -> Result<(), CassError>;
+    fn wait_for_schema_agreement(&self, timeout: Duration) -> Result<bool, CassError>;
+}
+
+struct SessionTable<'a> {
+    session: &'a CassSession,
+    keyspace: String,
+}
+
+impl<'a> Migrator<'a> {
+    // schema_migrations is kept in the given keyspace, which has to exist.
+    pub fn new(session: &'a CassSession, keyspace: &str) -> Migrator<'a> {
+        let table = SessionTable {
+            session: session,
+            keyspace: keyspace.to_owned(),
+        };
+        Migrator::with_table(Box::new(table))
+    }
+
+    fn with_table(table: Box<dyn MigrationTable + 'a>) -> Migrator<'a> {
+        Migrator {
+            table: table,
+            dry_run: false,
+            schema_agreement_timeout: Duration::from_secs(30),
+        }
+    }
+
+    // Work out what would be done without changing anything.
+    pub fn set_dry_run(&mut self, dry_run: bool) -> &mut Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn set_schema_agreement_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.schema_agreement_timeout = timeout;
+        self
+    }
+
+    pub fn applied(&self) -> Result<Vec<AppliedMigration>, MigrationError> {
+        // Nothing has been applied before the first run creates the table.
+        if self.dry_run && !try!(self.table.exists()) {
+            return Ok(Vec::new());
+        }
+        let mut applied = try!(self.table.rows());
+        applied.sort_by_key(|migration| migration.version);
+        Ok(applied)
+    }
+
+    // The scripts still to run, after checking that the applied ones are
+    // unchanged and all finished. Applied versions without a script are
+    // allowed; old scripts get deleted.
+    pub fn pending<'m>(&self,
+                       migrations: &'m [Migration])
+                       -> Result<Vec<&'m Migration>, MigrationError> {
+        let applied: BTreeMap<u32, AppliedMigration> = try!(self.applied())
+                                                           .into_iter()
+                                                           .map(|applied| (applied.version, applied))
+                                                           .collect();
+        let mut pending = Vec::new();
+        for migration in migrations {
+            match applied.get(&migration.version) {
+                Some(applied) => try!(check_applied(applied, migration)),
+                None => pending.push(migration),
+            }
+        }
+        Ok(pending)
+    }
+
+    // Runs every pending script in version order and returns the versions
+    // run, or in a dry run the versions that would be.
+    pub fn migrate(&self, migrations: &[Migration]) -> Result<Vec<u32>, MigrationError> {
+        if !self.dry_run {
+            try!(self.create_table());
+        }
+        let pending = try!(self.pending(migrations));
+        let mut versions = Vec::new();
+        for migration in pending {
+            if self.dry_run || try!(self.apply(migration)) {
+                versions.push(migration.version);
+            }
+        }
+        Ok(versions)
+    }
+
+    // Records the scripts up to and including the version as applied without
+    // running them, for keyspaces that were created before migrations were.
+    pub fn baseline(&self, migrations: &[Migration], version: u32) -> Result<Vec<u32>, MigrationError> {
+        if !self.dry_run {
+            try!(self.create_table());
+        }
+        if !try!(self.applied()).is_empty() {
+            return Err(MigrationError::AlreadyMigrated);
+        }
+        let mut versions = Vec::new();
+        for migration in migrations.iter().filter(|migration| migration.version <= version) {
+            if !self.dry_run && !try!(self.claim(migration, true)) {
+                return Err(MigrationError::Conflict(migration.version));
+            }
+            versions.push(migration.version);
+        }
+        Ok(versions)
+    }
+
+    // Returns false when another runner applied the script first.
+    fn apply(&self, migration: &Migration) -> Result<bool, MigrationError> {
+        if !try!(self.claim(migration, false)) {
+            return Ok(false);
+        }
+        let started = Instant::now();
+        for statement in try!(migration.statements()) {
+            if let Err(err) = self.table.execute(&statement) {
+                return Err(MigrationError::Statement {
+                    version: migration.version,
+                    statement: statement,
+                    error: err,
+                });
+            }
+            if is_schema_change(&statement) &&
+               !try!(self.table.wait_for_schema_agreement(self.schema_agreement_timeout)) {
+                return Err(MigrationError::SchemaDisagreement(migration.version));
+            }
+        }
+        let elapsed = started.elapsed();
+        let execution_ms = elapsed.as_secs() as i64 * 1000 + elapsed.subsec_nanos() as i64 / 1_000_000;
+        if !try!(self.table.finish(migration.version, execution_ms)) {
+            return Err(MigrationError::Conflict(migration.version));
+        }
+        Ok(true)
+    }
+
+    // Inserts the version's row if there is none, finished for a baseline and
+    // unfinished otherwise. Returns false when the row was already there for
+    // the same finished script, which happens when another runner got to it
+    // between reading schema_migrations and now.
+    fn claim(&self, migration: &Migration, baseline: bool) -> Result<bool, MigrationError> {
+        let row = AppliedMigration {
+            version: migration.version,
+            description: migration.description.clone(),
+            checksum: migration.checksum,
+            installed_on: now_ms(),
+            execution_ms: 0,
+            success: baseline,
+            baseline: baseline,
+        };
+        if try!(self.table.insert(&row)) {
+            return Ok(true);
+        }
+        let existing = try!(self.applied()).into_iter().find(|applied| applied.version == migration.version);
+        match existing {
+            Some(ref applied) if applied.success => {
+                try!(check_applied(applied, migration));
+                Ok(false)
+            }
+            _ => Err(MigrationError::Conflict(migration.version)),
+        }
+    }
+
+    fn create_table(&self) -> Result<(), MigrationError> {
+        try!(self.table.create());
+        if !try!(self.table.wait_for_schema_agreement(self.schema_agreement_timeout)) {
+            return Err(MigrationError::SchemaDisagreement(0));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> SessionTable<'a> {
+    fn table(&self) -> String {
+        format!("{}.schema_migrations", quote_identifier(&self.keyspace))
+    }
+
+    // Whether a lightweight transaction was applied.
+    fn execute_conditional(&self, statement: &CassStatement) -> Result<bool, CassError> {
+        let result = try!(self.session.execute_statement(statement).wait());
+        was_applied(&result)
+    }
+}
+
+impl<'a> MigrationTable for SessionTable<'a> {
+    fn exists(&self) -> Result<bool, CassError> {
+        let query = "SELECT table_name FROM system_schema.tables WHERE keyspace_name = ? AND \
+                     table_name = 'schema_migrations'";
+        let mut statement = try!(CassStatement::new(query, 1));
+        try!(statement.bind_string(0, &self.keyspace));
+        match self.session.execute_statement(&statement).wait() {
+            Ok(result) => Ok(result.row_count() > 0),
+            // Cassandra 2.x
+            Err(_) => {
+                let query = "SELECT columnfamily_name FROM system.schema_columnfamilies WHERE \
+                             keyspace_name = ? AND columnfamily_name = 'schema_migrations'";
+                let mut statement = try!(CassStatement::new(query, 1));
+                try!(statement.bind_string(0, &self.keyspace));
+                Ok(try!(self.session.execute_statement(&statement).wait()).row_count() > 0)
+            }
+        }
+    }
+
+    fn create(&self) -> Result<(), CassError> {
+        let query = format!("CREATE TABLE IF NOT EXISTS {} (version int PRIMARY KEY, description \
+                             text, checksum bigint, installed_on timestamp, execution_ms bigint, \
+                             success boolean, baseline boolean)",
+                            self.table());
+        try!(try!(self.session.execute(&query, 0)).wait());
+        Ok(())
+    }
+
+    fn rows(&self) -> Result<Vec<AppliedMigration>, CassError> {
+        let query = format!("SELECT version, description, checksum, installed_on, execution_ms, \
+                             success, baseline FROM {}",
+                            self.table());
+        let result = try!(try!(self.session.execute(&query, 0)).wait());
+        let mut applied = Vec::new();
+        let mut rows = result.iter();
+        while let Some(row) = rows.next() {
+            applied.push(AppliedMigration {
+                version: try!(try!(row.get_column(0)).get_int32()) as u32,
+                description: try!(try!(row.get_column(1)).get::<Option<String>>())
+                                 .unwrap_or_default(),
+                checksum: try!(try!(row.get_column(2)).get_int64()) as u32,
+                installed_on: try!(try!(row.get_column(3)).get::<Option<i64>>()).unwrap_or(0),
+                execution_ms: try!(try!(row.get_column(4)).get::<Option<i64>>()).unwrap_or(0),
+                success: try!(try!(row.get_column(5)).get::<Option<bool>>()).unwrap_or(false),
+                baseline: try!(try!(row.get_column(6)).get::<Option<bool>>()).unwrap_or(false),
+            });
+        }
+        Ok(applied)
+    }
+
+    fn insert(&self, row: &AppliedMigration) -> Result<bool, CassError> {
+        let query = format!("INSERT INTO {} (version, description, checksum, execution_ms, success, \
+                             baseline, installed_on) VALUES (?, ?, ?, ?, ?, ?, ?) IF NOT EXISTS",
+                            self.table());
+        let mut statement = try!(CassStatement::new(&query, 7));
+        try!(statement.bind_int32(0, row.version as i32));
+        try!(statement.bind_string(1, &row.description));
+        try!(statement.bind_int64(2, row.checksum as i64));
+        try!(statement.bind_int64(3, row.execution_ms));
+        try!(statement.bind_bool(4, row.success));
+        try!(statement.bind_bool(5, row.baseline));
+        try!(statement.bind_int64(6, row.installed_on));
+        self.execute_conditional(&statement)
+    }
+
+    fn finish(&self, version: u32, execution_ms: i64) -> Result<bool, CassError> {
+        let query = format!("UPDATE {} SET success = true, execution_ms = ? WHERE version = ? IF \
+                             success = false",
+                            self.table());
+        let mut statement = try!(CassStatement::new(&query, 2));
+        try!(statement.bind_int64(0, execution_ms));
+        try!(statement.bind_int32(1, version as i32));
+        self.execute_conditional(&statement)
+    }
+
+    fn execute(&self, statement: &str) -> Result<(), CassError> {
+        try!(try!(self.session.execute(statement, 0)).wait());
+        Ok(())
+    }
+
+    fn wait_for_schema_agreement(&self, timeout: Duration) -> Result<bool, CassError> {
+        self.session.wait_for_schema_agreement(timeout)
+    }
+}
+
+fn check_applied(applied: &AppliedMigration, migration: &Migration) -> Result<(), MigrationError> {
+    if !applied.success {
+        Err(MigrationError::Incomplete(applied.version))
+    } else if !applied.baseline && applied.checksum != migration.checksum {
+        Err(MigrationError::ChecksumMismatch {
+            version: applied.version,
+            applied: applied.checksum,
+            script: migration.checksum,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+// The first column of a lightweight transaction's result is [applied].
+fn was_applied(result: &CassResult) -> Result<bool, CassError> {
+    match result.first_row() {
+        Some(row) => try!(row.get_column(0)).get_bool(),
+        None => Ok(false),
+    }
+}
+
+// Read past any comments before the statement's first keyword.
+fn is_schema_change(statement: &str) -> bool {
+    let tokens = tokenize("<statement>", statement).unwrap_or_default();
+    match tokens.first().map(|token| &token.kind) {
+        Some(&TokenKind::Identifier(ref keyword)) => {
+            keyword == "create" || keyword == "alter" || keyword == "drop"
+        }
+        _ => false,
+    }
+}
+
+fn now_ms() -> i64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs() as i64 * 1000 + since.subsec_nanos() as i64 / 1_000_000,
+        Err(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::MigrationTable;
+    use std::cell::RefCell;
+    use std::path::Path;
+    use std::rc::Rc;
+
+    use cql_ffi::error::CassErrorTypes;
+
+    // Keeps schema_migrations in memory and applies the conditional statements
+    // the way Cassandra would. Clones share their state, so a test can look at
+    // it or play another runner.
+    #[derive(Clone,Default)]
+    struct StandIn {
+        state: Rc<RefCell<State>>,
+    }
+
+    #[derive(Default)]
+    struct State {
+        exists: bool,
+        rows: BTreeMap<u32, AppliedMigration>,
+        executed: Vec<String>,
+        // Written by another runner just before the next insert.
+        concurrent: Vec<AppliedMigration>,
+        // How many more times the schema agrees, if not always.
+        agreements: Option<u32>,
+    }
+
+    impl StandIn {
+        fn row(&self, version: u32) -> Option<AppliedMigration> {
+            self.state.borrow().rows.get(&version).cloned()
+        }
+
+        fn executed(&self) -> Vec<String> {
+            self.state.borrow().executed.clone()
+        }
+    }
+
+    impl MigrationTable for StandIn {
+        fn exists(&self) -> Result<bool, CassError> {
+            Ok(self.state.borrow().exists)
+        }
+
+        fn create(&self) -> Result<(), CassError> {
+            self.state.borrow_mut().exists = true;
+            Ok(())
+        }
+
+        fn rows(&self) -> Result<Vec<AppliedMigration>, CassError> {
+            let state = self.state.borrow();
+            if !state.exists {
+                return Err(CassError::build(CassErrorTypes::SERVER_INVALID_QUERY as u32));
+            }
+            Ok(state.rows.values().cloned().collect())
+        }
+
+        fn insert(&self, row: &AppliedMigration) -> Result<bool, CassError> {
+            let mut state = self.state.borrow_mut();
+            for concurrent in state.concurrent.split_off(0) {
+                state.rows.insert(concurrent.version, concurrent);
+            }
+            if state.rows.contains_key(&row.version) {
+                return Ok(false);
+            }
+            state.rows.insert(row.version, row.clone());
+            Ok(true)
+        }
+
+        fn finish(&self, version: u32, execution_ms: i64) -> Result<bool, CassError> {
+            match self.state.borrow_mut().rows.get_mut(&version) {
+                Some(row) if !row.success => {
+                    row.success = true;
+                    row.execution_ms = execution_ms;
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        }
+
+        fn execute(&self, statement: &str) -> Result<(), CassError> {
+            if statement.contains("fail") {
+                return Err(CassError::build(CassErrorTypes::SERVER_SYNTAX_ERROR as u32));
+            }
+            self.state.borrow_mut().executed.push(statement.to_owned());
+            Ok(())
+        }
+
+        fn wait_for_schema_agreement(&self, _timeout: Duration) -> Result<bool, CassError> {
+            let mut state = self.state.borrow_mut();
+            match state.agreements {
+                Some(0) => Ok(false),
+                Some(ref mut agreements) => {
+                    *agreements -= 1;
+                    Ok(true)
+                }
+                None => Ok(true),
+            }
+        }
+    }
+
+    fn migrator(table: &StandIn) -> Migrator<'static> {
+        Migrator::with_table(Box::new(table.clone()))
+    }
+
+    fn migrations() -> Vec<Migration> {
+        vec![Migration::new(1, "create users", "CREATE TABLE users (id int PRIMARY KEY);"),
+             Migration::new(2,
+                            "add admin",
+                            "INSERT INTO users (id) VALUES (1);\nALTER TABLE users ADD email text;"),
+             Migration::new(3, "add index", "CREATE INDEX ON users (email);")]
+    }
+
+    fn applied(version: u32, checksum: u32, success: bool, baseline: bool) -> AppliedMigration {
+        AppliedMigration {
+            version: version,
+            description: "create users".to_owned(),
+            checksum: checksum,
+            installed_on: 0,
+            execution_ms: 0,
+            success: success,
+            baseline: baseline,
+        }
+    }
+
+    #[test]
+    fn file_names() {
+        assert_eq!(parse_file_name(Path::new("migrations/V001__create_users.cql")),
+                   Some((1, "create users".to_owned())));
+        assert_eq!(parse_file_name(Path::new("V20240101__add_email_index.cql")),
+                   Some((20240101, "add email index".to_owned())));
+        assert_eq!(parse_file_name(Path::new("V2147483647__last.cql")),
+                   Some((2147483647, "last".to_owned())));
+        assert_eq!(parse_file_name(Path::new("V7__.cql")), Some((7, "".to_owned())));
+        for bad in &["V2147483648__too_big.cql", "V99999999999__too_big.cql", "V__none.cql",
+                     "V+1__sign.cql", "V-1__sign.cql", "v1__lower.cql", "V1_one_underscore.cql",
+                     "R1__repeatable.cql", "Vx__letters.cql"] {
+            assert_eq!(parse_file_name(Path::new(bad)), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn checksums() {
+        // the CRC-32 check value
+        assert_eq!(checksum("123456789"), 0xcbf4_3926);
+        assert_eq!(checksum(""), 0);
+        assert_eq!(checksum("CREATE TABLE t (k int PRIMARY KEY);\r\n"),
+                   checksum("CREATE TABLE t (k int PRIMARY KEY);\n"));
+        assert!(checksum("CREATE TABLE t (k int PRIMARY KEY);") !=
+                checksum("CREATE TABLE t (k bigint PRIMARY KEY);"));
+    }
+
+    #[test]
+    fn applied_checks() {
+        let migration = Migration::new(1, "create users", "CREATE TABLE users (id int PRIMARY KEY);");
+        assert!(check_applied(&applied(1, migration.checksum, true, false), &migration).is_ok());
+        match check_applied(&applied(1, migration.checksum + 1, true, false), &migration) {
+            Err(MigrationError::ChecksumMismatch { version: 1, applied, script }) => {
+                assert_eq!(applied, migration.checksum + 1);
+                assert_eq!(script, migration.checksum);
+            }
+            other => panic!("expected a checksum mismatch, got {:?}", other.is_ok()),
+        }
+        // baselined scripts were never run, so may have changed
+        assert!(check_applied(&applied(1, 0, true, true), &migration).is_ok());
+        match check_applied(&applied(1, migration.checksum, false, false), &migration) {
+            Err(MigrationError::Incomplete(1)) => {}
+            other => panic!("expected an incomplete migration, got {:?}", other.is_ok()),
+        }
+    }
+
+    #[test]
+    fn schema_changes() {
+        assert!(is_schema_change("CREATE TABLE users (id int PRIMARY KEY)"));
+        assert!(is_schema_change("alter table users add email text"));
+        assert!(is_schema_change("-- drop the old table\nDROP TABLE old_users"));
+        assert!(is_schema_change("/* new index */ create index on users (email)"));
+        assert!(is_schema_change("// a comment\n\n  Create Type address (street text)"));
+        assert!(!is_schema_change("INSERT INTO users (id) VALUES (1)"));
+        assert!(!is_schema_change("-- create a user\nINSERT INTO users (id) VALUES (1)"));
+        assert!(!is_schema_change(""));
+    }
+
+    #[test]
+    fn pending_versions_are_claimed_run_and_finished() {
+        let table = StandIn::default();
+        let migrations = migrations();
+        assert_eq!(migrator(&table).migrate(&migrations).unwrap(), [1, 2, 3]);
+        assert_eq!(table.executed(),
+                   ["CREATE TABLE users (id int PRIMARY KEY)",
+                    "INSERT INTO users (id) VALUES (1)",
+                    "ALTER TABLE users ADD email text",
+                    "CREATE INDEX ON users (email)"]);
+        for migration in &migrations {
+            let row = table.row(migration.version).unwrap();
+            assert_eq!(row.description, migration.description);
+            assert_eq!(row.checksum, migration.checksum);
+            assert!(row.success && !row.baseline);
+        }
+        // Nothing is left for the next run.
+        assert!(migrator(&table).migrate(&migrations).unwrap().is_empty());
+        assert_eq!(table.executed().len(), 4);
+    }
+
+    #[test]
+    fn a_version_claimed_by_an_unfinished_runner_is_a_conflict() {
+        let table = StandIn::default();
+        table.state.borrow_mut().concurrent.push(applied(1, migrations()[0].checksum, false, false));
+        match migrator(&table).migrate(&migrations()) {
+            Err(MigrationError::Conflict(1)) => {}
+            other => panic!("expected a conflict, got {:?}", other.is_ok()),
+        }
+        assert!(table.executed().is_empty());
+        assert!(!table.row(1).unwrap().success);
+        // It stays in the way until someone looks at it.
+        match migrator(&table).migrate(&migrations()) {
+            Err(MigrationError::Incomplete(1)) => {}
+            other => panic!("expected an incomplete migration, got {:?}", other.is_ok()),
+        }
+    }
+
+    #[test]
+    fn a_version_finished_by_another_runner_is_skipped() {
+        let table = StandIn::default();
+        let migrations = migrations();
+        table.state.borrow_mut().concurrent.push(applied(1, migrations[0].checksum, true, false));
+        assert_eq!(migrator(&table).migrate(&migrations).unwrap(), [2, 3]);
+        assert!(!table.executed().iter().any(|statement| statement.starts_with("CREATE TABLE")));
+
+        // Unless it ran a different script.
+        let table = StandIn::default();
+        table.state.borrow_mut().concurrent.push(applied(1, 0, true, false));
+        match migrator(&table).migrate(&migrations) {
+            Err(MigrationError::ChecksumMismatch { version: 1, .. }) => {}
+            other => panic!("expected a checksum mismatch, got {:?}", other.is_ok()),
+        }
+    }
+
+    #[test]
+    fn failures_leave_their_version_unfinished() {
+        let table = StandIn::default();
+        let mut broken = migrations();
+        broken[1] = Migration::new(2, "broken", "INSERT INTO users (id) VALUES (1);\nfail;");
+        match migrator(&table).migrate(&broken) {
+            Err(MigrationError::Statement { version: 2, ref statement, .. }) => {
+                assert_eq!(statement, "fail")
+            }
+            other => panic!("expected a failed statement, got {:?}", other.is_ok()),
+        }
+        assert!(table.row(1).unwrap().success);
+        assert!(!table.row(2).unwrap().success);
+        assert!(table.row(3).is_none());
+
+        let table = StandIn::default();
+        table.state.borrow_mut().agreements = Some(0);
+        match migrator(&table).migrate(&migrations()) {
+            Err(MigrationError::SchemaDisagreement(0)) => {}
+            other => panic!("expected a schema disagreement, got {:?}", other.is_ok()),
+        }
+        assert!(table.row(1).is_none());
+
+        let table = StandIn::default();
+        table.state.borrow_mut().agreements = Some(1);
+        match migrator(&table).migrate(&migrations()) {
+            Err(MigrationError::SchemaDisagreement(1)) => {}
+            other => panic!("expected a schema disagreement, got {:?}", other.is_ok()),
+        }
+        assert!(!table.row(1).unwrap().success);
+    }
+
+    #[test]
+    fn baselines_record_versions_without_running_them() {
+        let table = StandIn::default();
+        let migrations = migrations();
+        assert_eq!(migrator(&table).baseline(&migrations, 2).unwrap(), [1, 2]);
+        assert!(table.executed().is_empty());
+        for version in 1..3 {
+            let row = table.row(version).unwrap();
+            assert!(row.success && row.baseline);
+        }
+        assert!(table.row(3).is_none());
+        match migrator(&table).baseline(&migrations, 3) {
+            Err(MigrationError::AlreadyMigrated) => {}
+            other => panic!("expected a baseline to be refused, got {:?}", other.is_ok()),
+        }
+        assert_eq!(migrator(&table).migrate(&migrations).unwrap(), [3]);
+        assert_eq!(table.executed(), ["CREATE INDEX ON users (email)"]);
+    }
+
+    #[test]
+    fn dry_runs_change_nothing() {
+        let table = StandIn::default();
+        let migrations = migrations();
+        let mut dry_run = migrator(&table);
+        dry_run.set_dry_run(true);
+        assert_eq!(dry_run.migrate(&migrations).unwrap(), [1, 2, 3]);
+        assert_eq!(dry_run.baseline(&migrations, 2).unwrap(), [1, 2]);
+        assert!(!table.state.borrow().exists);
+
+        assert_eq!(migrator(&table).migrate(&migrations[..1]).unwrap(), [1]);
+        assert_eq!(dry_run.migrate(&migrations).unwrap(), [2, 3]);
+        assert_eq!(table.executed().len(), 1);
+        assert!(table.row(2).is_none());
+    }
+}
@@ -0,0 +1,238 @@
+#[cfg(feature = "bigdecimal")]
+use std::fmt::Write;
+
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigInt;
+#[cfg(feature = "bigdecimal")]
+use bigdecimal::BigDecimal;
+#[cfg(feature = "bigdecimal")]
+use bigdecimal::Num;
+
+#[cfg(feature = "bigdecimal")]
+use cql_bindgen::cass_collection_append_decimal;
+
+use cql_ffi::collection::collection::{CassCollection, ToCql};
+use cql_ffi::column::CassColumn;
+#[cfg(feature = "bigdecimal")]
+use cassandra_schema::varint_magnitude;
+use cql_ffi::error::CassError;
+#[cfg(feature = "bigdecimal")]
+use cql_ffi::error::CassErrorTypes;
+use cql_ffi::statement::CassStatement;
+use cql_ffi::tuple::CassTuple;
+use cql_ffi::udt::CassUserType;
+use cql_ffi::value::CassValue;
+
+// On the wire a varint is a big-endian two's complement integer, and a decimal
+// is a varint unscaled value together with an i32 scale, so that
+// value = unscaled * 10^-scale.
+
+#[cfg(feature = "num-bigint")]
+pub fn varint_from_bigint(value: &BigInt) -> Vec<u8> {
+    value.to_signed_bytes_be()
+}
+
+#[cfg(feature = "num-bigint")]
+pub fn bigint_from_varint(varint: &[u8]) -> BigInt {
+    BigInt::from_signed_bytes_be(varint)
+}
+
+#[cfg(feature = "bigdecimal")]
+pub fn decimal_from_bigdecimal(value: &BigDecimal) -> Result<(Vec<u8>, i32), CassError> {
+    let (unscaled, scale) = value.as_bigint_and_exponent();
+    if scale < i32::MIN as i64 || scale > i32::MAX as i64 {
+        return Err(CassError::build(CassErrorTypes::LIB_BAD_PARAMS as u32));
+    }
+    Ok((unscaled.to_signed_bytes_be(), scale as i32))
+}
+
+// bigdecimal doesn't export the BigInt its constructor takes, so the unscaled
+// value is read with the Num trait it does export, from the varint in hex.
+#[cfg(feature = "bigdecimal")]
+pub fn bigdecimal_from_decimal(varint: &[u8], scale: i32) -> Result<BigDecimal, CassError> {
+    let (negative, magnitude) = varint_magnitude(varint);
+    let mut hex = String::with_capacity(magnitude.len() * 2 + 2);
+    if negative {
+        hex.push('-');
+    }
+    for byte in &magnitude {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    if magnitude.is_empty() {
+        hex.push('0');
+    }
+    let unscaled = try!(Num::from_str_radix(&hex, 16)
+                            .map_err(|_| CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)));
+    Ok(BigDecimal::new(unscaled, scale as i64))
+}
+
+impl<'a> CassValue<'a> {
+    #[cfg(feature = "num-bigint")]
+    pub fn get_varint(&self) -> Result<BigInt, CassError> {
+        Ok(bigint_from_varint(&try!(self.get_varint_bytes())))
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    pub fn get_bigdecimal(&self) -> Result<BigDecimal, CassError> {
+        let (varint, scale) = try!(self.get_decimal_bytes());
+        bigdecimal_from_decimal(&varint, scale)
+    }
+}
+
+impl<'a> CassColumn<'a> {
+    #[cfg(feature = "num-bigint")]
+    pub fn get_varint(&self) -> Result<BigInt, CassError> {
+        CassValue::new(self.0).get_varint()
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    pub fn get_bigdecimal(&self) -> Result<BigDecimal, CassError> {
+        CassValue::new(self.0).get_bigdecimal()
+    }
+}
+
+impl CassStatement {
+    #[cfg(feature = "num-bigint")]
+    pub fn bind_varint(&mut self, index: u64, value: &BigInt) -> Result<&mut Self, CassError> {
+        self.bind_bytes(index, &varint_from_bigint(value))
+    }
+
+    #[cfg(feature = "num-bigint")]
+    pub fn bind_varint_by_name(&mut self,
+                               name: &str,
+                               value: &BigInt)
+                               -> Result<&mut Self, CassError> {
+        self.bind_bytes_by_name(name, &varint_from_bigint(value))
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    pub fn bind_bigdecimal(&mut self,
+                           index: u64,
+                           value: &BigDecimal)
+                           -> Result<&mut Self, CassError> {
+        let (varint, scale) = try!(decimal_from_bigdecimal(value));
+        self.bind_decimal(index, &varint, scale)
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    pub fn bind_bigdecimal_by_name(&mut self,
+                                   name: &str,
+                                   value: &BigDecimal)
+                                   -> Result<&mut Self, CassError> {
+        let (varint, scale) = try!(decimal_from_bigdecimal(value));
+        self.bind_decimal_by_name(name, &varint, scale)
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl ToCql for BigInt {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        varint_from_bigint(self)[..].append_to(collection)
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+impl ToCql for BigDecimal {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        let (varint, scale) = try!(decimal_from_bigdecimal(self));
+        unsafe {
+            CassError::build(cass_collection_append_decimal(collection.inner(),
+                                                            varint.as_ptr(),
+                                                            varint.len() as u64,
+                                                            scale))
+                .wrap(())
+        }
+    }
+}
+
+impl CassTuple {
+    #[cfg(feature = "num-bigint")]
+    pub fn set_varint(&mut self, index: u64, value: &BigInt) -> Result<(), CassError> {
+        self.set_bytes(index, &varint_from_bigint(value))
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    pub fn set_bigdecimal(&mut self, index: u64, value: &BigDecimal) -> Result<(), CassError> {
+        let (varint, scale) = try!(decimal_from_bigdecimal(value));
+        self.set_decimal(index, &varint, scale)
+    }
+}
+
+impl CassUserType {
+    #[cfg(feature = "num-bigint")]
+    pub fn set_varint(&mut self, index: u64, value: &BigInt) -> Result<(), CassError> {
+        self.set_bytes(index, &varint_from_bigint(value))
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    pub fn set_bigdecimal(&mut self, index: u64, value: &BigDecimal) -> Result<(), CassError> {
+        let (varint, scale) = try!(decimal_from_bigdecimal(value));
+        self.set_decimal(index, &varint, scale)
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    pub fn set_bigdecimal_by_name<S>(&mut self, name: S, value: &BigDecimal) -> Result<(), CassError>
+        where S: Into<String>
+    {
+        let (varint, scale) = try!(decimal_from_bigdecimal(value));
+        self.set_decimal_by_name(name, &varint, scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn varints_round_trip() {
+        use num_bigint::BigInt;
+        use super::{bigint_from_varint, varint_from_bigint};
+
+        for value in &["0", "1", "-1", "127", "128", "-128", "-129", "255", "-256",
+                       "123456789012345678901234567890", "-123456789012345678901234567890"] {
+            let value: BigInt = value.parse().unwrap();
+            assert_eq!(bigint_from_varint(&varint_from_bigint(&value)), value);
+        }
+        assert_eq!(varint_from_bigint(&BigInt::from(128)), vec![0x00, 0x80]);
+        assert_eq!(varint_from_bigint(&BigInt::from(-128)), vec![0x80]);
+        assert_eq!(bigint_from_varint(&[]), BigInt::from(0));
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn decimals_round_trip() {
+        use std::str::FromStr;
+        use bigdecimal::BigDecimal;
+        use super::{bigdecimal_from_decimal, decimal_from_bigdecimal};
+
+        for value in &["0", "0.00", "1", "-1", "12.345", "-12.345", "-0.001", "100",
+                       "1e3", "-1.5e10", "123456789012345678901234567890.0123456789",
+                       "-98765432109876543210e-40", "1e-300", "-7e300"] {
+            let value = BigDecimal::from_str(value).unwrap();
+            let (varint, scale) = decimal_from_bigdecimal(&value).unwrap();
+            let read = bigdecimal_from_decimal(&varint, scale).unwrap();
+            assert_eq!(read, value);
+            assert_eq!(read.as_bigint_and_exponent(), value.as_bigint_and_exponent());
+        }
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn decimals_keep_their_scale() {
+        use bigdecimal::BigDecimal;
+        use super::bigdecimal_from_decimal;
+
+        // -12345 with scale 2 is -123.45
+        let read = bigdecimal_from_decimal(&[0xcf, 0xc7], 2).unwrap();
+        assert_eq!(read.to_string(), "-123.45");
+        assert_eq!(read.as_bigint_and_exponent().1, 2);
+        // 5 with scale -3 is 5000
+        let read = bigdecimal_from_decimal(&[0x05], -3).unwrap();
+        assert_eq!(read.as_bigint_and_exponent().1, -3);
+        assert_eq!(read, BigDecimal::from(5000));
+        // trailing zeros are kept
+        assert_eq!(bigdecimal_from_decimal(&[0x64], 2).unwrap().as_bigint_and_exponent().1, 2);
+        assert_eq!(bigdecimal_from_decimal(&[], 0).unwrap(), BigDecimal::from(0));
+        assert_eq!(bigdecimal_from_decimal(&[0x7f], i32::MAX).unwrap().as_bigint_and_exponent().1,
+                   i32::MAX as i64);
+    }
+}
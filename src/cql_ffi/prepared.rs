@@ -1,11 +1,21 @@
+use std::ffi::CString;
+use std::mem;
+
+use cql_ffi::error::CassError;
+use cql_ffi::error::CassErrorTypes;
+use cql_ffi::helpers::str_from_raw;
+use cassandra_schema::TableMeta;
 use cql_ffi::statement::CassStatement;
+use cql_ffi::udt::CassConstDataType;
+use cql_ffi::udt::CassDataType;
 
 use cql_bindgen::CassPrepared as _CassPrepared;
+use cql_bindgen::CassDataType as _CassDataType;
 use cql_bindgen::cass_prepared_free;
 use cql_bindgen::cass_prepared_bind;
-//use cql_bindgen::cass_prepared_parameter_name;
-//use cql_bindgen::cass_prepared_parameter_data_type;
-//use cql_bindgen::cass_prepared_parameter_data_type_by_name;
+use cql_bindgen::cass_prepared_parameter_name;
+use cql_bindgen::cass_prepared_parameter_data_type;
+use cql_bindgen::cass_prepared_parameter_data_type_by_name;
 //use cql_bindgen::cass_prepared_parameter_data_type_by_name_n;
 
 pub struct CassPrepared(pub *const _CassPrepared);
@@ -21,10 +31,96 @@ impl Drop for CassPrepared {
     }
 }
 
+// The driver only describes a prepared statement's parameters; the columns it
+// returns are described by each CassResult.
 impl CassPrepared {
     pub fn bind(&self) -> CassStatement {
         unsafe {
-            CassStatement(cass_prepared_bind(self.0))
+            CassStatement::build(cass_prepared_bind(self.0))
+        }
+    }
+
+    // The column a bind marker is for, or the name of a :name marker.
+    pub fn parameter_name(&self, index: u64) -> Result<String, CassError> {
+        unsafe {
+            let mut name = mem::zeroed();
+            let mut name_length = mem::zeroed();
+            try!(CassError::build(cass_prepared_parameter_name(self.0,
+                                                               index,
+                                                               &mut name,
+                                                               &mut name_length))
+                     .wrap(()));
+            str_from_raw(name, name_length).map(str::to_owned)
+        }
+    }
+
+    pub fn parameter_data_type(&self, index: u64) -> Result<CassDataType, CassError> {
+        unsafe {
+            owned_data_type(cass_prepared_parameter_data_type(self.0, index),
+                            CassErrorTypes::LIB_INDEX_OUT_OF_BOUNDS)
+        }
+    }
+
+    pub fn parameter_data_type_by_name(&self, name: &str) -> Result<CassDataType, CassError> {
+        unsafe {
+            let name = try!(CString::new(name));
+            owned_data_type(cass_prepared_parameter_data_type_by_name(self.0, name.as_ptr()),
+                            CassErrorTypes::LIB_NAME_DOES_NOT_EXIST)
+        }
+    }
+
+    // The number of bind markers. The driver has no call for it, but has no
+    // type for an index past the last one.
+    pub fn parameter_count(&self) -> u64 {
+        let mut count = 0;
+        unsafe {
+            while !cass_prepared_parameter_data_type(self.0, count).is_null() {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    pub fn parameters(&self) -> Result<Vec<(String, CassDataType)>, CassError> {
+        (0..self.parameter_count())
+            .map(|index| Ok((try!(self.parameter_name(index)), try!(self.parameter_data_type(index)))))
+            .collect()
+    }
+
+    // The parameter for each column of the table's partition key, in key
+    // order, or None if the statement doesn't bind the whole key. Parameters
+    // are matched to columns by name, as the driver doesn't say which table a
+    // statement is for.
+    pub fn partition_key_indexes(&self, table: &TableMeta) -> Result<Option<Vec<u64>>, CassError> {
+        let mut names = Vec::new();
+        for index in 0..self.parameter_count() {
+            names.push(try!(self.parameter_name(index)));
+        }
+        Ok(table.partition_key()
+                .iter()
+                .map(|column| names.iter().position(|name| *name == column.name).map(|index| index as u64))
+                .collect())
+    }
+
+    // A statement with its key indexes added, so its routing key and token can
+    // be read once the values are bound, by index or by name.
+    pub fn bind_routed(&self, table: &TableMeta) -> Result<CassStatement, CassError> {
+        let mut statement = self.bind();
+        if let Some(indexes) = try!(self.partition_key_indexes(table)) {
+            for (index, column) in indexes.into_iter().zip(table.partition_key()) {
+                try!(statement.add_named_key_index(index, &column.name));
+            }
         }
+        Ok(statement)
+    }
+}
+
+unsafe fn owned_data_type(data_type: *const _CassDataType,
+                          missing: CassErrorTypes)
+                          -> Result<CassDataType, CassError> {
+    if data_type.is_null() {
+        Err(CassError::build(missing as u32))
+    } else {
+        Ok(CassDataType::new_from_const(&CassConstDataType(data_type)))
     }
 }
@@ -2,16 +2,22 @@
 #![allow(dead_code)]
 #![allow(missing_copy_implementations)]
 
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt;
 use std::mem;
-use std::slice;
 use std::str;
 
-use cql_ffi::value::CassValueType;
+use cql_ffi::error::CassError;
+use cql_ffi::helpers::str_from_raw;
+use cassandra_schema::CassValueType;
 use cql_ffi::row::CassRow;
+use cql_ffi::udt::CassConstDataType;
+use cql_ffi::udt::CassDataType;
+use cql_ffi::error::CassErrorTypes;
 
 use cql_bindgen::CassResult as _CassResult;
 use cql_bindgen::CassIterator as _CassIterator;
@@ -26,14 +32,25 @@ use cql_bindgen::cass_result_column_type;
 use cql_bindgen::cass_result_first_row;
 use cql_bindgen::cass_result_has_more_pages;
 use cql_bindgen::cass_iterator_from_result;
-//use cql_bindgen::cass_result_column_data_type;
-
-pub struct CassResult(pub *const _CassResult);
+use cql_bindgen::cass_result_column_data_type;
+
+// The second field caches column indexes by name, read the first time one is
+// looked up.
+pub struct CassResult(pub *const _CassResult, RefCell<Option<HashMap<String, u64>>>);
+
+// A column of a result. The driver doesn't pass on which keyspace and table a
+// column came from, only its name and type.
+#[derive(Debug,Clone,PartialEq)]
+pub struct ColumnSpec {
+    pub name: String,
+    pub data_type: CassDataType,
+}
 
 impl Debug for CassResult {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         try!(write!(f, "Result row count: {:?}\n", self.row_count()));
-        for row in self.iter() {
+        let mut rows = self.iter();
+        while let Some(row) = rows.next() {
             try!(write!(f, "{:?}\n",row));
         }
         Ok(())
@@ -43,7 +60,8 @@ impl Debug for CassResult {
 impl Display for CassResult {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         try!(write!(f, "Result row count: {:?}\n", self.row_count()));
-        for row in self.iter() {
+        let mut rows = self.iter();
+        while let Some(row) = rows.next() {
             try!(write!(f, "{}\n",row));
         }
         Ok(())
@@ -59,6 +77,10 @@ impl Drop for CassResult {
 }
 
 impl CassResult {
+    pub fn build(result: *const _CassResult) -> CassResult {
+        CassResult(result, RefCell::new(None))
+    }
+
     unsafe fn free(&mut self) {
         cass_result_free(self.0)
     }
@@ -75,32 +97,72 @@ impl CassResult {
         }
     }
 
-                                    //~ result: *const CassResult, index: size_t,
-                                   //~ name: *mut *const ::libc::c_char,
-                                   //~ name_length: *mut size_t
-
-
-    pub fn column_name(&self, index: u64) -> String {
+    pub fn column_name(&self, index: u64) -> Result<String, CassError> {
         unsafe {
-            let name = mem::zeroed();
-            let name_length = mem::zeroed();
-            cass_result_column_name(self.0, index, name, name_length);
-            let slice = slice::from_raw_parts(name as *const u8, name_length as usize);
-            str::from_utf8(slice).unwrap().to_owned()
+            let mut name = mem::zeroed();
+            let mut name_length = mem::zeroed();
+            try!(CassError::build(cass_result_column_name(self.0, index, &mut name, &mut name_length))
+                     .wrap(()));
+            str_from_raw(name, name_length).map(str::to_owned)
         }
     }
 
+    pub fn column_names(&self) -> Result<Vec<String>, CassError> {
+        (0..self.column_count()).map(|index| self.column_name(index)).collect()
+    }
+
     pub fn column_type(&self, index: u64) -> CassValueType {
         unsafe {
             CassValueType::build(cass_result_column_type(self.0, index))
         }
     }
 
-    pub fn first_row(&self) -> Option<CassRow> {
+    // The full type of a column, where column_type only gives the outermost
+    // one.
+    pub fn column_data_type(&self, index: u64) -> Result<CassDataType, CassError> {
+        unsafe {
+            let data_type = cass_result_column_data_type(self.0, index);
+            if data_type.is_null() {
+                Err(CassError::build(CassErrorTypes::LIB_INDEX_OUT_OF_BOUNDS as u32))
+            } else {
+                Ok(CassDataType::new_from_const(&CassConstDataType(data_type)))
+            }
+        }
+    }
+
+    pub fn columns(&self) -> Result<Vec<ColumnSpec>, CassError> {
+        (0..self.column_count())
+            .map(|index| {
+                Ok(ColumnSpec {
+                    name: try!(self.column_name(index)),
+                    data_type: try!(self.column_data_type(index)),
+                })
+            })
+            .collect()
+    }
+
+    // Names are matched exactly, as the server reports them: unquoted names
+    // in lower case, quoted ones as written but without the quotes.
+    pub fn column_index(&self, name: &str) -> Result<u64, CassError> {
+        let mut indexes = self.1.borrow_mut();
+        if indexes.is_none() {
+            let mut by_name = HashMap::new();
+            for index in 0..self.column_count() {
+                by_name.insert(try!(self.column_name(index)), index);
+            }
+            *indexes = Some(by_name);
+        }
+        match indexes.as_ref().and_then(|indexes| indexes.get(name)) {
+            Some(&index) => Ok(index),
+            None => Err(CassError::build(CassErrorTypes::LIB_NAME_DOES_NOT_EXIST as u32)),
+        }
+    }
+
+    pub fn first_row(&self) -> Option<CassRow<'_>> {
         unsafe {
             match self.row_count() {
                 0 => None,
-                _ => Some(CassRow(cass_result_first_row(self.0))),
+                _ => Some(CassRow(cass_result_first_row(self.0), self)),
             }
         }
     }
@@ -113,15 +175,20 @@ impl CassResult {
 
     pub fn iter(&self) -> ResultIterator {
         unsafe {
-            ResultIterator(cass_iterator_from_result(self.0))
+            ResultIterator(cass_iterator_from_result(self.0), self)
         }
     }
 
 }
 
-pub struct ResultIterator(pub *mut _CassIterator);
+// The driver decodes each row into storage owned by the iterator, so a row is
+// only lent until the next call, and this can't be an Iterator:
+//
+//     let mut rows = result.iter();
+//     while let Some(row) = rows.next() { ... }
+pub struct ResultIterator<'a>(*mut _CassIterator, &'a CassResult);
 
-impl Drop for ResultIterator {
+impl<'a> Drop for ResultIterator<'a> {
     fn drop(&mut self) {
         unsafe {
             cass_iterator_free(self.0)
@@ -129,9 +196,8 @@ impl Drop for ResultIterator {
     }
 }
 
-impl Iterator for ResultIterator {
-    type Item = CassRow;
-    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+impl<'a> ResultIterator<'a> {
+    pub fn next(&mut self) -> Option<CassRow<'_>> {
         unsafe {
             match cass_iterator_next(self.0) {
                 0 => None,
@@ -139,32 +205,45 @@ impl Iterator for ResultIterator {
             }
         }
     }
-}
 
-impl ResultIterator {
-    pub fn get_row(&mut self) -> CassRow {
+    pub fn get_row(&mut self) -> CassRow<'_> {
         unsafe {
-            CassRow(cass_iterator_get_row(self.0))
+            CassRow(cass_iterator_get_row(self.0), self.1)
         }
     }
 
 }
 
-impl IntoIterator for CassResult {
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
 
-    type Item = CassRow;
-    type IntoIter = ResultIterator;
+    use cql_ffi::row::CassRow;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter()
+    // A result whose column indexes are already cached, so looking names up
+    // doesn't reach the driver.
+    fn result(names: &[&str]) -> CassResult {
+        let indexes = names.iter()
+                           .enumerate()
+                           .map(|(index, name)| (name.to_string(), index as u64));
+        CassResult(ptr::null(), RefCell::new(Some(indexes.collect())))
     }
-}
 
-//impl<'a> IntoIterator for &'a CassResult {
-//    type Item = CassRow;
-//    type IntoIter = ResultIterator;
-//
-//    fn into_iter(self) -> Self::IntoIter {unsafe{
-//        ResultIterator(cass_iterator_from_result(self.0))
-//    }}
-//}
+    #[test]
+    fn columns_are_found_by_their_exact_names() {
+        let result = result(&["id", "Name"]);
+        assert!(match result.column_index("id") { Ok(0) => true, _ => false });
+        assert!(match result.column_index("Name") { Ok(1) => true, _ => false });
+        for name in &["ID", "name", "\"Name\"", "missing"] {
+            let row = CassRow(ptr::null(), &result);
+            let missing = CassErrorTypes::LIB_NAME_DOES_NOT_EXIST as u32;
+            assert!(match row.get_by_name::<i32>(name) {
+                        Err(CassError::Code(code)) => code as u32 == missing,
+                        _ => false,
+                    },
+                    "{}",
+                    name);
+        }
+    }
+}
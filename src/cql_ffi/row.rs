@@ -15,14 +15,20 @@ use std::fmt;
 use std::ffi::CString;
 use std::iter::IntoIterator;
 use std::iter;
+use std::marker::PhantomData;
 
 use cql_ffi::value::CassValue;
 use cql_ffi::error::CassError;
+use cql_ffi::error::CassErrorTypes;
+use cql_ffi::value::FromCql;
 use cql_ffi::column::CassColumn;
+use cql_ffi::result::CassResult;
 
-pub struct CassRow(pub *const _CassRow);
+// A row keeps the result it came from, whose cached column indexes
+// get_by_name uses.
+pub struct CassRow<'a>(pub(crate) *const _CassRow, pub(crate) &'a CassResult);
 
-impl Debug for CassRow {
+impl<'a> Debug for CassRow<'a> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         for column in self {
             //println!("foo:{:?}",column);
@@ -32,7 +38,7 @@ impl Debug for CassRow {
     }
 }
 
-impl Display for CassRow {
+impl<'a> Display for CassRow<'a> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         for column in self {
             try!(write!(f, "{}\t", CassValue::new(column.0)));
@@ -41,34 +47,52 @@ impl Display for CassRow {
     }
 }
 
-impl CassRow {
-    pub fn get_column(&self, index: u64) -> Result<CassColumn, CassError> {
+impl<'a> CassRow<'a> {
+    pub fn get_column(&self, index: u64) -> Result<CassColumn<'a>, CassError> {
         unsafe {
             let col = cass_row_get_column(self.0, index);
             match col.is_null() {
                 true => Err(CassError::build(CASS_ERROR_LIB_INDEX_OUT_OF_BOUNDS)),
-                false => Ok(CassColumn(col)),
+                false => Ok(CassColumn(col, PhantomData)),
             }
         }
     }
 
-    pub fn get_column_by_name<S>(&self, name: S) -> CassColumn
+    pub fn get_column_by_name<S>(&self, name: S) -> Result<CassColumn<'a>, CassError>
         where S: Into<String>
     {
         unsafe {
-            let name = CString::new(name.into()).unwrap();
-            println!("name: {:?}", name);
-            println!("self: {:?}", self);
-        //unimplemented!();
-            CassColumn(cass_row_get_column_by_name(self.0, name.as_ptr()))
+            let name = try!(CString::new(name.into()));
+            let col = cass_row_get_column_by_name(self.0, name.as_ptr());
+            match col.is_null() {
+                true => Err(CassError::build(CassErrorTypes::LIB_NAME_DOES_NOT_EXIST as u32)),
+                false => Ok(CassColumn(col, PhantomData)),
+            }
         }
     }
+
+    pub fn get<T>(&self, index: u64) -> Result<T, CassError>
+        where T: FromCql<'a>
+    {
+        try!(self.get_column(index)).get()
+    }
+
+    // Unlike get_column_by_name, which leaves the name to the driver, this
+    // matches it exactly against the result's column names, see
+    // CassResult::column_index.
+    pub fn get_by_name<T>(&self, name: &str) -> Result<T, CassError>
+        where T: FromCql<'a>
+    {
+        self.get(try!(self.1.column_index(name)))
+    }
 }
 
-pub struct RowIterator(pub *mut _CassIterator);
+// The columns belong to the row rather than the iterator, so unlike the other
+// iterators this one can hand them out for as long as the row lives.
+pub struct RowIterator<'a>(*mut _CassIterator, PhantomData<&'a CassResult>);
 
 
-impl Drop for RowIterator {
+impl<'a> Drop for RowIterator<'a> {
     fn drop(&mut self) {
         unsafe {
             cass_iterator_free(self.0)
@@ -76,35 +100,35 @@ impl Drop for RowIterator {
     }
 }
 
-impl iter::Iterator for RowIterator {
+impl<'a> iter::Iterator for RowIterator<'a> {
 
-    type Item = CassColumn;
+    type Item = CassColumn<'a>;
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         unsafe {
             match cass_iterator_next(self.0) {
                 0 => None,
-                _ => Some(CassColumn(cass_iterator_get_column(self.0))),
+                _ => Some(CassColumn(cass_iterator_get_column(self.0), PhantomData)),
             }
         }
     }
 }
 
-impl<'a> Iterator for &'a RowIterator {
+impl<'a, 'b> Iterator for &'b RowIterator<'a> {
 
-    type Item = CassColumn;
+    type Item = CassColumn<'a>;
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         unsafe {
             match cass_iterator_next(self.0) {
                 0 => None,
-                _ => Some(CassColumn(cass_iterator_get_column(self.0))),
+                _ => Some(CassColumn(cass_iterator_get_column(self.0), PhantomData)),
             }
         }
     }
 }
 
-impl Display for RowIterator {
+impl<'a> Display for RowIterator<'a> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         for item in self {
             try!(write!(f, "{}\t", CassValue::new(item.0)));
@@ -113,24 +137,24 @@ impl Display for RowIterator {
     }
 }
 
-impl IntoIterator for CassRow {
+impl<'a> IntoIterator for CassRow<'a> {
 
-    type Item = CassColumn;
-    type IntoIter = RowIterator;
+    type Item = CassColumn<'a>;
+    type IntoIter = RowIterator<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
         unsafe {
-            RowIterator(cass_iterator_from_row(self.0))
+            RowIterator(cass_iterator_from_row(self.0), PhantomData)
         }
     }
 }
 
-impl<'a> IntoIterator for &'a CassRow {
-    type Item = CassColumn;
-    type IntoIter = RowIterator;
+impl<'a, 'b> IntoIterator for &'b CassRow<'a> {
+    type Item = CassColumn<'a>;
+    type IntoIter = RowIterator<'a>;
     fn into_iter(self) -> Self::IntoIter {
         unsafe {
-            RowIterator(cass_iterator_from_row(self.0))
+            RowIterator(cass_iterator_from_row(self.0), PhantomData)
         }
     }
 }
@@ -4,11 +4,12 @@
 
 use std::ffi::CString;
 use std::mem;
-use std::slice;
 use std::str;
+use std::marker::PhantomData;
 
 use cql_ffi::value::CassValue;
 use cql_ffi::error::CassError;
+use cql_ffi::helpers::str_from_raw;
 use cql_ffi::collection::map::MapIterator;
 use cql_ffi::collection::set::SetIterator;
 
@@ -28,6 +29,11 @@ use cql_bindgen::cass_iterator_from_schema;
 use cql_bindgen::cass_iterator_from_schema_meta;
 use cql_bindgen::cass_iterator_fields_from_schema_meta;
 use cql_bindgen::cass_schema_get_udt;
+use cql_bindgen::CassIterator as _CassIterator;
+use cql_bindgen::cass_iterator_free;
+use cql_bindgen::cass_iterator_next;
+use cql_bindgen::cass_iterator_get_schema_meta;
+use cql_bindgen::cass_iterator_get_schema_meta_field;
 //use cql_bindgen::cass_schema_get_udt_n;
 
 
@@ -36,7 +42,7 @@ pub struct CassSchema(pub *const _CassSchema);
 pub struct CassSchemaMeta(pub *const _CassSchemaMeta);
 pub struct CassSchemaMetaField(pub *const _CassSchemaMetaField);
 //pub struct CassSchemaMetaType(pub _CassSchemaMetaType);
-pub struct CassSchemaMetaFieldIterator(pub *mut SetIterator);
+pub struct CassSchemaMetaFieldIterator<'a>(pub *mut SetIterator<'a>);
 
 //~ #[repr(C)]
 #[derive(Debug,Copy,Clone)]
@@ -68,27 +74,33 @@ impl CassSchemaMetaType {
 }
 
 impl CassSchema {
-    pub fn get_keyspace(&self, keyspace_name: &str) -> CassSchemaMeta {
+    pub fn get_keyspace(&self, keyspace_name: &str) -> Result<CassSchemaMeta, CassError> {
         unsafe {
-            let keyspace_name = CString::new(keyspace_name).unwrap();
-            CassSchemaMeta(cass_schema_get_keyspace(self.0, keyspace_name.as_ptr()))
+            let keyspace_name = try!(CString::new(keyspace_name));
+            Ok(CassSchemaMeta(cass_schema_get_keyspace(self.0, keyspace_name.as_ptr())))
         }
     }
 
-    pub fn get_udt<S>(&self, keyspace: S, type_name: S) -> CassConstDataType
+    pub fn get_udt<S>(&self, keyspace: S, type_name: S) -> Result<CassConstDataType, CassError>
         where S: Into<String>
     {
         unsafe {
-            let keyspace = CString::new(keyspace.into()).unwrap();
-            let type_name = CString::new(type_name.into()).unwrap();
-            CassConstDataType(cass_schema_get_udt(self.0, keyspace.as_ptr(), type_name.as_ptr()))
+            let keyspace = try!(CString::new(keyspace.into()));
+            let type_name = try!(CString::new(type_name.into()));
+            Ok(CassConstDataType(cass_schema_get_udt(self.0, keyspace.as_ptr(), type_name.as_ptr())))
         }
     }
 
 
 
     pub unsafe fn iterator(&self) -> SetIterator {
-        SetIterator(cass_iterator_from_schema(self.0))
+        SetIterator(cass_iterator_from_schema(self.0), PhantomData)
+    }
+
+    pub fn keyspaces(&self) -> SchemaMetaIterator {
+        unsafe {
+            SchemaMetaIterator(cass_iterator_from_schema(self.0), PhantomData)
+        }
     }
 }
 
@@ -108,23 +120,23 @@ impl CassSchemaMeta {
         }
     }
 
-    pub fn get_entry(&self, name: &str) -> CassSchemaMeta {
+    pub fn get_entry(&self, name: &str) -> Result<CassSchemaMeta, CassError> {
         unsafe {
-            let name = CString::new(name).unwrap();
-            CassSchemaMeta(cass_schema_meta_get_entry(self.0, name.as_ptr()))
+            let name = try!(CString::new(name));
+            Ok(CassSchemaMeta(cass_schema_meta_get_entry(self.0, name.as_ptr())))
         }
     }
 
-    pub fn get_field(&self, name: &str) -> CassSchemaMetaField {
+    pub fn get_field(&self, name: &str) -> Result<CassSchemaMetaField, CassError> {
         unsafe {
-            let name = CString::new(name).unwrap();
-            CassSchemaMetaField(cass_schema_meta_get_field(self.0, name.as_ptr()))
+            let name = try!(CString::new(name));
+            Ok(CassSchemaMetaField(cass_schema_meta_get_field(self.0, name.as_ptr())))
         }
     }
 
     pub fn fields_from_schema_meta(&self) -> SetIterator {
         unsafe {
-            SetIterator(cass_iterator_fields_from_schema_meta(self.0))
+            SetIterator(cass_iterator_fields_from_schema_meta(self.0), PhantomData)
         }
     }
 
@@ -134,27 +146,37 @@ impl CassSchemaMeta {
 
     pub fn iterator(&self) -> SetIterator {
         unsafe {
-            SetIterator(cass_iterator_from_schema_meta(self.0))
+            SetIterator(cass_iterator_from_schema_meta(self.0), PhantomData)
         }
     }
 
     pub fn fields_iterator(&self) -> MapIterator {
         unsafe {
-            MapIterator(cass_iterator_fields_from_schema_meta(self.0))
+            MapIterator(cass_iterator_fields_from_schema_meta(self.0), PhantomData)
         }
     }
 
+    // The tables of a keyspace, or the columns of a table.
+    pub fn entries(&self) -> SchemaMetaIterator {
+        unsafe {
+            SchemaMetaIterator(cass_iterator_from_schema_meta(self.0), PhantomData)
+        }
+    }
+
+    pub fn fields(&self) -> SchemaMetaFieldIterator {
+        unsafe {
+            SchemaMetaFieldIterator(cass_iterator_fields_from_schema_meta(self.0), PhantomData)
+        }
+    }
 }
 
 impl CassSchemaMetaField {
-    pub fn get_name(&self) -> String {
+    pub fn get_name(&self) -> Result<String, CassError> {
         unsafe {
             let mut name = mem::zeroed();
             let mut name_length = mem::zeroed();
             cass_schema_meta_field_name(self.0, &mut name, &mut name_length);
-
-            let slice = slice::from_raw_parts(name as *const u8, name_length as usize);
-            str::from_utf8(slice).unwrap().to_owned()
+            str_from_raw(name, name_length).map(str::to_owned)
         }
     }
 
@@ -164,3 +186,47 @@ impl CassSchemaMetaField {
         }
     }
 }
+
+pub struct SchemaMetaIterator<'a>(pub *mut _CassIterator, pub PhantomData<&'a ()>);
+
+impl<'a> Drop for SchemaMetaIterator<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            cass_iterator_free(self.0)
+        }
+    }
+}
+
+impl<'a> Iterator for SchemaMetaIterator<'a> {
+    type Item = CassSchemaMeta;
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            match cass_iterator_next(self.0) {
+                0 => None,
+                _ => Some(CassSchemaMeta(cass_iterator_get_schema_meta(self.0))),
+            }
+        }
+    }
+}
+
+pub struct SchemaMetaFieldIterator<'a>(pub *mut _CassIterator, pub PhantomData<&'a ()>);
+
+impl<'a> Drop for SchemaMetaFieldIterator<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            cass_iterator_free(self.0)
+        }
+    }
+}
+
+impl<'a> Iterator for SchemaMetaFieldIterator<'a> {
+    type Item = CassSchemaMetaField;
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            match cass_iterator_next(self.0) {
+                0 => None,
+                _ => Some(CassSchemaMetaField(cass_iterator_get_schema_meta_field(self.0))),
+            }
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
+use std::sync::mpsc::channel;
+use std::thread;
+use std::time::Duration;
+
+use cql_ffi::error::CassError;
+use cassandra_schema::AggregateMeta;
+use cassandra_schema::FunctionMeta;
+use cassandra_schema::KeyspaceMeta;
+use cassandra_schema::SchemaSnapshot;
+use cql_ffi::metadata::LoadSchema;
+use cql_ffi::session::CassSession;
+use cassandra_schema::CassUuid;
+
+// The driver doesn't pass the server's SCHEMA_CHANGE events on, so they are
+// worked out here instead: the schema is read again whenever the schema version
+// in system.local moves, and compared with the copy read before. Changes come
+// out the way the server describes them, so views are reported as tables.
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum SchemaChangeKind {
+    Created,
+    Updated,
+    Dropped,
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum SchemaChangeTarget {
+    Keyspace(String),
+    Table {
+        keyspace: String,
+        name: String,
+    },
+    Type {
+        keyspace: String,
+        name: String,
+    },
+    // Functions and aggregates are named by their signature, as overloads
+    // share a name.
+    Function {
+        keyspace: String,
+        signature: String,
+    },
+    Aggregate {
+        keyspace: String,
+        signature: String,
+    },
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct SchemaChange {
+    pub kind: SchemaChangeKind,
+    pub target: SchemaChangeTarget,
+}
+
+pub struct SchemaWatcher<'a> {
+    session: &'a CassSession,
+    version: Option<CassUuid>,
+    snapshot: SchemaSnapshot,
+}
+
+impl CassSession {
+    pub fn watch_schema(&self) -> Result<SchemaWatcher, CassError> {
+        Ok(SchemaWatcher {
+            session: self,
+            version: try!(local_schema_version(self)),
+            snapshot: try!(SchemaSnapshot::load(self)),
+        })
+    }
+}
+
+impl<'a> SchemaWatcher<'a> {
+    // The changes since the last poll. Cheap when there are none: only the
+    // schema version is read.
+    pub fn poll(&mut self) -> Result<Vec<SchemaChange>, CassError> {
+        let version = try!(local_schema_version(self.session));
+        if version == self.version {
+            return Ok(Vec::new());
+        }
+        let snapshot = try!(SchemaSnapshot::load(self.session));
+        let changes = SchemaChange::between(&self.snapshot, &snapshot);
+        self.version = version;
+        self.snapshot = snapshot;
+        Ok(changes)
+    }
+
+    // The schema as of the last poll.
+    pub fn snapshot(&self) -> &SchemaSnapshot {
+        &self.snapshot
+    }
+}
+
+// The changes a polling thread finds. Dropping it stops the thread, at once
+// if it is waiting for the next poll or else when the poll under way ends.
+pub struct SchemaSubscription {
+    changes: Receiver<Result<SchemaChange, CassError>>,
+    _stop: Sender<()>,
+}
+
+impl SchemaSubscription {
+    pub fn changes(&self) -> &Receiver<Result<SchemaChange, CassError>> {
+        &self.changes
+    }
+}
+
+// Polls on a thread of its own every interval and sends each change. The
+// thread stops when the subscription is dropped, or after sending the error
+// that made a poll fail.
+pub fn subscribe_schema_changes(session: Arc<CassSession>,
+                                interval: Duration)
+                                -> SchemaSubscription {
+    let (sender, receiver) = channel();
+    let (stop, stopped) = channel::<()>();
+    thread::spawn(move || {
+        let mut watcher = match session.watch_schema() {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                let _ = sender.send(Err(err));
+                return;
+            }
+        };
+        // Nothing is ever sent on stop, so this waits out the interval unless
+        // the subscription goes away.
+        while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+            match watcher.poll() {
+                Ok(changes) => {
+                    for change in changes {
+                        if sender.send(Ok(change)).is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(err) => {
+                    let _ = sender.send(Err(err));
+                    return;
+                }
+            }
+        }
+    });
+    SchemaSubscription {
+        changes: receiver,
+        _stop: stop,
+    }
+}
+
+impl SchemaChange {
+    pub fn between(before: &SchemaSnapshot, after: &SchemaSnapshot) -> Vec<SchemaChange> {
+        let mut changes = Vec::new();
+        for (name, keyspace) in &after.keyspaces {
+            match before.keyspaces.get(name) {
+                Some(previous) => keyspace_changes(previous, keyspace, &mut changes),
+                None => {
+                    push(&mut changes,
+                         SchemaChangeKind::Created,
+                         SchemaChangeTarget::Keyspace(name.clone()))
+                }
+            }
+        }
+        for name in before.keyspaces.keys() {
+            if !after.keyspaces.contains_key(name) {
+                push(&mut changes,
+                     SchemaChangeKind::Dropped,
+                     SchemaChangeTarget::Keyspace(name.clone()));
+            }
+        }
+        changes
+    }
+}
+
+fn keyspace_changes(before: &KeyspaceMeta, after: &KeyspaceMeta, changes: &mut Vec<SchemaChange>) {
+    let keyspace = &after.name;
+    if before.replication != after.replication || before.durable_writes != after.durable_writes {
+        push(changes,
+             SchemaChangeKind::Updated,
+             SchemaChangeTarget::Keyspace(keyspace.clone()));
+    }
+    compare(&before.tables, &after.tables, changes, |name| {
+        SchemaChangeTarget::Table {
+            keyspace: keyspace.clone(),
+            name: name.to_owned(),
+        }
+    });
+    compare(&before.views, &after.views, changes, |name| {
+        SchemaChangeTarget::Table {
+            keyspace: keyspace.clone(),
+            name: name.to_owned(),
+        }
+    });
+    compare(&before.user_types, &after.user_types, changes, |name| {
+        SchemaChangeTarget::Type {
+            keyspace: keyspace.clone(),
+            name: name.to_owned(),
+        }
+    });
+    compare(&functions(before), &functions(after), changes, |signature| {
+        SchemaChangeTarget::Function {
+            keyspace: keyspace.clone(),
+            signature: signature.to_owned(),
+        }
+    });
+    compare(&aggregates(before), &aggregates(after), changes, |signature| {
+        SchemaChangeTarget::Aggregate {
+            keyspace: keyspace.clone(),
+            signature: signature.to_owned(),
+        }
+    });
+}
+
+fn functions(keyspace: &KeyspaceMeta) -> BTreeMap<String, &FunctionMeta> {
+    keyspace.functions.iter().map(|function| (function.signature(), function)).collect()
+}
+
+fn aggregates(keyspace: &KeyspaceMeta) -> BTreeMap<String, &AggregateMeta> {
+    keyspace.aggregates.iter().map(|aggregate| (aggregate.signature(), aggregate)).collect()
+}
+
+fn compare<T, F>(before: &BTreeMap<String, T>,
+                 after: &BTreeMap<String, T>,
+                 changes: &mut Vec<SchemaChange>,
+                 target: F)
+    where T: PartialEq,
+          F: Fn(&str) -> SchemaChangeTarget
+{
+    for (name, value) in after {
+        match before.get(name) {
+            Some(previous) if previous == value => {}
+            Some(_) => push(changes, SchemaChangeKind::Updated, target(name)),
+            None => push(changes, SchemaChangeKind::Created, target(name)),
+        }
+    }
+    for name in before.keys() {
+        if !after.contains_key(name) {
+            push(changes, SchemaChangeKind::Dropped, target(name));
+        }
+    }
+}
+
+fn push(changes: &mut Vec<SchemaChange>, kind: SchemaChangeKind, target: SchemaChangeTarget) {
+    changes.push(SchemaChange {
+        kind: kind,
+        target: target,
+    });
+}
+
+fn local_schema_version(session: &CassSession) -> Result<Option<CassUuid>, CassError> {
+    let result = try!(try!(session.execute("SELECT schema_version FROM system.local", 0)).wait());
+    match result.first_row() {
+        Some(row) => try!(row.get_column(0)).get::<Option<CassUuid>>(),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cassandra_schema::SchemaSnapshot;
+    use super::*;
+
+    const KEYSPACES: &'static str = "
+        CREATE KEYSPACE kept WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 1};
+        CREATE KEYSPACE dropped WITH replication = {'class': 'SimpleStrategy', \
+                                                    'replication_factor': 1};
+        USE kept;
+        CREATE TYPE address (street text);
+        CREATE TABLE users (id int PRIMARY KEY, name text);
+        CREATE TABLE old (id int PRIMARY KEY);
+        CREATE FUNCTION twice(a int) RETURNS NULL ON NULL INPUT RETURNS int \
+            LANGUAGE java AS 'return a * 2;';
+    ";
+
+    fn change(kind: SchemaChangeKind, target: SchemaChangeTarget) -> SchemaChange {
+        SchemaChange {
+            kind: kind,
+            target: target,
+        }
+    }
+
+    fn table(name: &str) -> SchemaChangeTarget {
+        SchemaChangeTarget::Table {
+            keyspace: "kept".to_owned(),
+            name: name.to_owned(),
+        }
+    }
+
+    #[test]
+    fn nothing_changes_between_equal_schemas() {
+        let snapshot = SchemaSnapshot::from_cql(KEYSPACES).unwrap();
+        assert!(SchemaChange::between(&snapshot, &snapshot).is_empty());
+    }
+
+    #[test]
+    fn keyspaces_created_dropped_and_updated() {
+        let before = SchemaSnapshot::from_cql(KEYSPACES).unwrap();
+        let mut after = before.clone();
+        after.keyspaces.remove("dropped");
+        after.apply_cql("<test>",
+                        "CREATE KEYSPACE created WITH replication = {'class': 'SimpleStrategy', \
+                         'replication_factor': 1}")
+             .unwrap();
+        after.keyspaces.get_mut("kept").unwrap().durable_writes = false;
+        assert_eq!(SchemaChange::between(&before, &after),
+                   vec![change(SchemaChangeKind::Created,
+                               SchemaChangeTarget::Keyspace("created".to_owned())),
+                        change(SchemaChangeKind::Updated,
+                               SchemaChangeTarget::Keyspace("kept".to_owned())),
+                        change(SchemaChangeKind::Dropped,
+                               SchemaChangeTarget::Keyspace("dropped".to_owned()))]);
+    }
+
+    #[test]
+    fn objects_within_a_keyspace() {
+        let before = SchemaSnapshot::from_cql(KEYSPACES).unwrap();
+        let after = SchemaSnapshot::from_cql(&KEYSPACES.replace("CREATE TABLE old (id int PRIMARY KEY);",
+                                                                "CREATE TABLE new (id int PRIMARY KEY);")
+                                                       .replace("name text", "name text, age int")
+                                                       .replace("street text", "street text, zip int")
+                                                       .replace("twice(a int)", "twice(a bigint)")
+                                                       .replace("RETURNS int", "RETURNS bigint"))
+                        .unwrap();
+        assert_eq!(SchemaChange::between(&before, &after),
+                   vec![change(SchemaChangeKind::Created, table("new")),
+                        change(SchemaChangeKind::Updated, table("users")),
+                        change(SchemaChangeKind::Dropped, table("old")),
+                        change(SchemaChangeKind::Updated,
+                               SchemaChangeTarget::Type {
+                                   keyspace: "kept".to_owned(),
+                                   name: "address".to_owned(),
+                               }),
+                        change(SchemaChangeKind::Created,
+                               SchemaChangeTarget::Function {
+                                   keyspace: "kept".to_owned(),
+                                   signature: "kept.twice(bigint)".to_owned(),
+                               }),
+                        change(SchemaChangeKind::Dropped,
+                               SchemaChangeTarget::Function {
+                                   keyspace: "kept".to_owned(),
+                                   signature: "kept.twice(int)".to_owned(),
+                               })]);
+    }
+}
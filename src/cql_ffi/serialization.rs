@@ -0,0 +1,1022 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
+
+use serde::de::{self, DeserializeOwned, Deserializer, IntoDeserializer, MapAccess,
+                SeqAccess, Visitor};
+use serde::ser::{self, Impossible, Serialize, SerializeMap, SerializeSeq, SerializeStruct,
+                 SerializeTuple, SerializeTupleStruct, Serializer};
+
+use cql_ffi::collection::collection::CassCollection;
+use cql_ffi::collection::collection::ToCql;
+use cql_ffi::collection::list::CassList;
+use cql_ffi::collection::map::CassMap;
+use cql_ffi::collection::set::CassSet;
+use cassandra_schema::CqlValue;
+use cassandra_schema::date_to_string;
+use cql_ffi::error::CassError;
+use cql_ffi::prepared::CassPrepared;
+use cql_ffi::result::CassResult;
+use cql_ffi::row::CassRow;
+use cql_ffi::statement::CassStatement;
+use cql_ffi::tuple::CassTuple;
+use cql_ffi::udt::CassConstDataType;
+use cql_ffi::udt::CassDataType;
+use cql_ffi::udt::CassUserType;
+use cql_ffi::value::CassValue;
+use cassandra_schema::CassValueType;
+
+#[derive(Debug)]
+pub enum SerdeError {
+    Cass(CassError),
+    Message(String),
+}
+
+impl Display for SerdeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            SerdeError::Cass(ref err) => write!(f, "{}", err),
+            SerdeError::Message(ref message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for SerdeError {
+    fn description(&self) -> &str {
+        match *self {
+            SerdeError::Cass(ref err) => err.description(),
+            SerdeError::Message(ref message) => message,
+        }
+    }
+}
+
+impl From<CassError> for SerdeError {
+    fn from(err: CassError) -> SerdeError {
+        SerdeError::Cass(err)
+    }
+}
+
+impl de::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> SerdeError {
+        SerdeError::Message(msg.to_string())
+    }
+}
+
+impl ser::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> SerdeError {
+        SerdeError::Message(msg.to_string())
+    }
+}
+
+fn unsupported(what: &str) -> SerdeError {
+    SerdeError::Message(format!("{} cannot be bound to a statement", what))
+}
+
+impl CassResult {
+    pub fn deserialize_rows<T>(&self) -> Result<Vec<T>, SerdeError>
+        where T: DeserializeOwned
+    {
+        let column_names = try!(self.column_names());
+        let mut rows = self.iter();
+        let mut deserialized = Vec::new();
+        while let Some(row) = rows.next() {
+            deserialized.push(try!(T::deserialize(RowDeserializer {
+                row: &row,
+                column_names: &column_names,
+            })));
+        }
+        Ok(deserialized)
+    }
+
+    // Decodes one of this result's rows. Structs and maps are filled in by
+    // column name, tuples and sequences by column position.
+    pub fn deserialize_row<T>(&self, row: &CassRow) -> Result<T, SerdeError>
+        where T: DeserializeOwned
+    {
+        let column_names = try!(self.column_names());
+        T::deserialize(RowDeserializer { row: row, column_names: &column_names })
+    }
+}
+
+impl<'a> CassValue<'a> {
+    pub fn deserialize<T>(self) -> Result<T, SerdeError>
+        where T: DeserializeOwned
+    {
+        T::deserialize(self)
+    }
+}
+
+struct RowDeserializer<'r, 'a: 'r> {
+    row: &'r CassRow<'a>,
+    column_names: &'r [String],
+}
+
+impl<'r, 'a, 'de> Deserializer<'de> for RowDeserializer<'r, 'a> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, SerdeError>
+        where V: Visitor<'de>
+    {
+        let columns = self.column_names
+                          .iter()
+                          .cloned()
+                          .zip(self.row.into_iter().map(|column| CassValue::new(column.0)));
+        visitor.visit_map(ValueMapAccess { entries: columns, value: None })
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, SerdeError>
+        where V: Visitor<'de>
+    {
+        let columns = self.row.into_iter().map(|column| CassValue::new(column.0));
+        visitor.visit_seq(ValueSeqAccess(columns))
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, SerdeError>
+        where V: Visitor<'de>
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(self,
+                                   _name: &'static str,
+                                   _len: usize,
+                                   visitor: V)
+                                   -> Result<V::Value, SerdeError>
+        where V: Visitor<'de>
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'a, 'de> Deserializer<'de> for CassValue<'a> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, SerdeError>
+        where V: Visitor<'de>
+    {
+        if self.is_null() {
+            return visitor.visit_unit();
+        }
+        match self.get_type() {
+            CassValueType::ASCII | CassValueType::TEXT | CassValueType::VARCHAR => {
+                visitor.visit_str(try!(self.get_str()))
+            }
+            CassValueType::BIGINT | CassValueType::COUNTER | CassValueType::TIMESTAMP => {
+                visitor.visit_i64(try!(self.get_int64()))
+            }
+            CassValueType::INT => visitor.visit_i32(try!(self.get_int32())),
+            CassValueType::BOOLEAN => visitor.visit_bool(try!(self.get_bool())),
+            CassValueType::DOUBLE => visitor.visit_f64(try!(self.get_double())),
+            CassValueType::FLOAT => visitor.visit_f32(try!(self.get_float())),
+            CassValueType::UUID | CassValueType::TIMEUUID => {
+                visitor.visit_string(try!(self.get_uuid()).to_string())
+            }
+            // Collection items are only lent while their iterator is on them,
+            // so collections, tuples and UDTs are read whole first.
+            _ => ReadValue(try!(self.to_cql_value())).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, SerdeError>
+        where V: Visitor<'de>
+    {
+        if self.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf
+        unit unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier
+        ignored_any
+    }
+}
+
+// Values that have already been read into a CqlValue, used for collection
+// items and for the types without a direct serde equivalent.
+struct ReadValue(CqlValue);
+
+impl<'de> Deserializer<'de> for ReadValue {
+    type Error = SerdeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, SerdeError>
+        where V: Visitor<'de>
+    {
+        match self.0 {
+            CqlValue::Ascii(s) | CqlValue::Text(s) | CqlValue::Varchar(s) => visitor.visit_string(s),
+            CqlValue::BigInt(v) | CqlValue::Counter(v) | CqlValue::Timestamp(v) => {
+                visitor.visit_i64(v)
+            }
+            CqlValue::Int(v) => visitor.visit_i32(v),
+            CqlValue::SmallInt(v) => visitor.visit_i16(v),
+            CqlValue::TinyInt(v) => visitor.visit_i8(v),
+            CqlValue::Date(v) => visitor.visit_string(date_to_string(v)),
+            CqlValue::Time(v) => visitor.visit_i64(v),
+            CqlValue::Duration(v) => visitor.visit_string(v.to_string()),
+            CqlValue::Boolean(v) => visitor.visit_bool(v),
+            CqlValue::Double(v) => visitor.visit_f64(v),
+            CqlValue::Float(v) => visitor.visit_f32(v),
+            CqlValue::Blob(bytes) | CqlValue::Custom(bytes) => visitor.visit_byte_buf(bytes),
+            CqlValue::Uuid(uuid) | CqlValue::TimeUuid(uuid) => visitor.visit_string(uuid.to_string()),
+            CqlValue::Inet(addr) => visitor.visit_string(addr.to_string()),
+            decimal @ CqlValue::Decimal { .. } => visitor.visit_string(decimal.to_string()),
+            varint @ CqlValue::Varint(_) => visitor.visit_string(varint.to_string()),
+            CqlValue::List(items) | CqlValue::Set(items) | CqlValue::Tuple(items) => {
+                visitor.visit_seq(CqlSeqAccess(items.into_iter()))
+            }
+            CqlValue::Map(entries) => {
+                let entries = entries.into_iter()
+                                     .map(|(key, value)| (ReadValue(key), ReadValue(value)));
+                visitor.visit_map(ValueMapAccess { entries: entries, value: None })
+            }
+            CqlValue::Udt(fields) => {
+                let fields = fields.into_iter().map(|(name, value)| (name, ReadValue(value)));
+                visitor.visit_map(ValueMapAccess { entries: fields, value: None })
+            }
+            CqlValue::Null => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, SerdeError>
+        where V: Visitor<'de>
+    {
+        match self.0 {
+            CqlValue::Null => visitor.visit_none(),
+            other => visitor.visit_some(ReadValue(other)),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf
+        unit unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier
+        ignored_any
+    }
+}
+
+impl<'a, 'de> IntoDeserializer<'de, SerdeError> for CassValue<'a> {
+    type Deserializer = CassValue<'a>;
+    fn into_deserializer(self) -> CassValue<'a> {
+        self
+    }
+}
+
+impl<'de> IntoDeserializer<'de, SerdeError> for ReadValue {
+    type Deserializer = ReadValue;
+    fn into_deserializer(self) -> ReadValue {
+        self
+    }
+}
+
+struct ValueSeqAccess<I>(I);
+
+impl<'a, 'de, I> SeqAccess<'de> for ValueSeqAccess<I>
+    where I: Iterator<Item = CassValue<'a>>
+{
+    type Error = SerdeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, SerdeError>
+        where T: de::DeserializeSeed<'de>
+    {
+        match self.0.next() {
+            Some(value) => seed.deserialize(value).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct CqlSeqAccess<I>(I);
+
+impl<'de, I> SeqAccess<'de> for CqlSeqAccess<I>
+    where I: Iterator<Item = CqlValue>
+{
+    type Error = SerdeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, SerdeError>
+        where T: de::DeserializeSeed<'de>
+    {
+        match self.0.next() {
+            Some(value) => seed.deserialize(ReadValue(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+// Serves map entries, row columns and UDT fields. Keys are either column and
+// field names or map key values.
+struct ValueMapAccess<I, V> {
+    entries: I,
+    value: Option<V>,
+}
+
+impl<'de, I, K, V> MapAccess<'de> for ValueMapAccess<I, V>
+    where I: Iterator<Item = (K, V)>,
+          K: IntoDeserializer<'de, SerdeError>,
+          V: IntoDeserializer<'de, SerdeError>
+{
+    type Error = SerdeError;
+
+    fn next_key_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, SerdeError>
+        where S: de::DeserializeSeed<'de>
+    {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<S>(&mut self, seed: S) -> Result<S::Value, SerdeError>
+        where S: de::DeserializeSeed<'de>
+    {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value.into_deserializer()),
+            None => Err(de::Error::custom("map value requested before its key")),
+        }
+    }
+}
+
+impl CassStatement {
+    // Binds every field of a struct (or entry of a string keyed map) to the
+    // parameter of the same name. Without the parameters' types only scalars
+    // and maps of scalars can be bound; see CassPrepared::bind_from.
+    pub fn bind_by_name_from<T>(&mut self, value: &T) -> Result<&mut Self, SerdeError>
+        where T: Serialize
+    {
+        try!(bind_fields(self, None, value));
+        Ok(self)
+    }
+}
+
+impl CassPrepared {
+    // A statement with every field of a struct (or entry of a string keyed
+    // map) bound to the parameter of the same name. Sequences, tuples and
+    // nested structs are built as the list, set, tuple or user type the
+    // parameter is.
+    pub fn bind_from<T>(&self, value: &T) -> Result<CassStatement, SerdeError>
+        where T: Serialize
+    {
+        let mut statement = self.bind();
+        try!(bind_fields(&mut statement, Some(self), value));
+        Ok(statement)
+    }
+}
+
+fn not_a_struct() -> SerdeError {
+    SerdeError::Message("only structs and maps can be bound by name".to_owned())
+}
+
+fn bind_fields<T: ?Sized + Serialize>(statement: &mut CassStatement,
+                                      prepared: Option<&CassPrepared>,
+                                      value: &T)
+                                      -> Result<(), SerdeError> {
+    let fields = match try!(value.serialize(ValueSerializer)) {
+        CqlValue::Udt(fields) => fields,
+        CqlValue::Map(entries) => {
+            let mut fields = Vec::with_capacity(entries.len());
+            for (key, value) in entries {
+                match key {
+                    CqlValue::Text(name) => fields.push((name, value)),
+                    _ => return Err(SerdeError::Message("parameter names must be strings".to_owned())),
+                }
+            }
+            fields
+        }
+        _ => return Err(not_a_struct()),
+    };
+    for (name, value) in fields {
+        let built = match prepared {
+            Some(prepared) => {
+                let data_type = try!(prepared.parameter_data_type_by_name(&name));
+                try!(build(value, &CassConstDataType(data_type.0)))
+            }
+            None => try!(build_untyped(value)),
+        };
+        try!(bind(statement, &name, built));
+    }
+    Ok(())
+}
+
+// A value built into whatever the parameter, field or item it is for takes.
+enum Built {
+    Scalar(CqlValue),
+    List(CassList),
+    Set(CassSet),
+    Map(CassMap),
+    Tuple(CassTuple),
+    UserType(CassUserType),
+}
+
+fn mismatch(value: &CqlValue, data_type: &CassConstDataType) -> SerdeError {
+    let what = match *value {
+        CqlValue::Map(_) => "a map",
+        CqlValue::Udt(_) => "a struct",
+        _ => "a sequence",
+    };
+    SerdeError::Message(format!("{} cannot be bound to a {}", what, data_type))
+}
+
+// Sequences are built as a list, set or tuple and structs as a user type,
+// according to the type the driver has for them.
+fn build(value: CqlValue, data_type: &CassConstDataType) -> Result<Built, SerdeError> {
+    match (data_type.get_type(), value) {
+        (CassValueType::LIST, CqlValue::List(items)) |
+        (CassValueType::LIST, CqlValue::Tuple(items)) => {
+            let mut list = try!(CassList::new_from_data_type(&CassDataType::new_from_const(data_type),
+                                                             items.len() as u64));
+            let item_type = data_type.sub_data_type(0);
+            for item in items {
+                try!(append_built(&mut list, try!(build(item, &item_type))));
+            }
+            Ok(Built::List(list))
+        }
+        (CassValueType::SET, CqlValue::List(items)) |
+        (CassValueType::SET, CqlValue::Tuple(items)) => {
+            let mut set = try!(CassSet::new_from_data_type(&CassDataType::new_from_const(data_type),
+                                                           items.len() as u64));
+            let item_type = data_type.sub_data_type(0);
+            for item in items {
+                try!(append_built(&mut set, try!(build(item, &item_type))));
+            }
+            Ok(Built::Set(set))
+        }
+        (CassValueType::MAP, CqlValue::Map(entries)) => {
+            let mut map = try!(CassMap::new_from_data_type(&CassDataType::new_from_const(data_type),
+                                                           entries.len() as u64));
+            let key_type = data_type.sub_data_type(0);
+            let value_type = data_type.sub_data_type(1);
+            for (key, value) in entries {
+                try!(append_built(&mut map, try!(build(key, &key_type))));
+                try!(append_built(&mut map, try!(build(value, &value_type))));
+            }
+            Ok(Built::Map(map))
+        }
+        (CassValueType::TUPLE, CqlValue::List(items)) |
+        (CassValueType::TUPLE, CqlValue::Tuple(items)) => {
+            if items.len() as u64 != data_type.sub_type_count() {
+                return Err(SerdeError::Message(format!("{} items cannot be bound to a {}",
+                                                       items.len(),
+                                                       data_type)));
+            }
+            let mut tuple = CassTuple::new_from_data_type(&CassDataType::new_from_const(data_type));
+            for (index, item) in items.into_iter().enumerate() {
+                let index = index as u64;
+                try!(set_built(&mut tuple, index, try!(build(item, &data_type.sub_data_type(index)))));
+            }
+            Ok(Built::Tuple(tuple))
+        }
+        (CassValueType::UDT, CqlValue::Udt(fields)) => {
+            let mut user_type = CassUserType::new(CassConstDataType(data_type.0));
+            for (name, value) in fields {
+                let index = match (0..data_type.sub_type_count())
+                                      .find(|&index| data_type.sub_type_name(index).ok().as_ref() == Some(&name)) {
+                    Some(index) => index,
+                    None => {
+                        return Err(SerdeError::Message(format!("{} has no field {}", data_type, name)))
+                    }
+                };
+                try!(set_built(&mut user_type, index, try!(build(value, &data_type.sub_data_type(index)))));
+            }
+            Ok(Built::UserType(user_type))
+        }
+        (_, value @ CqlValue::List(_)) |
+        (_, value @ CqlValue::Tuple(_)) |
+        (_, value @ CqlValue::Map(_)) |
+        (_, value @ CqlValue::Udt(_)) => Err(mismatch(&value, data_type)),
+        (_, value) => Ok(Built::Scalar(value)),
+    }
+}
+
+// Without a type a sequence could be a list, set or tuple, and a struct has
+// no user type to be built as, so only scalars and maps are taken.
+fn build_untyped(value: CqlValue) -> Result<Built, SerdeError> {
+    match value {
+        CqlValue::Map(entries) => {
+            let mut map = CassMap::new(entries.len() as u64);
+            for (key, value) in entries {
+                try!(append_built(&mut map, try!(build_untyped(key))));
+                try!(append_built(&mut map, try!(build_untyped(value))));
+            }
+            Ok(Built::Map(map))
+        }
+        CqlValue::List(_) | CqlValue::Tuple(_) | CqlValue::Udt(_) => {
+            Err(SerdeError::Message("sequences and structs need the parameter's type to be \
+                                     bound; bind them with CassPrepared::bind_from"
+                                        .to_owned()))
+        }
+        value => Ok(Built::Scalar(value)),
+    }
+}
+
+fn bind(statement: &mut CassStatement, name: &str, built: Built) -> Result<(), SerdeError> {
+    match built {
+        Built::Scalar(CqlValue::Null) => try!(statement.bind_null_by_name(name)),
+        Built::Scalar(CqlValue::Boolean(v)) => try!(statement.bind_bool_by_name(name, v)),
+        Built::Scalar(CqlValue::Int(v)) => try!(statement.bind_int32_by_name(name, v)),
+        Built::Scalar(CqlValue::SmallInt(v)) => try!(statement.bind_int16_by_name(name, v)),
+        Built::Scalar(CqlValue::TinyInt(v)) => try!(statement.bind_int8_by_name(name, v)),
+        Built::Scalar(CqlValue::BigInt(v)) => try!(statement.bind_int64_by_name(name, v)),
+        Built::Scalar(CqlValue::Float(v)) => try!(statement.bind_float_by_name(name, v)),
+        Built::Scalar(CqlValue::Double(v)) => try!(statement.bind_double_by_name(name, v)),
+        Built::Scalar(CqlValue::Text(v)) => try!(statement.bind_string_by_name(name, &v)),
+        Built::Scalar(CqlValue::Blob(v)) => try!(statement.bind_bytes_by_name(name, &v)),
+        Built::Scalar(_) => return Err(unsupported("this value")),
+        Built::List(list) => try!(statement.bind_list_by_name(name, list)),
+        Built::Set(set) => try!(statement.bind_set_by_name(name, set)),
+        Built::Map(map) => try!(statement.bind_map_by_name(name, map)),
+        Built::Tuple(tuple) => try!(statement.bind_tuple_by_name(name, tuple)),
+        Built::UserType(user_type) => try!(statement.bind_user_type_by_name(name, user_type)),
+    };
+    Ok(())
+}
+
+fn append_built<C: CassCollection>(collection: &mut C, built: Built) -> Result<(), SerdeError> {
+    match built {
+        Built::Scalar(CqlValue::Boolean(v)) => try!(v.append_to(collection)),
+        Built::Scalar(CqlValue::Int(v)) => try!(v.append_to(collection)),
+        Built::Scalar(CqlValue::SmallInt(v)) => try!(v.append_to(collection)),
+        Built::Scalar(CqlValue::TinyInt(v)) => try!(v.append_to(collection)),
+        Built::Scalar(CqlValue::BigInt(v)) => try!(v.append_to(collection)),
+        Built::Scalar(CqlValue::Float(v)) => try!(v.append_to(collection)),
+        Built::Scalar(CqlValue::Double(v)) => try!(v.append_to(collection)),
+        Built::Scalar(CqlValue::Text(v)) => try!(v.append_to(collection)),
+        Built::Scalar(CqlValue::Blob(v)) => try!(v[..].append_to(collection)),
+        Built::Scalar(_) => return Err(unsupported("a null collection item")),
+        Built::List(list) => try!(list.append_to(collection)),
+        Built::Set(set) => try!(set.append_to(collection)),
+        Built::Map(map) => try!(map.append_to(collection)),
+        Built::Tuple(tuple) => try!(tuple.append_to(collection)),
+        Built::UserType(user_type) => try!(user_type.append_to(collection)),
+    }
+    Ok(())
+}
+
+// Tuples and user types are both filled in by item index.
+trait SetByIndex {
+    fn set_null(&mut self, index: u64) -> Result<(), CassError>;
+    fn set_bool(&mut self, index: u64, value: bool) -> Result<(), CassError>;
+    fn set_int8(&mut self, index: u64, value: i8) -> Result<(), CassError>;
+    fn set_int16(&mut self, index: u64, value: i16) -> Result<(), CassError>;
+    fn set_int32(&mut self, index: u64, value: i32) -> Result<(), CassError>;
+    fn set_int64(&mut self, index: u64, value: i64) -> Result<(), CassError>;
+    fn set_float(&mut self, index: u64, value: f32) -> Result<(), CassError>;
+    fn set_double(&mut self, index: u64, value: f64) -> Result<(), CassError>;
+    fn set_string(&mut self, index: u64, value: String) -> Result<(), CassError>;
+    fn set_bytes(&mut self, index: u64, value: &[u8]) -> Result<(), CassError>;
+    fn set_collection<C: CassCollection>(&mut self, index: u64, value: C) -> Result<(), CassError>;
+    fn set_tuple(&mut self, index: u64, value: CassTuple) -> Result<(), CassError>;
+    fn set_user_type(&mut self, index: u64, value: CassUserType) -> Result<(), CassError>;
+}
+
+macro_rules! impl_set_by_index {
+    ($t:ty, $set_string:ident) => {
+        impl SetByIndex for $t {
+            fn set_null(&mut self, index: u64) -> Result<(), CassError> {
+                <$t>::set_null(self, index)
+            }
+            fn set_bool(&mut self, index: u64, value: bool) -> Result<(), CassError> {
+                <$t>::set_bool(self, index, value)
+            }
+            fn set_int8(&mut self, index: u64, value: i8) -> Result<(), CassError> {
+                <$t>::set_int8(self, index, value)
+            }
+            fn set_int16(&mut self, index: u64, value: i16) -> Result<(), CassError> {
+                <$t>::set_int16(self, index, value)
+            }
+            fn set_int32(&mut self, index: u64, value: i32) -> Result<(), CassError> {
+                <$t>::set_int32(self, index, value)
+            }
+            fn set_int64(&mut self, index: u64, value: i64) -> Result<(), CassError> {
+                <$t>::set_int64(self, index, value)
+            }
+            fn set_float(&mut self, index: u64, value: f32) -> Result<(), CassError> {
+                <$t>::set_float(self, index, value)
+            }
+            fn set_double(&mut self, index: u64, value: f64) -> Result<(), CassError> {
+                <$t>::set_double(self, index, value)
+            }
+            fn set_string(&mut self, index: u64, value: String) -> Result<(), CassError> {
+                <$t>::$set_string(self, index, value)
+            }
+            fn set_bytes(&mut self, index: u64, value: &[u8]) -> Result<(), CassError> {
+                <$t>::set_bytes(self, index, value)
+            }
+            fn set_collection<C: CassCollection>(&mut self,
+                                                 index: u64,
+                                                 value: C)
+                                                 -> Result<(), CassError> {
+                <$t>::set_collection(self, index, value)
+            }
+            fn set_tuple(&mut self, index: u64, value: CassTuple) -> Result<(), CassError> {
+                <$t>::set_tuple(self, index, value)
+            }
+            fn set_user_type(&mut self, index: u64, value: CassUserType) -> Result<(), CassError> {
+                <$t>::set_user_type(self, index, value)
+            }
+        }
+    }
+}
+
+impl_set_by_index!(CassTuple, set_string);
+impl_set_by_index!(CassUserType, set_stringl);
+
+fn set_built<T: SetByIndex>(target: &mut T, index: u64, built: Built) -> Result<(), SerdeError> {
+    match built {
+        Built::Scalar(CqlValue::Null) => try!(target.set_null(index)),
+        Built::Scalar(CqlValue::Boolean(v)) => try!(target.set_bool(index, v)),
+        Built::Scalar(CqlValue::Int(v)) => try!(target.set_int32(index, v)),
+        Built::Scalar(CqlValue::SmallInt(v)) => try!(target.set_int16(index, v)),
+        Built::Scalar(CqlValue::TinyInt(v)) => try!(target.set_int8(index, v)),
+        Built::Scalar(CqlValue::BigInt(v)) => try!(target.set_int64(index, v)),
+        Built::Scalar(CqlValue::Float(v)) => try!(target.set_float(index, v)),
+        Built::Scalar(CqlValue::Double(v)) => try!(target.set_double(index, v)),
+        Built::Scalar(CqlValue::Text(v)) => try!(target.set_string(index, v)),
+        Built::Scalar(CqlValue::Blob(v)) => try!(target.set_bytes(index, &v)),
+        Built::Scalar(_) => return Err(unsupported("this value")),
+        Built::List(list) => try!(target.set_collection(index, list)),
+        Built::Set(set) => try!(target.set_collection(index, set)),
+        Built::Map(map) => try!(target.set_collection(index, map)),
+        Built::Tuple(tuple) => try!(target.set_tuple(index, tuple)),
+        Built::UserType(user_type) => try!(target.set_user_type(index, user_type)),
+    }
+    Ok(())
+}
+
+// u32 and u64 have no CQL type that holds them without loss: u32 is the date
+// representation ToCql uses, and u64 doesn't fit in a bigint.
+fn lossy_unsigned(what: &str) -> SerdeError {
+    SerdeError::Message(format!("{} has no lossless CQL type; convert it to a signed integer",
+                                what))
+}
+
+// Turns a value into the CqlValue it is bound as: sequences become lists,
+// tuples tuples, maps maps and structs user types, for build to fit to the
+// parameter's type.
+struct ValueSerializer;
+
+impl Serializer for ValueSerializer {
+    type Ok = CqlValue;
+    type Error = SerdeError;
+    type SerializeSeq = Items;
+    type SerializeTuple = Items;
+    type SerializeTupleStruct = Items;
+    type SerializeTupleVariant = Impossible<CqlValue, SerdeError>;
+    type SerializeMap = Entries;
+    type SerializeStruct = Fields;
+    type SerializeStructVariant = Impossible<CqlValue, SerdeError>;
+
+    fn serialize_bool(self, v: bool) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Boolean(v))
+    }
+    fn serialize_i8(self, v: i8) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::TinyInt(v))
+    }
+    fn serialize_i16(self, v: i16) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::SmallInt(v))
+    }
+    fn serialize_i32(self, v: i32) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Int(v))
+    }
+    fn serialize_i64(self, v: i64) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::BigInt(v))
+    }
+    fn serialize_u8(self, v: u8) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Int(v as i32))
+    }
+    fn serialize_u16(self, v: u16) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Int(v as i32))
+    }
+    fn serialize_u32(self, _v: u32) -> Result<CqlValue, SerdeError> {
+        Err(lossy_unsigned("u32"))
+    }
+    fn serialize_u64(self, _v: u64) -> Result<CqlValue, SerdeError> {
+        Err(lossy_unsigned("u64"))
+    }
+    fn serialize_f32(self, v: f32) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Float(v))
+    }
+    fn serialize_f64(self, v: f64) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Double(v))
+    }
+    fn serialize_char(self, v: char) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Text(v.to_string()))
+    }
+    fn serialize_str(self, v: &str) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Text(v.to_owned()))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Blob(v.to_vec()))
+    }
+    fn serialize_none(self) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Null)
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<CqlValue, SerdeError> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Null)
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Null)
+    }
+    fn serialize_unit_variant(self,
+                              _name: &'static str,
+                              _index: u32,
+                              variant: &'static str)
+                              -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Text(variant.to_owned()))
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self,
+                                                       _name: &'static str,
+                                                       value: &T)
+                                                       -> Result<CqlValue, SerdeError> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(self,
+                                                        _name: &'static str,
+                                                        _index: u32,
+                                                        _variant: &'static str,
+                                                        _value: &T)
+                                                        -> Result<CqlValue, SerdeError> {
+        Err(unsupported("an enum variant with data"))
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<Items, SerdeError> {
+        Ok(Items(Vec::with_capacity(len.unwrap_or(0))))
+    }
+    fn serialize_tuple(self, len: usize) -> Result<Items, SerdeError> {
+        Ok(Items(Vec::with_capacity(len)))
+    }
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Items, SerdeError> {
+        Ok(Items(Vec::with_capacity(len)))
+    }
+    fn serialize_tuple_variant(self,
+                               _name: &'static str,
+                               _index: u32,
+                               _variant: &'static str,
+                               _len: usize)
+                               -> Result<Self::SerializeTupleVariant, SerdeError> {
+        Err(unsupported("an enum variant with data"))
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<Entries, SerdeError> {
+        Ok(Entries { entries: Vec::with_capacity(len.unwrap_or(0)), key: None })
+    }
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Fields, SerdeError> {
+        Ok(Fields(Vec::with_capacity(len)))
+    }
+    fn serialize_struct_variant(self,
+                                _name: &'static str,
+                                _index: u32,
+                                _variant: &'static str,
+                                _len: usize)
+                                -> Result<Self::SerializeStructVariant, SerdeError> {
+        Err(unsupported("an enum variant with data"))
+    }
+}
+
+struct Items(Vec<CqlValue>);
+
+impl SerializeSeq for Items {
+    type Ok = CqlValue;
+    type Error = SerdeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, item: &T) -> Result<(), SerdeError> {
+        self.0.push(try!(item.serialize(ValueSerializer)));
+        Ok(())
+    }
+
+    fn end(self) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::List(self.0))
+    }
+}
+
+impl SerializeTuple for Items {
+    type Ok = CqlValue;
+    type Error = SerdeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, item: &T) -> Result<(), SerdeError> {
+        self.0.push(try!(item.serialize(ValueSerializer)));
+        Ok(())
+    }
+
+    fn end(self) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Tuple(self.0))
+    }
+}
+
+impl SerializeTupleStruct for Items {
+    type Ok = CqlValue;
+    type Error = SerdeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, item: &T) -> Result<(), SerdeError> {
+        self.0.push(try!(item.serialize(ValueSerializer)));
+        Ok(())
+    }
+
+    fn end(self) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Tuple(self.0))
+    }
+}
+
+struct Entries {
+    entries: Vec<(CqlValue, CqlValue)>,
+    key: Option<CqlValue>,
+}
+
+impl SerializeMap for Entries {
+    type Ok = CqlValue;
+    type Error = SerdeError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), SerdeError> {
+        self.key = Some(try!(key.serialize(ValueSerializer)));
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerdeError> {
+        match self.key.take() {
+            Some(key) => {
+                self.entries.push((key, try!(value.serialize(ValueSerializer))));
+                Ok(())
+            }
+            None => Err(SerdeError::Message("map value serialized before its key".to_owned())),
+        }
+    }
+
+    fn end(self) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Map(self.entries))
+    }
+}
+
+struct Fields(Vec<(String, CqlValue)>);
+
+impl SerializeStruct for Fields {
+    type Ok = CqlValue;
+    type Error = SerdeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self,
+                                              key: &'static str,
+                                              value: &T)
+                                              -> Result<(), SerdeError> {
+        self.0.push((key.to_owned(), try!(value.serialize(ValueSerializer))));
+        Ok(())
+    }
+
+    fn end(self) -> Result<CqlValue, SerdeError> {
+        Ok(CqlValue::Udt(self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::collections::HashMap;
+
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+    use cql_ffi::cluster::CassCluster;
+    use cassandra_schema::CqlValue;
+    use cql_ffi::session::CassSession;
+    use super::{Built, ValueSerializer, build_untyped};
+
+    struct Address {
+        street: &'static str,
+        zip: i32,
+    }
+
+    impl Serialize for Address {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut fields = try!(serializer.serialize_struct("Address", 2));
+            try!(fields.serialize_field("street", self.street));
+            try!(fields.serialize_field("zip", &self.zip));
+            fields.end()
+        }
+    }
+
+    struct Person {
+        id: i32,
+        tags: Vec<&'static str>,
+        point: (i32, i32),
+        home: Address,
+    }
+
+    impl Serialize for Person {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut fields = try!(serializer.serialize_struct("Person", 4));
+            try!(fields.serialize_field("id", &self.id));
+            try!(fields.serialize_field("tags", &self.tags));
+            try!(fields.serialize_field("point", &self.point));
+            try!(fields.serialize_field("home", &self.home));
+            fields.end()
+        }
+    }
+
+    fn person() -> Person {
+        Person {
+            id: 1,
+            tags: vec!["a", "b"],
+            point: (3, 4),
+            home: Address { street: "Main St", zip: 12345 },
+        }
+    }
+
+    #[test]
+    fn values_keep_their_shape() {
+        assert_eq!(vec![1i32, 2].serialize(ValueSerializer).unwrap(),
+                   CqlValue::List(vec![CqlValue::Int(1), CqlValue::Int(2)]));
+        assert_eq!((1i64, "a").serialize(ValueSerializer).unwrap(),
+                   CqlValue::Tuple(vec![CqlValue::BigInt(1), CqlValue::Text("a".to_owned())]));
+        let mut map = BTreeMap::new();
+        map.insert("k", Some(1.5f64));
+        map.insert("n", None);
+        assert_eq!(map.serialize(ValueSerializer).unwrap(),
+                   CqlValue::Map(vec![(CqlValue::Text("k".to_owned()), CqlValue::Double(1.5)),
+                                      (CqlValue::Text("n".to_owned()), CqlValue::Null)]));
+        assert_eq!(person().serialize(ValueSerializer).unwrap(),
+                   CqlValue::Udt(vec![("id".to_owned(), CqlValue::Int(1)),
+                                      ("tags".to_owned(),
+                                       CqlValue::List(vec![CqlValue::Text("a".to_owned()),
+                                                           CqlValue::Text("b".to_owned())])),
+                                      ("point".to_owned(),
+                                       CqlValue::Tuple(vec![CqlValue::Int(3), CqlValue::Int(4)])),
+                                      ("home".to_owned(),
+                                       CqlValue::Udt(vec![("street".to_owned(),
+                                                           CqlValue::Text("Main St".to_owned())),
+                                                          ("zip".to_owned(),
+                                                           CqlValue::Int(12345))]))]));
+    }
+
+    #[test]
+    fn only_lossless_unsigned_types() {
+        assert_eq!(255u8.serialize(ValueSerializer).unwrap(), CqlValue::Int(255));
+        assert_eq!(65535u16.serialize(ValueSerializer).unwrap(), CqlValue::Int(65535));
+        assert!(1u32.serialize(ValueSerializer).is_err());
+        assert!(1u64.serialize(ValueSerializer).is_err());
+        assert!(vec![1u64].serialize(ValueSerializer).is_err());
+    }
+
+    #[test]
+    fn untyped_sequences_are_rejected() {
+        match build_untyped(CqlValue::Int(1)) {
+            Ok(Built::Scalar(CqlValue::Int(1))) => {}
+            _ => panic!("scalars bind as themselves"),
+        }
+        assert!(build_untyped(CqlValue::List(vec![])).is_err());
+        assert!(build_untyped(CqlValue::Tuple(vec![CqlValue::Int(1)])).is_err());
+        assert!(build_untyped(CqlValue::Udt(vec![])).is_err());
+    }
+
+    // The rest need a node on 127.0.0.1: cargo test -- --ignored
+
+    fn session() -> CassSession {
+        let mut cluster = CassCluster::new();
+        cluster.set_contact_points("127.0.0.1").unwrap();
+        let session = CassSession::new().connect(&mut cluster).wait().unwrap();
+        for query in &["CREATE KEYSPACE IF NOT EXISTS serde_test WITH replication = \
+                        {'class': 'SimpleStrategy', 'replication_factor': 1}",
+                       "CREATE TYPE IF NOT EXISTS serde_test.address (street text, zip int)",
+                       "CREATE TABLE IF NOT EXISTS serde_test.people (id int PRIMARY KEY, \
+                        tags set<text>, point tuple<int, int>, home frozen<address>)"] {
+            session.execute(query, 0).unwrap().wait().unwrap();
+        }
+        session
+    }
+
+    #[test]
+    #[ignore]
+    fn binds_by_parameter_type() {
+        let session = session();
+        let prepared = session.prepare("INSERT INTO serde_test.people (id, tags, point, home) \
+                                        VALUES (:id, :tags, :point, :home)")
+                              .unwrap()
+                              .wait()
+                              .unwrap();
+        let statement = prepared.bind_from(&person()).unwrap();
+        session.execute_statement(&statement).wait().unwrap();
+
+        let result = session.execute("SELECT id, tags, point FROM serde_test.people WHERE id = 1",
+                                     0)
+                            .unwrap()
+                            .wait()
+                            .unwrap();
+        let row = result.first_row().unwrap();
+        let (id, tags, point): (i32, Vec<String>, (i32, i32)) = result.deserialize_row(&row)
+                                                                       .unwrap();
+        assert_eq!((id, tags, point), (1, vec!["a".to_owned(), "b".to_owned()], (3, 4)));
+
+        let result = session.execute("SELECT id, home.zip FROM serde_test.people WHERE id = 1", 0)
+                            .unwrap()
+                            .wait()
+                            .unwrap();
+        let rows: Vec<HashMap<String, i32>> = result.deserialize_rows().unwrap();
+        assert_eq!(rows[0]["id"], 1);
+        assert_eq!(rows[0]["home.zip"], 12345);
+    }
+}
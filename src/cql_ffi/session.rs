@@ -2,16 +2,23 @@
 #![allow(dead_code)]
 #![allow(missing_copy_implementations)]
 
+use std::collections::BTreeSet;
 use std::ffi::CString;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
 use cql_ffi::batch::CassBatch;
 use cql_ffi::future::CassFuture;
 use cql_ffi::future::ResultFuture;
 use cql_ffi::future::PreparedFuture;
 use cql_ffi::error::CassError;
+use cql_ffi::result::CassResult;
 use cql_ffi::statement::CassStatement;
 use cql_ffi::schema::CassSchema;
 use cql_ffi::cluster::CassCluster;
+use cassandra_schema::CassUuid;
+use cql_ffi::value::CassValue;
 use cql_bindgen::CassFuture as _CassFuture;
 use cql_bindgen::cass_future_free;
 use cql_bindgen::cass_future_wait;
@@ -62,16 +69,14 @@ impl CassSession {
 
     pub fn prepare(&self, query: &str) -> Result<PreparedFuture, CassError> {
         unsafe {
-            let query = CString::new(query).unwrap();
+            let query = try!(CString::new(query));
             Ok(PreparedFuture(cass_session_prepare(self.0, query.as_ptr())))
         }
     }
 
-    pub fn execute(&self, statement: &str, parameter_count: u64) -> ResultFuture {
-        unsafe {
-            ResultFuture(cass_session_execute(self.0,
-                                              CassStatement::new(statement,parameter_count).0))
-        }
+    pub fn execute(&self, statement: &str, parameter_count: u64) -> Result<ResultFuture, CassError> {
+        let statement = try!(CassStatement::new(statement, parameter_count));
+        Ok(self.execute_statement(&statement))
     }
 
     pub fn execute_statement(&self, statement: &CassStatement) -> ResultFuture {
@@ -92,6 +97,39 @@ impl CassSession {
         }
     }
 
+    // DDL returns once the coordinator has applied it, and the other nodes
+    // catch up after. Polls system.local and system.peers until every node
+    // reports the same schema version, or the timeout passes, and returns
+    // whether they agreed. Both are read from the same node, as a node isn't
+    // among its own peers.
+    //
+    // Peers without a host_id or rpc_address are left out; those rows are
+    // what removed nodes leave behind, and nodes that haven't finished
+    // joining. The driver doesn't say which hosts are down, so a node that is
+    // down but still listed keeps the schema version it had and is waited for.
+    pub fn wait_for_schema_agreement(&self, timeout: Duration) -> Result<bool, CassError> {
+        let started = Instant::now();
+        loop {
+            let mut versions = BTreeSet::new();
+            let mut local = try!(self.execute("SELECT schema_version FROM system.local", 0));
+            try!(schema_versions(&try!(local.wait()), &mut versions));
+            let mut peers = try!(CassStatement::new("SELECT schema_version, host_id, rpc_address \
+                                                     FROM system.peers",
+                                                    0));
+            if let Some(node) = local.coordinator() {
+                try!(peers.set_node(&node));
+            }
+            try!(schema_versions(&try!(self.execute_statement(&peers).wait()), &mut versions));
+            if versions.len() <= 1 {
+                return Ok(true);
+            }
+            if started.elapsed() >= timeout {
+                return Ok(false);
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+    }
+
     pub unsafe fn connect_keyspace(&self,
                                    cluster: CassCluster,
                                    keyspace: *const ::libc::c_char)
@@ -100,6 +138,26 @@ impl CassSession {
     }
 }
 
+// The schema version comes first, and rows with any of the other columns null
+// are skipped.
+fn schema_versions(result: &CassResult, versions: &mut BTreeSet<CassUuid>) -> Result<(), CassError> {
+    let mut rows = result.iter();
+    while let Some(row) = rows.next() {
+        let mut complete = true;
+        for index in 1..result.column_count() {
+            complete = complete &&
+                       try!(try!(row.get_column(index)).get::<Option<CassValue>>()).is_some();
+        }
+        if !complete {
+            continue;
+        }
+        if let Some(version) = try!(try!(row.get_column(0)).get::<Option<CassUuid>>()) {
+            versions.insert(version);
+        }
+    }
+    Ok(())
+}
+
 pub struct SessionFuture(pub *mut _CassFuture, pub CassSession);
 
 impl SessionFuture {
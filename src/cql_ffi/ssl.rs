@@ -29,7 +29,7 @@ impl CassSsl {
 
     pub fn add_trusted_cert(&mut self, cert: &str) -> Result<&Self, CassError> {
         unsafe {
-            let cert = CString::new(cert).unwrap();
+            let cert = try!(CString::new(cert));
             CassError::build(cass_ssl_add_trusted_cert(self.0, cert.as_ptr())).wrap(self)
         }
     }
@@ -42,14 +42,14 @@ impl CassSsl {
 
     pub fn set_cert(&mut self, cert: &str) -> Result<&Self, CassError> {
         unsafe {
-            let cert = CString::new(cert).unwrap();
+            let cert = try!(CString::new(cert));
             CassError::build(cass_ssl_set_cert(self.0,cert.as_ptr())).wrap(self)
         }
     }
 
     pub fn set_private_key(&mut self, key: &str, password: *const i8) -> Result<&Self, CassError> {
         unsafe {
-            let key = CString::new(key).unwrap();
+            let key = try!(CString::new(key));
             CassError::build(cass_ssl_set_private_key(self.0,key.as_ptr(), password)).wrap(self)
         }
     }
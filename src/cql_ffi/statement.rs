@@ -4,11 +4,28 @@ use cql_ffi::collection::set::CassSet;
 use cql_ffi::collection::map::CassMap;
 use cql_ffi::collection::list::CassList;
 use cql_ffi::error::CassError;
-use cql_ffi::uuid::CassUuid;
+use cql_ffi::error::CassErrorTypes;
+use cassandra_schema::CassUuid;
+use cql_ffi::uuid::to_driver;
 use cql_ffi::inet::CassInet;
 use cql_ffi::result::CassResult;
 use cql_ffi::consistency::CassConsistency;
+use cql_ffi::future::CassNode;
 use cql_ffi::udt::CassUserType;
+use cql_ffi::tuple::CassTuple;
+use cassandra_schema::CqlDuration;
+use cql_ffi::token::Murmur3Partitioner;
+use cql_ffi::token::big_endian;
+use cql_ffi::token::composite_key;
+use cql_ffi::bindings::cass_statement_bind_int16;
+use cql_ffi::bindings::cass_statement_bind_int8;
+use cql_ffi::bindings::cass_statement_bind_uint32;
+use cql_ffi::bindings::cass_statement_bind_duration;
+use cql_ffi::bindings::cass_statement_set_node;
+use cql_ffi::bindings::cass_statement_bind_int16_by_name;
+use cql_ffi::bindings::cass_statement_bind_int8_by_name;
+use cql_ffi::bindings::cass_statement_bind_uint32_by_name;
+use cql_ffi::bindings::cass_statement_bind_duration_by_name;
 
 use cql_bindgen::CassStatement as _CassStatement;
 use cql_bindgen::cass_statement_new;
@@ -29,14 +46,15 @@ use cql_bindgen::cass_statement_bind_double;
 use cql_bindgen::cass_statement_bind_bool;
 use cql_bindgen::cass_statement_bind_string;
 use cql_bindgen::cass_statement_bind_bytes;
-//use cql_bindgen::cass_statement_bind_tuple;
-//use cql_bindgen::cass_statement_bind_tuple_by_name;
+use cql_bindgen::cass_statement_bind_tuple;
+use cql_bindgen::cass_statement_bind_tuple_by_name;
 use cql_bindgen::cass_statement_bind_user_type;
-//use cql_bindgen::cass_statement_bind_user_type_by_name;
+use cql_bindgen::cass_statement_bind_user_type_by_name;
 use cql_bindgen::cass_statement_bind_collection;
-//use cql_bindgen::cass_statement_bind_decimal;
+use cql_bindgen::cass_statement_bind_decimal;
 use cql_bindgen::cass_statement_bind_inet;
 use cql_bindgen::cass_statement_bind_uuid;
+use cql_bindgen::cass_statement_bind_null_by_name;
 use cql_bindgen::cass_statement_bind_int32_by_name;
 use cql_bindgen::cass_statement_bind_int64_by_name;
 use cql_bindgen::cass_statement_bind_float_by_name;
@@ -46,12 +64,24 @@ use cql_bindgen::cass_statement_bind_string_by_name;
 use cql_bindgen::cass_statement_bind_bytes_by_name;
 //use cql_bindgen::cass_statement_bind_custom_by_name;
 use cql_bindgen::cass_statement_bind_collection_by_name;
-//use cql_bindgen::cass_statement_bind_decimal_by_name;
+use cql_bindgen::cass_statement_bind_decimal_by_name;
 use cql_bindgen::cass_statement_bind_inet_by_name;
 use cql_bindgen::cass_statement_bind_uuid_by_name;
 
 
-pub struct CassStatement(pub *mut _CassStatement);
+// The second field holds the partition key values bound so far, for the
+// routing key, as the driver can't be asked for them.
+pub struct CassStatement(pub *mut _CassStatement, RoutingKey);
+
+#[derive(Default)]
+struct RoutingKey {
+    // The key indexes in the order they were added, with the parameter name of
+    // each if known and the bytes bound to it.
+    columns: Vec<(u64, Option<String>, Option<Vec<u8>>)>,
+    // Whether a value was bound by name, which may have been for a key index
+    // of unknown name.
+    bound_by_name: bool,
+}
 
 impl Drop for CassStatement {
     fn drop(&mut self) {
@@ -75,24 +105,92 @@ impl CassStatement {
         unimplemented!();
     }
 
-    pub fn new(query: &str, parameter_count: u64) -> Self {
+    pub fn new(query: &str, parameter_count: u64) -> Result<Self, CassError> {
         unsafe {
-            let query = CString::new(query).unwrap();
-            CassStatement(cass_statement_new(query.as_ptr(), parameter_count))
+            let query = try!(CString::new(query));
+            Ok(CassStatement::build(cass_statement_new(query.as_ptr(), parameter_count)))
         }
     }
 
+    pub fn build(statement: *mut _CassStatement) -> CassStatement {
+        CassStatement(statement, RoutingKey::default())
+    }
+
+    // Key indexes are added in the order of the partition key's columns, and
+    // before their values are bound for routing_key to see those.
     pub fn add_key_index(&mut self, index: u64) -> Result<&Self, CassError> {
         unsafe {
-            CassError::build(
-                cass_statement_add_key_index(self.0,index)
-            ).wrap(self)
+            try!(CassError::build(cass_statement_add_key_index(self.0, index)).wrap(()));
+        }
+        self.1.columns.push((index, None, None));
+        Ok(self)
+    }
+
+    // As add_key_index, for a parameter also bound by name, as a prepared
+    // statement's parameters can be.
+    pub fn add_named_key_index(&mut self, index: u64, name: &str) -> Result<&Self, CassError> {
+        try!(self.add_key_index(index));
+        if let Some(column) = self.1.columns.last_mut() {
+            column.1 = Some(name.to_owned());
+        }
+        Ok(self)
+    }
+
+    // The routing key the driver computes from the key indexes, once there is
+    // a value bound to each. Values bound as collections, tuples or user types
+    // aren't seen. A value bound by name is an error unless every key index was
+    // added with its name, as it may have been for any of them.
+    pub fn routing_key(&self) -> Result<Option<Vec<u8>>, CassError> {
+        if self.1.columns.is_empty() {
+            return Ok(None);
+        }
+        if self.1.bound_by_name && self.1.columns.iter().any(|column| column.1.is_none()) {
+            return Err(CassError::build(CassErrorTypes::LIB_NAME_DOES_NOT_EXIST as u32));
+        }
+        let mut components = Vec::new();
+        for column in &self.1.columns {
+            match column.2 {
+                Some(ref value) => components.push(&value[..]),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(composite_key(&components)))
+    }
+
+    pub fn token(&self) -> Result<Option<i64>, CassError> {
+        self.routing_key().map(|key| key.map(|key| Murmur3Partitioner::token(&key)))
+    }
+
+    fn key_value<F>(&mut self, index: u64, value: F)
+        where F: FnOnce() -> Option<Vec<u8>>
+    {
+        if let Some(column) = self.1.columns.iter_mut().find(|column| column.0 == index) {
+            column.2 = value();
+        }
+    }
+
+    // Names are matched as the driver does: lower cased unless quoted.
+    fn key_value_by_name<F>(&mut self, name: &str, value: F)
+        where F: FnOnce() -> Option<Vec<u8>>
+    {
+        self.1.bound_by_name = true;
+        let name = if name.len() > 1 && name.starts_with('"') && name.ends_with('"') {
+            name[1..name.len() - 1].to_owned()
+        } else {
+            name.to_lowercase()
+        };
+        let column = self.1
+                         .columns
+                         .iter_mut()
+                         .find(|column| column.1.as_ref() == Some(&name));
+        if let Some(column) = column {
+            column.2 = value();
         }
     }
 
     pub fn set_keyspace(&mut self, keyspace: String) -> Result<&Self, CassError> {
         unsafe {
-            let keyspace = CString::new(keyspace).unwrap();
+            let keyspace = try!(CString::new(keyspace));
             CassError::build(
                 cass_statement_set_keyspace(self.0,(keyspace.as_ptr()))
             ).wrap(self)
@@ -117,6 +215,14 @@ impl CassStatement {
         }
     }
 
+    // Sends the statement to the node rather than the one the load balancing
+    // policy would pick.
+    pub fn set_node(&mut self, node: &CassNode) -> Result<&mut Self, CassError> {
+        unsafe {
+            CassError::build(cass_statement_set_node(self.0, node.0)).wrap(self)
+        }
+    }
+
     pub fn set_paging_size(&mut self, page_size: i32) -> Result<&mut Self, CassError> {
         unsafe {
             CassError::build(
@@ -138,119 +244,198 @@ impl CassStatement {
 
     pub fn bind_null(&mut self, index: u64) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_null(self.0,index)
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || None);
+        Ok(self)
     }
 
     pub fn bind_int32(&mut self, index: u64, value: i32) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_int32(self.0,index, value)
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || Some(big_endian(value as u64, 4)));
+        Ok(self)
     }
 
     pub fn bind_int64(&mut self, index: u64, value: i64) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_int64(self.0,index, value)
-            ).wrap(self)
+            ).wrap(()));
+        }
+        self.key_value(index, || Some(big_endian(value as u64, 8)));
+        Ok(self)
+    }
+
+    pub fn bind_int16(&mut self, index: u64, value: i16) -> Result<&mut Self, CassError> {
+        unsafe {
+            try!(CassError::build(
+                cass_statement_bind_int16(self.0,index,value)
+            ).wrap(()));
+        }
+        self.key_value(index, || Some(big_endian(value as u64, 2)));
+        Ok(self)
+    }
+
+    pub fn bind_int8(&mut self, index: u64, value: i8) -> Result<&mut Self, CassError> {
+        unsafe {
+            try!(CassError::build(
+                cass_statement_bind_int8(self.0,index,value)
+            ).wrap(()));
         }
+        self.key_value(index, || Some(vec![value as u8]));
+        Ok(self)
+    }
+
+    // Binds the raw value of a date, see time::date_from_days.
+    pub fn bind_uint32(&mut self, index: u64, value: u32) -> Result<&mut Self, CassError> {
+        unsafe {
+            try!(CassError::build(
+                cass_statement_bind_uint32(self.0,index,value)
+            ).wrap(()));
+        }
+        self.key_value(index, || Some(big_endian(value as u64, 4)));
+        Ok(self)
+    }
+
+    pub fn bind_duration(&mut self,
+                         index: u64,
+                         value: CqlDuration)
+                         -> Result<&mut Self, CassError> {
+        unsafe {
+            try!(CassError::build(
+                cass_statement_bind_duration(
+                    self.0,
+                    index,
+                    value.months,
+                    value.days,
+                    value.nanos
+                )
+            ).wrap(()));
+        }
+        self.key_value(index, || None);
+        Ok(self)
     }
 
     pub fn bind_float(&mut self, index: u64, value: f32) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_float(self.0,index, value)
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || Some(big_endian(value.to_bits() as u64, 4)));
+        Ok(self)
     }
 
     pub fn bind_double(&mut self, index: u64, value: f64) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_double(self.0,index, value)
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || Some(big_endian(value.to_bits(), 8)));
+        Ok(self)
     }
 
     pub fn bind_bool(&mut self, index: u64, value: bool) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_bool(
                     self.0,
                     index,
                     if value{1} else {0}
                 )
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || Some(vec![value as u8]));
+        Ok(self)
     }
 
     pub fn bind_string(&mut self, index: u64, value: &str) -> Result<&mut Self, CassError> {
         unsafe {
-            let value = CString::new(value).unwrap();
-            CassError::build(
+            let value = try!(CString::new(value));
+            try!(CassError::build(
                 cass_statement_bind_string(
                     self.0,index,
                     value.as_ptr()
                 )
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || Some(value.as_bytes().to_vec()));
+        Ok(self)
     }
 
-    pub fn bind_bytes(&mut self, index: u64, value: Vec<u8>) -> Result<&mut Self, CassError> {
+    pub fn bind_bytes(&mut self, index: u64, value: &[u8]) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_bytes(
                     self.0,
                     index,
                     value.as_ptr(),
                     value.len() as u64
                 )
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || Some(value.to_vec()));
+        Ok(self)
     }
 
     pub fn bind_map(&mut self, index: u64, collection: CassMap) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_collection(self.0,index,collection.0)
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || None);
+        Ok(self)
     }
 
     pub fn bind_set(&mut self, index: u64, collection: CassSet) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_collection(self.0,index,collection.0)
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || None);
+        Ok(self)
     }
 
     pub fn bind_list(&mut self, index: u64, collection: CassList) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_collection(self.0,index,collection.0)
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || None);
+        Ok(self)
     }
 
     pub fn bind_uuid(&mut self, index: u64, value: CassUuid) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
-                cass_statement_bind_uuid(self.0,index, value.0)
-            ).wrap(self)
+            try!(CassError::build(
+                cass_statement_bind_uuid(self.0,index, to_driver(value))
+            ).wrap(()));
         }
+        self.key_value(index, || Some(value.to_bytes().to_vec()));
+        Ok(self)
     }
 
-    pub fn bind_inet(&mut self, index: u64, value: CassInet) -> Result<&mut Self, CassError> {
+    pub fn bind_inet<S>(&mut self, index: u64, value: S) -> Result<&mut Self, CassError>
+        where S: Into<CassInet>
+    {
+        let value = value.into();
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_inet(self.0,index, value.0)
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || Some(value.0.address[..value.0.address_length as usize].to_vec()));
+        Ok(self)
     }
 
     pub fn bind_user_type(&mut self,
@@ -258,30 +443,49 @@ impl CassStatement {
                           value: CassUserType)
                           -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            try!(CassError::build(
                 cass_statement_bind_user_type(
                     self.0,
                     index,
                     value.0
                 )
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value(index, || None);
+        Ok(self)
     }
 
-//    pub fn bind_decimal<'a>(&'a self,
-//                            index: cass_size_t,
-//                            value: String)
-//                            -> Result<&'a Self, CassError> {
-//        unsafe {
-//            CassError::build(
-//                cass_statement_bind_decimal(
-//                    self.0,
-//                    index,
-//                    value
-//                )
-//            ).wrap(&self)
-//        }
-//    }
+    pub fn bind_tuple(&mut self, index: u64, value: CassTuple) -> Result<&mut Self, CassError> {
+        unsafe {
+            try!(CassError::build(
+                cass_statement_bind_tuple(self.0, index, value.0)
+            ).wrap(()));
+        }
+        self.key_value(index, || None);
+        Ok(self)
+    }
+
+    // A decimal is bound as its unscaled value, a big-endian two's complement
+    // varint, together with the scale.
+    pub fn bind_decimal(&mut self,
+                        index: u64,
+                        varint: &[u8],
+                        scale: i32)
+                        -> Result<&mut Self, CassError> {
+        unsafe {
+            try!(CassError::build(
+                cass_statement_bind_decimal(
+                    self.0,
+                    index,
+                    varint.as_ptr(),
+                    varint.len() as u64,
+                    scale
+                )
+            ).wrap(()));
+        }
+        self.key_value(index, || Some(decimal_bytes(varint, scale)));
+        Ok(self)
+    }
 
 //    pub fn bind_custom(&mut self,
 //                       index: u64,
@@ -300,91 +504,184 @@ impl CassStatement {
 //        }
 //    }
 
+    pub fn bind_null_by_name(&mut self, name: &str) -> Result<&mut Self, CassError> {
+        unsafe {
+            let name = try!(CString::new(name));
+            try!(CassError::build(
+                cass_statement_bind_null_by_name(
+                    self.0,
+                    name.as_ptr()
+                )
+            ).wrap(()));
+        }
+        self.key_value_by_name(name, || None);
+        Ok(self)
+    }
+
     pub fn bind_int32_by_name(&mut self, name: &str, value: i32) -> Result<&mut Self, CassError> {
         unsafe {
-            let name = CString::new(name).unwrap();
-            CassError::build(
+            let name = try!(CString::new(name));
+            try!(CassError::build(
                 cass_statement_bind_int32_by_name(
                     self.0,
                     name.as_ptr(),
                     value
                 )
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value_by_name(name, || Some(big_endian(value as u64, 4)));
+        Ok(self)
     }
 
     pub fn bind_int64_by_name(&mut self, name: &str, value: i64) -> Result<&mut Self, CassError> {
         unsafe {
-            CassError::build(
+            let name = try!(CString::new(name));
+            try!(CassError::build(
                 cass_statement_bind_int64_by_name(
                     self.0,
-                    CString::new(name).unwrap().as_ptr(),
+                    name.as_ptr(),
                     value
                 )
-            ).wrap(self)
+            ).wrap(()));
+        }
+        self.key_value_by_name(name, || Some(big_endian(value as u64, 8)));
+        Ok(self)
+    }
+
+    pub fn bind_int16_by_name(&mut self, name: &str, value: i16) -> Result<&mut Self, CassError> {
+        unsafe {
+            let name = try!(CString::new(name));
+            try!(CassError::build(
+                cass_statement_bind_int16_by_name(
+                    self.0,
+                    name.as_ptr(),
+                    value
+                )
+            ).wrap(()));
+        }
+        self.key_value_by_name(name, || Some(big_endian(value as u64, 2)));
+        Ok(self)
+    }
+
+    pub fn bind_int8_by_name(&mut self, name: &str, value: i8) -> Result<&mut Self, CassError> {
+        unsafe {
+            let name = try!(CString::new(name));
+            try!(CassError::build(
+                cass_statement_bind_int8_by_name(
+                    self.0,
+                    name.as_ptr(),
+                    value
+                )
+            ).wrap(()));
+        }
+        self.key_value_by_name(name, || Some(vec![value as u8]));
+        Ok(self)
+    }
+
+    pub fn bind_uint32_by_name(&mut self, name: &str, value: u32) -> Result<&mut Self, CassError> {
+        unsafe {
+            let name = try!(CString::new(name));
+            try!(CassError::build(
+                cass_statement_bind_uint32_by_name(
+                    self.0,
+                    name.as_ptr(),
+                    value
+                )
+            ).wrap(()));
+        }
+        self.key_value_by_name(name, || Some(big_endian(value as u64, 4)));
+        Ok(self)
+    }
+
+    pub fn bind_duration_by_name(&mut self,
+                                 name: &str,
+                                 value: CqlDuration)
+                                 -> Result<&mut Self, CassError> {
+        unsafe {
+            let name = try!(CString::new(name));
+            try!(CassError::build(
+                cass_statement_bind_duration_by_name(
+                    self.0,
+                    name.as_ptr(),
+                    value.months,
+                    value.days,
+                    value.nanos
+                )
+            ).wrap(()));
         }
+        self.key_value_by_name(name, || None);
+        Ok(self)
     }
 
     pub fn bind_float_by_name(&mut self, name: &str, value: f32) -> Result<&mut Self, CassError> {
         unsafe {
-            let name = CString::new(name).unwrap();
-            CassError::build(
+            let name = try!(CString::new(name));
+            try!(CassError::build(
                 cass_statement_bind_float_by_name(
                     self.0,name.as_ptr(),
                     value
                 )
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value_by_name(name, || Some(big_endian(value.to_bits() as u64, 4)));
+        Ok(self)
     }
 
     pub fn bind_double_by_name(&mut self, name: &str, value: f64) -> Result<&mut Self, CassError> {
         unsafe {
-            let name = CString::new(name).unwrap();
-            CassError::build(
+            let name = try!(CString::new(name));
+            try!(CassError::build(
                 cass_statement_bind_double_by_name(
                     self.0,
                     name.as_ptr(),
                     value
                 )
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value_by_name(name, || Some(big_endian(value.to_bits(), 8)));
+        Ok(self)
     }
 
     pub fn bind_bool_by_name(&mut self, name: &str, value: bool) -> Result<&mut Self, CassError> {
         unsafe {
-            let name = CString::new(name).unwrap();
-            CassError::build(
+            let name = try!(CString::new(name));
+            try!(CassError::build(
                 cass_statement_bind_bool_by_name(
                     self.0,
                     name.as_ptr(),
                     if value {1} else {0}
                 )
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value_by_name(name, || Some(vec![value as u8]));
+        Ok(self)
     }
 
     pub fn bind_string_by_name(&mut self, name: &str, value: &str) -> Result<&mut Self, CassError> {
         unsafe {
-            let name = CString::new(name).unwrap();
-            let value = CString::new(value).unwrap();
+            let name = try!(CString::new(name));
+            let value = try!(CString::new(value));
             let result = cass_statement_bind_string_by_name(self.0, name.as_ptr(), value.as_ptr());
-            CassError::build(result).wrap(self)
+            try!(CassError::build(result).wrap(()));
         }
+        self.key_value_by_name(name, || Some(value.as_bytes().to_vec()));
+        Ok(self)
     }
 
     pub fn bind_bytes_by_name(&mut self,
                               name: &str,
-                              mut value: Vec<u8>)
+                              value: &[u8])
                               -> Result<&mut Self, CassError> {
         unsafe {
-            let name = CString::new(name).unwrap();
-            let val = value.as_mut_ptr();
+            let name = try!(CString::new(name));
             let result = cass_statement_bind_bytes_by_name(self.0,
                                                            name.as_ptr(),
-                                                           val,
+                                                           value.as_ptr(),
                                                            value.len() as u64);
-            CassError::build(result).wrap(self)
+            try!(CassError::build(result).wrap(()));
         }
+        self.key_value_by_name(name, || Some(value.to_vec()));
+        Ok(self)
     }
 
     pub fn bind_uuid_by_name(&mut self,
@@ -392,48 +689,58 @@ impl CassStatement {
                              value: CassUuid)
                              -> Result<&mut Self, CassError> {
         unsafe {
-            let name = CString::new(name).unwrap();
-            CassError::build(
+            let name = try!(CString::new(name));
+            try!(CassError::build(
                 cass_statement_bind_uuid_by_name(
                     self.0,
                     name.as_ptr(),
-                    value.0
+                    to_driver(value)
                 )
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value_by_name(name, || Some(value.to_bytes().to_vec()));
+        Ok(self)
     }
 
-    pub fn bind_inet_by_name(&mut self,
-                             name: &str,
-                             value: CassInet)
-                             -> Result<&mut Self, CassError> {
+    pub fn bind_inet_by_name<S>(&mut self, name: &str, value: S) -> Result<&mut Self, CassError>
+        where S: Into<CassInet>
+    {
+        let value = value.into();
         unsafe {
-            let name = CString::new(name).unwrap();
-            CassError::build(
+            let name = try!(CString::new(name));
+            try!(CassError::build(
                 cass_statement_bind_inet_by_name(
                     self.0,
                     name.as_ptr(),
                     value.0
                 )
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value_by_name(name,
+                               || Some(value.0.address[..value.0.address_length as usize].to_vec()));
+        Ok(self)
     }
 
-//    pub fn bind_decimal_by_name<'a>(&'a self,
-//                                    name: &str,
-//                                    value: String)
-//                                    -> Result<&'a Self, CassError> {
-//        unsafe {
-//            let name = CString::new(name).unwrap();
-//            CassError::build(
-//            cass_statement_bind_decimal_by_name(
-//                self.0,
-//                name.as_ptr(),
-//                value
-//            )
-//        ).wrap(&self)
-//        }
-//    }
+    pub fn bind_decimal_by_name(&mut self,
+                                name: &str,
+                                varint: &[u8],
+                                scale: i32)
+                                -> Result<&mut Self, CassError> {
+        unsafe {
+            let name = try!(CString::new(name));
+            try!(CassError::build(
+                cass_statement_bind_decimal_by_name(
+                    self.0,
+                    name.as_ptr(),
+                    varint.as_ptr(),
+                    varint.len() as u64,
+                    scale
+                )
+            ).wrap(()));
+        }
+        self.key_value_by_name(name, || Some(decimal_bytes(varint, scale)));
+        Ok(self)
+    }
 
 //    pub fn bind_custom_by_name(&mut self,
 //                               name: &str,
@@ -441,7 +748,7 @@ impl CassStatement {
 //                               output: *mut *mut u8)
 //                               -> Result<&mut Self, CassError> {
 //        unsafe {
-//            let name = CString::new(name).unwrap();
+//            let name = try!(CString::new(name));
 //            CassError::build(
 //            cass_statement_bind_custom_by_name(
 //                self.0,
@@ -452,19 +759,117 @@ impl CassStatement {
 //        }
 //    }
 
+    pub fn bind_tuple_by_name(&mut self,
+                              name: &str,
+                              value: CassTuple)
+                              -> Result<&mut Self, CassError> {
+        unsafe {
+            let name = try!(CString::new(name));
+            try!(CassError::build(
+                cass_statement_bind_tuple_by_name(
+                    self.0,
+                    name.as_ptr(),
+                    value.0
+                )
+            ).wrap(()));
+        }
+        self.key_value_by_name(name, || None);
+        Ok(self)
+    }
+
+    pub fn bind_user_type_by_name(&mut self,
+                                  name: &str,
+                                  value: CassUserType)
+                                  -> Result<&mut Self, CassError> {
+        unsafe {
+            let name = try!(CString::new(name));
+            try!(CassError::build(
+                cass_statement_bind_user_type_by_name(
+                    self.0,
+                    name.as_ptr(),
+                    value.0
+                )
+            ).wrap(()));
+        }
+        self.key_value_by_name(name, || None);
+        Ok(self)
+    }
+
     pub fn bind_set_by_name(&mut self,
                             name: &str,
                             collection: CassSet)
                             -> Result<&mut Self, CassError> {
         unsafe {
-            let name = CString::new(name).unwrap();
-            CassError::build(
+            let name = try!(CString::new(name));
+            try!(CassError::build(
                 cass_statement_bind_collection_by_name(
                     self.0,
                     name.as_ptr(),
                     collection.0
                 )
-            ).wrap(self)
+            ).wrap(()));
         }
+        self.key_value_by_name(name, || None);
+        Ok(self)
+    }
+
+    pub fn bind_list_by_name(&mut self,
+                             name: &str,
+                             collection: CassList)
+                             -> Result<&mut Self, CassError> {
+        unsafe {
+            let name = try!(CString::new(name));
+            try!(CassError::build(
+                cass_statement_bind_collection_by_name(
+                    self.0,
+                    name.as_ptr(),
+                    collection.0
+                )
+            ).wrap(()));
+        }
+        self.key_value_by_name(name, || None);
+        Ok(self)
+    }
+
+    pub fn bind_map_by_name(&mut self,
+                            name: &str,
+                            collection: CassMap)
+                            -> Result<&mut Self, CassError> {
+        unsafe {
+            let name = try!(CString::new(name));
+            try!(CassError::build(
+                cass_statement_bind_collection_by_name(
+                    self.0,
+                    name.as_ptr(),
+                    collection.0
+                )
+            ).wrap(()));
+        }
+        self.key_value_by_name(name, || None);
+        Ok(self)
+    }
+}
+
+// A decimal's bytes in a routing key: the scale, then the unscaled value.
+fn decimal_bytes(varint: &[u8], scale: i32) -> Vec<u8> {
+    let mut bytes = big_endian(scale as u64, 4);
+    bytes.extend_from_slice(varint);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn values_that_fail_to_bind_are_left_out_of_the_routing_key() {
+        let key = RoutingKey {
+            columns: vec![(0, Some("k".to_owned()), None)],
+            bound_by_name: false,
+        };
+        let mut statement = CassStatement(ptr::null_mut(), key);
+        assert!(statement.bind_string_by_name("k", "a\0b").is_err());
+        assert!(match statement.routing_key() { Ok(None) => true, _ => false });
     }
 }
@@ -0,0 +1,269 @@
+use std::time::SystemTime;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, NaiveDate, NaiveTime, Timelike, Utc};
+
+#[cfg(feature = "chrono")]
+use cql_ffi::collection::collection::{CassCollection, ToCql};
+use cassandra_schema::{system_time_from_timestamp, timestamp_from_system_time};
+#[cfg(feature = "chrono")]
+use cassandra_schema::{date_from_days, days_from_date, is_valid_time};
+use cql_ffi::column::CassColumn;
+use cql_ffi::error::CassError;
+#[cfg(feature = "chrono")]
+use cql_ffi::error::CassErrorTypes;
+use cql_ffi::statement::CassStatement;
+use cql_ffi::tuple::CassTuple;
+use cql_ffi::udt::CassUserType;
+use cql_ffi::value::CassValue;
+
+#[cfg(feature = "chrono")]
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+#[cfg(feature = "chrono")]
+pub fn timestamp_from_datetime(datetime: &DateTime<Utc>) -> i64 {
+    datetime.timestamp_millis()
+}
+
+#[cfg(feature = "chrono")]
+pub fn datetime_from_timestamp(timestamp: i64) -> Result<DateTime<Utc>, CassError> {
+    let mut seconds = timestamp / 1000;
+    let mut millis = timestamp % 1000;
+    if millis < 0 {
+        seconds -= 1;
+        millis += 1000;
+    }
+    let nanos = (millis * 1_000_000) as u32;
+    match DateTime::from_timestamp(seconds, nanos) {
+        Some(datetime) => Ok(datetime),
+        None => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
+    }
+}
+
+#[cfg(feature = "chrono")]
+pub fn date_from_naive_date(date: &NaiveDate) -> u32 {
+    date_from_days(date.signed_duration_since(unix_epoch()).num_days())
+}
+
+#[cfg(feature = "chrono")]
+pub fn naive_date_from_date(date: u32) -> Result<NaiveDate, CassError> {
+    unix_epoch()
+        .checked_add_signed(::chrono::Duration::days(days_from_date(date)))
+        .ok_or(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32))
+}
+
+#[cfg(feature = "chrono")]
+fn unix_epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).expect("1970-01-01 is a valid date")
+}
+
+#[cfg(feature = "chrono")]
+pub fn time_from_naive_time(time: &NaiveTime) -> i64 {
+    time.num_seconds_from_midnight() as i64 * NANOS_PER_SECOND + time.nanosecond() as i64
+}
+
+#[cfg(feature = "chrono")]
+pub fn naive_time_from_time(time: i64) -> Result<NaiveTime, CassError> {
+    if !is_valid_time(time) {
+        return Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32));
+    }
+    NaiveTime::from_num_seconds_from_midnight_opt((time / NANOS_PER_SECOND) as u32,
+                                                  (time % NANOS_PER_SECOND) as u32)
+        .ok_or(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32))
+}
+
+impl<'a> CassValue<'a> {
+    pub fn get_system_time(&self) -> Result<SystemTime, CassError> {
+        Ok(system_time_from_timestamp(try!(self.get_int64())))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn get_datetime(&self) -> Result<DateTime<Utc>, CassError> {
+        datetime_from_timestamp(try!(self.get_int64()))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn get_naive_date(&self) -> Result<NaiveDate, CassError> {
+        naive_date_from_date(try!(self.get_uint32()))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn get_naive_time(&self) -> Result<NaiveTime, CassError> {
+        naive_time_from_time(try!(self.get_int64()))
+    }
+}
+
+impl<'a> CassColumn<'a> {
+    pub fn get_system_time(&self) -> Result<SystemTime, CassError> {
+        CassValue::new(self.0).get_system_time()
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn get_datetime(&self) -> Result<DateTime<Utc>, CassError> {
+        CassValue::new(self.0).get_datetime()
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn get_naive_date(&self) -> Result<NaiveDate, CassError> {
+        CassValue::new(self.0).get_naive_date()
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn get_naive_time(&self) -> Result<NaiveTime, CassError> {
+        CassValue::new(self.0).get_naive_time()
+    }
+}
+
+impl CassStatement {
+    pub fn bind_system_time(&mut self,
+                            index: u64,
+                            value: SystemTime)
+                            -> Result<&mut Self, CassError> {
+        self.bind_int64(index, timestamp_from_system_time(value))
+    }
+
+    pub fn bind_system_time_by_name(&mut self,
+                                    name: &str,
+                                    value: SystemTime)
+                                    -> Result<&mut Self, CassError> {
+        self.bind_int64_by_name(name, timestamp_from_system_time(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn bind_datetime(&mut self,
+                         index: u64,
+                         value: &DateTime<Utc>)
+                         -> Result<&mut Self, CassError> {
+        self.bind_int64(index, timestamp_from_datetime(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn bind_datetime_by_name(&mut self,
+                                 name: &str,
+                                 value: &DateTime<Utc>)
+                                 -> Result<&mut Self, CassError> {
+        self.bind_int64_by_name(name, timestamp_from_datetime(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn bind_naive_date(&mut self,
+                           index: u64,
+                           value: &NaiveDate)
+                           -> Result<&mut Self, CassError> {
+        self.bind_uint32(index, date_from_naive_date(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn bind_naive_date_by_name(&mut self,
+                                   name: &str,
+                                   value: &NaiveDate)
+                                   -> Result<&mut Self, CassError> {
+        self.bind_uint32_by_name(name, date_from_naive_date(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn bind_naive_time(&mut self,
+                           index: u64,
+                           value: &NaiveTime)
+                           -> Result<&mut Self, CassError> {
+        self.bind_int64(index, time_from_naive_time(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn bind_naive_time_by_name(&mut self,
+                                   name: &str,
+                                   value: &NaiveTime)
+                                   -> Result<&mut Self, CassError> {
+        self.bind_int64_by_name(name, time_from_naive_time(value))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ToCql for DateTime<Utc> {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        timestamp_from_datetime(self).append_to(collection)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ToCql for NaiveDate {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        date_from_naive_date(self).append_to(collection)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ToCql for NaiveTime {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        time_from_naive_time(self).append_to(collection)
+    }
+}
+
+impl CassTuple {
+    pub fn set_system_time(&mut self, index: u64, value: SystemTime) -> Result<(), CassError> {
+        self.set_int64(index, timestamp_from_system_time(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn set_datetime(&mut self, index: u64, value: &DateTime<Utc>) -> Result<(), CassError> {
+        self.set_int64(index, timestamp_from_datetime(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn set_naive_date(&mut self, index: u64, value: &NaiveDate) -> Result<(), CassError> {
+        self.set_uint32(index, date_from_naive_date(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn set_naive_time(&mut self, index: u64, value: &NaiveTime) -> Result<(), CassError> {
+        self.set_int64(index, time_from_naive_time(value))
+    }
+}
+
+impl CassUserType {
+    pub fn set_system_time(&mut self, index: u64, value: SystemTime) -> Result<(), CassError> {
+        self.set_int64(index, timestamp_from_system_time(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn set_datetime(&mut self, index: u64, value: &DateTime<Utc>) -> Result<(), CassError> {
+        self.set_int64(index, timestamp_from_datetime(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn set_naive_date(&mut self, index: u64, value: &NaiveDate) -> Result<(), CassError> {
+        self.set_uint32(index, date_from_naive_date(value))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn set_naive_time(&mut self, index: u64, value: &NaiveTime) -> Result<(), CassError> {
+        self.set_int64(index, time_from_naive_time(value))
+    }
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chrono_conversions() {
+        use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+
+        let datetime = Utc.with_ymd_and_hms(2017, 7, 14, 2, 40, 0).unwrap();
+        let timestamp = timestamp_from_datetime(&datetime);
+        assert_eq!(timestamp, 1_500_000_000_000);
+        assert_eq!(datetime_from_timestamp(timestamp).unwrap(), datetime);
+        assert_eq!(datetime_from_timestamp(-1).unwrap().to_string(),
+                   "1969-12-31 23:59:59.999 UTC");
+        assert!(datetime_from_timestamp(i64::MAX).is_err());
+
+        let date = NaiveDate::from_ymd_opt(1969, 12, 31).unwrap();
+        assert_eq!(days_from_date(date_from_naive_date(&date)), -1);
+        assert_eq!(naive_date_from_date(date_from_naive_date(&date)).unwrap(), date);
+
+        let time = NaiveTime::from_hms_nano_opt(12, 34, 56, 7).unwrap();
+        assert_eq!(time_from_naive_time(&time), 45_296_000_000_007);
+        assert_eq!(naive_time_from_time(45_296_000_000_007).unwrap(), time);
+        assert!(naive_time_from_time(86_400 * NANOS_PER_SECOND).is_err());
+    }
+}
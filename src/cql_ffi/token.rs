@@ -0,0 +1,166 @@
+// Cassandra's default partitioner: a partition's token is the first half of
+// the 128 bit MurmurHash3 (x64 variant, seed 0) of its key. Cassandra's copy
+// of the hash reads the last, partial block as signed bytes, unlike the
+// reference implementation, and that is followed here so the tokens match.
+pub struct Murmur3Partitioner;
+
+const C1: u64 = 0x87c37b91114253d5;
+const C2: u64 = 0x4cf5ad432745937f;
+
+impl Murmur3Partitioner {
+    pub fn token(key: &[u8]) -> i64 {
+        // As in Cassandra, an empty key has the minimum token.
+        if key.is_empty() {
+            return i64::MIN;
+        }
+        let token = murmur3_x64_128(key).0 as i64;
+        // The minimum token is kept out of the ring, for ranges to start at.
+        if token == i64::MIN {
+            i64::MAX
+        } else {
+            token
+        }
+    }
+
+    // The token of a partition key made of several columns, each given as
+    // bound: the same bytes bind_int32, bind_string and so on send.
+    pub fn composite_token(components: &[&[u8]]) -> i64 {
+        Murmur3Partitioner::token(&composite_key(components))
+    }
+
+    pub fn min_token() -> i64 {
+        i64::MIN
+    }
+
+    pub fn max_token() -> i64 {
+        i64::MAX
+    }
+}
+
+// The routing key of a partition key. A key of one column is that column's
+// bytes; otherwise each column is written with a two byte length before it
+// and a zero byte after it.
+pub fn composite_key(components: &[&[u8]]) -> Vec<u8> {
+    if components.len() == 1 {
+        return components[0].to_vec();
+    }
+    let mut key = Vec::new();
+    for component in components {
+        key.push((component.len() >> 8) as u8);
+        key.push(component.len() as u8);
+        key.extend_from_slice(component);
+        key.push(0);
+    }
+    key
+}
+
+fn murmur3_x64_128(data: &[u8]) -> (u64, u64) {
+    let length = data.len();
+    let mut h1: u64 = 0;
+    let mut h2: u64 = 0;
+
+    for block in data.chunks(16).take(length / 16) {
+        let k1 = little_endian(&block[..8]);
+        let k2 = little_endian(&block[8..]);
+
+        h1 ^= mix_k1(k1);
+        h1 = h1.rotate_left(27).wrapping_add(h2).wrapping_mul(5).wrapping_add(0x52dce729);
+        h2 ^= mix_k2(k2);
+        h2 = h2.rotate_left(31).wrapping_add(h1).wrapping_mul(5).wrapping_add(0x38495ab5);
+    }
+
+    let tail = &data[length / 16 * 16..];
+    let mut k1: u64 = 0;
+    let mut k2: u64 = 0;
+    for (i, &byte) in tail.iter().enumerate() {
+        // Sign extended, as Java's (long) of a byte is.
+        let byte = byte as i8 as i64 as u64;
+        if i < 8 {
+            k1 ^= byte << (i * 8);
+        } else {
+            k2 ^= byte << ((i - 8) * 8);
+        }
+    }
+    if tail.len() > 8 {
+        h2 ^= mix_k2(k2);
+    }
+    if !tail.is_empty() {
+        h1 ^= mix_k1(k1);
+    }
+
+    h1 ^= length as u64;
+    h2 ^= length as u64;
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    h1 = fmix(h1);
+    h2 = fmix(h2);
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    (h1, h2)
+}
+
+fn little_endian(bytes: &[u8]) -> u64 {
+    bytes.iter().rev().fold(0, |value, &byte| value << 8 | byte as u64)
+}
+
+fn mix_k1(k1: u64) -> u64 {
+    k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2)
+}
+
+fn mix_k2(k2: u64) -> u64 {
+    k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1)
+}
+
+fn fmix(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xff51afd7ed558ccd);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xc4ceb9fe1a85ec53);
+    k ^= k >> 33;
+    k
+}
+
+// The bytes a value is sent as, for routing keys.
+pub(crate) fn big_endian(value: u64, length: usize) -> Vec<u8> {
+    (0..length).rev().map(|i| (value >> (i * 8)) as u8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tokens Cassandra gives these keys. The sign extended tail of Cassandra's
+    // hash shows in the keys with bytes over 0x7f.
+    static GOLDEN: &'static [(&'static [u8], i64)] =
+        &[(b"", i64::MIN),
+          (b"123", -7468325962851647638),
+          (b"\x00\xff\x10\xfa\x99\x00\xff\x10\xfa\x99\x00\xff\x10\xfa\x99\x00\xff\x10\xfa\x99\x00\xff\
+             \x10\xfa\x99\x00\xff\x10\xfa\x99\x00\xff\x10\xfa\x99\x00\xff\x10\xfa\x99\x00\xff\x10\xfa\
+             \x99\x00\xff\x10\xfa\x99",
+            5837342703291459765),
+          (b"\xfe\xfe\xfe\xfe\xfe\xfe\xfe\xfe", -8927430733708461935),
+          (b"\x10\x10\x10\x10\x10\x10\x10\x10", 1446172840243228796),
+          (b"9223372036854775807", 7162290910810015547)];
+
+    #[test]
+    fn golden_tokens() {
+        for &(key, expected) in GOLDEN {
+            assert_eq!(Murmur3Partitioner::token(key), expected, "key {:?}", key);
+        }
+    }
+
+    #[test]
+    fn composite_keys() {
+        assert_eq!(composite_key(&[b"abc"]), b"abc".to_vec());
+        assert_eq!(composite_key(&[b"a", b"bc"]),
+                   vec![0, 1, b'a', 0, 0, 2, b'b', b'c', 0]);
+        assert_eq!(Murmur3Partitioner::composite_token(&[b"123"]),
+                   Murmur3Partitioner::token(b"123"));
+    }
+
+    #[test]
+    fn big_endian_values() {
+        assert_eq!(big_endian(1, 4), vec![0, 0, 0, 1]);
+        assert_eq!(big_endian(-2i64 as u64, 2), vec![0xff, 0xfe]);
+    }
+}
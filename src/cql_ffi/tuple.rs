@@ -13,23 +13,37 @@ use cql_bindgen::cass_tuple_set_string;
 use cql_bindgen::cass_tuple_set_bytes;
 use cql_bindgen::cass_tuple_set_uuid;
 use cql_bindgen::cass_tuple_set_inet;
-//use cql_bindgen::cass_tuple_set_decimal;
-//use cql_bindgen::cass_tuple_set_collection;
-//use cql_bindgen::cass_tuple_set_tuple;
-//use cql_bindgen::cass_tuple_set_user_type;
-//use cql_bindgen::cass_iterator_from_tuple;
+use cql_bindgen::cass_tuple_set_decimal;
+use cql_bindgen::cass_tuple_set_collection;
+use cql_bindgen::cass_tuple_set_tuple;
+use cql_bindgen::cass_tuple_set_user_type;
+use cql_bindgen::CassIterator as _CassIterator;
+use cql_bindgen::cass_iterator_free;
+use cql_bindgen::cass_iterator_next;
+use cql_bindgen::cass_iterator_get_value;
 
 use std::ffi::CString;
+use std::marker::PhantomData;
 
-use cql_ffi::inet::AsCassInet;
-use std::net::SocketAddr;
+use cql_ffi::inet::CassInet;
 use cql_bindgen::CassTuple as _CassTuple;
-use cql_ffi::uuid::CassUuid;
+use cassandra_schema::CassUuid;
+use cql_ffi::uuid::to_driver;
 use cql_ffi::udt::CassDataType;
+use cql_ffi::udt::CassUserType;
+use cql_ffi::collection::collection::CassCollection;
 use cql_ffi::udt::CassConstDataType;
 use cql_ffi::error::CassError;
+use cassandra_schema::CqlDuration;
+use cql_ffi::value::CassValue;
+use cql_ffi::value::FromCql;
+use cql_ffi::error::CassErrorTypes;
+use cql_ffi::bindings::cass_tuple_set_int16;
+use cql_ffi::bindings::cass_tuple_set_int8;
+use cql_ffi::bindings::cass_tuple_set_uint32;
+use cql_ffi::bindings::cass_tuple_set_duration;
 
-pub struct CassTuple(pub *mut _CassTuple);
+pub struct CassTuple(pub(crate) *mut _CassTuple);
 
 impl CassTuple {
     pub fn new(item_count: u64) -> Self {
@@ -44,7 +58,7 @@ impl CassTuple {
         }
     }
 
-    pub fn new_from_data_type(data_type: CassDataType) -> CassTuple {
+    pub fn new_from_data_type(data_type: &CassDataType) -> CassTuple {
         unsafe {
             CassTuple(cass_tuple_new_from_data_type(data_type.0))
         }
@@ -74,6 +88,32 @@ impl CassTuple {
         }
     }
 
+    pub fn set_int16(&mut self, index: u64, value: i16) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(cass_tuple_set_int16(self.0, index, value)).wrap(())
+        }
+    }
+
+    pub fn set_int8(&mut self, index: u64, value: i8) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(cass_tuple_set_int8(self.0, index, value)).wrap(())
+        }
+    }
+
+    pub fn set_uint32(&mut self, index: u64, value: u32) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(cass_tuple_set_uint32(self.0, index, value)).wrap(())
+        }
+    }
+
+    pub fn set_duration(&mut self, index: u64, value: CqlDuration) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(
+                cass_tuple_set_duration(self.0, index, value.months, value.days, value.nanos)
+            ).wrap(())
+        }
+    }
+
     pub fn set_float(&mut self, index: u64, value: f32) -> Result<(), CassError> {
         unsafe {
             CassError::build(cass_tuple_set_float(self.0, index, value)).wrap(())
@@ -104,27 +144,28 @@ impl CassTuple {
         where S: Into<String>
     {
         unsafe {
-            let value = CString::new(value.into()).unwrap();
+            let value = try!(CString::new(value.into()));
             CassError::build(
                 cass_tuple_set_string(self.0, index, value.as_ptr())
             ).wrap(())
         }
     }
 
-    pub fn set_inet(&mut self, index: u64, value: SocketAddr) -> Result<(), CassError> {
-        let inet = AsCassInet::as_cass_inet(&value);
+    pub fn set_inet<S>(&mut self, index: u64, value: S) -> Result<(), CassError>
+        where S: Into<CassInet>
+    {
         unsafe {
             CassError::build(
                 cass_tuple_set_inet(
                     self.0,
                     index,
-                    inet.0,
+                    value.into().0,
                 )
             ).wrap(())
         }
     }
 
-    pub fn set_bytes(&mut self, index: u64, value: Vec<u8>) -> Result<(), CassError> {
+    pub fn set_bytes(&mut self, index: u64, value: &[u8]) -> Result<(), CassError> {
         unsafe {
             CassError::build(
                 cass_tuple_set_bytes(
@@ -142,10 +183,48 @@ impl CassTuple {
     {
         unsafe {
             CassError::build(
-                cass_tuple_set_uuid(self.0, index, value.into().0)
+                cass_tuple_set_uuid(self.0, index, to_driver(value.into()))
+            ).wrap(())
+        }
+    }
+
+    pub fn set_decimal(&mut self, index: u64, varint: &[u8], scale: i32) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(
+                cass_tuple_set_decimal(
+                    self.0,
+                    index,
+                    varint.as_ptr(),
+                    varint.len() as u64,
+                    scale
+                )
+            ).wrap(())
+        }
+    }
+
+    // The driver copies the collection, tuple or user type into the tuple, so
+    // the value can be dropped once it has been set.
+    pub fn set_collection<C>(&mut self, index: u64, value: C) -> Result<(), CassError>
+        where C: CassCollection
+    {
+        unsafe {
+            CassError::build(
+                cass_tuple_set_collection(self.0, index, value.inner())
             ).wrap(())
         }
     }
+
+    pub fn set_tuple(&mut self, index: u64, value: CassTuple) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(cass_tuple_set_tuple(self.0, index, value.0)).wrap(())
+        }
+    }
+
+    pub fn set_user_type(&mut self, index: u64, value: CassUserType) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(cass_tuple_set_user_type(self.0, index, value.0)).wrap(())
+        }
+    }
 }
 
 impl Drop for CassTuple {
@@ -155,3 +234,74 @@ impl Drop for CassTuple {
         }
     }
 }
+
+pub struct TupleIterator<'a>(pub(crate) *mut _CassIterator, pub(crate) PhantomData<&'a ()>);
+
+impl<'a> Drop for TupleIterator<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            cass_iterator_free(self.0)
+        }
+    }
+}
+
+// Lends each item until the next call, as ListIterator does.
+impl<'a> TupleIterator<'a> {
+    pub fn next(&mut self) -> Option<CassValue<'_>> {
+        unsafe {
+            match cass_iterator_next(self.0) {
+                0 => None,
+                _ => Some(self.get_value()),
+            }
+        }
+    }
+
+    pub fn get_value(&mut self) -> CassValue<'_> {
+        unsafe {
+            CassValue::new(cass_iterator_get_value(self.0))
+        }
+    }
+}
+
+// Reads a tuple value back into a Rust tuple of the same arity. Each item is
+// read with its own FromCql, so nested tuples, collections and nulls (as an
+// Option) work as they do at the top level. As with collections, the items
+// must be owned types.
+macro_rules! impl_from_cql_for_tuple {
+    ($arity:expr; $($name:ident),+) => {
+        impl<'a, $($name: for<'b> FromCql<'b>),+> FromCql<'a> for ($($name,)+) {
+            fn from_cql(value: &CassValue<'a>) -> Result<Self, CassError> {
+                let mut items = try!(value.tuple_iter());
+                if value.item_count() != $arity {
+                    return Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32));
+                }
+                Ok(($(
+                    match items.next() {
+                        Some(item) => try!($name::from_cql(&item)),
+                        None => {
+                            return Err(CassError::build(
+                                CassErrorTypes::LIB_INDEX_OUT_OF_BOUNDS as u32))
+                        }
+                    },
+                )+))
+            }
+        }
+    }
+}
+
+impl_from_cql_for_tuple!(1; A);
+impl_from_cql_for_tuple!(2; A, B);
+impl_from_cql_for_tuple!(3; A, B, C);
+impl_from_cql_for_tuple!(4; A, B, C, D);
+impl_from_cql_for_tuple!(5; A, B, C, D, E);
+impl_from_cql_for_tuple!(6; A, B, C, D, E, F);
+impl_from_cql_for_tuple!(7; A, B, C, D, E, F, G);
+impl_from_cql_for_tuple!(8; A, B, C, D, E, F, G, H);
+impl_from_cql_for_tuple!(9; A, B, C, D, E, F, G, H, I);
+impl_from_cql_for_tuple!(10; A, B, C, D, E, F, G, H, I, J);
+impl_from_cql_for_tuple!(11; A, B, C, D, E, F, G, H, I, J, K);
+impl_from_cql_for_tuple!(12; A, B, C, D, E, F, G, H, I, J, K, L);
+impl_from_cql_for_tuple!(13; A, B, C, D, E, F, G, H, I, J, K, L, M);
+impl_from_cql_for_tuple!(14; A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+impl_from_cql_for_tuple!(15; A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+impl_from_cql_for_tuple!(16; A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
@@ -1,13 +1,25 @@
 use std::ffi::CString;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt;
 use std::mem;
-use std::slice;
 use std::str;
+use std::marker::PhantomData;
 
 use cql_ffi::value::CassValue;
-use cql_ffi::uuid::CassUuid;
+use cassandra_schema::CassUuid;
+use cql_ffi::uuid::to_driver;
 use cql_ffi::inet::CassInet;
 use cql_ffi::collection::set::CassSet;
+use cql_ffi::collection::collection::CassCollection;
 use cql_ffi::tuple::CassTuple;
+use cql_ffi::bindings::cass_user_type_set_int16;
+use cql_ffi::bindings::cass_user_type_set_int8;
+use cql_ffi::bindings::cass_user_type_set_uint32;
+use cql_ffi::bindings::cass_user_type_set_duration;
+use cql_ffi::bindings::cass_data_type_is_frozen;
+use cql_ffi::bindings::cass_data_type_sub_type_count;
 
 use cql_bindgen::cass_data_type_new;
 use cql_bindgen::CassIterator as _CassIterator;
@@ -21,7 +33,7 @@ use cql_bindgen::cass_data_type_new_from_existing;
 
 use cql_bindgen::cass_data_type_new_tuple;
 use cql_bindgen::cass_data_type_new_udt;
-//use cql_bindgen::cass_data_type_free;
+use cql_bindgen::cass_data_type_free;
 use cql_bindgen::cass_data_type_type;
 use cql_bindgen::cass_data_type_type_name;
 use cql_bindgen::cass_data_type_set_type_name;
@@ -78,8 +90,8 @@ use cql_bindgen::cass_user_type_set_uuid;
 use cql_bindgen::cass_user_type_set_inet;
 //use cql_bindgen::cass_user_type_set_inet_by_name;
 //use cql_bindgen::cass_user_type_set_inet_by_name_n;
-//use cql_bindgen::cass_user_type_set_decimal;
-//use cql_bindgen::cass_user_type_set_decimal_by_name;
+use cql_bindgen::cass_user_type_set_decimal;
+use cql_bindgen::cass_user_type_set_decimal_by_name;
 //use cql_bindgen::cass_user_type_set_decimal_by_name_n;
 use cql_bindgen::cass_user_type_set_collection;
 use cql_bindgen::cass_user_type_set_collection_by_name;
@@ -97,10 +109,14 @@ use cql_bindgen::cass_iterator_get_user_type_field_value;
 use cql_bindgen::CassDataType as _CassDataType;
 use cql_bindgen::CassUserType as _CassUserType;
 
-use cql_ffi::value::CassValueType;
+use cassandra_schema::CassValueType;
 use cql_ffi::error::CassError;
+use cql_ffi::helpers::str_from_raw;
+use cql_ffi::helpers::str_from_raw_lossy;
+use cassandra_schema::CqlType;
+use cassandra_schema::CqlDuration;
 
-pub struct CassDataType(pub *mut _CassDataType);
+pub struct CassDataType(pub *mut _CassDataType, Option<CqlType>);
 pub struct CassConstDataType(pub *const _CassDataType);
 
 pub struct CassUserType(pub *mut _CassUserType);
@@ -108,71 +124,125 @@ pub struct CassUserType(pub *mut _CassUserType);
 impl CassDataType {
     pub fn new(value_type: CassValueType) -> Self {
         unsafe {
-            CassDataType(cass_data_type_new(value_type as u32))
+            CassDataType(cass_data_type_new(value_type as u32), None)
         }
     }
 
-    pub fn new_from_existing(data_type: CassDataType) -> Self {
+    pub fn new_from_existing(data_type: &CassDataType) -> Self {
         unsafe {
-            CassDataType(cass_data_type_new_from_existing(data_type.0))
+            CassDataType(cass_data_type_new_from_existing(data_type.0), data_type.1.clone())
+        }
+    }
+
+    // A copy of a type the driver owns, such as a prepared statement's
+    // parameter type, subtypes included.
+    pub fn new_from_const(data_type: &CassConstDataType) -> Self {
+        unsafe {
+            CassDataType(cass_data_type_new_from_existing(data_type.0), None)
         }
     }
 
     pub fn new_tuple(item_count: u64) -> CassDataType {
         unsafe {
-            CassDataType(cass_data_type_new_tuple(item_count))
+            CassDataType(cass_data_type_new_tuple(item_count), None)
         }
     }
 
     pub fn new_udt(field_count: u64) -> CassDataType {
         unsafe {
-            CassDataType(cass_data_type_new_udt(field_count))
+            CassDataType(cass_data_type_new_udt(field_count), None)
         }
     }
 
-    pub fn get_type(data_type: CassDataType) -> CassValueType {
+    pub fn parse(type_string: &str) -> Result<CassDataType, CassError> {
+        CassDataType::from_cql_type(&try!(CqlType::parse(type_string)))
+    }
+
+    // The driver has no way to mark a data type as frozen, so the type it was
+    // built from is kept around to display it the way it was written.
+    pub fn from_cql_type(cql_type: &CqlType) -> Result<CassDataType, CassError> {
+        let mut data_type = match *cql_type.unfrozen() {
+            CqlType::Native(value_type) => CassDataType::new(value_type),
+            CqlType::List(ref item) | CqlType::Set(ref item) => {
+                let mut data_type = CassDataType::new(cql_type.value_type());
+                try!(data_type.add_sub_type(&try!(CassDataType::from_cql_type(item))));
+                data_type
+            }
+            CqlType::Map(ref key, ref value) => {
+                let mut data_type = CassDataType::new(CassValueType::MAP);
+                try!(data_type.add_sub_type(&try!(CassDataType::from_cql_type(key))));
+                try!(data_type.add_sub_type(&try!(CassDataType::from_cql_type(value))));
+                data_type
+            }
+            CqlType::Tuple(ref items) => {
+                let mut data_type = CassDataType::new_tuple(items.len() as u64);
+                for item in items {
+                    try!(data_type.add_sub_type(&try!(CassDataType::from_cql_type(item))));
+                }
+                data_type
+            }
+            CqlType::Udt { ref keyspace, ref name } => {
+                let mut data_type = CassDataType::new_udt(0);
+                if let Some(ref keyspace) = *keyspace {
+                    try!(data_type.set_keyspace(keyspace.clone()));
+                }
+                try!(data_type.set_type_name(name.clone()));
+                data_type
+            }
+            CqlType::Custom(ref class_name) => {
+                let mut data_type = CassDataType::new(CassValueType::CUSTOM);
+                try!(data_type.set_class_name(class_name.clone()));
+                data_type
+            }
+            CqlType::Frozen(_) => unreachable!(),
+        };
+        data_type.1 = Some(cql_type.clone());
+        Ok(data_type)
+    }
+
+    pub fn to_cql_type(&self) -> CqlType {
+        match self.1 {
+            Some(ref cql_type) => cql_type.clone(),
+            None => unsafe { cql_type_of(self.0) },
+        }
+    }
+
+    pub fn get_type(&self) -> CassValueType {
         unsafe {
-            CassValueType::build(cass_data_type_type(data_type.0))
+            CassValueType::build(cass_data_type_type(self.0))
         }
     }
 
-    pub fn type_name<S>(data_type: CassDataType, type_name: S) -> Result<(), CassError>
-        where S: Into<String>
-    {
+    pub fn type_name(&self) -> Result<String, CassError> {
         unsafe {
-            let type_name = CString::new(type_name.into()).unwrap();
-            CassError::build(
-                cass_data_type_type_name(
-                    data_type.0,
-                    &mut type_name.as_ptr(),
-                    &mut(type_name.as_bytes().len() as u64)
-                )
-            ).wrap(())
+            data_type_type_name(self.0)
         }
     }
 
-    pub fn set_type_name<S>(data_type: CassDataType, type_name: S) -> Result<(), CassError>
+    pub fn set_type_name<S>(&mut self, type_name: S) -> Result<(), CassError>
         where S: Into<String>
     {
         unsafe {
-            let type_name = CString::new(type_name.into()).unwrap();
+            self.1 = None;
+            let type_name = try!(CString::new(type_name.into()));
             CassError::build(
                 cass_data_type_set_type_name(
-                    data_type.0,
+                    self.0,
                     type_name.as_ptr())
                 )
             .wrap(())
         }
     }
 
-    pub fn set_type_name_n<S>(data_type: CassDataType, type_name: S) -> Result<(), CassError>
+    pub fn set_type_name_n<S>(&mut self, type_name: S) -> Result<(), CassError>
         where S: Into<String>
     {
         unsafe {
-            let type_name = CString::new(type_name.into()).unwrap();
+            self.1 = None;
+            let type_name = try!(CString::new(type_name.into()));
             CassError::build(
                 cass_data_type_set_type_name_n(
-                    data_type.0,
+                    self.0,
                     type_name.as_ptr(),
                     type_name.as_bytes().len() as u64
                 )
@@ -180,43 +250,36 @@ impl CassDataType {
         }
     }
 
-    pub fn keyspace<S>(data_type: CassDataType, keyspace: S) -> Result<(), CassError>
-        where S: Into<String>
-    {
+    pub fn keyspace(&self) -> Result<String, CassError> {
         unsafe {
-            let keyspace = CString::new(keyspace.into()).unwrap();
-            CassError::build(
-                cass_data_type_keyspace(
-                    data_type.0,
-                    &mut(keyspace.as_ptr()),
-                    &mut(keyspace.as_bytes().len() as u64)
-                )
-            ).wrap(())
+            data_type_keyspace(self.0)
         }
     }
 
-    pub fn set_keyspace<S>(data_type: CassDataType, keyspace: S) -> Result<(), CassError>
+    pub fn set_keyspace<S>(&mut self, keyspace: S) -> Result<(), CassError>
         where S: Into<String>
     {
         unsafe {
-            let keyspace = CString::new(keyspace.into()).unwrap();
+            self.1 = None;
+            let keyspace = try!(CString::new(keyspace.into()));
             CassError::build(
                 cass_data_type_set_keyspace(
-                    data_type.0,
+                    self.0,
                     keyspace.as_ptr()
                 )
             ).wrap(())
         }
     }
 
-    pub fn set_keyspace_n<S>(data_type: CassDataType, keyspace: S) -> Result<(), CassError>
+    pub fn set_keyspace_n<S>(&mut self, keyspace: S) -> Result<(), CassError>
         where S: Into<String>
     {
         unsafe {
-            let keyspace = CString::new(keyspace.into()).unwrap();
+            self.1 = None;
+            let keyspace = try!(CString::new(keyspace.into()));
             CassError::build(
                 cass_data_type_set_keyspace_n(
-                    data_type.0,
+                    self.0,
                     keyspace.as_ptr(),
                     keyspace.as_bytes().len() as u64
                 )
@@ -224,44 +287,36 @@ impl CassDataType {
         }
     }
 
-    pub fn class_name<S>(data_type: CassDataType, class_name: S) -> Result<(), CassError>
-        where S: Into<String>
-    {
+    pub fn class_name(&self) -> Result<String, CassError> {
         unsafe {
-            let class_name = CString::new(class_name.into()).unwrap();
-            CassError::build(
-                cass_data_type_class_name(
-                    data_type.0,
-                    &mut class_name.as_ptr(),
-                    &mut(class_name.as_bytes().len() as u64)
-                )
-            ).wrap(())
+            data_type_class_name(self.0)
         }
     }
 
-
-    pub fn set_class_name<S>(data_type: CassDataType, class_name: S) -> Result<(), CassError>
+    pub fn set_class_name<S>(&mut self, class_name: S) -> Result<(), CassError>
         where S: Into<String>
     {
         unsafe {
-            let class_name = CString::new(class_name.into()).unwrap();
+            self.1 = None;
+            let class_name = try!(CString::new(class_name.into()));
             CassError::build(
                 cass_data_type_set_class_name(
-                    data_type.0,
+                    self.0,
                     class_name.as_ptr()
                 )
             ).wrap(())
         }
     }
 
-    pub fn set_class_name_n<S>(data_type: CassDataType, class_name: S) -> Result<(), CassError>
+    pub fn set_class_name_n<S>(&mut self, class_name: S) -> Result<(), CassError>
         where S: Into<String>
     {
         unsafe {
-            let class_name = CString::new(class_name.into()).unwrap();
+            self.1 = None;
+            let class_name = try!(CString::new(class_name.into()));
             CassError::build(
                 cass_data_type_set_class_name_n(
-                    data_type.0,
+                    self.0,
                     class_name.as_ptr(),
                     class_name.as_bytes().len() as u64
                 )
@@ -269,70 +324,65 @@ impl CassDataType {
         }
     }
 
-    pub fn sub_data_type(data_type: CassDataType, index: u64) -> CassConstDataType {
+    pub fn sub_type_count(&self) -> u64 {
         unsafe {
-            CassConstDataType(cass_data_type_sub_data_type(data_type.0, index))
+            cass_data_type_sub_type_count(self.0)
         }
     }
 
-    pub fn sub_data_type_by_name<S>(data_type: CassDataType, name: S) -> CassConstDataType
-        where S: Into<String>
-    {
+    pub fn sub_data_type(&self, index: u64) -> CassConstDataType {
         unsafe {
-            let name = CString::new(name.into()).unwrap();
-            CassConstDataType(cass_data_type_sub_data_type_by_name(data_type.0, name.as_ptr()))
+            CassConstDataType(cass_data_type_sub_data_type(self.0, index))
         }
     }
 
-    pub fn sub_data_type_by_name_n<S>(data_type: CassDataType, name: S) -> CassConstDataType
+    pub fn sub_data_type_by_name<S>(&self, name: S) -> Result<CassConstDataType, CassError>
         where S: Into<String>
     {
         unsafe {
-            let name = CString::new(name.into()).unwrap();
-            CassConstDataType(cass_data_type_sub_data_type_by_name_n(data_type.0,
-                                                                     name.as_ptr(),
-                                                                     name.as_bytes().len() as u64))
+            let name = try!(CString::new(name.into()));
+            Ok(CassConstDataType(cass_data_type_sub_data_type_by_name(self.0, name.as_ptr())))
         }
     }
 
-
-    pub fn sub_type_name<S>(data_type: CassDataType, index: u64, name: S) -> Result<(), CassError>
+    pub fn sub_data_type_by_name_n<S>(&self, name: S) -> Result<CassConstDataType, CassError>
         where S: Into<String>
     {
         unsafe {
-            let name = CString::new(name.into()).unwrap();
-            CassError::build(
-                cass_data_type_sub_type_name(
-                    data_type.0,
-                    index,
-                    &mut name.as_ptr(),
-                    &mut(name.as_bytes().len() as u64)
-                )
-            ).wrap(())
+            let name = try!(CString::new(name.into()));
+            Ok(CassConstDataType(cass_data_type_sub_data_type_by_name_n(self.0,
+                                                                        name.as_ptr(),
+                                                                        name.as_bytes().len() as u64)))
         }
     }
 
-    pub fn add_sub_type(data_type: CassDataType,
-                        sub_data_type: CassDataType)
-                        -> Result<(), CassError> {
+    pub fn sub_type_name(&self, index: u64) -> Result<String, CassError> {
         unsafe {
+            data_type_sub_type_name(self.0, index)
+        }
+    }
+
+    pub fn add_sub_type(&mut self, sub_data_type: &CassDataType) -> Result<(), CassError> {
+        unsafe {
+            self.1 = None;
             CassError::build(
-                cass_data_type_add_sub_type(data_type.0, sub_data_type.0)
+                cass_data_type_add_sub_type(self.0, sub_data_type.0)
             ).wrap(())
         }
     }
 
-    pub fn add_sub_type_by_name<S>(data_type: CassDataType,
+    pub fn add_sub_type_by_name<S>(&mut self,
                                    name: S,
-                                   sub_data_type: CassDataType)
+                                   sub_data_type: &CassDataType)
                                    -> Result<(), CassError>
         where S: Into<String>
     {
         unsafe {
-            let name = CString::new(name.into()).unwrap();
+            self.1 = None;
+            let name = try!(CString::new(name.into()));
             CassError::build(
                 cass_data_type_add_sub_type_by_name(
-                    data_type.0,
+                    self.0,
                     name.as_ptr(),
                     sub_data_type.0
                 )
@@ -340,15 +390,12 @@ impl CassDataType {
         }
     }
 
-    pub fn add_sub_value_type<S>(data_type: CassDataType,
-                                 sub_value_type: CassValueType)
-                                 -> Result<(), CassError>
-        where S: Into<String>
-    {
+    pub fn add_sub_value_type(&mut self, sub_value_type: CassValueType) -> Result<(), CassError> {
         unsafe {
+            self.1 = None;
             CassError::build(
                 cass_data_type_add_sub_value_type(
-                    data_type.0,
+                    self.0,
                     sub_value_type as u32
                 )
             ).wrap(())
@@ -356,17 +403,173 @@ impl CassDataType {
     }
 }
 
-//impl Drop for CassDataType {
-//    fn drop(&mut self) {unsafe{
-//        cass_data_type_free(&mut self.0)
-//    }}
-//}
+impl Drop for CassDataType {
+    fn drop(&mut self) {
+        unsafe {
+            cass_data_type_free(self.0)
+        }
+    }
+}
 
-//impl Drop for CassUserType {
-//    fn drop(&mut self) {unsafe{
-//        cass_user_type_free(self.0)
-//    }}
-//}
+impl Clone for CassDataType {
+    fn clone(&self) -> Self {
+        CassDataType::new_from_existing(self)
+    }
+}
+
+// Two data types are the same if they describe the same CQL type.
+impl PartialEq for CassDataType {
+    fn eq(&self, other: &CassDataType) -> bool {
+        self.to_cql_type() == other.to_cql_type()
+    }
+}
+
+impl Display for CassDataType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_cql_type())
+    }
+}
+
+impl Debug for CassDataType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.to_cql_type())
+    }
+}
+
+impl CassConstDataType {
+    pub fn to_cql_type(&self) -> CqlType {
+        unsafe {
+            cql_type_of(self.0)
+        }
+    }
+
+    pub fn get_type(&self) -> CassValueType {
+        unsafe {
+            CassValueType::build(cass_data_type_type(self.0))
+        }
+    }
+
+    pub fn type_name(&self) -> Result<String, CassError> {
+        unsafe {
+            data_type_type_name(self.0)
+        }
+    }
+
+    pub fn keyspace(&self) -> Result<String, CassError> {
+        unsafe {
+            data_type_keyspace(self.0)
+        }
+    }
+
+    pub fn class_name(&self) -> Result<String, CassError> {
+        unsafe {
+            data_type_class_name(self.0)
+        }
+    }
+
+    pub fn sub_type_count(&self) -> u64 {
+        unsafe {
+            cass_data_type_sub_type_count(self.0)
+        }
+    }
+
+    pub fn sub_data_type(&self, index: u64) -> CassConstDataType {
+        unsafe {
+            CassConstDataType(cass_data_type_sub_data_type(self.0, index))
+        }
+    }
+
+    pub fn sub_type_name(&self, index: u64) -> Result<String, CassError> {
+        unsafe {
+            data_type_sub_type_name(self.0, index)
+        }
+    }
+}
+
+impl Display for CassConstDataType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_cql_type())
+    }
+}
+
+impl Debug for CassConstDataType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.to_cql_type())
+    }
+}
+
+unsafe fn cql_type_of(data_type: *const _CassDataType) -> CqlType {
+    if data_type.is_null() {
+        return CqlType::Native(CassValueType::UNKNOWN);
+    }
+    let sub_type = |index: u64| Box::new(cql_type_of(cass_data_type_sub_data_type(data_type, index)));
+    let cql_type = match CassValueType::build(cass_data_type_type(data_type)) {
+        CassValueType::LIST => CqlType::List(sub_type(0)),
+        CassValueType::SET => CqlType::Set(sub_type(0)),
+        CassValueType::MAP => CqlType::Map(sub_type(0), sub_type(1)),
+        CassValueType::TUPLE => {
+            CqlType::Tuple((0..cass_data_type_sub_type_count(data_type))
+                               .map(|index| *sub_type(index))
+                               .collect())
+        }
+        CassValueType::UDT => {
+            CqlType::Udt {
+                keyspace: data_type_keyspace(data_type).ok().and_then(|keyspace| {
+                    if keyspace.is_empty() { None } else { Some(keyspace) }
+                }),
+                name: data_type_type_name(data_type).unwrap_or(String::new()),
+            }
+        }
+        CassValueType::CUSTOM => {
+            CqlType::Custom(data_type_class_name(data_type).unwrap_or(String::new()))
+        }
+        native => CqlType::Native(native),
+    };
+    match cass_data_type_is_frozen(data_type) {
+        0 => cql_type,
+        _ => CqlType::Frozen(Box::new(cql_type)),
+    }
+}
+
+unsafe fn data_type_type_name(data_type: *const _CassDataType) -> Result<String, CassError> {
+    let mut name = mem::zeroed();
+    let mut name_length = mem::zeroed();
+    try!(CassError::build(cass_data_type_type_name(data_type, &mut name, &mut name_length))
+             .wrap(()));
+    str_from_raw(name, name_length).map(str::to_owned)
+}
+
+unsafe fn data_type_keyspace(data_type: *const _CassDataType) -> Result<String, CassError> {
+    let mut keyspace = mem::zeroed();
+    let mut keyspace_length = mem::zeroed();
+    try!(CassError::build(cass_data_type_keyspace(data_type, &mut keyspace, &mut keyspace_length))
+             .wrap(()));
+    str_from_raw(keyspace, keyspace_length).map(str::to_owned)
+}
+
+unsafe fn data_type_class_name(data_type: *const _CassDataType) -> Result<String, CassError> {
+    let mut class_name = mem::zeroed();
+    let mut class_name_length = mem::zeroed();
+    // cql_bindgen has the pointer as mut, though the driver doesn't change it
+    try!(CassError::build(cass_data_type_class_name(data_type as *mut _,
+                                                    &mut class_name,
+                                                    &mut class_name_length))
+             .wrap(()));
+    str_from_raw(class_name, class_name_length).map(str::to_owned)
+}
+
+unsafe fn data_type_sub_type_name(data_type: *const _CassDataType,
+                                  index: u64)
+                                  -> Result<String, CassError> {
+    let mut name = mem::zeroed();
+    let mut name_length = mem::zeroed();
+    try!(CassError::build(cass_data_type_sub_type_name(data_type,
+                                                       index,
+                                                       &mut name,
+                                                       &mut name_length))
+             .wrap(()));
+    str_from_raw(name, name_length).map(str::to_owned)
+}
 
 impl CassUserType {
     pub fn new(data_type: CassConstDataType) -> Self {
@@ -408,14 +611,8 @@ impl CassUserType {
         where S: Into<String>
     {
         unsafe {
-            match CString::new(name.into()) {
-                Ok(name) => {
-                    let rc = cass_user_type_set_int32_by_name(self.0, name.as_ptr(), value);
-                    CassError::build(rc).wrap(())
-                }
-                Err(err) => panic!("error: {}", err),
-            }
-
+            let name = try!(CString::new(name.into()));
+            CassError::build(cass_user_type_set_int32_by_name(self.0, name.as_ptr(), value)).wrap(())
         }
     }
 
@@ -429,7 +626,7 @@ impl CassUserType {
         where S: Into<String>
     {
         unsafe {
-            let name = CString::new(name.into()).unwrap();
+            let name = try!(CString::new(name.into()));
             CassError::build(
                 cass_user_type_set_int64_by_name(
                     self.0,
@@ -440,6 +637,32 @@ impl CassUserType {
         }
     }
 
+    pub fn set_int16(&mut self, index: u64, value: i16) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(cass_user_type_set_int16(self.0, index, value)).wrap(())
+        }
+    }
+
+    pub fn set_int8(&mut self, index: u64, value: i8) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(cass_user_type_set_int8(self.0, index, value)).wrap(())
+        }
+    }
+
+    pub fn set_uint32(&mut self, index: u64, value: u32) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(cass_user_type_set_uint32(self.0, index, value)).wrap(())
+        }
+    }
+
+    pub fn set_duration(&mut self, index: u64, value: CqlDuration) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(
+                cass_user_type_set_duration(self.0, index, value.months, value.days, value.nanos)
+            ).wrap(())
+        }
+    }
+
     pub fn set_float(&mut self, index: u64, value: f32) -> Result<(), CassError> {
         unsafe {
             CassError::build(
@@ -456,7 +679,7 @@ impl CassUserType {
         where S: Into<String>
     {
         unsafe {
-            let name = CString::new(name.into()).unwrap();
+            let name = try!(CString::new(name.into()));
             CassError::build(
                 cass_user_type_set_float_by_name(
                     self.0,
@@ -477,7 +700,7 @@ impl CassUserType {
         where S: Into<String>
     {
         unsafe {
-            let name = CString::new(name.into()).unwrap();
+            let name = try!(CString::new(name.into()));
             CassError::build(
                 cass_user_type_set_double_by_name(
                     self.0,
@@ -504,7 +727,7 @@ impl CassUserType {
         where S: Into<String>
     {
         unsafe {
-            let value = CString::new(value.into()).unwrap();
+            let value = try!(CString::new(value.into()));
             CassError::build(cass_user_type_set_string(self.0, index, value.as_ptr())).wrap(())
         }
     }
@@ -513,8 +736,8 @@ impl CassUserType {
         where S: Into<String>
     {
         unsafe {
-            let name = CString::new(name.into()).unwrap();
-            let value = CString::new(value.into()).unwrap();
+            let name = try!(CString::new(name.into()));
+            let value = try!(CString::new(value.into()));
             CassError::build(
                 cass_user_type_set_string_by_name(
                     self.0,
@@ -525,8 +748,7 @@ impl CassUserType {
         }
     }
 
-    //FIXME. right way to pass the vec?
-    pub fn set_bytes(&mut self, index: u64, value: Vec<u8>) -> Result<(), CassError> {
+    pub fn set_bytes(&mut self, index: u64, value: &[u8]) -> Result<(), CassError> {
         unsafe {
             CassError::build(
                 cass_user_type_set_bytes(
@@ -543,7 +765,7 @@ impl CassUserType {
         where S: Into<CassUuid>
     {
         unsafe {
-            CassError::build(cass_user_type_set_uuid(self.0, index, value.into().0)).wrap(())
+            CassError::build(cass_user_type_set_uuid(self.0, index, to_driver(value.into()))).wrap(())
         }
     }
 
@@ -555,11 +777,46 @@ impl CassUserType {
         }
     }
 
-    pub fn set_collection<S>(&mut self, index: u64, value: S) -> Result<(), CassError>
-        where S: Into<CassSet>
+    pub fn set_decimal(&mut self, index: u64, varint: &[u8], scale: i32) -> Result<(), CassError> {
+        unsafe {
+            CassError::build(
+                cass_user_type_set_decimal(
+                    self.0,
+                    index,
+                    varint.as_ptr(),
+                    varint.len() as u64,
+                    scale
+                )
+            ).wrap(())
+        }
+    }
+
+    pub fn set_decimal_by_name<S>(&mut self,
+                                  name: S,
+                                  varint: &[u8],
+                                  scale: i32)
+                                  -> Result<(), CassError>
+        where S: Into<String>
     {
         unsafe {
-            CassError::build(cass_user_type_set_collection(self.0, index, value.into().0)).wrap(())
+            let name = try!(CString::new(name.into()));
+            CassError::build(
+                cass_user_type_set_decimal_by_name(
+                    self.0,
+                    name.as_ptr(),
+                    varint.as_ptr(),
+                    varint.len() as u64,
+                    scale
+                )
+            ).wrap(())
+        }
+    }
+
+    pub fn set_collection<C>(&mut self, index: u64, value: C) -> Result<(), CassError>
+        where C: CassCollection
+    {
+        unsafe {
+            CassError::build(cass_user_type_set_collection(self.0, index, value.inner())).wrap(())
         }
     }
 
@@ -567,7 +824,7 @@ impl CassUserType {
         where S: Into<String>
     {
         unsafe {
-            let name = CString::new(name.into()).unwrap();
+            let name = try!(CString::new(name.into()));
             CassError::build(
                 cass_user_type_set_collection_by_name(
                     self.0,
@@ -591,9 +848,9 @@ impl CassUserType {
     }
 }
 
-pub struct UserTypeIterator(pub *mut _CassIterator);
+pub struct UserTypeIterator<'a>(pub(crate) *mut _CassIterator, pub(crate) PhantomData<&'a ()>);
 
-impl Drop for UserTypeIterator {
+impl<'a> Drop for UserTypeIterator<'a> {
     fn drop(&mut self) {
         unsafe {
             cass_iterator_free(self.0)
@@ -601,20 +858,22 @@ impl Drop for UserTypeIterator {
     }
 }
 
-impl Iterator for UserTypeIterator {
-    type Item = (String,CassValue);
-    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+impl<'a> UserTypeIterator<'a> {
+    // The field names are copied, but each value is lent until the next call.
+    pub fn next(&mut self) -> Option<(String, CassValue<'_>)> {
         unsafe {
             match cass_iterator_next(self.0) {
                 0 => None,
                 //cass_iterator_get_user_type_field_name(fields, &field_name, &field_name_length);
                 _ => {//
-                    let field_name = mem::zeroed();
-                    let field_name_length = mem::zeroed();
-                    cass_iterator_get_user_type_field_name(self.0, field_name, field_name_length);
-                    let slice = slice::from_raw_parts(field_name as *const u8,
-                                                      field_name_length as usize);
-                    let key = str::from_utf8(slice).unwrap().to_owned();
+                    let mut field_name = mem::zeroed();
+                    let mut field_name_length = mem::zeroed();
+                    cass_iterator_get_user_type_field_name(self.0,
+                                                           &mut field_name,
+                                                           &mut field_name_length);
+                    // The names come from the schema, so a bad one is replaced
+                    // rather than ending the iteration.
+                    let key = str_from_raw_lossy(field_name, field_name_length).into_owned();
 
                     let field_value = cass_iterator_get_user_type_field_value(self.0);
                     Some((key, CassValue::new(field_value)))
@@ -622,9 +881,7 @@ impl Iterator for UserTypeIterator {
             }
         }
     }
-}
 
-impl UserTypeIterator {
 //    pub fn get_field_name(&mut self)-> CassValue {unsafe{
 //
 //        CassValue::new(cass_iterator_get_user_type_field_name(self.0))
@@ -1,10 +1,6 @@
-use std::fmt::Formatter;
-use std::fmt;
-use std::fmt::Display;
-use std::fmt::Debug;
 use std::mem;
-use std::ffi::CStr;
-use std::str;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::time::SystemTime;
 
 use cql_bindgen::CassUuid as _CassUuid;
 use cql_bindgen::CassUuidGen as _CassUuidGen;
@@ -14,29 +10,22 @@ use cql_bindgen::cass_uuid_gen_time;
 use cql_bindgen::cass_uuid_gen_new_with_node;
 use cql_bindgen::cass_uuid_gen_random;
 use cql_bindgen::cass_uuid_gen_from_time;
-use cql_bindgen::cass_uuid_min_from_time;
-use cql_bindgen::cass_uuid_max_from_time;
-use cql_bindgen::cass_uuid_timestamp;
-use cql_bindgen::cass_uuid_version;
-use cql_bindgen::cass_uuid_string;
-//use cql_bindgen::raw2utf8;
-//use cql_bindgen::cass_uuid_from_string;
 
-//use cql_ffi::error::CassError;
+use cassandra_schema::CassUuid;
+#[cfg(feature = "uuid")]
+use cql_ffi::collection::collection::{CassCollection, ToCql};
+use cql_ffi::error::CassError;
+use cql_ffi::statement::CassStatement;
+use cassandra_schema::timestamp_from_system_time;
 
-const CASS_UUID_STRING_LENGTH:usize = 37;
+// The driver keeps the 60 bit timestamp of a version 1 uuid in the low bits of
+// time_and_version, with the version in the top nibble.
+const TIMESTAMP_MASK:u64 = 0x0FFFFFFFFFFFFFFF;
+const TIME_VERSION:u64 = 1 << 60;
 
-
-#[derive(Copy,Clone)]
-pub struct CassUuid(pub _CassUuid);
-
-impl ::std::default::Default for CassUuid {
-    fn default() -> CassUuid {
-        unsafe {
-            ::std::mem::zeroed()
-        }
-    }
-}
+// The last timestamp handed out by CassUuidGen::get_time_increasing, shared by
+// every generator in the process.
+static LAST_TICKS: AtomicU64 = AtomicU64::new(0);
 
 pub struct CassUuidGen(pub *mut _CassUuidGen);
 
@@ -48,59 +37,27 @@ impl Drop for CassUuidGen {
     }
 }
 
-impl Debug for CassUuid {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{:?}", self.to_string())
+// CassUuid comes from the cassandra_schema crate, which has no driver bindings,
+// so uuids are copied field by field on their way to and from the driver.
+pub(crate) fn to_driver(uuid: CassUuid) -> _CassUuid {
+    _CassUuid {
+        time_and_version: uuid.time_and_version,
+        clock_seq_and_node: uuid.clock_seq_and_node,
     }
 }
 
-impl Display for CassUuid {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{}", self.to_string())
+pub(crate) fn from_driver(uuid: _CassUuid) -> CassUuid {
+    CassUuid {
+        time_and_version: uuid.time_and_version,
+        clock_seq_and_node: uuid.clock_seq_and_node,
     }
 }
 
-impl CassUuid {
-    pub fn min_from_time(&mut self, time: u64) {
-        unsafe {
-            cass_uuid_min_from_time(time, &mut self.0)
-        }
-    }
-
-    pub fn max_from_time(&mut self, time: u64) {
-        unsafe {
-            cass_uuid_max_from_time(time, &mut self.0)
-        }
-    }
-
-    pub fn timestamp(&self) -> u64 {
-        unsafe {
-            cass_uuid_timestamp(self.0)
-        }
+#[cfg(feature = "uuid")]
+impl ToCql for ::uuid::Uuid {
+    fn append_to<C: CassCollection>(&self, collection: &mut C) -> Result<(), CassError> {
+        CassUuid::from(*self).append_to(collection)
     }
-
-    pub fn version(&self) -> u8 {
-        unsafe {
-            cass_uuid_version(self.0)
-        }
-    }
-
-    //FIXME
-    pub fn to_string(&self) -> String {
-        unsafe {
-            let mut time_str: [i8; CASS_UUID_STRING_LENGTH] = [0;CASS_UUID_STRING_LENGTH];
-
-            cass_uuid_string(self.0, time_str[..].as_mut_ptr());
-            let mut output: i8 = mem::zeroed();
-            cass_uuid_string(self.0, &mut output);
-            let slice = CStr::from_ptr(&output);
-            str::from_utf8(slice.to_bytes()).unwrap().to_owned()
-        }
-    }
-
-//    pub unsafe fn from_string(&mut self, str: *const c_char) -> Result<(),CassError> {
-//        CassError::build(cass_uuid_from_string(str,&mut self.0))
-//    }
 }
 
 impl CassUuidGen {
@@ -120,27 +77,157 @@ impl CassUuidGen {
         unsafe {
             let mut output: _CassUuid = mem::zeroed();
             cass_uuid_gen_time(self.0, &mut output);
-            CassUuid(output)
+            from_driver(output)
         }
     }
 
-    pub fn fill_random(&self, mut output: CassUuid) {
-        unsafe {
-            cass_uuid_gen_random(self.0, &mut output.0)
-        }
+    pub fn fill_random(&self, output: &mut CassUuid) -> CassUuid {
+        *output = self.random();
+        *output
     }
 
     pub fn random(&self) -> CassUuid {
         unsafe {
             let mut output: _CassUuid = mem::zeroed();
             cass_uuid_gen_random(self.0, &mut output);
-            CassUuid(output)
+            from_driver(output)
+        }
+    }
+
+    // Unlike get_time, never returns a uuid that is not greater than every
+    // earlier one from this method in the process, even for uuids generated in
+    // the same clock tick or after the clock has gone backwards. Under bursts the
+    // timestamps run slightly ahead of the clock until it catches up.
+    pub fn get_time_increasing(&self) -> CassUuid {
+        let mut uuid = self.get_time();
+        let now = uuid.time_and_version & TIMESTAMP_MASK;
+        let mut last = LAST_TICKS.load(AtomicOrdering::SeqCst);
+        loop {
+            let next = if now > last { now } else { last + 1 };
+            match LAST_TICKS.compare_exchange(last,
+                                              next,
+                                              AtomicOrdering::SeqCst,
+                                              AtomicOrdering::SeqCst) {
+                Ok(_) => {
+                    uuid.time_and_version = next | TIME_VERSION;
+                    return uuid;
+                }
+                Err(current) => last = current,
+            }
         }
     }
 
-    pub fn with_time(&self, timestamp: u64, mut output: CassUuid) {
+    pub fn with_time(&self, timestamp: u64) -> CassUuid {
         unsafe {
-            cass_uuid_gen_from_time(self.0, timestamp, &mut output.0)
+            let mut output: _CassUuid = mem::zeroed();
+            cass_uuid_gen_from_time(self.0, timestamp, &mut output);
+            from_driver(output)
+        }
+    }
+}
+
+// Bounds for selecting the time uuids of a time range, for statements like
+// "WHERE id > ? AND id < ?". Both ends are inclusive to the millisecond, and
+// times before the unix epoch are clamped to it.
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+pub struct TimeUuidRange {
+    pub min: CassUuid,
+    pub max: CassUuid,
+}
+
+impl TimeUuidRange {
+    pub fn between(start: SystemTime, end: SystemTime) -> TimeUuidRange {
+        let mut min = CassUuid::default();
+        let mut max = CassUuid::default();
+        min.min_from_time(epoch_millis(start));
+        // The driver's bound is at the first tick of the millisecond, so the
+        // last tick is the one before the next millisecond's.
+        max.max_from_time(epoch_millis(end) + 1);
+        max.time_and_version -= 1;
+        TimeUuidRange { min: min, max: max }
+    }
+
+    // Binds min at index and max at index + 1.
+    pub fn bind<'a>(&self,
+                    statement: &'a mut CassStatement,
+                    index: u64)
+                    -> Result<&'a mut CassStatement, CassError> {
+        try!(statement.bind_uuid(index, self.min));
+        statement.bind_uuid(index + 1, self.max)
+    }
+
+    pub fn bind_by_name<'a>(&self,
+                            statement: &'a mut CassStatement,
+                            min_name: &str,
+                            max_name: &str)
+                            -> Result<&'a mut CassStatement, CassError> {
+        try!(statement.bind_uuid_by_name(min_name, self.min));
+        statement.bind_uuid_by_name(max_name, self.max)
+    }
+
+    pub fn contains(&self, uuid: &CassUuid) -> bool {
+        *uuid > self.min && *uuid < self.max
+    }
+}
+
+fn epoch_millis(time: SystemTime) -> u64 {
+    let millis = timestamp_from_system_time(time);
+    if millis < 0 { 0 } else { millis as u64 }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+
+    const GREGORIAN_OFFSET: u64 = 0x01B21DD213814000;
+
+    fn uuid(time_and_version: u64, clock_seq_and_node: u64) -> CassUuid {
+        CassUuid {
+            time_and_version: time_and_version,
+            clock_seq_and_node: clock_seq_and_node,
         }
     }
+
+    fn at_millis(millis: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn time_uuid_ranges_span_whole_milliseconds() {
+        let range = TimeUuidRange::between(at_millis(1500), at_millis(2500));
+        let ticks = |millis: u64| GREGORIAN_OFFSET + millis * 10_000;
+        assert_eq!(range.min, uuid(TIME_VERSION | ticks(1500), 0x8080808080808080));
+        assert_eq!(range.max, uuid(TIME_VERSION | ticks(2501) - 1, 0x7f7f7f7f7f7f7f7f));
+        assert_eq!(range.min.timestamp(), 1500);
+        assert_eq!(range.max.timestamp(), 2500);
+
+        // the bounds are below and above every other lsb
+        for &lsb in &[0, 0x8000000000000000, 0x8080808080808081, 0x7f7f7f7f7f7f7f7e,
+                      0xffffffffffffffff] {
+            assert!(range.contains(&uuid(TIME_VERSION | ticks(1500), lsb)));
+            assert!(range.contains(&uuid(TIME_VERSION | ticks(2500) + 9_999, lsb)));
+            assert!(!range.contains(&uuid(TIME_VERSION | ticks(1500) - 1, lsb)));
+            assert!(!range.contains(&uuid(TIME_VERSION | ticks(2501), lsb)));
+        }
+        assert!(!range.contains(&range.min));
+        assert!(!range.contains(&range.max));
+    }
+
+    #[test]
+    fn time_uuid_ranges_clamp_to_the_epoch() {
+        let range = TimeUuidRange::between(UNIX_EPOCH - Duration::from_secs(1), at_millis(0));
+        assert_eq!(range.min.timestamp(), 0);
+        assert_eq!(range.max.timestamp(), 0);
+        let empty = TimeUuidRange::between(at_millis(2000), at_millis(1000));
+        assert!(!empty.contains(&uuid(TIME_VERSION | GREGORIAN_OFFSET + 15_000_000, 0)));
+    }
+
+    #[test]
+    fn uuids_keep_their_fields_through_the_driver_layout() {
+        let id = uuid(TIME_VERSION | 1234, 0x8899aabbccddeeff);
+        assert_eq!(from_driver(to_driver(id)), id);
+    }
 }
@@ -1,27 +1,51 @@
+use std::borrow::Cow;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt;
-use std::ffi::CString;
 use std::str;
 use std::slice;
+use std::net::IpAddr;
+use std::convert::TryFrom;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use libc::c_char;
 
 use cql_ffi::error::CassError;
+use cql_ffi::helpers::str_from_raw;
+use cql_ffi::helpers::str_from_raw_lossy;
 use cql_ffi::inet::CassInet;
-use cql_ffi::uuid::CassUuid;
+use cassandra_schema::CassUuid;
+use cassandra_schema::CassValueType;
+use cql_ffi::uuid::from_driver;
+use cql_ffi::uuid::to_driver;
 use cql_ffi::collection::map::MapIterator;
+use cql_ffi::collection::collection::CollectionIterator;
+use cql_ffi::collection::list::ListIterator;
 use cql_ffi::collection::set::SetIterator;
 use cql_ffi::error::CassErrorTypes;
+use cql_ffi::tuple::TupleIterator;
 use cql_ffi::udt::UserTypeIterator;
+use cassandra_schema::CqlValue;
+use cassandra_schema::CqlDuration;
+use cql_ffi::bindings::cass_value_get_int16;
+use cql_ffi::bindings::cass_value_get_int8;
+use cql_ffi::bindings::cass_value_get_uint32;
+use cql_ffi::bindings::cass_value_get_duration;
 
 use cql_bindgen::CassValue as _CassValue;
+use cql_bindgen::CassUuid as _CassUuid;
 use cql_bindgen::cass_value_secondary_sub_type;
 use cql_bindgen::cass_value_primary_sub_type;
 use cql_bindgen::cass_value_item_count;
 use cql_bindgen::cass_value_is_collection;
 use cql_bindgen::cass_value_is_null;
 use cql_bindgen::cass_value_type;
-//use cql_bindgen::cass_value_get_decimal;
+use cql_bindgen::cass_value_get_decimal;
 use cql_bindgen::cass_value_get_inet;
 use cql_bindgen::cass_value_get_string;
 use cql_bindgen::cass_value_get_bytes;
@@ -34,218 +58,164 @@ use cql_bindgen::cass_value_get_int32;
 use cql_bindgen::cass_iterator_from_user_type;
 use cql_bindgen::cass_iterator_from_collection;
 use cql_bindgen::cass_iterator_from_map;
+use cql_bindgen::cass_iterator_from_tuple;
 //use cql_bindgen::cass_value_data_type;
 
-
-use cql_bindgen::CASS_VALUE_TYPE_UNKNOWN;
-use cql_bindgen::CASS_VALUE_TYPE_CUSTOM;
-use cql_bindgen::CASS_VALUE_TYPE_ASCII;
-use cql_bindgen::CASS_VALUE_TYPE_BIGINT;
-use cql_bindgen::CASS_VALUE_TYPE_BLOB;
-use cql_bindgen::CASS_VALUE_TYPE_BOOLEAN;
-use cql_bindgen::CASS_VALUE_TYPE_COUNTER;
-use cql_bindgen::CASS_VALUE_TYPE_DECIMAL;
-use cql_bindgen::CASS_VALUE_TYPE_DOUBLE;
-use cql_bindgen::CASS_VALUE_TYPE_FLOAT;
-use cql_bindgen::CASS_VALUE_TYPE_INT;
-use cql_bindgen::CASS_VALUE_TYPE_TEXT;
-use cql_bindgen::CASS_VALUE_TYPE_TIMESTAMP;
-use cql_bindgen::CASS_VALUE_TYPE_UUID;
-use cql_bindgen::CASS_VALUE_TYPE_VARCHAR;
-use cql_bindgen::CASS_VALUE_TYPE_TIMEUUID;
-use cql_bindgen::CASS_VALUE_TYPE_INET;
-use cql_bindgen::CASS_VALUE_TYPE_LIST;
-use cql_bindgen::CASS_VALUE_TYPE_SET;
-use cql_bindgen::CASS_VALUE_TYPE_MAP;
-use cql_bindgen::CASS_VALUE_TYPE_VARINT;
-use cql_bindgen::CASS_VALUE_TYPE_UDT;
-use cql_bindgen::CASS_VALUE_TYPE_TUPLE;
-use cql_bindgen::CASS_VALUE_TYPE_LAST_ENTRY;
-
 use std::mem;
+use std::marker::PhantomData;
 
-pub struct CassValue(*const _CassValue);
-
-#[derive(Debug)]
-pub enum CassValueType {
-    UNKNOWN = CASS_VALUE_TYPE_UNKNOWN as isize,
-    CUSTOM = CASS_VALUE_TYPE_CUSTOM as isize,
-    ASCII = CASS_VALUE_TYPE_ASCII as isize,
-    BIGINT = CASS_VALUE_TYPE_BIGINT as isize,
-    BLOB = CASS_VALUE_TYPE_BLOB as isize,
-    BOOLEAN = CASS_VALUE_TYPE_BOOLEAN as isize,
-    COUNTER = CASS_VALUE_TYPE_COUNTER as isize,
-    DECIMAL = CASS_VALUE_TYPE_DECIMAL as isize,
-    DOUBLE = CASS_VALUE_TYPE_DOUBLE as isize,
-    FLOAT = CASS_VALUE_TYPE_FLOAT as isize,
-    INT = CASS_VALUE_TYPE_INT as isize,
-    TEXT = CASS_VALUE_TYPE_TEXT as isize,
-    TIMESTAMP = CASS_VALUE_TYPE_TIMESTAMP as isize,
-    UUID = CASS_VALUE_TYPE_UUID as isize,
-    VARCHAR = CASS_VALUE_TYPE_VARCHAR as isize,
-    VARINT = CASS_VALUE_TYPE_VARINT as isize,
-    TIMEUUID = CASS_VALUE_TYPE_TIMEUUID as isize,
-    INET = CASS_VALUE_TYPE_INET as isize,
-    LIST = CASS_VALUE_TYPE_LIST as isize,
-    MAP = CASS_VALUE_TYPE_MAP as isize,
-    SET = CASS_VALUE_TYPE_SET as isize,
-    UDT = CASS_VALUE_TYPE_UDT as isize,
-    TUPLE = CASS_VALUE_TYPE_TUPLE as isize,
-    LASTENTRY = CASS_VALUE_TYPE_LAST_ENTRY as isize,
-}
+// Borrows from whatever holds the driver's value: a row, a schema, or for a
+// collection item the iterator that read it.
+#[derive(Copy,Clone)]
+pub struct CassValue<'a>(*const _CassValue, PhantomData<&'a ()>);
 
-impl CassValueType {
-    pub fn build(_type: u32) -> Self {
-        match _type {
-            CASS_VALUE_TYPE_UNKNOWN => CassValueType::UNKNOWN,
-            CASS_VALUE_TYPE_CUSTOM => CassValueType::CUSTOM,
-            CASS_VALUE_TYPE_ASCII => CassValueType::ASCII,
-            CASS_VALUE_TYPE_BIGINT => CassValueType::BIGINT,
-            CASS_VALUE_TYPE_BLOB => CassValueType::BLOB,
-            CASS_VALUE_TYPE_BOOLEAN => CassValueType::BOOLEAN,
-            CASS_VALUE_TYPE_COUNTER => CassValueType::COUNTER,
-            CASS_VALUE_TYPE_DECIMAL => CassValueType::DECIMAL,
-            CASS_VALUE_TYPE_DOUBLE => CassValueType::DOUBLE,
-            CASS_VALUE_TYPE_FLOAT => CassValueType::FLOAT,
-            CASS_VALUE_TYPE_INT => CassValueType::INT,
-            CASS_VALUE_TYPE_TEXT => CassValueType::TEXT,
-            CASS_VALUE_TYPE_TIMESTAMP => CassValueType::TIMESTAMP,
-            CASS_VALUE_TYPE_UUID => CassValueType::UUID,
-            CASS_VALUE_TYPE_VARCHAR => CassValueType::VARCHAR,
-            CASS_VALUE_TYPE_VARINT => CassValueType::VARINT,
-            CASS_VALUE_TYPE_TIMEUUID => CassValueType::TIMEUUID,
-            CASS_VALUE_TYPE_INET => CassValueType::INET,
-            CASS_VALUE_TYPE_LIST => CassValueType::LIST,
-            CASS_VALUE_TYPE_MAP => CassValueType::MAP,
-            CASS_VALUE_TYPE_SET => CassValueType::SET,
-            CASS_VALUE_TYPE_UDT => CassValueType::UDT,
-            err => panic!("impossible value type{}", err),
+impl<'a> Debug for CassValue<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.to_cql_value() {
+            Ok(value) => write!(f, "{:?}", value),
+            Err(err) => write!(f, "{:?}", err),
         }
     }
 }
 
-impl Debug for CassValue {
+impl<'a> Display for CassValue<'a> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match self.is_null() {
-            true => Ok(()),
-            false => match self.get_type() {
-                CassValueType::UNKNOWN => write!(f, "{:?}", "unknown"),
-                CassValueType::CUSTOM => write!(f, "{:?}", "custom"),
-                CassValueType::ASCII => write!(f, "{:?}", self.get_string().unwrap()),
-                CassValueType::BIGINT => write!(f, "{:?}", self.get_int64().unwrap()),
-                CassValueType::VARCHAR => write!(f, "{:?}", self.get_string().unwrap()),
-                CassValueType::BOOLEAN => write!(f, "{:?}", self.get_bool().unwrap()),
-                CassValueType::DOUBLE => write!(f, "{:?}", self.get_double().unwrap()),
-                CassValueType::FLOAT => write!(f, "{:?}", self.get_float().unwrap()),
-                CassValueType::INT => write!(f, "{:?}", self.get_int32().unwrap()),
-                CassValueType::TIMEUUID => write!(f, "TIMEUUID: {:?}", self.get_uuid().unwrap()),
-                CassValueType::SET => {
-                    try!(write!(f, "["));
-                    for item in self.as_set_iterator().unwrap() {
-                        try!(write!(f,"SET {:?} ",item))
-                    }
-                    try!(write!(f, "]"));
-                    Ok(())
-                }
-                CassValueType::MAP => {
-                    for item in self.as_map_iterator().unwrap() {
-                        try!(write!(f, "MAP {:?}:{:?}", item.0,item.1))
-                    }
-                    Ok(())
-                }
-                CassValueType::UDT => {
-//                    for item in self.as_map_iterator().unwrap() {
-//                        try!(write!(f, "MAP {:?}:{:?}", item.0,item.1))
-//                    }
-                    Ok(())
-                }
-
-                //FIXME
-                err => write!(f, "{:?}", err),
-            },
+        match self.to_cql_value() {
+            Ok(value) => write!(f, "{}", value),
+            Err(err) => write!(f, "<{}>", err),
         }
     }
 }
 
-impl Display for CassValue {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match self.is_null() {
-            true => Ok(()),
-            false => match self.get_type() {
-                CassValueType::UNKNOWN => write!(f, "{}", "unknown"),
-                CassValueType::CUSTOM => write!(f, "{}", "custom"),
-                CassValueType::ASCII => write!(f, "{}", self.get_string().unwrap()),
-                CassValueType::BIGINT => write!(f, "{}", self.get_int64().unwrap()),
-                CassValueType::VARCHAR => write!(f, "{}", self.get_string().unwrap()),
-                CassValueType::BOOLEAN => write!(f, "{}", self.get_bool().unwrap()),
-                CassValueType::DOUBLE => write!(f, "{}", self.get_double().unwrap()),
-                CassValueType::FLOAT => write!(f, "{}", self.get_float().unwrap()),
-                CassValueType::INT => write!(f, "{}", self.get_int32().unwrap()),
-                CassValueType::TIMEUUID => write!(f, "TIMEUUID: {}", self.get_uuid().unwrap()),
-                CassValueType::SET => {
-                    try!(write!(f, "["));
-                    for item in self.as_set_iterator().unwrap() {
-                        try!(write!(f,"{} ",item))
-                    }
-                    try!(write!(f, "]"));
-                    Ok(())
-                }
-                CassValueType::MAP => {
-                    for item in self.as_map_iterator().unwrap() {
-                        try!(write!(f, "MAP {}:{}", item.0,item.1))
-                    }
-                    Ok(())
-                }
+impl<'a> CassValue<'a> {
+    pub(crate) fn new(value: *const _CassValue) -> Self {
+        CassValue(value, PhantomData)
+    }
 
-                //FIXME
-                err => write!(f, "{:?}", err),
-            },
+    pub fn fill_uuid(&self, uuid: CassUuid) -> Result<CassUuid, CassError> {
+        unsafe {
+            let mut output = to_driver(uuid);
+            CassError::build(cass_value_get_uuid(self.0,&mut output)).wrap(from_driver(output))
         }
     }
-}
 
-impl CassValue {
-    pub fn new(value: *const _CassValue) -> Self {
-//        println!("building value: {:?}", CassValue(value).get_type());
-        CassValue(value)
+    pub fn fill_string(&self) -> Result<String, CassError> {
+        self.get_str().map(str::to_owned)
     }
 
-    pub fn fill_uuid(&self, mut uuid: CassUuid) -> Result<CassUuid, CassError> {
+    // The bytes are owned by the driver and live as long as the result the value
+    // came from; to_vec copies them out.
+    pub fn get_bytes(&self) -> Result<&'a [u8], CassError> {
         unsafe {
-            CassError::build(cass_value_get_uuid(self.0,&mut uuid.0)).wrap(uuid)
+            let mut output = mem::zeroed();
+            let mut output_size = mem::zeroed();
+            try!(CassError::build(cass_value_get_bytes(self.0, &mut output, &mut output_size))
+                     .wrap(()));
+            Ok(slice::from_raw_parts(output as *const u8, output_size as usize))
         }
     }
 
-    pub fn fill_string(&self) -> Result<String, CassError> {
-        unsafe {
-            let output = mem::zeroed();
-            let output_length = mem::zeroed();
-            let err = cass_value_get_string(self.0, output, output_length);
+    pub fn to_vec(&self) -> Result<Vec<u8>, CassError> {
+        Ok(try!(self.get_bytes()).to_vec())
+    }
 
-            let slice = slice::from_raw_parts(output as *const u8, output_length as usize);
-            let string = str::from_utf8(slice).unwrap().to_owned();
-            CassError::build(err).wrap(string)
+    pub fn get_decimal_bytes(&self) -> Result<(Vec<u8>, i32), CassError> {
+        unsafe {
+            let mut varint = mem::zeroed();
+            let mut varint_size = mem::zeroed();
+            let mut scale = mem::zeroed();
+            try!(CassError::build(cass_value_get_decimal(self.0,
+                                                         &mut varint,
+                                                         &mut varint_size,
+                                                         &mut scale))
+                     .wrap(()));
+            let slice = slice::from_raw_parts(varint as *const u8, varint_size as usize);
+            Ok((slice.to_vec(), scale))
         }
     }
 
-    //FIXME test this
-    pub fn get_bytes(&self) -> Result<Vec<*const u8>, CassError> {
-        unsafe {
-            let mut output: *const u8 = mem::zeroed();
-            let output_size = mem::zeroed();
-            let result = cass_value_get_bytes(self.0, &mut output, output_size);
-        //let output:*mut u8 = &mut*output;
-            let slice: Vec<*const u8> = Vec::from_raw_parts(&mut output,
-                                                            output_size as usize,
-                                                            output_size as usize);
-            let r = CassError::build(result);
-            r.wrap(slice)
+    pub fn get_varint_bytes(&self) -> Result<Vec<u8>, CassError> {
+        match self.get_type() {
+            CassValueType::VARINT => self.to_vec(),
+            _ => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
         }
     }
 
-//    pub fn get_decimal<'a>(&'a self, mut output: String) -> Result<String,CassError> {unsafe{
-//        CassError::build(cass_value_get_decimal(self.0,&mut output)).wrap(output)
-//    }}
+    pub fn to_cql_value(&self) -> Result<CqlValue, CassError> {
+        if self.is_null() {
+            return Ok(CqlValue::Null);
+        }
+        Ok(match self.get_type() {
+            CassValueType::ASCII => CqlValue::Ascii(try!(self.get_str()).to_owned()),
+            CassValueType::TEXT => CqlValue::Text(try!(self.get_str()).to_owned()),
+            CassValueType::VARCHAR => CqlValue::Varchar(try!(self.get_str()).to_owned()),
+            CassValueType::BIGINT => CqlValue::BigInt(try!(self.get_int64())),
+            CassValueType::BLOB => CqlValue::Blob(try!(self.to_vec())),
+            CassValueType::BOOLEAN => CqlValue::Boolean(try!(self.get_bool())),
+            CassValueType::COUNTER => CqlValue::Counter(try!(self.get_int64())),
+            CassValueType::DECIMAL => {
+                let (unscaled, scale) = try!(self.get_decimal_bytes());
+                CqlValue::Decimal { unscaled: unscaled, scale: scale }
+            }
+            CassValueType::DOUBLE => CqlValue::Double(try!(self.get_double())),
+            CassValueType::FLOAT => CqlValue::Float(try!(self.get_float())),
+            CassValueType::INT => CqlValue::Int(try!(self.get_int32())),
+            CassValueType::TIMESTAMP => CqlValue::Timestamp(try!(self.get_int64())),
+            CassValueType::DATE => CqlValue::Date(try!(self.get_uint32())),
+            CassValueType::TIME => CqlValue::Time(try!(self.get_int64())),
+            CassValueType::SMALL_INT => CqlValue::SmallInt(try!(self.get_int16())),
+            CassValueType::TINY_INT => CqlValue::TinyInt(try!(self.get_int8())),
+            CassValueType::DURATION => CqlValue::Duration(try!(self.get_duration())),
+            CassValueType::UUID => CqlValue::Uuid(try!(self.get_uuid())),
+            CassValueType::TIMEUUID => CqlValue::TimeUuid(try!(self.get_uuid())),
+            CassValueType::INET => CqlValue::Inet(try!(IpAddr::try_from(try!(self.get_inet())))),
+            CassValueType::VARINT => CqlValue::Varint(try!(self.to_vec())),
+            CassValueType::CUSTOM => CqlValue::Custom(try!(self.to_vec())),
+            CassValueType::LIST => {
+                let mut items = try!(self.list_iter());
+                let mut list = Vec::new();
+                while let Some(item) = items.next() {
+                    list.push(try!(item.to_cql_value()));
+                }
+                CqlValue::List(list)
+            }
+            CassValueType::SET => {
+                let mut items = try!(self.as_set_iterator());
+                let mut set = Vec::new();
+                while let Some(item) = items.next() {
+                    set.push(try!(item.to_cql_value()));
+                }
+                CqlValue::Set(set)
+            }
+            CassValueType::MAP => {
+                let mut pairs = try!(self.as_map_iterator());
+                let mut entries = Vec::new();
+                while let Some((key, value)) = pairs.next() {
+                    entries.push((try!(key.to_cql_value()), try!(value.to_cql_value())));
+                }
+                CqlValue::Map(entries)
+            }
+            CassValueType::TUPLE => {
+                let mut items = try!(self.tuple_iter());
+                let mut tuple = Vec::new();
+                while let Some(item) = items.next() {
+                    tuple.push(try!(item.to_cql_value()));
+                }
+                CqlValue::Tuple(tuple)
+            }
+            CassValueType::UDT => {
+                let mut values = try!(self.as_user_type_iterator());
+                let mut fields = Vec::new();
+                while let Some((name, value)) = values.next() {
+                    fields.push((name, try!(value.to_cql_value())));
+                }
+                CqlValue::Udt(fields)
+            }
+            CassValueType::UNKNOWN | CassValueType::LASTENTRY => {
+                return Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32))
+            }
+        })
+    }
 
     pub fn get_type(&self) -> CassValueType {
         unsafe {
@@ -283,28 +253,59 @@ impl CassValue {
         }
     }
 
-    pub fn as_set_iterator(&self) -> Result<SetIterator, CassError> {
+    pub fn as_set_iterator(&self) -> Result<SetIterator<'a>, CassError> {
+        unsafe {
+            match self.get_type() {
+                CassValueType::SET => Ok(SetIterator(cass_iterator_from_collection(self.0), PhantomData)),
+                _ => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
+            }
+        }
+    }
+
+    pub fn list_iter(&self) -> Result<ListIterator<'a>, CassError> {
+        unsafe {
+            match self.get_type() {
+                CassValueType::LIST => Ok(ListIterator(cass_iterator_from_collection(self.0), PhantomData)),
+                _ => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
+            }
+        }
+    }
+
+    pub fn tuple_iter(&self) -> Result<TupleIterator<'a>, CassError> {
         unsafe {
             match self.get_type() {
-                CassValueType::SET => Ok(SetIterator(cass_iterator_from_collection(self.0))),
+                CassValueType::TUPLE => Ok(TupleIterator(cass_iterator_from_tuple(self.0), PhantomData)),
                 _ => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
             }
         }
     }
 
-    pub fn as_map_iterator(&self) -> Result<MapIterator, CassError> {
+    pub fn collection_iter(&self) -> Result<CollectionIterator<'a>, CassError> {
+        unsafe {
+            let iterator = match self.get_type() {
+                CassValueType::LIST | CassValueType::SET | CassValueType::MAP => {
+                    cass_iterator_from_collection(self.0)
+                }
+                CassValueType::TUPLE => cass_iterator_from_tuple(self.0),
+                _ => return Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
+            };
+            Ok(CollectionIterator::new(iterator, self))
+        }
+    }
+
+    pub fn as_map_iterator(&self) -> Result<MapIterator<'a>, CassError> {
         unsafe {
             match self.get_type() {
-                CassValueType::MAP => Ok(MapIterator(cass_iterator_from_map(self.0))),
+                CassValueType::MAP => Ok(MapIterator(cass_iterator_from_map(self.0), PhantomData)),
                 _ => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
             }
         }
     }
 
-    pub fn as_user_type_iterator(&self) -> Result<UserTypeIterator, CassError> {
+    pub fn as_user_type_iterator(&self) -> Result<UserTypeIterator<'a>, CassError> {
         unsafe {
             match self.get_type() {
-                CassValueType::UDT => Ok(UserTypeIterator(cass_iterator_from_user_type(self.0))),
+                CassValueType::UDT => Ok(UserTypeIterator(cass_iterator_from_user_type(self.0), PhantomData)),
                 _ => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
             }
         }
@@ -313,7 +314,7 @@ impl CassValue {
 
     //~ pub fn map_iter(&self) -> Result<MapIterator,CassError> {unsafe{
         //~ match self.get_type() {
-            //~ CassValueType::MAP => Ok(MapIterator(cass_iterator_from_map(self.0))),
+            //~ CassValueType::MAP => Ok(MapIterator(cass_iterator_from_map(self.0), PhantomData)),
             //~ type_no => {
                 //~ println!("wrong_type: {:?}", type_no);
                 //~ Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32))
@@ -321,21 +322,36 @@ impl CassValue {
         //~ }
     //~ }}
 
-    pub fn get_string(&self) -> Result<String, CassError> {
+    // Like the bytes, the text is borrowed from the driver for as long as the
+    // result lives.
+    pub fn get_str(&self) -> Result<&'a str, CassError> {
         unsafe {
-            let message: CString = mem::zeroed();
-            let mut message = message.as_ptr();
-            let mut message_length = mem::zeroed();
-            cass_value_get_string(self.0, &mut message, &mut (message_length));
+            let (message, message_length) = try!(self.get_raw_string());
+            str_from_raw(message, message_length)
+        }
+    }
 
-            let slice = slice::from_raw_parts(message as *const u8, message_length as usize);
-            let err = CassError::build(cass_value_get_string(self.0,
-                                                             &mut message,
-                                                             &mut (message_length)));
-            err.wrap(str::from_utf8(slice).unwrap().to_owned())
+    // Replaces invalid UTF-8 with U+FFFD instead of failing, for ascii columns
+    // written by clients that didn't check their input.
+    pub fn get_str_lossy(&self) -> Result<Cow<'a, str>, CassError> {
+        unsafe {
+            let (message, message_length) = try!(self.get_raw_string());
+            Ok(str_from_raw_lossy(message, message_length))
         }
     }
 
+    pub fn get_string(&self) -> Result<&'a str, CassError> {
+        self.get_str()
+    }
+
+    unsafe fn get_raw_string(&self) -> Result<(*const c_char, u64), CassError> {
+        let mut message = mem::zeroed();
+        let mut message_length = mem::zeroed();
+        try!(CassError::build(cass_value_get_string(self.0, &mut message, &mut message_length))
+                 .wrap(()));
+        Ok((message, message_length))
+    }
+
     //~ pub fn get_string(&self) -> Result<String,CassError> {unsafe{
         //~ let mut output = mem::zeroed();
         //~ let mut output_size = mem::zeroed();
@@ -352,8 +368,11 @@ impl CassValue {
         //~ err.wrap(string)
     //~ }}
 
-    pub unsafe fn get_inet(&self, mut output: CassInet) -> Result<CassInet, CassError> {
-        CassError::build(cass_value_get_inet(self.0,&mut output.0)).wrap(output)
+    pub fn get_inet(&self) -> Result<CassInet, CassError> {
+        unsafe {
+            let mut output = CassInet::default();
+            CassError::build(cass_value_get_inet(self.0, &mut output.0)).wrap(output)
+        }
     }
 
     pub fn get_int32(&self) -> Result<i32, CassError> {
@@ -363,6 +382,39 @@ impl CassValue {
         }
     }
 
+    pub fn get_int16(&self) -> Result<i16, CassError> {
+        unsafe {
+            let mut output = mem::zeroed();
+            CassError::build(cass_value_get_int16(self.0, &mut output)).wrap(output)
+        }
+    }
+
+    pub fn get_int8(&self) -> Result<i8, CassError> {
+        unsafe {
+            let mut output = mem::zeroed();
+            CassError::build(cass_value_get_int8(self.0, &mut output)).wrap(output)
+        }
+    }
+
+    // The raw value of a date column, see time::days_from_date.
+    pub fn get_uint32(&self) -> Result<u32, CassError> {
+        unsafe {
+            let mut output = mem::zeroed();
+            CassError::build(cass_value_get_uint32(self.0, &mut output)).wrap(output)
+        }
+    }
+
+    pub fn get_duration(&self) -> Result<CqlDuration, CassError> {
+        unsafe {
+            let mut duration = CqlDuration::default();
+            CassError::build(cass_value_get_duration(self.0,
+                                                     &mut duration.months,
+                                                     &mut duration.days,
+                                                     &mut duration.nanos))
+                .wrap(duration)
+        }
+    }
+
     pub fn get_int64(&self) -> Result<i64, CassError> {
         unsafe {
             let mut output = mem::zeroed();
@@ -393,9 +445,209 @@ impl CassValue {
 
     pub fn get_uuid(&self) -> Result<CassUuid, CassError> {
         unsafe {
-            let mut output: CassUuid = mem::zeroed();
-            CassError::build(cass_value_get_uuid(self.0,&mut output.0)).wrap(output)
+            let mut output: _CassUuid = mem::zeroed();
+            CassError::build(cass_value_get_uuid(self.0,&mut output)).wrap(from_driver(output))
+        }
+    }
+
+    pub fn get<T>(&self) -> Result<T, CassError>
+        where T: FromCql<'a>
+    {
+        T::from_cql(self)
+    }
+}
+
+// A Rust type a value can be read into. Reading a null fails with
+// LIB_NULL_VALUE unless it is read as an Option.
+pub trait FromCql<'a>: Sized {
+    fn from_cql(value: &CassValue<'a>) -> Result<Self, CassError>;
+}
+
+macro_rules! impl_from_cql {
+    ($t:ty, $get:ident) => {
+        impl<'a> FromCql<'a> for $t {
+            fn from_cql(value: &CassValue<'a>) -> Result<Self, CassError> {
+                value.$get()
+            }
+        }
+    }
+}
+
+impl_from_cql!(i8, get_int8);
+impl_from_cql!(i16, get_int16);
+impl_from_cql!(i32, get_int32);
+impl_from_cql!(i64, get_int64);
+impl_from_cql!(u32, get_uint32);
+impl_from_cql!(f32, get_float);
+impl_from_cql!(f64, get_double);
+impl_from_cql!(bool, get_bool);
+impl_from_cql!(&'a str, get_str);
+impl_from_cql!(String, fill_string);
+impl_from_cql!(&'a [u8], get_bytes);
+impl_from_cql!(CassUuid, get_uuid);
+impl_from_cql!(CassInet, get_inet);
+impl_from_cql!(CqlDuration, get_duration);
+impl_from_cql!(CqlValue, to_cql_value);
+
+impl<'a> FromCql<'a> for IpAddr {
+    fn from_cql(value: &CassValue<'a>) -> Result<Self, CassError> {
+        IpAddr::try_from(try!(value.get_inet()))
+    }
+}
+
+impl<'a> FromCql<'a> for CassValue<'a> {
+    fn from_cql(value: &CassValue<'a>) -> Result<Self, CassError> {
+        Ok(*value)
+    }
+}
+
+impl<'a, T: FromCql<'a>> FromCql<'a> for Option<T> {
+    fn from_cql(value: &CassValue<'a>) -> Result<Self, CassError> {
+        if value.is_null() {
+            Ok(None)
+        } else {
+            T::from_cql(value).map(Some)
+        }
+    }
+}
+
+// Items only live until the iterator reading them moves on, so collections
+// are read into owned types: a Vec<String> rather than a Vec<&str>.
+macro_rules! read_items {
+    ($items:expr, $t:ty) => {{
+        let mut items = try!($items);
+        let mut read = Self::default();
+        while let Some(item) = items.next() {
+            read.extend(Some(try!(<$t>::from_cql(&item))));
+        }
+        Ok(read)
+    }}
+}
+
+macro_rules! read_pairs {
+    ($pairs:expr, $k:ty, $v:ty) => {{
+        let mut pairs = try!($pairs);
+        let mut read = Self::default();
+        while let Some((key, value)) = pairs.next() {
+            read.extend(Some((try!(<$k>::from_cql(&key)), try!(<$v>::from_cql(&value)))));
+        }
+        Ok(read)
+    }}
+}
+
+// Lists and sets can both be read into a Vec, keeping the order the server
+// returned them in.
+impl<'a, T: for<'b> FromCql<'b>> FromCql<'a> for Vec<T> {
+    fn from_cql(value: &CassValue<'a>) -> Result<Self, CassError> {
+        match value.get_type() {
+            CassValueType::LIST => read_items!(value.list_iter(), T),
+            CassValueType::SET => read_items!(value.as_set_iterator(), T),
+            _ => Err(CassError::build(CassErrorTypes::LIB_INVALID_VALUE_TYPE as u32)),
         }
     }
+}
+
+impl<'a, T: for<'b> FromCql<'b> + Eq + Hash> FromCql<'a> for HashSet<T> {
+    fn from_cql(value: &CassValue<'a>) -> Result<Self, CassError> {
+        read_items!(value.as_set_iterator(), T)
+    }
+}
+
+impl<'a, T: for<'b> FromCql<'b> + Ord> FromCql<'a> for BTreeSet<T> {
+    fn from_cql(value: &CassValue<'a>) -> Result<Self, CassError> {
+        read_items!(value.as_set_iterator(), T)
+    }
+}
+
+impl<'a, K, V> FromCql<'a> for HashMap<K, V>
+    where K: for<'b> FromCql<'b> + Eq + Hash,
+          V: for<'b> FromCql<'b>
+{
+    fn from_cql(value: &CassValue<'a>) -> Result<Self, CassError> {
+        read_pairs!(value.as_map_iterator(), K, V)
+    }
+}
+
+impl<'a, K, V> FromCql<'a> for BTreeMap<K, V>
+    where K: for<'b> FromCql<'b> + Ord,
+          V: for<'b> FromCql<'b>
+{
+    fn from_cql(value: &CassValue<'a>) -> Result<Self, CassError> {
+        read_pairs!(value.as_map_iterator(), K, V)
+    }
+}
 
+#[cfg(test)]
+mod tests {
+    use cql_ffi::bindings::CASS_VALUE_TYPE_DATE;
+    use cql_ffi::bindings::CASS_VALUE_TYPE_TIME;
+    use cql_ffi::bindings::CASS_VALUE_TYPE_SMALL_INT;
+    use cql_ffi::bindings::CASS_VALUE_TYPE_TINY_INT;
+    use cql_ffi::bindings::CASS_VALUE_TYPE_DURATION;
+    use cql_bindgen::CASS_VALUE_TYPE_UNKNOWN;
+    use cql_bindgen::CASS_VALUE_TYPE_CUSTOM;
+    use cql_bindgen::CASS_VALUE_TYPE_ASCII;
+    use cql_bindgen::CASS_VALUE_TYPE_BIGINT;
+    use cql_bindgen::CASS_VALUE_TYPE_BLOB;
+    use cql_bindgen::CASS_VALUE_TYPE_BOOLEAN;
+    use cql_bindgen::CASS_VALUE_TYPE_COUNTER;
+    use cql_bindgen::CASS_VALUE_TYPE_DECIMAL;
+    use cql_bindgen::CASS_VALUE_TYPE_DOUBLE;
+    use cql_bindgen::CASS_VALUE_TYPE_FLOAT;
+    use cql_bindgen::CASS_VALUE_TYPE_INT;
+    use cql_bindgen::CASS_VALUE_TYPE_TEXT;
+    use cql_bindgen::CASS_VALUE_TYPE_TIMESTAMP;
+    use cql_bindgen::CASS_VALUE_TYPE_UUID;
+    use cql_bindgen::CASS_VALUE_TYPE_VARCHAR;
+    use cql_bindgen::CASS_VALUE_TYPE_TIMEUUID;
+    use cql_bindgen::CASS_VALUE_TYPE_INET;
+    use cql_bindgen::CASS_VALUE_TYPE_LIST;
+    use cql_bindgen::CASS_VALUE_TYPE_SET;
+    use cql_bindgen::CASS_VALUE_TYPE_MAP;
+    use cql_bindgen::CASS_VALUE_TYPE_VARINT;
+    use cql_bindgen::CASS_VALUE_TYPE_UDT;
+    use cql_bindgen::CASS_VALUE_TYPE_TUPLE;
+    use cql_bindgen::CASS_VALUE_TYPE_LAST_ENTRY;
+    use super::*;
+
+    // The schema crate numbers the value types itself, as the protocol does; the
+    // driver has to agree.
+    #[test]
+    fn value_types_are_numbered_as_the_driver_numbers_them() {
+        let types = [(CassValueType::DATE, CASS_VALUE_TYPE_DATE),
+                     (CassValueType::TIME, CASS_VALUE_TYPE_TIME),
+                     (CassValueType::SMALL_INT, CASS_VALUE_TYPE_SMALL_INT),
+                     (CassValueType::TINY_INT, CASS_VALUE_TYPE_TINY_INT),
+                     (CassValueType::DURATION, CASS_VALUE_TYPE_DURATION),
+                     (CassValueType::UNKNOWN, CASS_VALUE_TYPE_UNKNOWN),
+                     (CassValueType::CUSTOM, CASS_VALUE_TYPE_CUSTOM),
+                     (CassValueType::ASCII, CASS_VALUE_TYPE_ASCII),
+                     (CassValueType::BIGINT, CASS_VALUE_TYPE_BIGINT),
+                     (CassValueType::BLOB, CASS_VALUE_TYPE_BLOB),
+                     (CassValueType::BOOLEAN, CASS_VALUE_TYPE_BOOLEAN),
+                     (CassValueType::COUNTER, CASS_VALUE_TYPE_COUNTER),
+                     (CassValueType::DECIMAL, CASS_VALUE_TYPE_DECIMAL),
+                     (CassValueType::DOUBLE, CASS_VALUE_TYPE_DOUBLE),
+                     (CassValueType::FLOAT, CASS_VALUE_TYPE_FLOAT),
+                     (CassValueType::INT, CASS_VALUE_TYPE_INT),
+                     (CassValueType::TEXT, CASS_VALUE_TYPE_TEXT),
+                     (CassValueType::TIMESTAMP, CASS_VALUE_TYPE_TIMESTAMP),
+                     (CassValueType::UUID, CASS_VALUE_TYPE_UUID),
+                     (CassValueType::VARCHAR, CASS_VALUE_TYPE_VARCHAR),
+                     (CassValueType::TIMEUUID, CASS_VALUE_TYPE_TIMEUUID),
+                     (CassValueType::INET, CASS_VALUE_TYPE_INET),
+                     (CassValueType::LIST, CASS_VALUE_TYPE_LIST),
+                     (CassValueType::SET, CASS_VALUE_TYPE_SET),
+                     (CassValueType::MAP, CASS_VALUE_TYPE_MAP),
+                     (CassValueType::VARINT, CASS_VALUE_TYPE_VARINT),
+                     (CassValueType::UDT, CASS_VALUE_TYPE_UDT),
+                     (CassValueType::TUPLE, CASS_VALUE_TYPE_TUPLE),
+                     (CassValueType::LASTENTRY, CASS_VALUE_TYPE_LAST_ENTRY)];
+        for &(value_type, number) in types.iter() {
+            assert_eq!(value_type as u32, number);
+            if value_type != CassValueType::LASTENTRY {
+                assert_eq!(CassValueType::build(number), value_type);
+            }
+        }
+    }
 }
@@ -25,7 +25,7 @@ fn insert_into_async(session: &mut CassSession, key: String) -> Result<(), CassE
     let mut futures = Vec::<ResultFuture>::new();
     for i in (0..NUM_CONCURRENT_REQUESTS) {
         let key: String = key.clone() + &i.to_string();
-        let mut statement = CassStatement::new(query, 6);
+        let mut statement = try!(CassStatement::new(query, 6));
         statement
             .bind_string(0, &key).unwrap()
             .bind_bool(1, i % 2 == 0).unwrap()
@@ -53,9 +53,9 @@ pub fn main() {
     cluster.set_contact_points("127.0.0.1").unwrap();
     match CassSession::new().connect(&cluster).wait() {
         Ok(mut session) => {
-            session.execute(CREATE_KEYSPACE,0).wait().unwrap();
-            session.execute(CREATE_TABLE,0).wait().unwrap();
-            session.execute("USE examples",0);
+            session.execute(CREATE_KEYSPACE,0).unwrap().wait().unwrap();
+            session.execute(CREATE_TABLE,0).unwrap().wait().unwrap();
+            session.execute("USE examples",0).unwrap();
             insert_into_async(&mut session, "test".to_owned()).unwrap();
             session.close().wait().unwrap();
         }
@@ -41,7 +41,7 @@ fn insert_into_batch_with_prepared<'a>(session: &mut CassSession,
 }
 
 pub fn verify_batch(session: &mut CassSession) {
-    let result = session.execute(SELECT_QUERY,0).wait().unwrap();
+    let result = session.execute(SELECT_QUERY,0).unwrap().wait().unwrap();
     println!("{:?}",result);
 }
 
@@ -57,8 +57,8 @@ fn main() {
         Pair{key:"d", value:"4"},
     );
 
-    session.execute(CREATE_KEYSPACE,0).wait().unwrap();
-    session.execute_statement(&CassStatement::new(CREATE_TABLE,0)).wait().unwrap();
+    session.execute(CREATE_KEYSPACE,0).unwrap().wait().unwrap();
+    session.execute_statement(&CassStatement::new(CREATE_TABLE,0).unwrap()).wait().unwrap();
     insert_into_batch_with_prepared(&mut session, pairs).unwrap();
     verify_batch(&mut session);
     session.close();
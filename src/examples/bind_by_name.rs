@@ -48,12 +48,12 @@ unsafe fn select_from_basic(session: &mut CassSession,
     match session.execute_statement(&statement).wait() {
         Ok(result) => {
             println!("{:?}", result);
-            for row in result.iter() {
-                basic.bln = try!(row.get_column_by_name("BLN").get_bool());
-                basic.dbl = try!(row.get_column_by_name("dbl").get_double());
-                basic.flt = try!(row.get_column_by_name("flt").get_float());
-                basic.i32 = try!(row.get_column_by_name("i32").get_int32());
-                basic.i64 = try!(row.get_column_by_name("i64").get_int64());
+            if let Some(row) = result.first_row() {
+                basic.bln = try!(try!(row.get_column_by_name("BLN")).get_bool());
+                basic.dbl = try!(try!(row.get_column_by_name("dbl")).get_double());
+                basic.flt = try!(try!(row.get_column_by_name("flt")).get_float());
+                basic.i32 = try!(try!(row.get_column_by_name("i32")).get_int32());
+                basic.i64 = try!(try!(row.get_column_by_name("i64")).get_int64());
             }
             Ok(result)
         }
@@ -70,8 +70,8 @@ fn main() {
             Ok(mut session) => {
                 let input = Basic { bln: true, flt: 0.001f32, dbl: 0.0002, i32: 1, i64: 2 };
                 let mut output = Basic { bln: false, flt: 0f32, dbl: 0.0, i32: 0, i64: 0 };
-                session.execute(CREATE_KEYSPACE,0).wait().unwrap();
-                session.execute(CREATE_TABLE,0).wait().unwrap();
+                session.execute(CREATE_KEYSPACE,0).unwrap().wait().unwrap();
+                session.execute(CREATE_TABLE,0).unwrap().wait().unwrap();
                 match session.prepare(INSERT_QUERY).unwrap().wait() {
                     Ok(insert_prepared) => {
                         insert_into_basic(
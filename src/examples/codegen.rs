@@ -0,0 +1,84 @@
+extern crate cassandra;
+
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::process;
+
+use cassandra::*;
+
+static USAGE: &'static str = "usage: cassandra-codegen --keyspace <keyspace> [--hosts <contact points>] \
+                              [--output <file>]";
+
+struct Options {
+    hosts: String,
+    keyspace: String,
+    output: Option<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut hosts = "127.0.0.1".to_owned();
+    let mut keyspace = None;
+    let mut output = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match &arg[..] {
+            "--hosts" => hosts = try!(args.next().ok_or("--hosts needs a value")),
+            "--keyspace" => keyspace = Some(try!(args.next().ok_or("--keyspace needs a value"))),
+            "--output" => output = Some(try!(args.next().ok_or("--output needs a file"))),
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+    Ok(Options {
+        hosts: hosts,
+        keyspace: try!(keyspace.ok_or("--keyspace is required")),
+        output: output,
+    })
+}
+
+fn generate(session: &CassSession, keyspace: &str) -> Result<String, String> {
+    // read from the system tables, as the driver's schema metadata has no user types
+    let snapshot = try!(SchemaSnapshot::load(session).map_err(|err| format!("{:?}", err)));
+    match snapshot.keyspace(keyspace) {
+        Some(keyspace) => Ok(keyspace.to_rust()),
+        None => Err(format!("no keyspace {}", keyspace)),
+    }
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            process::exit(64);
+        }
+    };
+    let mut cluster = CassCluster::new();
+    cluster.set_contact_points(&options.hosts[..]).unwrap();
+    let session = match CassSession::new().connect(&mut cluster).wait() {
+        Ok(session) => session,
+        Err(err) => {
+            eprintln!("Unable to connect: '{:?}'", err);
+            process::exit(1);
+        }
+    };
+    let result = generate(&session, &options.keyspace);
+    session.close().wait().unwrap();
+    let rust = match result {
+        Ok(rust) => rust,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    };
+    match options.output {
+        Some(path) => {
+            let written = File::create(&path).and_then(|mut file| file.write_all(rust.as_bytes()));
+            if let Err(err) = written {
+                eprintln!("Unable to write {}: {}", path, err);
+                process::exit(1);
+            }
+        }
+        None => print!("{}", rust),
+    }
+}
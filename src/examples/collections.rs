@@ -13,25 +13,22 @@ fn insert_into_collections(session: &mut CassSession,
                            key: &str,
                            items: Vec<String>)
                            -> Result<CassResult, CassError> {
-    let mut statement = CassStatement::new(INSERT_QUERY, 2);
+    let mut statement = try!(CassStatement::new(INSERT_QUERY, 2));
     try!(statement.bind_string(0, key));
-    let mut set = CassSet::new(2);
-    for item in items {
-        try!(set.append_string(&item));
-    }
-    try!(statement.bind_set(1, set));
+    try!(statement.bind_set(1, try!(CassSet::from_iter(items))));
     session.execute_statement(&statement).wait()
 }
 
 fn select_from_collections(session: &mut CassSession, key: &str) -> Result<(), CassError> {
-    let mut statement = CassStatement::new(SELECT_QUERY, 1);
+    let mut statement = try!(CassStatement::new(SELECT_QUERY, 1));
     try!(statement.bind_string(0, key));
     let result = try!(session.execute_statement(&statement).wait());
     println!("{:?}", result);
-    for row in result.iter() {
+    let mut rows = result.iter();
+    while let Some(row) = rows.next() {
         let column = row.get_column(0);
-        let items_iterator: SetIterator = try!(try!(column).set_iter());
-        for item in items_iterator {
+        let mut items_iterator: SetIterator = try!(try!(column).set_iter());
+        while let Some(item) = items_iterator.next() {
             println!("item: {:?}", item);
         }
     }
@@ -46,8 +43,8 @@ fn main() {
 
     let items =
         vec!("apple".to_string(), "orange".to_string(), "banana".to_string(), "mango".to_string());
-    session.execute(CREATE_KEYSPACE,0);
-    session.execute(CREATE_TABLE,0);
+    session.execute(CREATE_KEYSPACE,0).unwrap();
+    session.execute(CREATE_TABLE,0).unwrap();
     insert_into_collections(session, "test", items).unwrap();
     select_from_collections(session, "test").unwrap();
 }
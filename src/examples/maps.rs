@@ -20,28 +20,25 @@ fn insert_into_maps(session: &mut CassSession,
                     key: &str,
                     items: Vec<Pair>)
                     -> Result<(), CassError> {
-    let mut statement = CassStatement::new(INSERT_QUERY, 2);
+    let mut statement = try!(CassStatement::new(INSERT_QUERY, 2));
     statement.bind_string(0, key).unwrap();
 
-    let mut map = CassMap::new(5);
-    for item in items {
-        map.append_string(item.key).unwrap();
-        map.append_int32(item.value).unwrap();
-    }
+    let map = try!(CassMap::from_iter(items.iter().map(|item| (item.key, item.value))));
     try!(statement.bind_map(1, map));
     try!(session.execute_statement(&statement).wait());
     Ok(())
 }
 
 fn select_from_maps(session: &mut CassSession, key: &str) -> Result<(), CassError> {
-    let mut statement = CassStatement::new(SELECT_QUERY, 1);
+    let mut statement = try!(CassStatement::new(SELECT_QUERY, 1));
     try!(statement.bind_string(0, key));
     let result = try!(session.execute_statement(&statement).wait());
     //println!("{:?}", result);
-    for row in result.iter() {
+    let mut rows = result.iter();
+    while let Some(row) = rows.next() {
         let column = row.get_column(0).unwrap(); //FIXME
-        let items_iterator: MapIterator = column.map_iter().unwrap();
-        for item in items_iterator {
+        let mut items_iterator: MapIterator = column.map_iter().unwrap();
+        while let Some(item) = items_iterator.next() {
             println!("item: {:?}", item);
         }
     }
@@ -70,8 +67,8 @@ fn foo() -> Result<(), CassError> {
     let session_future = CassSession::new().connect(&cluster).wait();
     match session_future {
         Ok(mut session) => {
-            try!(session.execute(CREATE_KEYSPACE,0).wait());
-            try!(session.execute(CREATE_TABLE,0).wait());
+            try!(try!(session.execute(CREATE_KEYSPACE,0)).wait());
+            try!(try!(session.execute(CREATE_TABLE,0)).wait());
             try!(insert_into_maps(&mut session, "test", items));
             try!(select_from_maps(&mut session, "test"));
             session.close();
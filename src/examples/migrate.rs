@@ -0,0 +1,101 @@
+extern crate cassandra;
+
+use std::env;
+use std::process;
+
+use cassandra::*;
+
+static USAGE: &'static str = "usage: cassandra-migrate --keyspace <keyspace> [--hosts <contact points>] \
+                              [--dry-run] [--baseline <version>] <directory>";
+
+struct Options {
+    hosts: String,
+    keyspace: String,
+    directory: String,
+    dry_run: bool,
+    baseline: Option<u32>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut hosts = "127.0.0.1".to_owned();
+    let mut keyspace = None;
+    let mut directory = None;
+    let mut dry_run = false;
+    let mut baseline = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match &arg[..] {
+            "--hosts" => hosts = try!(args.next().ok_or("--hosts needs a value")),
+            "--keyspace" => keyspace = Some(try!(args.next().ok_or("--keyspace needs a value"))),
+            "--dry-run" => dry_run = true,
+            "--baseline" => {
+                let version = try!(args.next().ok_or("--baseline needs a version"));
+                baseline = Some(try!(version.parse::<u32>()
+                                            .map_err(|_| format!("bad version {}", version))));
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ => directory = Some(arg),
+        }
+    }
+    Ok(Options {
+        hosts: hosts,
+        keyspace: try!(keyspace.ok_or("--keyspace is required")),
+        directory: try!(directory.ok_or("no directory given")),
+        dry_run: dry_run,
+        baseline: baseline,
+    })
+}
+
+fn run(session: &CassSession, options: &Options) -> Result<(), MigrationError> {
+    let migrations = try!(Migration::load_directory(&options.directory));
+    let mut migrator = Migrator::new(session, &options.keyspace);
+    migrator.set_dry_run(options.dry_run);
+    if let Some(version) = options.baseline {
+        for version in try!(migrator.baseline(&migrations, version)) {
+            println!("Baselined version {}", version);
+        }
+        return Ok(());
+    }
+    if options.dry_run {
+        for migration in try!(migrator.pending(&migrations)) {
+            println!("-- V{} {}", migration.version, migration.description);
+            for statement in try!(migration.statements()) {
+                println!("{};", statement);
+            }
+        }
+        return Ok(());
+    }
+    let applied = try!(migrator.migrate(&migrations));
+    if applied.is_empty() {
+        println!("{} is up to date", options.keyspace);
+    }
+    for version in applied {
+        println!("Applied version {}", version);
+    }
+    Ok(())
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            process::exit(64);
+        }
+    };
+    let mut cluster = CassCluster::new();
+    cluster.set_contact_points(&options.hosts[..]).unwrap();
+    let session = match CassSession::new().connect(&mut cluster).wait() {
+        Ok(session) => session,
+        Err(err) => {
+            eprintln!("Unable to connect: '{:?}'", err);
+            process::exit(1);
+        }
+    };
+    let result = run(&session, &options);
+    session.close().wait().unwrap();
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
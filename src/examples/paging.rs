@@ -21,7 +21,7 @@ fn insert_into_paging(session: &mut CassSession /* , uuid_gen:&mut CassUuidGen *
     for i in 0..NUM_CONCURRENT_REQUESTS {
         let key = i.to_string();
         println!("key ={:?}", key);
-        let mut statement = CassStatement::new(INSERT_QUERY, 2);
+        let mut statement = try!(CassStatement::new(INSERT_QUERY, 2));
         try!(statement.bind_string(0, &key));
         try!(statement.bind_string(1, &key));
         let future = session.execute_statement(&statement);
@@ -36,14 +36,15 @@ fn insert_into_paging(session: &mut CassSession /* , uuid_gen:&mut CassUuidGen *
 
 fn select_from_paging(session: &mut CassSession) -> Result<(), CassError> {
     let has_more_pages = true;
-    let mut statement = CassStatement::new(SELECT_QUERY, 0);
+    let mut statement = try!(CassStatement::new(SELECT_QUERY, 0));
     statement.set_paging_size(100).unwrap();
 
 	//FIXME must understaned statement lifetime better for paging
     while has_more_pages {
         let result = try!(session.execute_statement(&statement).wait());
         //println!("{:?}", result);
-        for row in result.iter() {
+        let mut rows = result.iter();
+        while let Some(row) = rows.next() {
             match try!(row.get_column(0)).get_string() {
                 Ok(key) => {
                     let key_str = key.to_string();
@@ -71,9 +72,9 @@ fn main() {
 
     let mut session = CassSession::new().connect(&cluster).wait().unwrap();
 
-    session.execute(CREATE_KEYSPACE,0).wait().unwrap();
-    session.execute(CREATE_TABLE,0).wait().unwrap();
-    session.execute("USE examples",0).wait().unwrap();
+    session.execute(CREATE_KEYSPACE,0).unwrap().wait().unwrap();
+    session.execute(CREATE_TABLE,0).unwrap().wait().unwrap();
+    session.execute("USE examples",0).unwrap().wait().unwrap();
     let results = insert_into_paging(&mut session/*, uuid_gen*/).unwrap();
     for result in results {
         print!("{:?}", result.unwrap().wait().unwrap());
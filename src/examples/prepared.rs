@@ -23,7 +23,7 @@ fn insert_into_basic(session: &mut CassSession,
                      key: &str,
                      basic: &mut Basic)
                      -> Result<(), CassError> {
-    let mut statement = CassStatement::new(INSERT_QUERY, 6);
+    let mut statement = try!(CassStatement::new(INSERT_QUERY, 6));
     statement
         .bind_string(0, key).unwrap()
         .bind_bool(1, basic.bln).unwrap()
@@ -47,7 +47,8 @@ fn select_from_basic(session: &mut CassSession,
     match future.wait() {
         Ok(result) => {
             println!("{:?}", result);
-            for row in result.iter() {
+            let mut rows = result.iter();
+            while let Some(row) = rows.next() {
                 basic.bln = try!(try!(row.get_column(1)).get_bool());
                 basic.dbl = try!(try!(row.get_column(2)).get_double());
                 basic.flt = try!(try!(row.get_column(3)).get_float());
@@ -66,8 +67,8 @@ fn main() {
     let mut session = CassSession::new().connect(&mut cluster).wait().unwrap();
     let mut input = Basic { bln: true, flt: 0.001f32, dbl: 0.0002f64, i32: 1, i64: 2 };
     let mut output = Basic { bln: false, flt: 0f32, dbl: 0f64, i32: 0, i64: 0 };
-    session.execute(CREATE_KEYSPACE,0);
-    session.execute(CREATE_TABLE,0);
+    session.execute(CREATE_KEYSPACE,0).unwrap();
+    session.execute(CREATE_TABLE,0).unwrap();
     insert_into_basic(&mut session, "prepared_test", &mut input).unwrap();
     match session.prepare(SELECT_QUERY).unwrap().wait() {
         Ok(prepared) => {
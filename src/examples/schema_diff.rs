@@ -0,0 +1,54 @@
+extern crate cassandra;
+
+use std::env;
+use std::process;
+
+use cassandra::*;
+
+// Compares the cluster's schema with the .cql files in a directory and prints
+// what it would take to converge. Exits with 1 when they differ and with 2 when
+// some of the differences can't be made in place, so it can gate a CI job.
+fn main() {
+    let directory = match env::args().nth(1) {
+        Some(directory) => directory,
+        None => {
+            println!("usage: schema_diff <directory of .cql files> [contact points]");
+            process::exit(64);
+        }
+    };
+    let contact_points = env::args().nth(2).unwrap_or("127.0.0.1".to_owned());
+    let mut cluster = CassCluster::new();
+    cluster.set_contact_points(&contact_points).unwrap();
+    let session = match CassSession::new().connect(&mut cluster).wait() {
+        Ok(session) => session,
+        Err(err) => {
+            println!("Unable to connect: '{:?}'", err);
+            process::exit(64);
+        }
+    };
+    let current = match SchemaSnapshot::load(&session) {
+        Ok(current) => current,
+        Err(err) => {
+            println!("Unable to read the schema: '{:?}'", err);
+            process::exit(64);
+        }
+    };
+    session.close().wait().unwrap();
+    let diff = match SchemaDiff::against_directory(&current, &directory) {
+        Ok(diff) => diff,
+        Err(err) => {
+            println!("{}", err);
+            process::exit(64);
+        }
+    };
+    if diff.is_empty() {
+        println!("The schema matches {}", directory);
+        return;
+    }
+    print!("{}", diff);
+    println!("");
+    for statement in diff.statements() {
+        println!("{}", statement);
+    }
+    process::exit(if diff.blocking().is_empty() { 1 } else { 2 });
+}
@@ -11,10 +11,11 @@ fn main() {
         .set_contact_points(CONTACT_POINTS).unwrap()
         .set_load_balance_round_robin().unwrap();
     let session = CassSession::new().connect(&cluster).wait().unwrap();
-    let result = session.execute(QUERY, 0).wait().unwrap();
+    let result = session.execute(QUERY, 0).unwrap().wait().unwrap();
     println!("{}",result);
-    for row in result.iter() {
-        println!("ks name = {}", row.get_column_by_name(COL_NAME));
+    let mut rows = result.iter();
+    while let Some(row) = rows.next() {
+        println!("ks name = {}", row.get_column_by_name(COL_NAME).unwrap());
     }
     session.close().wait().unwrap();
 }
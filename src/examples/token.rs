@@ -0,0 +1,17 @@
+extern crate cassandra;
+
+use std::env;
+use std::process;
+
+use cassandra::*;
+
+// Prints the token of a text partition key made of the arguments.
+fn main() {
+    let components: Vec<String> = env::args().skip(1).collect();
+    if components.is_empty() {
+        println!("usage: token <key column>...");
+        process::exit(64);
+    }
+    let components: Vec<&[u8]> = components.iter().map(|component| component.as_bytes()).collect();
+    println!("{}", Murmur3Partitioner::composite_token(&components));
+}
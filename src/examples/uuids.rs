@@ -7,9 +7,14 @@ use cassandra::CassResult;
 use cassandra::CassError;
 use cassandra::CassUuidGen;
 use cassandra::CassCluster;
+use cassandra::TimeUuidRange;
+
+use std::time::{Duration, SystemTime};
 
 static INSERT_QUERY:&'static str = "INSERT INTO examples.log (key, time, entry) VALUES (?, ?, ?);";
 static SELECT_QUERY:&'static str = "SELECT * FROM examples.log WHERE key = ?";
+static SELECT_RANGE_QUERY:&'static str = "SELECT * FROM examples.log WHERE key = ? AND time > ? \
+                                          AND time < ?";
 static CREATE_KEYSPACE:&'static str = "CREATE KEYSPACE IF NOT EXISTS examples WITH replication = { \
                                        \'class\': \'SimpleStrategy\', \'replication_factor\': \
                                        \'3\' };";
@@ -21,7 +26,7 @@ fn insert_into_log(session: &mut CassSession,
                    time: CassUuid,
                    entry: &str)
                    -> Result<CassResult, CassError> {
-    let mut statement = CassStatement::new(INSERT_QUERY, 3);
+    let mut statement = try!(CassStatement::new(INSERT_QUERY, 3));
     statement.bind_string(0, key).unwrap();
     statement.bind_uuid(1, time).unwrap();
     statement.bind_string(2, &entry).unwrap();
@@ -30,26 +35,40 @@ fn insert_into_log(session: &mut CassSession,
 }
 
 fn select_from_log(session: &mut CassSession, key: &str) -> Result<CassResult, CassError> {
-    let mut statement = CassStatement::new(SELECT_QUERY, 1);
+    let mut statement = try!(CassStatement::new(SELECT_QUERY, 1));
     statement.bind_string(0, &key).unwrap();
     let mut future = session.execute_statement(&statement);
     let results = try!(future.wait());
     Ok(results)
 }
 
+fn select_range_from_log(session: &mut CassSession,
+                         key: &str,
+                         range: TimeUuidRange)
+                         -> Result<CassResult, CassError> {
+    let mut statement = try!(CassStatement::new(SELECT_RANGE_QUERY, 3));
+    try!(statement.bind_string(0, key));
+    try!(range.bind(&mut statement, 1));
+    let mut future = session.execute_statement(&statement);
+    future.wait()
+}
+
 fn main() {
     let uuid_gen = CassUuidGen::new();
     let mut cluster = CassCluster::new();
     cluster.set_contact_points("127.0.0.1").unwrap();
     let session = &mut CassSession::new().connect(&cluster).wait().unwrap();
 
-    session.execute(CREATE_KEYSPACE, 0);
-    session.execute(CREATE_TABLE, 0);
+    session.execute(CREATE_KEYSPACE, 0).unwrap();
+    session.execute(CREATE_TABLE, 0).unwrap();
     println!("uuid_gen = {:?}", uuid_gen.get_time());
-    insert_into_log(session, "test", uuid_gen.get_time(), "Log entry #1").unwrap();
-    insert_into_log(session, "test", uuid_gen.get_time(), "Log entry #2").unwrap();
-    insert_into_log(session, "test", uuid_gen.get_time(), "Log entry #3").unwrap();
-    insert_into_log(session, "test", uuid_gen.get_time(), "Log entry #4").unwrap();
+    let start = SystemTime::now();
+    insert_into_log(session, "test", uuid_gen.get_time_increasing(), "Log entry #1").unwrap();
+    insert_into_log(session, "test", uuid_gen.get_time_increasing(), "Log entry #2").unwrap();
+    insert_into_log(session, "test", uuid_gen.get_time_increasing(), "Log entry #3").unwrap();
+    insert_into_log(session, "test", uuid_gen.get_time_increasing(), "Log entry #4").unwrap();
+    let range = TimeUuidRange::between(start, SystemTime::now() + Duration::from_secs(1));
+    println!("{:?}", select_range_from_log(session, "test", range).unwrap());
     let results = select_from_log(session, "test").unwrap();
 //		for row in results.iter() {
 //		let time = row.get_column(1).unwrap();
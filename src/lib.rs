@@ -2,9 +2,22 @@
 //#![plugin(clippy)]
 //#![plugin(bindgen_plugin)]
 extern crate libc;
+extern crate cassandra_schema;
 #[macro_use]
 extern crate log;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "num-bigint")]
+extern crate num_bigint;
+#[cfg(feature = "bigdecimal")]
+extern crate bigdecimal;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "uuid")]
+extern crate uuid;
 
+pub use cassandra_schema::*;
 pub use cql_ffi::consistency::*;
 pub use cql_ffi::inet::*;
 pub use cql_ffi::uuid::*;
@@ -30,6 +43,17 @@ pub use cql_ffi::collection::set::*;
 pub use cql_ffi::collection::list::*;
 pub use cql_ffi::tuple::*;
 pub use cql_ffi::udt::*;
+pub use cql_ffi::lease::*;
+pub use cql_ffi::metadata::*;
+pub use cql_ffi::migrate::*;
+pub use cql_ffi::schema_change::*;
+pub use cql_ffi::checked_query::*;
+pub use cql_ffi::token::*;
+pub use cql_ffi::time::*;
+#[cfg(feature = "serde")]
+pub use cql_ffi::serialization::*;
+#[cfg(any(feature = "num-bigint", feature = "bigdecimal"))]
+pub use cql_ffi::numeric::*;
 
 extern crate cql_bindgen;
 
@@ -54,9 +78,21 @@ mod cql_ffi {
     pub mod log;
     pub mod error;
     pub mod helpers;
+    pub mod bindings;
     pub mod column;
     pub mod udt;
     pub mod tuple;
+    pub mod lease;
+    pub mod metadata;
+    pub mod migrate;
+    pub mod schema_change;
+    pub mod checked_query;
+    pub mod token;
+    pub mod time;
+    #[cfg(feature = "serde")]
+    pub mod serialization;
+    #[cfg(any(feature = "num-bigint", feature = "bigdecimal"))]
+    pub mod numeric;
 }
 
 
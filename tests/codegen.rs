@@ -0,0 +1,66 @@
+extern crate cassandra;
+extern crate trybuild;
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use cassandra::*;
+
+// A keyspace using every type codegen knows, and names that need changing to
+// be Rust identifiers.
+const SCHEMA: &'static str = "
+    CREATE KEYSPACE shop WITH replication = {'class': 'SimpleStrategy',
+        'replication_factor': 1};
+    CREATE TYPE shop.address (street text, \"Street\" text, zip int, \"_\" int);
+    CREATE TYPE shop.customer (name text, home frozen<address>, tags set<text>,
+        phones map<text, text>);
+    CREATE TYPE shop.\"String\" (value text);
+    CREATE TABLE shop.address (id uuid PRIMARY KEY, home frozen<address>);
+    CREATE TABLE shop.orders (
+        customer timeuuid,
+        \"1st\" timestamp,
+        \"type\" text,
+        total double,
+        ratio float,
+        count bigint,
+        small smallint,
+        tiny tinyint,
+        day date,
+        at time,
+        paid boolean,
+        origin inet,
+        took duration,
+        receipt blob,
+        amount decimal,
+        big varint,
+        items list<int>,
+        codes set<text>,
+        prices map<text, double>,
+        nested map<text, frozen<list<int>>>,
+        pair tuple<int, text>,
+        buyer frozen<customer>,
+        addresses list<frozen<address>>,
+        blobs list<blob>,
+        PRIMARY KEY ((customer), \"1st\")
+    );
+    CREATE TABLE shop.\"1st place\" (statement int, schema int, \"a b\" text, a_b text,
+        PRIMARY KEY (statement, schema));
+    CREATE TABLE shop.\"1ST PLACE\" (id int PRIMARY KEY, name frozen<\"String\">);
+    CREATE TABLE shop.\"String\" (id int PRIMARY KEY, home frozen<address>);
+";
+
+#[test]
+fn generated_code_compiles() {
+    let snapshot = SchemaSnapshot::from_cql(SCHEMA).unwrap();
+    let rust = snapshot.keyspace("shop").unwrap().to_rust();
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("target").join("codegen");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("shop.rs");
+    let mut file = File::create(&path).unwrap();
+    write!(file,
+           "extern crate cassandra;\n\nmod shop {{\n{}}}\n\nfn main() {{}}\n",
+           rust)
+        .unwrap();
+    trybuild::TestCases::new().pass(&path);
+}